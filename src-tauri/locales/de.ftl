@@ -0,0 +1,68 @@
+tray-show = Anzeigen
+tray-quit = Beenden
+
+api-key-missing = Kein API-Schlüssel konfiguriert. Bitte den Soniox-API-Schlüssel in den Einstellungen eintragen.
+openai-key-missing = Kein OpenAI-API-Schlüssel konfiguriert. Bitte in den Einstellungen eintragen.
+rewrite-clipboard-empty = Die Zwischenablage enthält keinen Text zum Umschreiben.
+azure-key-missing = Azure-Speech-Schlüssel oder -Region fehlt. Bitte beides in den Einstellungen eintragen.
+target-window-failed = Zielfenster konnte nicht ermittelt werden: { $error }
+unknown-seat = Unbekannter Arbeitsplatz '{ $name }'
+unknown-profile = Unbekanntes Profil '{ $name }'
+unknown-credential = Unbekannte Zugangsdaten '{ $name }'
+
+microphone-muted = Das Mikrofon scheint stummgeschaltet zu sein. Prüfe die Systemtoneinstellungen oder den Stummschalter des Geräts.
+microphone-missing-after-resume = Nach dem Aufwachen ist kein Mikrofon verfügbar
+bluetooth-headset-mode = Bluetooth-Mikrofon im Headset-Modus (HFP){ $rate ->
+        [none] {""}
+       *[other] { " " }mit { $rate } Hz
+    }: Die Erkennungsqualität sinkt und die Wiedergabe wechselt auf Telefonqualität.
+bluetooth-alternatives = Erwäge, den Eingang umzustellen auf: { $devices }
+
+hotkey-lost-after-sleep = Tastenkürzel nach dem Ruhezustand verloren: { $error }
+hotkey-lost = Globales Tastenkürzel verloren ({ $reason }): { $error }
+hotkey-reason-display-changed = Bildschirmkonfiguration geändert
+hotkey-reason-unregistered = Tastenkürzel ist nicht mehr registriert
+
+status-ready = Bereit
+status-dictating = Diktiere...
+status-last = Zuletzt: { $text }
+status-not-running = Desktop Dictate läuft nicht
+
+conflict-dictation-app = { $name } läuft. Es tippt womöglich in dasselbe Fenster oder belegt das Mikrofon; beende es, während du hier diktierst.
+conflict-hotkey-tool = { $name } läuft und fängt { $hotkey } womöglich ab, bevor Desktop Dictate es sieht. Wenn das Tastenkürzel nichts tut, entferne die Belegung dort oder wähle ein anderes Tastenkürzel.
+conflict-hotkey-taken = { $hotkey } konnte nicht registriert werden ({ $error }); vermutlich belegt es ein anderes Programm. Wähle ein anderes Tastenkürzel oder gib es dort frei.
+conflict-ydotoold-stopped = ydotool ist installiert, aber ydotoold läuft nicht, daher wird über die Zwischenablage eingefügt. Richte es unter Tipp-Werkzeuge in den Einstellungen ein.
+
+review-mode-sharing = Bildschirmfreigabe erkannt ({ $name }): Der Text dieser Sitzung bleibt im Hauptfenster und wird nicht getippt.
+screen-share-portal = eine Desktop-Bildschirmaufnahme
+
+action-start = Diktat starten
+action-stop = Diktat beenden
+action-cancel = Diktat abbrechen (nicht speichern)
+action-toggle = Diktat starten/beenden
+action-rewrite-clipboard = Zwischenablage per gesprochener Anweisung umschreiben
+action-incognito-on = Inkognito-Modus einschalten (Sitzungen nicht speichern)
+action-incognito-off = Inkognito-Modus ausschalten
+action-stop-playback = Audiowiedergabe stoppen
+action-show-window = Hauptfenster anzeigen
+action-export-history = Transkriptionsverlauf exportieren
+action-clear-history = Transkriptionsverlauf löschen
+action-default-name = Standard
+action-profile = Profil: { $name }
+action-workspace = Arbeitsbereich: { $name }
+unknown-action = Unbekannte Aktion '{ $name }'
+
+rewrite-busy = Beende die laufende Sitzung, bevor du die Zwischenablage umschreibst.
+clipboard-read-failed = Zwischenablage konnte nicht gelesen werden: { $error }
+clipboard-not-held = Die Zwischenablage enthält nicht den einzufügenden Text.
+analytics-off = Die lokale Auswertung ist ausgeschaltet.
+no-speech-in-file = Keine Sprache erkannt in '{ $path }'
+no-speech-in-recording = In der Aufnahme wurde keine Sprache erkannt.
+retranscribe-merged = Dieser Eintrag fasst mehrere Sitzungen zusammen, die Aufnahme enthält aber nur die erste. Er kann daher nicht erneut transkribiert werden.
+azure-recognition-failed = Azure-Erkennung fehlgeschlagen: { $status }
+vosk-model-missing = Kein Vosk-Modell ausgewählt. Bitte in den Einstellungen wählen.
+vosk-model-not-downloaded = Vosk-Modell '{ $name }' ist nicht heruntergeladen.
+vosk-not-built = Vosk-Unterstützung ist nicht enthalten; mit `--features vosk` neu bauen.
+whisper-not-built = Offline-Transkription braucht Whisper-Unterstützung; mit `--features whisper` neu bauen.
+tooling-not-authorized = Die Installation wurde nicht autorisiert.
+tooling-linux-only = Tipp-Werkzeuge werden nur unter Linux benötigt.
@@ -0,0 +1,71 @@
+# Backend-generated, user-facing strings. Keys are shared by all locales;
+# a key missing from a locale falls back to English.
+
+tray-show = Show
+tray-quit = Quit
+
+api-key-missing = API key not configured. Please set your Soniox API key in settings.
+openai-key-missing = OpenAI API key not configured. Please set it in settings.
+rewrite-clipboard-empty = The clipboard has no text to rewrite.
+azure-key-missing = Azure Speech key or region not configured. Please set both in settings.
+target-window-failed = Failed to capture target window: { $error }
+unknown-seat = Unknown seat '{ $name }'
+unknown-profile = Unknown profile '{ $name }'
+unknown-credential = Unknown credential '{ $name }'
+
+microphone-muted = Your microphone appears to be muted. Check the system sound settings or the device's mute switch.
+microphone-missing-after-resume = No microphone available after resume
+bluetooth-headset-mode = Bluetooth microphone in headset (HFP) mode{ $rate ->
+        [none] {""}
+       *[other] { " " }at { $rate } Hz
+    }: recognition quality will be reduced and playback switches to telephony quality.
+bluetooth-alternatives = Consider switching the input to: { $devices }
+
+hotkey-lost-after-sleep = Hotkey lost after sleep: { $error }
+hotkey-lost = Global hotkey lost ({ $reason }): { $error }
+hotkey-reason-display-changed = display configuration changed
+hotkey-reason-unregistered = hotkey is no longer registered
+
+status-ready = Ready
+status-dictating = Dictating...
+status-last = Last: { $text }
+status-not-running = Desktop Dictate is not running
+
+conflict-dictation-app = { $name } is running. It may type into the same window or hold the microphone; quit it while dictating here.
+conflict-hotkey-tool = { $name } is running and may grab { $hotkey } before Desktop Dictate sees it. If the hotkey does nothing, remove that binding or choose another hotkey.
+conflict-hotkey-taken = { $hotkey } could not be registered ({ $error }); another program probably holds it. Choose another hotkey or free it there.
+conflict-ydotoold-stopped = ydotool is installed but ydotoold is not running, so typing falls back to the clipboard. Set it up under Typing Tools in settings.
+
+review-mode-sharing = Screen sharing detected ({ $name }): this session's text stays in the main window instead of being typed.
+screen-share-portal = a desktop screencast
+
+action-start = Start dictation
+action-stop = Stop dictation
+action-cancel = Cancel dictation (don't save it)
+action-toggle = Toggle dictation
+action-rewrite-clipboard = Rewrite clipboard with a spoken instruction
+action-incognito-on = Turn on incognito mode (don't save sessions)
+action-incognito-off = Turn off incognito mode
+action-stop-playback = Stop audio playback
+action-show-window = Show main window
+action-export-history = Export transcription history
+action-clear-history = Clear transcription history
+action-default-name = Default
+action-profile = Profile: { $name }
+action-workspace = Workspace: { $name }
+unknown-action = Unknown action '{ $name }'
+
+rewrite-busy = Stop the current session before rewriting the clipboard.
+clipboard-read-failed = Failed to read the clipboard: { $error }
+clipboard-not-held = The clipboard does not hold the text to paste.
+analytics-off = Local analytics is off.
+no-speech-in-file = No speech recognized in '{ $path }'
+no-speech-in-recording = No speech recognized in the recording.
+retranscribe-merged = This entry combines several sessions but the recording holds only the first, so it cannot be transcribed again.
+azure-recognition-failed = Azure recognition failed: { $status }
+vosk-model-missing = No Vosk model selected. Choose one in settings.
+vosk-model-not-downloaded = Vosk model '{ $name }' is not downloaded.
+vosk-not-built = Vosk support is not built in; rebuild with `--features vosk`.
+whisper-not-built = Offline transcription needs Whisper support; rebuild with `--features whisper`.
+tooling-not-authorized = Installation was not authorized.
+tooling-linux-only = Typing tools are only needed on Linux.
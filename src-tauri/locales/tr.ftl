@@ -0,0 +1,68 @@
+tray-show = Göster
+tray-quit = Çıkış
+
+api-key-missing = API anahtarı ayarlanmamış. Lütfen ayarlardan Soniox API anahtarınızı girin.
+openai-key-missing = OpenAI API anahtarı ayarlanmamış. Lütfen ayarlardan girin.
+rewrite-clipboard-empty = Panoda yeniden yazılacak metin yok.
+azure-key-missing = Azure Speech anahtarı veya bölgesi ayarlanmamış. Lütfen ikisini de ayarlardan girin.
+target-window-failed = Hedef pencere alınamadı: { $error }
+unknown-seat = Bilinmeyen koltuk '{ $name }'
+unknown-profile = Bilinmeyen profil '{ $name }'
+unknown-credential = Bilinmeyen kimlik bilgisi '{ $name }'
+
+microphone-muted = Mikrofonunuz kapalı görünüyor. Sistem ses ayarlarını veya cihazın sessiz düğmesini kontrol edin.
+microphone-missing-after-resume = Uykudan dönüşte mikrofon bulunamadı
+bluetooth-headset-mode = Bluetooth mikrofon kulaklık (HFP) modunda{ $rate ->
+        [none] {""}
+       *[other] { " " }({ $rate } Hz)
+    }: tanıma kalitesi düşecek ve ses çıkışı telefon kalitesine geçecek.
+bluetooth-alternatives = Girişi şunlardan birine geçirmeyi düşünün: { $devices }
+
+hotkey-lost-after-sleep = Uykudan sonra kısayol tuşu kayboldu: { $error }
+hotkey-lost = Genel kısayol tuşu kayboldu ({ $reason }): { $error }
+hotkey-reason-display-changed = ekran yapılandırması değişti
+hotkey-reason-unregistered = kısayol artık kayıtlı değil
+
+status-ready = Hazır
+status-dictating = Dikte ediliyor...
+status-last = Son: { $text }
+status-not-running = Desktop Dictate çalışmıyor
+
+conflict-dictation-app = { $name } çalışıyor. Aynı pencereye yazabilir veya mikrofonu tutabilir; burada dikte ederken kapatın.
+conflict-hotkey-tool = { $name } çalışıyor ve { $hotkey } kısayolunu Desktop Dictate'ten önce yakalayabilir. Kısayol bir şey yapmıyorsa oradaki atamayı kaldırın veya başka bir kısayol seçin.
+conflict-hotkey-taken = { $hotkey } kaydedilemedi ({ $error }); muhtemelen başka bir program kullanıyor. Başka bir kısayol seçin veya orada serbest bırakın.
+conflict-ydotoold-stopped = ydotool kurulu ama ydotoold çalışmıyor, bu yüzden metin pano üzerinden yapıştırılıyor. Ayarlarda Yazma Araçları bölümünden kurun.
+
+review-mode-sharing = Ekran paylaşımı algılandı ({ $name }): bu oturumun metni yazılmak yerine ana pencerede kalır.
+screen-share-portal = bir masaüstü ekran yayını
+
+action-start = Dikteyi başlat
+action-stop = Dikteyi durdur
+action-cancel = Dikteyi iptal et (kaydetme)
+action-toggle = Dikteyi başlat/durdur
+action-rewrite-clipboard = Panoyu sesli bir talimatla yeniden yaz
+action-incognito-on = Gizli modu aç (oturumları kaydetme)
+action-incognito-off = Gizli modu kapat
+action-stop-playback = Ses oynatmayı durdur
+action-show-window = Ana pencereyi göster
+action-export-history = Yazıya dökme geçmişini dışa aktar
+action-clear-history = Yazıya dökme geçmişini temizle
+action-default-name = Varsayılan
+action-profile = Profil: { $name }
+action-workspace = Çalışma alanı: { $name }
+unknown-action = Bilinmeyen eylem '{ $name }'
+
+rewrite-busy = Panoyu yeniden yazmadan önce mevcut oturumu durdurun.
+clipboard-read-failed = Pano okunamadı: { $error }
+clipboard-not-held = Pano yapıştırılacak metni içermiyor.
+analytics-off = Yerel analiz kapalı.
+no-speech-in-file = '{ $path }' içinde konuşma tanınmadı
+no-speech-in-recording = Kayıtta konuşma tanınmadı.
+retranscribe-merged = Bu kayıt birden fazla oturumu birleştiriyor ancak ses kaydı yalnızca ilkini içeriyor, bu yüzden yeniden yazıya dökülemez.
+azure-recognition-failed = Azure tanıma başarısız oldu: { $status }
+vosk-model-missing = Vosk modeli seçilmemiş. Lütfen ayarlardan seçin.
+vosk-model-not-downloaded = Vosk modeli '{ $name }' indirilmemiş.
+vosk-not-built = Vosk desteği derlemede yok; `--features vosk` ile yeniden derleyin.
+whisper-not-built = Çevrimdışı yazıya dökme Whisper desteği gerektirir; `--features whisper` ile yeniden derleyin.
+tooling-not-authorized = Kurulum yetkilendirilmedi.
+tooling-linux-only = Yazma araçları yalnızca Linux'ta gereklidir.
@@ -0,0 +1,174 @@
+//! Recognition accuracy against a reference transcript.
+//!
+//! A WAV file is streamed through Soniox with the configured settings and the
+//! result is scored with word and character error rates (edit distance over
+//! the reference length). Both texts are normalized first so punctuation and
+//! casing, which the text processors change anyway, don't count as errors.
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AccuracyReport {
+    /// What the provider recognized
+    pub hypothesis: String,
+    pub reference: String,
+    /// Word error rate: word edits / reference words
+    pub wer: f64,
+    /// Character error rate: character edits / reference characters
+    pub cer: f64,
+    pub word_errors: usize,
+    pub reference_words: usize,
+    pub char_errors: usize,
+    pub reference_chars: usize,
+}
+
+/// Transcribe `audio_file` with `settings` and score it against `reference`.
+/// Only Soniox can transcribe a buffer of samples; the other providers
+/// stream into a target window, so they are refused.
+pub async fn evaluate(settings: crate::AppSettings, audio_file: &str, reference: &str) -> Result<AccuracyReport, String> {
+    match settings.provider {
+        crate::TranscriptionProvider::Soniox => {
+            if settings.effective_api_key().is_empty() {
+                return Err("API key not configured".to_string());
+            }
+        }
+        provider => {
+            return Err(format!(
+                "Accuracy evaluation is not available for {}",
+                crate::usage::provider_id(provider)
+            ))
+        }
+    }
+    let samples = crate::audio::load_fixture(audio_file)?;
+    log::info!(
+        "Evaluating accuracy on '{}' ({:.1}s of audio)",
+        audio_file,
+        samples.len() as f64 / 16000.0
+    );
+
+    let (hypothesis, _) = crate::soniox::transcribe_samples(settings, samples).await?;
+    let report = score(reference, &hypothesis);
+    log::info!(
+        "Accuracy: WER {:.1}% ({}/{}), CER {:.1}% ({}/{})",
+        report.wer * 100.0,
+        report.word_errors,
+        report.reference_words,
+        report.cer * 100.0,
+        report.char_errors,
+        report.reference_chars,
+    );
+    Ok(report)
+}
+
+/// Score `hypothesis` against `reference`.
+pub fn score(reference: &str, hypothesis: &str) -> AccuracyReport {
+    let reference_norm = normalize(reference);
+    let hypothesis_norm = normalize(hypothesis);
+
+    let reference_words: Vec<&str> = reference_norm.split_whitespace().collect();
+    let hypothesis_words: Vec<&str> = hypothesis_norm.split_whitespace().collect();
+    let word_errors = edit_distance(&reference_words, &hypothesis_words);
+
+    let reference_chars: Vec<char> = reference_norm.chars().collect();
+    let hypothesis_chars: Vec<char> = hypothesis_norm.chars().collect();
+    let char_errors = edit_distance(&reference_chars, &hypothesis_chars);
+
+    AccuracyReport {
+        hypothesis: hypothesis.to_string(),
+        reference: reference.to_string(),
+        wer: rate(word_errors, reference_words.len()),
+        cer: rate(char_errors, reference_chars.len()),
+        word_errors,
+        reference_words: reference_words.len(),
+        char_errors,
+        reference_chars: reference_chars.len(),
+    }
+}
+
+/// Errors per reference unit; an empty reference scores 0 only if the
+/// hypothesis is empty too.
+fn rate(errors: usize, reference_len: usize) -> f64 {
+    match reference_len {
+        0 if errors == 0 => 0.0,
+        0 => 1.0,
+        n => errors as f64 / n as f64,
+    }
+}
+
+/// Lowercase, drop punctuation (keeping in-word apostrophes and hyphens)
+/// and collapse whitespace.
+fn normalize(text: &str) -> String {
+    let lowered = text.to_lowercase();
+    let cleaned: String = lowered
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '\'' || c == '’' || c == '-' { c } else { ' ' })
+        .collect();
+    cleaned
+        .split_whitespace()
+        .map(|w| w.trim_matches(|c| c == '\'' || c == '’' || c == '-'))
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Levenshtein distance (substitutions, insertions, deletions).
+fn edit_distance<T: PartialEq>(a: &[T], b: &[T]) -> usize {
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, x) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, y) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(x != y);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_drops_punctuation_and_case() {
+        assert_eq!(normalize("Hello, World!  It's fine."), "hello world it's fine");
+        assert_eq!(normalize("'quoted' -dash- well-known"), "quoted dash well-known");
+        assert_eq!(normalize(" ... "), "");
+    }
+
+    #[test]
+    fn edit_distance_counts_edits() {
+        assert_eq!(edit_distance(&['a', 'b', 'c'], &['a', 'b', 'c']), 0);
+        assert_eq!(edit_distance(&['a', 'b', 'c'], &['a', 'x', 'c']), 1);
+        assert_eq!(edit_distance(&['a', 'b', 'c'], &['a', 'c']), 1);
+        assert_eq!(edit_distance(&['a', 'c'], &['a', 'b', 'c']), 1);
+        assert_eq!(edit_distance::<char>(&[], &['a', 'b']), 2);
+        assert_eq!(edit_distance(&["kitten"], &["sitting"]), 1);
+    }
+
+    #[test]
+    fn score_ignores_formatting() {
+        let report = score("Hello, world.", "hello world");
+        assert_eq!(report.word_errors, 0);
+        assert_eq!(report.wer, 0.0);
+        assert_eq!(report.cer, 0.0);
+        assert_eq!(report.hypothesis, "hello world");
+    }
+
+    #[test]
+    fn score_counts_word_and_char_errors() {
+        let report = score("the cat sat", "the hat sat down");
+        assert_eq!(report.reference_words, 3);
+        assert_eq!(report.word_errors, 2);
+        assert!((report.wer - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(report.reference_chars, 11);
+        assert_eq!(report.char_errors, 6);
+    }
+
+    #[test]
+    fn score_empty_reference() {
+        assert_eq!(score("", "").wer, 0.0);
+        assert_eq!(score("", "something").wer, 1.0);
+    }
+}
@@ -0,0 +1,172 @@
+//! Named actions for the command palette.
+//!
+//! Every user-facing operation that makes sense as a one-shot command is
+//! listed here, so the palette (and anything else driving the app through
+//! `invoke_action`) goes through the same code paths as the hotkey and tray.
+//! Parameterized actions use `name:argument`, e.g. `switch_profile:work`.
+//! `start` and `toggle` take `key=value` parameters separated by `;`:
+//! `start:lang=tr` starts a session with only the Turkish hint, whatever
+//! the active profile says (`lang=tr,en` for several).
+
+use std::sync::atomic::Ordering;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::i18n::{t, t_args};
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ActionInfo {
+    /// Stable identifier passed to `invoke_action`
+    pub name: String,
+    /// Human-readable label for the palette
+    pub title: String,
+    /// False when the action currently has no effect (e.g. stop while idle)
+    pub enabled: bool,
+}
+
+fn action(name: impl Into<String>, title: impl Into<String>, enabled: bool) -> ActionInfo {
+    ActionInfo {
+        name: name.into(),
+        title: title.into(),
+        enabled,
+    }
+}
+
+/// All actions with their current availability.
+pub fn list(app: &AppHandle) -> Vec<ActionInfo> {
+    let state = app.state::<crate::AppState>();
+    let recording = state.is_recording.load(Ordering::SeqCst);
+    let incognito = state.incognito.load(Ordering::SeqCst);
+    let settings = state.settings.lock().unwrap().clone();
+
+    let mut actions = vec![
+        action("start", t("action-start"), !recording),
+        action("stop", t("action-stop"), recording),
+        action("cancel", t("action-cancel"), recording),
+        action("toggle", t("action-toggle"), true),
+        action("rewrite_clipboard", t("action-rewrite-clipboard"), !recording),
+        action(
+            "toggle_incognito",
+            t(if incognito { "action-incognito-off" } else { "action-incognito-on" }),
+            true,
+        ),
+        action("stop_playback", t("action-stop-playback"), true),
+        action("show_window", t("action-show-window"), true),
+        action("export_history", t("action-export-history"), true),
+        action("clear_history", t("action-clear-history"), true),
+    ];
+
+    let mut profiles: Vec<&String> = settings.profiles.keys().collect();
+    profiles.sort();
+    let default_profile = t("action-default-name");
+    actions.push(action(
+        "switch_profile",
+        t_args("action-profile", &[("name", default_profile.clone())]),
+        settings.active_profile.is_some(),
+    ));
+    for name in profiles {
+        let active = settings.active_profile.as_ref() == Some(name);
+        let title = t_args("action-profile", &[("name", name.clone())]);
+        actions.push(action(format!("switch_profile:{}", name), title, !active));
+    }
+
+    let mut credentials: Vec<&String> = settings.credentials.keys().collect();
+    credentials.sort();
+    if !credentials.is_empty() {
+        actions.push(action(
+            "switch_credential",
+            t_args("action-workspace", &[("name", default_profile)]),
+            settings.active_credential.is_some(),
+        ));
+    }
+    for name in credentials {
+        let active = settings.active_credential.as_ref() == Some(name);
+        let title = t_args("action-workspace", &[("name", name.clone())]);
+        actions.push(action(format!("switch_credential:{}", name), title, !active));
+    }
+
+    if crate::faults::enabled() {
+        for fault in crate::faults::ALL {
+            actions.push(action(format!("debug_fault:{}", fault.name()), format!("Debug: {}", fault.title()), true));
+        }
+        actions.push(action("debug_fault:clear", "Debug: Clear injected faults", true));
+    }
+
+    actions
+}
+
+/// Run the action called `name`.
+pub async fn invoke(app: &AppHandle, name: &str) -> Result<(), String> {
+    let (action, argument) = match name.split_once(':') {
+        Some((action, argument)) => (action, Some(argument.to_string())),
+        None => (name, None),
+    };
+    log::info!("Invoking action '{}'", name);
+
+    let recording = app.state::<crate::AppState>().is_recording.load(Ordering::SeqCst);
+    match action {
+        "start" if !recording => {
+            let hints = session_language(argument.as_deref())?;
+            crate::toggle_recording(app.clone(), app.state(), None, hints).await?
+        }
+        "stop" if recording => {
+            crate::stop_active_session(app);
+        }
+        "cancel" if recording => {
+            crate::cancel_active_session(app);
+        }
+        "rewrite_clipboard" if !recording => crate::rewrite::start(app).await?,
+        "start" | "stop" | "cancel" | "rewrite_clipboard" => {}
+        "toggle" => {
+            let hints = session_language(argument.as_deref())?;
+            crate::toggle_recording(app.clone(), app.state(), None, hints).await?
+        }
+        "toggle_incognito" => {
+            let incognito = app.state::<crate::AppState>().incognito.load(Ordering::SeqCst);
+            crate::set_incognito(app, !incognito);
+        }
+        "stop_playback" => crate::stop_playback(app.state()),
+        "show_window" => crate::show_main_window(app),
+        "export_history" => {
+            let path = crate::export_history(app.clone(), None)?;
+            crate::console::record(app, "export", format!("History exported to {}", path));
+        }
+        "clear_history" => crate::clear_transcriptions(app.clone())?,
+        "switch_profile" => {
+            let settings = crate::set_active_profile(app, argument)?;
+            app.emit("settings-changed", settings).ok();
+        }
+        "switch_credential" => {
+            let settings = crate::set_active_credential(app, argument)?;
+            app.emit("settings-changed", settings).ok();
+        }
+        "debug_fault" if crate::faults::enabled() => match argument.as_deref() {
+            Some("clear") => crate::faults::clear(),
+            Some(fault) => crate::faults::arm(crate::faults::Fault::parse(fault)?),
+            None => return Err("debug_fault needs a fault name".to_string()),
+        },
+        _ => return Err(t_args("unknown-action", &[("name", name.to_string())])),
+    }
+    Ok(())
+}
+
+/// Language hints from the `lang=` parameter of a session action.
+fn session_language(argument: Option<&str>) -> Result<Option<Vec<String>>, String> {
+    let mut hints = None;
+    for param in argument.unwrap_or_default().split(';').filter(|p| !p.trim().is_empty()) {
+        match param.split_once('=').map(|(k, v)| (k.trim(), v)) {
+            Some(("lang", value)) => {
+                let languages: Vec<String> = value
+                    .split(',')
+                    .map(|l| l.trim().to_lowercase())
+                    .filter(|l| !l.is_empty())
+                    .collect();
+                if languages.is_empty() {
+                    return Err("lang needs at least one language code".to_string());
+                }
+                hints = Some(languages);
+            }
+            _ => return Err(format!("Unknown action parameter '{}'", param)),
+        }
+    }
+    Ok(hints)
+}
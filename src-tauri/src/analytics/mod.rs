@@ -0,0 +1,100 @@
+//! Opt-in dictation habits, computed on this computer only.
+//!
+//! Nothing new is recorded for this: sessions per provider (how many, how
+//! long, how many failed) come from the session counts in `usage.json`,
+//! typing failures and reconnects from the metrics counters, and target
+//! apps and hours of day from the history entries. Nothing is sent
+//! anywhere.
+
+use chrono::{Local, TimeZone, Timelike};
+use std::collections::BTreeMap;
+use tauri::AppHandle;
+
+const TOP_APPS: usize = 10;
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct AppCount {
+    pub app: String,
+    pub sessions: usize,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ProviderStats {
+    pub provider: String,
+    pub sessions: u64,
+    pub failed: u64,
+    /// Failed sessions as a fraction of all sessions
+    pub error_rate: f64,
+    pub average_session_secs: f64,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct Analytics {
+    /// Most dictated-into apps in history, most used first
+    pub top_apps: Vec<AppCount>,
+    /// History sessions by local hour of day, 0 to 23
+    pub hours: Vec<usize>,
+    pub average_session_secs: f64,
+    pub providers: Vec<ProviderStats>,
+    /// Failed insertions since the app started
+    pub typing_failures: u64,
+    /// Provider reconnection attempts since the app started
+    pub reconnects: u64,
+}
+
+pub fn compute(app: &AppHandle, history: &[crate::TranscriptionEntry]) -> Result<Analytics, String> {
+    let live = history.iter().filter(|e| e.source == crate::EntrySource::Live);
+
+    let mut apps: BTreeMap<String, usize> = BTreeMap::new();
+    let mut hours = vec![0; 24];
+    for entry in live {
+        if let Some(focused) = &entry.app {
+            *apps.entry(focused.app_id.clone()).or_default() += 1;
+        }
+        if let Some(hour) = local_hour(entry.timestamp) {
+            hours[hour] += 1;
+        }
+    }
+    let mut top_apps: Vec<AppCount> = apps.into_iter().map(|(app, sessions)| AppCount { app, sessions }).collect();
+    top_apps.sort_by(|a, b| b.sessions.cmp(&a.sessions));
+    top_apps.truncate(TOP_APPS);
+
+    let totals = crate::usage::session_totals(app)?;
+    let (sessions, seconds) = totals
+        .values()
+        .fold((0, 0.0), |(sessions, seconds), c| (sessions + c.sessions, seconds + c.seconds));
+    let providers = totals
+        .into_iter()
+        .map(|(provider, c)| ProviderStats {
+            provider,
+            sessions: c.sessions,
+            failed: c.failed,
+            error_rate: ratio(c.failed as f64, c.sessions),
+            average_session_secs: ratio(c.seconds, c.sessions),
+        })
+        .collect();
+
+    Ok(Analytics {
+        top_apps,
+        hours,
+        average_session_secs: ratio(seconds, sessions),
+        providers,
+        typing_failures: crate::metrics::typing_failures(),
+        reconnects: crate::metrics::reconnects(),
+    })
+}
+
+/// The local hour of day at `timestamp`, with the UTC offset in effect
+/// then rather than now, so entries from the other side of a DST change
+/// land in the hour they were dictated.
+fn local_hour(timestamp: u64) -> Option<usize> {
+    Local.timestamp_opt(timestamp as i64, 0).single().map(|time| time.hour() as usize)
+}
+
+fn ratio(value: f64, count: u64) -> f64 {
+    if count == 0 {
+        0.0
+    } else {
+        value / count as f64
+    }
+}
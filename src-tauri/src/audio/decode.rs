@@ -0,0 +1,77 @@
+//! Decoding of recorded audio files (WAV, MP3, OGG Vorbis) into the 16 kHz
+//! mono pcm16 the providers expect.
+
+use std::path::Path;
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::DecoderOptions;
+use symphonia::core::errors::Error;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+/// Decode the first audio track of `path`, downmixed and resampled.
+pub fn load_file(path: &str) -> Result<Vec<i16>, String> {
+    let file = std::fs::File::open(path).map_err(|e| format!("Failed to open '{}': {}", path, e))?;
+    let source = MediaSourceStream::new(Box::new(file), Default::default());
+    let mut hint = Hint::new();
+    if let Some(extension) = Path::new(path).extension().and_then(|e| e.to_str()) {
+        hint.with_extension(extension);
+    }
+
+    let probed = symphonia::default::get_probe()
+        .format(&hint, source, &FormatOptions::default(), &MetadataOptions::default())
+        .map_err(|e| format!("Unsupported audio file '{}': {}", path, e))?;
+    let mut format = probed.format;
+    let track = format.default_track().ok_or_else(|| format!("'{}' has no audio track", path))?;
+    let track_id = track.id;
+    let mut sample_rate = track.codec_params.sample_rate.unwrap_or(super::TARGET_SAMPLE_RATE);
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .map_err(|e| format!("Unsupported codec in '{}': {}", path, e))?;
+
+    let mut mono: Vec<f32> = Vec::new();
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            // Symphonia reports the end of the stream as an EOF error
+            Err(Error::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(format!("Failed to read '{}': {}", path, e)),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            // A corrupt frame is skipped, like players do
+            Err(Error::DecodeError(e)) => {
+                log::warn!("Skipping undecodable frame in '{}': {}", path, e);
+                continue;
+            }
+            Err(e) => return Err(format!("Failed to decode '{}': {}", path, e)),
+        };
+
+        let spec = *decoded.spec();
+        sample_rate = spec.rate;
+        let channels = spec.channels.count().max(1);
+        let mut buffer = SampleBuffer::<f32>::new(decoded.capacity() as u64, spec);
+        buffer.copy_interleaved_ref(decoded);
+        mono.extend(
+            buffer
+                .samples()
+                .chunks(channels)
+                .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32),
+        );
+    }
+
+    log::info!(
+        "Decoded '{}': {:.1}s at {} Hz",
+        path,
+        mono.len() as f64 / sample_rate as f64,
+        sample_rate
+    );
+    Ok(super::resample::resample(&mono, sample_rate, super::TARGET_SAMPLE_RATE)
+        .iter()
+        .map(|&s| (s.clamp(-1.0, 1.0) * 32767.0_f32) as i16)
+        .collect())
+}
@@ -0,0 +1,240 @@
+use serde::{Deserialize, Serialize};
+
+/// One step of the audio preprocessing chain, as stored in settings.
+/// Effects run in list order on the 16 kHz mono stream before it is sent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EffectConfig {
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(flatten)]
+    pub effect: Effect,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Effect {
+    /// Remove rumble and boominess below `cutoff_hz`
+    HighPass {
+        #[serde(default = "default_high_pass_cutoff")]
+        cutoff_hz: f32,
+    },
+    /// Turn down the sibilant band above `frequency_hz` when it exceeds
+    /// `threshold_db`, by at most `max_reduction_db`
+    DeEsser {
+        #[serde(default = "default_de_esser_frequency")]
+        frequency_hz: f32,
+        #[serde(default = "default_de_esser_threshold")]
+        threshold_db: f32,
+        #[serde(default = "default_de_esser_reduction")]
+        max_reduction_db: f32,
+    },
+    /// Downward compressor with makeup gain
+    Compressor {
+        #[serde(default = "default_compressor_threshold")]
+        threshold_db: f32,
+        #[serde(default = "default_compressor_ratio")]
+        ratio: f32,
+        #[serde(default = "default_compressor_attack")]
+        attack_ms: f32,
+        #[serde(default = "default_compressor_release")]
+        release_ms: f32,
+        #[serde(default)]
+        makeup_db: f32,
+    },
+}
+
+fn default_high_pass_cutoff() -> f32 {
+    80.0
+}
+
+fn default_de_esser_frequency() -> f32 {
+    5000.0
+}
+
+fn default_de_esser_threshold() -> f32 {
+    -30.0
+}
+
+fn default_de_esser_reduction() -> f32 {
+    6.0
+}
+
+fn default_compressor_threshold() -> f32 {
+    -18.0
+}
+
+fn default_compressor_ratio() -> f32 {
+    3.0
+}
+
+fn default_compressor_attack() -> f32 {
+    5.0
+}
+
+fn default_compressor_release() -> f32 {
+    100.0
+}
+
+/// Stateful processors built from the configured effects.
+pub struct Chain {
+    stages: Vec<Stage>,
+}
+
+enum Stage {
+    HighPass(Biquad),
+    DeEsser {
+        band: Biquad,
+        envelope: Envelope,
+        threshold_db: f32,
+        max_reduction_db: f32,
+    },
+    Compressor {
+        envelope: Envelope,
+        threshold_db: f32,
+        ratio: f32,
+        makeup: f32,
+    },
+}
+
+impl Chain {
+    pub fn new(effects: &[EffectConfig], sample_rate: u32) -> Self {
+        let rate = sample_rate as f32;
+        let stages = effects
+            .iter()
+            .filter(|e| e.enabled)
+            .map(|e| match e.effect {
+                Effect::HighPass { cutoff_hz } => Stage::HighPass(Biquad::high_pass(cutoff_hz, rate)),
+                Effect::DeEsser { frequency_hz, threshold_db, max_reduction_db } => Stage::DeEsser {
+                    band: Biquad::high_pass(frequency_hz, rate),
+                    envelope: Envelope::new(1.0, 50.0, rate),
+                    threshold_db,
+                    max_reduction_db: max_reduction_db.max(0.0),
+                },
+                Effect::Compressor { threshold_db, ratio, attack_ms, release_ms, makeup_db } => Stage::Compressor {
+                    envelope: Envelope::new(attack_ms, release_ms, rate),
+                    threshold_db,
+                    ratio: ratio.max(1.0),
+                    makeup: db_to_gain(makeup_db),
+                },
+            })
+            .collect();
+        Self { stages }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stages.is_empty()
+    }
+
+    /// Process PCM s16le bytes in place.
+    pub fn process(&mut self, pcm: &mut [u8]) {
+        for pair in pcm.chunks_exact_mut(2) {
+            let mut x = i16::from_le_bytes([pair[0], pair[1]]) as f32 / 32768.0;
+            for stage in &mut self.stages {
+                x = stage.process(x);
+            }
+            let out = (x.clamp(-1.0, 1.0) * 32767.0) as i16;
+            pair.copy_from_slice(&out.to_le_bytes());
+        }
+    }
+}
+
+impl Stage {
+    fn process(&mut self, x: f32) -> f32 {
+        match self {
+            Stage::HighPass(filter) => filter.process(x),
+            Stage::DeEsser { band, envelope, threshold_db, max_reduction_db } => {
+                // Split-band: only the sibilant band is attenuated
+                let high = band.process(x);
+                let level_db = gain_to_db(envelope.process(high.abs()));
+                let reduction_db = (level_db - *threshold_db).clamp(0.0, *max_reduction_db);
+                x - high * (1.0 - db_to_gain(-reduction_db))
+            }
+            Stage::Compressor { envelope, threshold_db, ratio, makeup } => {
+                let level_db = gain_to_db(envelope.process(x.abs()));
+                let over = level_db - *threshold_db;
+                let gain_db = if over > 0.0 { over / *ratio - over } else { 0.0 };
+                x * db_to_gain(gain_db) * *makeup
+            }
+        }
+    }
+}
+
+fn db_to_gain(db: f32) -> f32 {
+    10f32.powf(db / 20.0)
+}
+
+fn gain_to_db(gain: f32) -> f32 {
+    20.0 * gain.max(1e-6).log10()
+}
+
+/// RBJ cookbook biquad, Butterworth Q.
+struct Biquad {
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+    x1: f32,
+    x2: f32,
+    y1: f32,
+    y2: f32,
+}
+
+impl Biquad {
+    fn high_pass(cutoff_hz: f32, sample_rate: f32) -> Self {
+        // Keep the cutoff below Nyquist so the coefficients stay stable
+        let cutoff = cutoff_hz.clamp(10.0, sample_rate * 0.45);
+        let w0 = 2.0 * std::f32::consts::PI * cutoff / sample_rate;
+        let alpha = w0.sin() / (2.0 * std::f32::consts::FRAC_1_SQRT_2);
+        let cos = w0.cos();
+        let a0 = 1.0 + alpha;
+        Self {
+            b0: (1.0 + cos) / 2.0 / a0,
+            b1: -(1.0 + cos) / a0,
+            b2: (1.0 + cos) / 2.0 / a0,
+            a1: -2.0 * cos / a0,
+            a2: (1.0 - alpha) / a0,
+            x1: 0.0,
+            x2: 0.0,
+            y1: 0.0,
+            y2: 0.0,
+        }
+    }
+
+    fn process(&mut self, x: f32) -> f32 {
+        let y = self.b0 * x + self.b1 * self.x1 + self.b2 * self.x2 - self.a1 * self.y1 - self.a2 * self.y2;
+        self.x2 = self.x1;
+        self.x1 = x;
+        self.y2 = self.y1;
+        self.y1 = y;
+        y
+    }
+}
+
+/// Peak envelope follower with separate attack and release times.
+struct Envelope {
+    attack: f32,
+    release: f32,
+    value: f32,
+}
+
+impl Envelope {
+    fn new(attack_ms: f32, release_ms: f32, sample_rate: f32) -> Self {
+        let coeff = |ms: f32| (-1.0 / (ms.max(0.1) / 1000.0 * sample_rate)).exp();
+        Self {
+            attack: coeff(attack_ms),
+            release: coeff(release_ms),
+            value: 0.0,
+        }
+    }
+
+    fn process(&mut self, level: f32) -> f32 {
+        let coeff = if level > self.value { self.attack } else { self.release };
+        self.value = coeff * self.value + (1.0 - coeff) * level;
+        self.value
+    }
+}
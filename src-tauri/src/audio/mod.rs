@@ -1,81 +1,145 @@
-use std::sync::atomic::{AtomicBool, Ordering};
+pub mod decode;
+pub mod dsp;
+pub mod mulaw;
+pub mod resample;
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use tauri::{Emitter, Manager};
 use cpal::{SampleFormat, Stream, StreamConfig};
 use tokio::sync::mpsc;
 
+use crate::error::TranscriptionError;
+
 // Target format for Soniox
 const TARGET_SAMPLE_RATE: u32 = 16000;
 const TARGET_CHANNELS: u16 = 1;
 
+// Env var pointing at a WAV file to stream instead of the mic.
+// Takes precedence over the `audio_fixture_path` setting.
+const FIXTURE_ENV_VAR: &str = "DICTATE_AUDIO_FIXTURE";
+// Fixture audio is sent in 20ms chunks, similar to a live input callback
+const FIXTURE_CHUNK_MS: u64 = 20;
+// Digital silence this long means the source is muted, not a quiet room
+const MUTED_SILENCE_SECS: u64 = 3;
+// Speech state holds this long after the level drops, so pauses between
+// words do not make the indicator flicker
+const SPEECH_HANGOVER_MS: u64 = 300;
+// Auto-stop counts down on the overlay for the last seconds of silence
+const AUTO_STOP_COUNTDOWN_SECS: u64 = 5;
+
+/// A block of PCM s16le audio together with the time it was captured.
+pub struct AudioChunk {
+    pub data: Vec<u8>,
+    pub captured_at: Instant,
+}
+
+impl AudioChunk {
+    fn new(data: Vec<u8>) -> Self {
+        Self {
+            data,
+            captured_at: Instant::now(),
+        }
+    }
+}
+
 pub async fn start_audio_capture(
-    api_key: String,
-    language_hints: Vec<String>,
-    language_restrictions: Option<Vec<String>>,
-    stop_signal: Arc<AtomicBool>,
+    settings: crate::AppSettings,
+    stop_signal: tokio_util::sync::CancellationToken,
     app: tauri::AppHandle,
     target_window_id: String,
-) -> Result<(), String> {
+) -> Result<(), TranscriptionError> {
     log::info!("Initializing audio capture...");
     
-    let (tx, mut rx) = mpsc::channel::<Vec<u8>>(100);
+    let (tx, rx) = mpsc::channel::<AudioChunk>(100);
 
-    // Get default input device
-    let host = cpal::default_host();
-    let device = match host.default_input_device() {
+    let fixture = std::env::var(FIXTURE_ENV_VAR)
+        .ok()
+        .or_else(|| settings.audio_fixture_path.clone())
+        .filter(|p| !p.is_empty());
+    if let Some(path) = fixture {
+        log::info!("Streaming audio fixture '{}' instead of the microphone", path);
+        let fixture_thread = spawn_fixture_source(path, tx, stop_signal.clone());
+        return finish_capture(settings, stop_signal, rx, app, target_window_id, fixture_thread).await;
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        if default_source_muted() {
+            log::warn!("Default input source is muted");
+            warn_muted(&app);
+        }
+        if let Some(warning) = bluetooth_profile_warning() {
+            log::warn!("{}", warning);
+            crate::console::record(&app, "error", warning.clone());
+            app.emit("bluetooth-input-warning", warning).ok();
+        }
+    }
+
+    let (audio_thread, capture_path) = spawn_mic_capture(&app, tx, stop_signal.clone())?;
+    crate::metrics::inc_capture_path(capture_path);
+    finish_capture(settings, stop_signal, rx, app, target_window_id, audio_thread).await
+}
+
+/// Open the selected (or default) input device and stream its audio as
+/// 16 kHz mono pcm16 chunks into `tx` on a capture thread, until
+/// `stop_signal` fires. Also returns the label of the capture path taken.
+pub fn spawn_mic_capture(
+    app: &tauri::AppHandle,
+    tx: mpsc::Sender<AudioChunk>,
+    stop_signal: tokio_util::sync::CancellationToken,
+) -> Result<(std::thread::JoinHandle<Result<(), String>>, &'static str), TranscriptionError> {
+    let selected = app.state::<crate::AppState>().settings.lock().unwrap().selected_device.clone();
+    let device = match input_device(app, selected.as_deref()) {
         Some(d) => d,
         None => {
             let err = "No input device available".to_string();
             log::error!("{}", err);
-            return Err(err);
+            return Err(TranscriptionError::AudioDevice(err));
         }
     };
     
     log::info!("Using audio device: {:?}", device.name());
 
-    // Try to get the default config first to see what the device supports
-    let default_config = match device.default_input_config() {
-        Ok(c) => c,
-        Err(e) => {
-            let err = format!("No default input config: {}", e);
-            log::error!("{}", err);
-            return Err(err);
-        }
-    };
-    
-    // Build a config with our target sample rate
-    let mut config: StreamConfig = default_config.config();
-    config.sample_rate.0 = TARGET_SAMPLE_RATE;
-    config.channels = TARGET_CHANNELS;
-    
-    log::info!("Audio config: sample_rate={:?}, channels={:?}", config.sample_rate, config.channels);
+    let (config, sample_format, path) = choose_capture(&device).map_err(TranscriptionError::AudioDevice)?;
+    log::info!(
+        "Audio config: sample_rate={:?}, channels={:?}, format={:?}, path={:?}",
+        config.sample_rate,
+        config.channels,
+        sample_format,
+        path
+    );
+    if let CapturePath::Resample { from_rate, channels } = path {
+        let message = format!(
+            "Input device has no 16 kHz mono mode; downmixing {} channels and resampling {} Hz in software",
+            channels, from_rate
+        );
+        log::warn!("{}", message);
+        crate::console::record(app, "audio", message);
+    }
 
     // Spawn audio capture in a separate thread
-    let stop_flag_for_thread = stop_signal.clone();
+    let stop_flag_for_thread = stop_signal;
     let audio_thread = std::thread::spawn(move || {
         let err_fn = |err| log::error!("Audio stream error: {}", err);
 
-        let stream_result: Result<Stream, cpal::BuildStreamError> = match default_config.sample_format() {
+        let stream_result: Result<Stream, cpal::BuildStreamError> = match sample_format {
             SampleFormat::F32 => {
                 let tx_clone = tx.clone();
                 let stop = stop_flag_for_thread.clone();
+                let mut converter = Converter::new(path);
                 let data_callback = move |data: &[f32], _: &cpal::InputCallbackInfo| {
-                    if stop.load(Ordering::SeqCst) {
+                    if stop.is_cancelled() {
                         return;
                     }
                     
-                    // Convert f32 to i16
-                    let pcm_data: Vec<u8> = data
-                        .iter()
-                        .flat_map(|&sample| {
-                            let sample_i16 = (sample * 32767.0_f32) as i16;
-                            sample_i16.to_le_bytes()
-                        })
-                        .collect();
+                    let pcm_data = converter.convert(data, |sample| sample);
                     
                     if !pcm_data.is_empty() {
-                        tx_clone.blocking_send(pcm_data).ok();
+                        tx_clone.blocking_send(AudioChunk::new(pcm_data)).ok();
                     }
                 };
                 device.build_input_stream(&config, data_callback, err_fn, None)
@@ -83,18 +147,20 @@ pub async fn start_audio_capture(
             SampleFormat::I16 => {
                 let tx_clone = tx.clone();
                 let stop = stop_flag_for_thread.clone();
+                let mut converter = Converter::new(path);
                 let data_callback = move |data: &[i16], _: &cpal::InputCallbackInfo| {
-                    if stop.load(Ordering::SeqCst) {
+                    if stop.is_cancelled() {
                         return;
                     }
                     
-                    let pcm_data: Vec<u8> = data
-                        .iter()
-                        .flat_map(|&sample| sample.to_le_bytes())
-                        .collect();
+                    let pcm_data = if path == CapturePath::Passthrough {
+                        pcm_bytes(data)
+                    } else {
+                        converter.convert(data, |sample| sample as f32 / 32768.0)
+                    };
                     
                     if !pcm_data.is_empty() {
-                        tx_clone.blocking_send(pcm_data).ok();
+                        tx_clone.blocking_send(AudioChunk::new(pcm_data)).ok();
                     }
                 };
                 device.build_input_stream(&config, data_callback, err_fn, None)
@@ -102,21 +168,16 @@ pub async fn start_audio_capture(
             SampleFormat::U16 => {
                 let tx_clone = tx.clone();
                 let stop = stop_flag_for_thread.clone();
+                let mut converter = Converter::new(path);
                 let data_callback = move |data: &[u16], _: &cpal::InputCallbackInfo| {
-                    if stop.load(Ordering::SeqCst) {
+                    if stop.is_cancelled() {
                         return;
                     }
                     
-                    let pcm_data: Vec<u8> = data
-                        .iter()
-                        .flat_map(|&sample| {
-                            let sample_i16 = (sample as i32 - 32768) as i16;
-                            sample_i16.to_le_bytes()
-                        })
-                        .collect();
+                    let pcm_data = converter.convert(data, |sample| (sample as f32 - 32768.0) / 32768.0);
                     
                     if !pcm_data.is_empty() {
-                        tx_clone.blocking_send(pcm_data).ok();
+                        tx_clone.blocking_send(AudioChunk::new(pcm_data)).ok();
                     }
                 };
                 device.build_input_stream(&config, data_callback, err_fn, None)
@@ -142,26 +203,742 @@ pub async fn start_audio_capture(
         
         log::info!("Audio capture started successfully");
         
-        // Keep thread alive until stop signal
-        while !stop_flag_for_thread.load(Ordering::SeqCst) {
-            std::thread::sleep(std::time::Duration::from_millis(100));
+        // Keep thread alive until stop signal; wakes at once on stop and
+        // every 100 ms to check for an injected device loss
+        while !crate::stop::wait(&stop_flag_for_thread, Duration::from_millis(100)) {
+            if crate::faults::fire(crate::faults::Fault::DeviceLoss) {
+                // Dropping the stream closes the audio channel like a vanished device
+                drop(stream);
+                log::error!("Audio stream error: input device lost (injected)");
+                return Err("Input device lost (injected)".to_string());
+            }
         }
         
         drop(stream);
         log::info!("Audio capture stopped");
         Ok(())
     });
-    
+
+    Ok((audio_thread, path.label()))
+}
+
+/// How microphone audio becomes 16 kHz mono pcm16.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CapturePath {
+    /// The device delivers 16 kHz mono i16, which is sent as is
+    Passthrough,
+    /// 16 kHz mono in another sample format; only the format is converted
+    Convert,
+    /// The device's own rate and channels, downmixed and resampled here
+    Resample { from_rate: u32, channels: u16 },
+}
+
+impl CapturePath {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Passthrough => "passthrough",
+            Self::Convert => "convert",
+            Self::Resample { .. } => "resample",
+        }
+    }
+}
+
+/// Pick the cheapest way to get 16 kHz mono out of `device`: a native
+/// 16 kHz mono mode, preferably i16, or else its default config.
+fn choose_capture(device: &cpal::Device) -> Result<(StreamConfig, SampleFormat, CapturePath), String> {
+    let default_config = device.default_input_config().map_err(|e| {
+        let err = format!("No default input config: {}", e);
+        log::error!("{}", err);
+        err
+    })?;
+    let supports_target = |format: SampleFormat| {
+        device.supported_input_configs().is_ok_and(|mut configs| {
+            configs.any(|c| {
+                c.channels() == TARGET_CHANNELS
+                    && c.sample_format() == format
+                    && (c.min_sample_rate().0..=c.max_sample_rate().0).contains(&TARGET_SAMPLE_RATE)
+            })
+        })
+    };
+    let target_config = StreamConfig {
+        channels: TARGET_CHANNELS,
+        sample_rate: cpal::SampleRate(TARGET_SAMPLE_RATE),
+        buffer_size: cpal::BufferSize::Default,
+    };
+
+    if supports_target(SampleFormat::I16) {
+        return Ok((target_config, SampleFormat::I16, CapturePath::Passthrough));
+    }
+    if let Some(format) = [SampleFormat::F32, SampleFormat::U16].into_iter().find(|&f| supports_target(f)) {
+        return Ok((target_config, format, CapturePath::Convert));
+    }
+    let config = default_config.config();
+    let path = CapturePath::Resample { from_rate: config.sample_rate.0, channels: config.channels.max(1) };
+    Ok((config, default_config.sample_format(), path))
+}
+
+/// i16 samples as s16le bytes, in one copy on little-endian machines.
+fn pcm_bytes(data: &[i16]) -> Vec<u8> {
+    #[cfg(target_endian = "little")]
+    {
+        // SAFETY: i16 has no padding and u8 has no alignment requirement
+        unsafe { std::slice::from_raw_parts(data.as_ptr() as *const u8, std::mem::size_of_val(data)) }.to_vec()
+    }
+    #[cfg(target_endian = "big")]
+    {
+        data.iter().flat_map(|s| s.to_le_bytes()).collect()
+    }
+}
+
+/// Turns callback buffers into 16 kHz mono s16le, downmixing and
+/// resampling when the stream runs in the device's own config. One per
+/// stream, since the resampler carries state from buffer to buffer.
+struct Converter {
+    path: CapturePath,
+    resampler: Option<resample::Resampler>,
+}
+
+impl Converter {
+    fn new(path: CapturePath) -> Self {
+        let resampler = match path {
+            CapturePath::Resample { from_rate, .. } => Some(resample::Resampler::new(from_rate, TARGET_SAMPLE_RATE)),
+            CapturePath::Passthrough | CapturePath::Convert => None,
+        };
+        Self { path, resampler }
+    }
+
+    fn convert<T: Copy>(&mut self, data: &[T], to_f32: impl Fn(T) -> f32) -> Vec<u8> {
+        let samples: Vec<f32> = match (self.path, self.resampler.as_mut()) {
+            (CapturePath::Resample { channels, .. }, Some(resampler)) => {
+                let mono: Vec<f32> = data
+                    .chunks(channels as usize)
+                    .map(|frame| frame.iter().map(|&s| to_f32(s)).sum::<f32>() / frame.len() as f32)
+                    .collect();
+                resampler.process(&mono)
+            }
+            _ => data.iter().map(|&s| to_f32(s)).collect(),
+        };
+        samples
+            .iter()
+            .flat_map(|&s| ((s.clamp(-1.0, 1.0) * 32767.0_f32) as i16).to_le_bytes())
+            .collect()
+    }
+}
+
+/// Whether a default input device is present (it can vanish after sleep).
+pub fn input_device_available() -> bool {
+    cpal::default_host().default_input_device().is_some()
+}
+
+/// An input device as offered in settings.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct AudioDevice {
+    pub name: String,
+    /// The system's default input
+    pub is_default: bool,
+}
+
+/// Names of the available input devices, the system default first.
+pub fn list_input_devices() -> Vec<AudioDevice> {
+    let host = cpal::default_host();
+    let default_name = host.default_input_device().and_then(|d| d.name().ok());
+    let mut devices: Vec<AudioDevice> = match host.input_devices() {
+        Ok(devices) => devices
+            .filter_map(|d| d.name().ok())
+            .map(|name| AudioDevice { is_default: default_name.as_ref() == Some(&name), name })
+            .collect(),
+        Err(e) => {
+            log::warn!("Cannot list input devices: {}", e);
+            Vec::new()
+        }
+    };
+    devices.sort_by_key(|d| !d.is_default);
+    devices
+}
+
+/// The input device called `name`, falling back to the system default
+/// when none is selected or it is not connected.
+fn input_device(app: &tauri::AppHandle, name: Option<&str>) -> Option<cpal::Device> {
+    let host = cpal::default_host();
+    if let Some(name) = name.filter(|n| !n.is_empty()) {
+        let found = host
+            .input_devices()
+            .ok()
+            .and_then(|mut devices| devices.find(|d| d.name().is_ok_and(|n| n == name)));
+        if found.is_some() {
+            return found;
+        }
+        let message = format!("Input device '{}' not found, using the default", name);
+        log::warn!("{}", message);
+        crate::console::record(app, "audio", message);
+    }
+    host.default_input_device()
+}
+
+/// Run transcription on the captured audio and shut the source down afterwards.
+async fn finish_capture(
+    settings: crate::AppSettings,
+    stop_signal: tokio_util::sync::CancellationToken,
+    mut rx: mpsc::Receiver<AudioChunk>,
+    app: tauri::AppHandle,
+    target_window_id: String,
+    audio_thread: std::thread::JoinHandle<Result<(), String>>,
+) -> Result<(), TranscriptionError> {
+    rx = watch_for_silence(rx, app.clone());
+
+    let chain = dsp::Chain::new(&settings.audio_effects, TARGET_SAMPLE_RATE);
+    if !chain.is_empty() {
+        rx = apply_effects(rx, chain);
+    }
+    // After the effects, so filtered rumble does not count as speech
+    rx = watch_for_speech(rx, app.clone(), settings.auto_stop_silence_secs, settings.speech_threshold);
+    rx = crate::voicecommands::watch_for_stop(rx, app.clone());
+    if settings.audio_chunk_ms > 0 {
+        rx = rechunk(rx, settings.audio_chunk_ms);
+    }
+    let (counted_rx, streamed_bytes) = count_audio(rx);
+    rx = counted_rx;
+
+    // Optionally keep a WAV copy of everything sent to the provider
+    let mut recording = None;
+    let incognito = app.state::<crate::AppState>().incognito.load(Ordering::SeqCst);
+    if settings.save_session_audio && !incognito {
+        if let Some(path) = session_audio_path(&app) {
+            match tee_to_wav(rx, &path) {
+                Ok((tee_rx, writer)) => {
+                    rx = tee_rx;
+                    recording = Some((path, writer));
+                }
+                Err((original_rx, e)) => {
+                    log::error!("Session audio recording disabled: {}", e);
+                    rx = original_rx;
+                }
+            }
+        }
+    }
+
+    let provider = settings.provider;
+    let compared = settings.comparison.enabled && provider == crate::TranscriptionProvider::Soniox;
+
     // Run transcription
-    let result = crate::soniox::connect_and_transcribe(api_key, language_hints, language_restrictions, stop_signal.clone(), &mut rx, app, target_window_id).await;
+    // Only Soniox classifies its errors; the other providers report plain messages
+    let mut result = if crate::faults::fire(crate::faults::Fault::ProviderError) {
+        Err(TranscriptionError::Provider("Injected provider error".to_string()))
+    } else {
+        match settings.provider {
+            crate::TranscriptionProvider::Soniox => {
+                crate::soniox::connect_and_transcribe(settings, stop_signal.clone(), &mut rx, app.clone(), target_window_id).await
+            }
+            crate::TranscriptionProvider::OpenAi => {
+                crate::openai::connect_and_transcribe(settings, stop_signal.clone(), &mut rx, app.clone(), target_window_id)
+                    .await
+                    .map_err(TranscriptionError::Provider)
+            }
+            crate::TranscriptionProvider::Azure => {
+                crate::azure::connect_and_transcribe(settings, stop_signal.clone(), &mut rx, app.clone(), target_window_id)
+                    .await
+                    .map_err(TranscriptionError::Provider)
+            }
+            crate::TranscriptionProvider::Whisper => {
+                crate::whisper::transcribe(settings, stop_signal.clone(), &mut rx, app.clone(), target_window_id)
+                    .await
+                    .map_err(TranscriptionError::Provider)
+            }
+            crate::TranscriptionProvider::Vosk => {
+                crate::vosk::transcribe(settings, stop_signal.clone(), &mut rx, app.clone(), target_window_id)
+                    .await
+                    .map_err(TranscriptionError::Provider)
+            }
+        }
+    };
     
     // Signal audio capture to stop (in case it hasn't already)
-    stop_signal.store(true, Ordering::SeqCst);
+    stop_signal.cancel();
+
+    let seconds = streamed_bytes.load(Ordering::Relaxed) as f64 / (TARGET_SAMPLE_RATE as f64 * 2.0);
+    crate::usage::record(&app, provider, if compared { seconds * 2.0 } else { seconds });
     
     // Wait for audio thread to finish
-    if let Err(e) = audio_thread.join() {
-        log::error!("Audio thread panicked: {:?}", e);
+    match audio_thread.join() {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => {
+            log::error!("Audio capture ended with an error: {}", e);
+            crate::console::record(&app, "error", format!("Audio capture: {}", e));
+            // A lost or unopenable microphone explains an otherwise empty session
+            if result.is_ok() {
+                result = Err(TranscriptionError::AudioDevice(e));
+            }
+        }
+        Err(e) => log::error!("Audio thread panicked: {:?}", e),
+    }
+    crate::usage::record_session(&app, provider, seconds, result.is_err());
+
+    if let Some((path, writer)) = recording {
+        // Dropping rx above closes the tee, which finalizes the WAV header
+        drop(rx);
+        match writer.await {
+            Ok(Ok(())) => {
+                let state: tauri::State<'_, crate::AppState> = app.state();
+                *state.last_session_audio.lock().unwrap() = Some(path.to_string_lossy().into_owned());
+            }
+            Ok(Err(e)) => log::error!("Failed to write session audio: {}", e),
+            Err(e) => log::error!("Session audio writer failed: {}", e),
+        }
     }
+    // After the recording is finished, so the entry can link to it
+    crate::save_session_history(&app);
     
     result
 }
+
+/// `<app data>/recordings/<session id>.wav` for the current session.
+fn session_audio_path(app: &tauri::AppHandle) -> Option<std::path::PathBuf> {
+    let state: tauri::State<'_, crate::AppState> = app.state();
+    let session_id = state.session_id.lock().unwrap().clone()?;
+    let dir = crate::datadir::dir(app).ok()?.join("recordings");
+    std::fs::create_dir_all(&dir).ok()?;
+    Some(dir.join(format!("{}.wav", session_id)))
+}
+
+fn warn_muted(app: &tauri::AppHandle) {
+    crate::console::record(app, "error", "Microphone appears muted");
+    app.emit("microphone-muted", crate::i18n::t("microphone-muted")).ok();
+}
+
+/// Whether PulseAudio/PipeWire reports the default source as muted.
+/// Unknown (no `pactl`) counts as not muted.
+#[cfg(target_os = "linux")]
+fn default_source_muted() -> bool {
+    pactl(&["get-source-mute", "@DEFAULT_SOURCE@"]).is_some_and(|out| out.trim() == "Mute: yes")
+}
+
+/// Run `pactl` and return its stdout, or None if it is missing or fails.
+#[cfg(target_os = "linux")]
+fn pactl(args: &[&str]) -> Option<String> {
+    let out = std::process::Command::new("pactl").args(args).output().ok()?;
+    out.status
+        .success()
+        .then(|| String::from_utf8_lossy(&out.stdout).into_owned())
+}
+
+/// Bluetooth headsets only expose a microphone in the HFP/HSP profile,
+/// which captures narrowband (8 kHz, or 16 kHz with mSBC) audio and drops
+/// playback to telephony quality. Returns a warning when the default source
+/// is such a device, naming wired/built-in alternatives if there are any.
+#[cfg(target_os = "linux")]
+fn bluetooth_profile_warning() -> Option<String> {
+    let source = pactl(&["get-default-source"])?.trim().to_string();
+    if !source.starts_with("bluez_") {
+        return None;
+    }
+
+    // "Sample Specification: s16le 1ch 8000Hz" of the default source
+    let sources = pactl(&["list", "sources"])?;
+    let rate = sources
+        .split("Source #")
+        .find(|block| block.lines().any(|l| l.trim() == format!("Name: {}", source)))
+        .and_then(|block| {
+            block
+                .lines()
+                .find_map(|l| l.trim().strip_prefix("Sample Specification:"))
+                .and_then(|spec| spec.split_whitespace().find_map(|p| p.strip_suffix("Hz")))
+                .and_then(|hz| hz.parse::<u32>().ok())
+        });
+    // Wideband Bluetooth capture (e.g. LE Audio) is fine for recognition
+    if rate.is_some_and(|r| r > TARGET_SAMPLE_RATE) {
+        return None;
+    }
+
+    let alternatives: Vec<String> = pactl(&["list", "short", "sources"])
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|l| l.split('\t').nth(1))
+        .filter(|name| !name.starts_with("bluez_") && !name.ends_with(".monitor"))
+        .map(str::to_string)
+        .collect();
+
+    let mut warning = crate::i18n::t_args(
+        "bluetooth-headset-mode",
+        &[("rate", rate.map_or_else(|| "none".to_string(), |r| r.to_string()))],
+    );
+    if !alternatives.is_empty() {
+        warning.push(' ');
+        warning.push_str(&crate::i18n::t_args("bluetooth-alternatives", &[("devices", alternatives.join(", "))]));
+    }
+    Some(warning)
+}
+
+/// Warn once per session if the input stays at exactly zero, which real
+/// microphones never produce even in a silent room.
+fn watch_for_silence(mut rx: mpsc::Receiver<AudioChunk>, app: tauri::AppHandle) -> mpsc::Receiver<AudioChunk> {
+    let (tx, watched_rx) = mpsc::channel::<AudioChunk>(100);
+    tokio::spawn(async move {
+        let threshold = (TARGET_SAMPLE_RATE as u64 * MUTED_SILENCE_SECS) as usize;
+        let mut silent_samples = 0usize;
+        let mut warned = false;
+        while let Some(chunk) = rx.recv().await {
+            if !warned {
+                if chunk.data.iter().all(|&b| b == 0) {
+                    silent_samples += chunk.data.len() / 2;
+                } else {
+                    silent_samples = 0;
+                }
+                if silent_samples >= threshold {
+                    log::warn!("Input has been digital silence for {}s", MUTED_SILENCE_SECS);
+                    warn_muted(&app);
+                    warned = true;
+                }
+            }
+            if tx.send(chunk).await.is_err() {
+                break;
+            }
+        }
+    });
+    watched_rx
+}
+
+/// Emit `speech-detected` (true/false) whenever the input starts or stops
+/// looking like speech, so the overlay can show whether anything is heard.
+/// A plain energy gate: instant on, off after `SPEECH_HANGOVER_MS` below it.
+///
+/// With `auto_stop_secs` set, the session is stopped after that much
+/// silence; `auto-stop-countdown` carries the seconds left over the last
+/// `AUTO_STOP_COUNTDOWN_SECS`, and null when speech resumes.
+fn watch_for_speech(
+    mut rx: mpsc::Receiver<AudioChunk>,
+    app: tauri::AppHandle,
+    auto_stop_secs: u64,
+    threshold: u32,
+) -> mpsc::Receiver<AudioChunk> {
+    let (tx, watched_rx) = mpsc::channel::<AudioChunk>(100);
+    tokio::spawn(async move {
+        let hangover = (TARGET_SAMPLE_RATE as u64 * SPEECH_HANGOVER_MS / 1000) as usize;
+        let mut quiet_samples = 0usize;
+        let mut speaking = false;
+        let mut countdown: Option<u64> = None;
+        let mut stopped = false;
+        let set = |speaking: bool| {
+            app.emit("speech-detected", speaking).ok();
+            crate::native_overlay::set_speaking(&app, speaking);
+        };
+        while let Some(chunk) = rx.recv().await {
+            let samples = chunk.data.len() / 2;
+            let energy: f64 = chunk
+                .data
+                .chunks_exact(2)
+                .map(|b| (i16::from_le_bytes([b[0], b[1]]) as f64).powi(2))
+                .sum();
+            let loud = samples > 0 && (energy / samples as f64).sqrt() > threshold as f64;
+            quiet_samples = if loud { 0 } else { quiet_samples + samples };
+            let now_speaking = loud || (speaking && quiet_samples < hangover);
+            if now_speaking != speaking {
+                speaking = now_speaking;
+                set(speaking);
+            }
+
+            if auto_stop_secs > 0 && !stopped {
+                let quiet_secs = quiet_samples as u64 / TARGET_SAMPLE_RATE as u64;
+                let left = auto_stop_secs.saturating_sub(quiet_secs);
+                let now_counting = (left <= AUTO_STOP_COUNTDOWN_SECS && !loud).then_some(left);
+                if now_counting != countdown {
+                    countdown = now_counting;
+                    app.emit("auto-stop-countdown", countdown).ok();
+                }
+                if left == 0 {
+                    stopped = true;
+                    log::info!("No speech for {}s, stopping", auto_stop_secs);
+                    if crate::stop_active_session(&app) {
+                        crate::console::record(&app, "session", format!("Recording stopped after {}s of silence", auto_stop_secs));
+                    }
+                }
+            }
+
+            if tx.send(chunk).await.is_err() {
+                break;
+            }
+        }
+        if speaking {
+            set(false);
+        }
+    });
+    watched_rx
+}
+
+/// Count the audio bytes passed on to the provider, for usage tracking.
+fn count_audio(mut rx: mpsc::Receiver<AudioChunk>) -> (mpsc::Receiver<AudioChunk>, Arc<AtomicU64>) {
+    let (tx, counted_rx) = mpsc::channel::<AudioChunk>(100);
+    let bytes = Arc::new(AtomicU64::new(0));
+    let counter = bytes.clone();
+    tokio::spawn(async move {
+        while let Some(chunk) = rx.recv().await {
+            let len = chunk.data.len() as u64;
+            if tx.send(chunk).await.is_err() {
+                break;
+            }
+            counter.fetch_add(len, Ordering::Relaxed);
+        }
+    });
+    (counted_rx, bytes)
+}
+
+/// Join chunks into blocks of at least `ms` of audio. A block keeps the
+/// capture time of its first chunk, so latency includes the wait.
+fn rechunk(mut rx: mpsc::Receiver<AudioChunk>, ms: u64) -> mpsc::Receiver<AudioChunk> {
+    let (tx, blocks_rx) = mpsc::channel::<AudioChunk>(100);
+    let block_bytes = (TARGET_SAMPLE_RATE as u64 * ms / 1000) as usize * 2;
+    tokio::spawn(async move {
+        let mut block: Option<AudioChunk> = None;
+        while let Some(chunk) = rx.recv().await {
+            match block.as_mut() {
+                Some(pending) => pending.data.extend_from_slice(&chunk.data),
+                None => block = Some(chunk),
+            }
+            let full = block.as_ref().is_some_and(|pending| pending.data.len() >= block_bytes);
+            if full && tx.send(block.take().unwrap()).await.is_err() {
+                return;
+            }
+        }
+        // The rest when the capture ends
+        if let Some(rest) = block {
+            tx.send(rest).await.ok();
+        }
+    });
+    blocks_rx
+}
+
+/// Run every chunk through the preprocessing chain on its way to the provider.
+fn apply_effects(mut rx: mpsc::Receiver<AudioChunk>, mut chain: dsp::Chain) -> mpsc::Receiver<AudioChunk> {
+    let (tx, processed_rx) = mpsc::channel::<AudioChunk>(100);
+    tokio::spawn(async move {
+        while let Some(mut chunk) = rx.recv().await {
+            chain.process(&mut chunk.data);
+            if tx.send(chunk).await.is_err() {
+                break;
+            }
+        }
+    });
+    processed_rx
+}
+
+type WavWriterTask = tokio::task::JoinHandle<Result<(), String>>;
+
+/// Forward audio chunks to a new channel while writing them to `path`.
+/// On failure the original receiver is handed back untouched.
+fn tee_to_wav(
+    mut rx: mpsc::Receiver<AudioChunk>,
+    path: &std::path::Path,
+) -> Result<(mpsc::Receiver<AudioChunk>, WavWriterTask), (mpsc::Receiver<AudioChunk>, String)> {
+    let spec = hound::WavSpec {
+        channels: TARGET_CHANNELS,
+        sample_rate: TARGET_SAMPLE_RATE,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer = match hound::WavWriter::create(path, spec) {
+        Ok(w) => w,
+        Err(e) => return Err((rx, format!("Cannot create {:?}: {}", path, e))),
+    };
+
+    let (tx, tee_rx) = mpsc::channel::<AudioChunk>(100);
+    let task = tokio::spawn(async move {
+        while let Some(chunk) = rx.recv().await {
+            for pair in chunk.data.chunks_exact(2) {
+                writer
+                    .write_sample(i16::from_le_bytes([pair[0], pair[1]]))
+                    .map_err(|e| e.to_string())?;
+            }
+            if tx.send(chunk).await.is_err() {
+                break;
+            }
+        }
+        writer.finalize().map_err(|e| e.to_string())
+    });
+
+    Ok((tee_rx, task))
+}
+
+/// Play a 16-bit WAV file from `start_ms` on the default output device until
+/// it ends or `stop` is set. Returns once playback has started.
+pub fn play_wav(path: &str, start_ms: u64, stop: Arc<AtomicBool>) -> Result<(), String> {
+    let mut reader = hound::WavReader::open(path)
+        .map_err(|e| format!("Failed to open {}: {}", path, e))?;
+    let spec = reader.spec();
+    let mono: Vec<f32> = reader
+        .samples::<i16>()
+        .map(|s| s.map(|v| v as f32 / 32768.0))
+        .collect::<Result<_, _>>()
+        .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let skip = (start_ms * spec.sample_rate as u64 / 1000) as usize;
+    let mono = &mono[skip.min(mono.len())..];
+
+    let host = cpal::default_host();
+    let device = host
+        .default_output_device()
+        .ok_or_else(|| "No output device available".to_string())?;
+    let default_config = device
+        .default_output_config()
+        .map_err(|e| format!("No default output config: {}", e))?;
+    let config: StreamConfig = default_config.config();
+    let out_channels = config.channels as usize;
+
+    // Resample to the device rate, then duplicate across output channels
+    let samples: Vec<f32> = resample_linear(mono, spec.sample_rate, config.sample_rate.0)
+        .into_iter()
+        .flat_map(|s| std::iter::repeat(s).take(out_channels))
+        .collect();
+    let total = samples.len();
+    let samples = Arc::new(samples);
+    let position = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+
+    let (started_tx, started_rx) = std::sync::mpsc::channel::<Result<(), String>>();
+    std::thread::spawn(move || {
+        let err_fn = |err| log::error!("Playback stream error: {}", err);
+
+        macro_rules! output_stream {
+            ($t:ty, $convert:expr) => {{
+                let samples = samples.clone();
+                let position = position.clone();
+                device.build_output_stream(
+                    &config,
+                    move |data: &mut [$t], _: &cpal::OutputCallbackInfo| {
+                        let start = position.fetch_add(data.len(), Ordering::SeqCst);
+                        for (i, out) in data.iter_mut().enumerate() {
+                            let s = samples.get(start + i).copied().unwrap_or(0.0);
+                            *out = $convert(s);
+                        }
+                    },
+                    err_fn,
+                    None,
+                )
+            }};
+        }
+
+        let stream_result = match default_config.sample_format() {
+            SampleFormat::F32 => output_stream!(f32, |s: f32| s),
+            SampleFormat::I16 => output_stream!(i16, |s: f32| (s * 32767.0) as i16),
+            SampleFormat::U16 => output_stream!(u16, |s: f32| ((s * 32767.0) as i32 + 32768) as u16),
+            _ => {
+                let _ = started_tx.send(Err("Unsupported output sample format".to_string()));
+                return;
+            }
+        };
+
+        let stream = match stream_result.map_err(|e| e.to_string()).and_then(|s| {
+            s.play().map_err(|e| e.to_string())?;
+            Ok(s)
+        }) {
+            Ok(s) => s,
+            Err(e) => {
+                let _ = started_tx.send(Err(format!("Failed to start playback: {}", e)));
+                return;
+            }
+        };
+        let _ = started_tx.send(Ok(()));
+
+        while !stop.load(Ordering::SeqCst) && position.load(Ordering::SeqCst) < total {
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        drop(stream);
+        log::info!("Playback finished");
+    });
+
+    started_rx
+        .recv()
+        .map_err(|_| "Playback thread exited unexpectedly".to_string())?
+}
+
+/// Stream a WAV file at real-time pace as if it came from the microphone.
+///
+/// Used for reproducible debugging and latency measurement with known
+/// utterances. Any PCM/float WAV is accepted; it is downmixed to mono and
+/// resampled to the Soniox target rate before streaming.
+fn spawn_fixture_source(
+    path: String,
+    tx: mpsc::Sender<AudioChunk>,
+    stop_signal: tokio_util::sync::CancellationToken,
+) -> std::thread::JoinHandle<Result<(), String>> {
+    std::thread::spawn(move || {
+        let samples = load_fixture(&path).map_err(|e| {
+            log::error!("{}", e);
+            e
+        })?;
+
+        let samples_per_chunk = (TARGET_SAMPLE_RATE as u64 * FIXTURE_CHUNK_MS / 1000) as usize;
+        let started = Instant::now();
+
+        for (i, chunk) in samples.chunks(samples_per_chunk).enumerate() {
+            // Pace chunks at real time so provider behaviour matches a live mic
+            let due = started + Duration::from_millis(i as u64 * FIXTURE_CHUNK_MS);
+            let wait = due.saturating_duration_since(Instant::now());
+            if crate::stop::wait(&stop_signal, wait) {
+                break;
+            }
+
+            let pcm_data: Vec<u8> = chunk.iter().flat_map(|s| s.to_le_bytes()).collect();
+            if tx.blocking_send(AudioChunk::new(pcm_data)).is_err() {
+                break;
+            }
+        }
+
+        log::info!("Audio fixture finished after {:?}", started.elapsed());
+        // Dropping tx closes the channel, which ends the Soniox session
+        Ok(())
+    })
+}
+
+/// Read a WAV file and convert it to 16 kHz mono i16 samples.
+pub fn load_fixture(path: &str) -> Result<Vec<i16>, String> {
+    let mut reader = hound::WavReader::open(path)
+        .map_err(|e| format!("Failed to open audio fixture '{}': {}", path, e))?;
+    let spec = reader.spec();
+    log::info!("Audio fixture format: {:?}", spec);
+
+    let interleaved: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => reader
+            .samples::<f32>()
+            .collect::<Result<_, _>>(),
+        hound::SampleFormat::Int => {
+            let scale = (1_i64 << (spec.bits_per_sample - 1)) as f32;
+            reader
+                .samples::<i32>()
+                .map(|s| s.map(|v| v as f32 / scale))
+                .collect::<Result<_, _>>()
+        }
+    }
+    .map_err(|e| format!("Failed to read audio fixture: {}", e))?;
+
+    // Downmix to mono by averaging channels
+    let channels = spec.channels.max(1) as usize;
+    let mono: Vec<f32> = interleaved
+        .chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+        .collect();
+
+    let resampled = resample::resample(&mono, spec.sample_rate, TARGET_SAMPLE_RATE);
+
+    Ok(resampled
+        .iter()
+        .map(|&s| (s.clamp(-1.0, 1.0) * 32767.0_f32) as i16)
+        .collect())
+}
+
+/// Linear-interpolation resampler. Good enough for speech.
+pub fn resample_linear(input: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
+    if from_rate == to_rate || input.is_empty() {
+        return input.to_vec();
+    }
+
+    let ratio = from_rate as f64 / to_rate as f64;
+    let out_len = (input.len() as f64 / ratio).floor() as usize;
+
+    (0..out_len)
+        .map(|i| {
+            let pos = i as f64 * ratio;
+            let idx = pos.floor() as usize;
+            let frac = (pos - idx as f64) as f32;
+            let a = input[idx];
+            let b = *input.get(idx + 1).unwrap_or(&a);
+            a + (b - a) * frac
+        })
+        .collect()
+}
@@ -0,0 +1,42 @@
+//! G.711 µ-law: 16-bit PCM squeezed into 8 bits per sample with a
+//! logarithmic curve. Half the upload of raw PCM at a quality still fine
+//! for speech, and cheap enough to run on every chunk (battery saver).
+
+const BIAS: i32 = 0x84;
+const CLIP: i32 = 32635;
+
+/// Encode little-endian 16-bit PCM.
+pub fn encode(pcm: &[u8]) -> Vec<u8> {
+    pcm.chunks_exact(2)
+        .map(|b| encode_sample(i16::from_le_bytes([b[0], b[1]])))
+        .collect()
+}
+
+fn encode_sample(sample: i16) -> u8 {
+    let magnitude = (sample as i32).abs().min(CLIP) + BIAS;
+    let sign = if sample < 0 { 0x80 } else { 0 };
+    // Position of the highest set bit above the 7 low bits: 0..=7
+    let exponent = 31 - (magnitude >> 7).leading_zeros() as i32;
+    let mantissa = (magnitude >> (exponent + 3)) & 0x0F;
+    !(sign | (exponent << 4) | mantissa) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_reference_values() {
+        assert_eq!(encode_sample(0), 0xFF);
+        assert_eq!(encode_sample(-1), 0x7F);
+        assert_eq!(encode_sample(i16::MAX), 0x80);
+        assert_eq!(encode_sample(i16::MIN), 0x00);
+        assert_eq!(encode_sample(1000), 0xCE);
+    }
+
+    #[test]
+    fn halves_the_bytes() {
+        let pcm: Vec<u8> = [0i16, 100, -100, 20000].iter().flat_map(|s| s.to_le_bytes()).collect();
+        assert_eq!(encode(&pcm).len(), 4);
+    }
+}
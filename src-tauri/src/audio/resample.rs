@@ -0,0 +1,144 @@
+//! Streaming conversion to 16 kHz for input devices that only run at their
+//! own rate (typically 44.1 or 48 kHz).
+//!
+//! A windowed-sinc low-pass first removes everything above the target's
+//! Nyquist frequency, so hiss and sibilants don't fold back into the speech
+//! band, then linear interpolation picks the output samples. The filter
+//! history and the fractional read position carry over between callback
+//! buffers, so no samples are dropped or repeated at buffer boundaries.
+
+/// Low-pass length; odd so the filter has a whole-sample delay
+const TAPS: usize = 33;
+/// Cutoff as a fraction of the target's Nyquist frequency, leaving room
+/// for the filter's transition band
+const CUTOFF: f64 = 0.9;
+
+pub struct Resampler {
+    /// Input samples per output sample
+    step: f64,
+    taps: Vec<f32>,
+    /// The last `TAPS - 1` input samples of the previous buffer
+    history: Vec<f32>,
+    /// Last filtered sample of the previous buffer, the left end for
+    /// interpolating across the boundary
+    last: f32,
+    /// Read position of the next output sample; 0 is `last`
+    position: f64,
+}
+
+impl Resampler {
+    pub fn new(from_rate: u32, to_rate: u32) -> Self {
+        Self {
+            step: from_rate as f64 / to_rate as f64,
+            taps: low_pass(CUTOFF * 0.5 * (to_rate as f64 / from_rate as f64).min(1.0)),
+            history: vec![0.0; TAPS - 1],
+            last: 0.0,
+            position: 1.0,
+        }
+    }
+
+    /// Resample the next buffer of a continuous mono stream.
+    pub fn process(&mut self, input: &[f32]) -> Vec<f32> {
+        if self.step == 1.0 || input.is_empty() {
+            return input.to_vec();
+        }
+
+        let mut window = std::mem::take(&mut self.history);
+        window.extend_from_slice(input);
+        let filtered: Vec<f32> = window
+            .windows(TAPS)
+            .map(|w| w.iter().zip(&self.taps).map(|(s, t)| s * t).sum())
+            .collect();
+        self.history = window.split_off(window.len() - (TAPS - 1));
+
+        // filtered[i] sits at position i + 1, after `last`
+        let sample_at = |i: usize| if i == 0 { self.last } else { filtered[i - 1] };
+        let mut output = Vec::with_capacity((filtered.len() as f64 / self.step) as usize + 1);
+        while self.position < filtered.len() as f64 {
+            let index = self.position.floor() as usize;
+            let frac = (self.position - index as f64) as f32;
+            let (a, b) = (sample_at(index), sample_at(index + 1));
+            output.push(a + (b - a) * frac);
+            self.position += self.step;
+        }
+        self.position -= filtered.len() as f64;
+        self.last = *filtered.last().unwrap_or(&self.last);
+        output
+    }
+}
+
+/// Resample a whole recording in one go.
+pub fn resample(input: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
+    Resampler::new(from_rate, to_rate).process(input)
+}
+
+/// Blackman-windowed sinc low-pass with unity DC gain; `cutoff` is in
+/// cycles per input sample.
+fn low_pass(cutoff: f64) -> Vec<f32> {
+    let middle = (TAPS / 2) as f64;
+    let taps: Vec<f64> = (0..TAPS)
+        .map(|n| {
+            let x = n as f64 - middle;
+            let sinc = if x == 0.0 {
+                2.0 * cutoff
+            } else {
+                (2.0 * std::f64::consts::PI * cutoff * x).sin() / (std::f64::consts::PI * x)
+            };
+            let phase = 2.0 * std::f64::consts::PI * n as f64 / (TAPS - 1) as f64;
+            sinc * (0.42 - 0.5 * phase.cos() + 0.08 * (2.0 * phase).cos())
+        })
+        .collect();
+    let sum: f64 = taps.iter().sum();
+    taps.iter().map(|t| (t / sum) as f32).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(frequency: f64, rate: u32, len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|n| (2.0 * std::f64::consts::PI * frequency * n as f64 / rate as f64).sin() as f32)
+            .collect()
+    }
+
+    fn rms(samples: &[f32]) -> f32 {
+        (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+    }
+
+    #[test]
+    fn same_rate_is_unchanged() {
+        let input = sine(440.0, 16_000, 1000);
+        assert_eq!(resample(&input, 16_000, 16_000), input);
+    }
+
+    #[test]
+    fn output_length_follows_the_rate_ratio() {
+        assert_eq!(resample(&[0.0; 48_000], 48_000, 16_000).len(), 16_000);
+        let len = resample(&[0.0; 44_100], 44_100, 16_000).len();
+        assert!((15_999..=16_001).contains(&len), "{}", len);
+    }
+
+    #[test]
+    fn buffers_resample_like_one_stream() {
+        let input = sine(300.0, 48_000, 4800);
+        let whole = resample(&input, 48_000, 16_000);
+        let mut resampler = Resampler::new(48_000, 16_000);
+        // Uneven callback sizes, as cpal delivers them
+        let pieces: Vec<f32> = input.chunks(441).flat_map(|chunk| resampler.process(chunk)).collect();
+        assert_eq!(pieces.len(), whole.len());
+        for (a, b) in pieces.iter().zip(&whole) {
+            assert!((a - b).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn keeps_speech_and_removes_what_would_alias() {
+        // Past the filter's delay, 1 kHz passes and 12 kHz (above 8 kHz
+        // Nyquist) is attenuated instead of folding back to 4 kHz
+        let speech = resample(&sine(1000.0, 48_000, 9600), 48_000, 16_000);
+        let hiss = resample(&sine(12_000.0, 48_000, 9600), 48_000, 16_000);
+        assert!(rms(&speech[100..]) > 0.6);
+        assert!(rms(&hiss[100..]) < 0.05);
+    }
+}
@@ -0,0 +1,429 @@
+//! Azure Speech-to-Text provider, speaking the Speech SDK's WebSocket
+//! protocol directly.
+//!
+//! Differences from Soniox that this module absorbs:
+//! - every message carries HTTP-style headers (`Path`, `X-RequestId`, ...);
+//!   text messages separate them from the JSON body with a blank line,
+//!   binary audio messages prefix them with a big-endian length
+//! - the first audio message starts with a WAV header, an empty audio
+//!   message ends the stream
+//! - results are whole phrases rather than tokens: `speech.hypothesis`
+//!   messages are the non-final text of the current phrase (live preview),
+//!   `speech.phrase` is its final text, and `turn.end` closes the session
+
+use futures_util::{SinkExt, StreamExt};
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tauri::Emitter;
+use tokio::sync::mpsc;
+use tokio::time::{Duration, Instant};
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::Message;
+
+const SAMPLE_RATE: u32 = 16000;
+
+/// Payload for typed provider error events, as sent by the Soniox session.
+#[derive(Debug, Clone, Serialize)]
+struct ProviderError {
+    code: String,
+    message: String,
+}
+
+pub async fn connect_and_transcribe(
+    settings: crate::AppSettings,
+    stop_signal: tokio_util::sync::CancellationToken,
+    audio_rx: &mut mpsc::Receiver<crate::audio::AudioChunk>,
+    app: tauri::AppHandle,
+    target_window_id: String,
+) -> Result<(), String> {
+    let region = settings.azure_region.trim();
+    if region.is_empty() {
+        return Err(crate::i18n::t("azure-key-missing"));
+    }
+    let locale = azure_locale(settings.language_hints.first().map(String::as_str).unwrap_or("en"));
+    let punctuation = settings.provider_punctuation;
+    // Dictation mode turns spoken punctuation into symbols
+    let mode = if punctuation == crate::ProviderPunctuation::Spoken { "dictation" } else { "conversation" };
+    let url = format!(
+        "wss://{}.stt.speech.microsoft.com/speech/recognition/{}/cognitiveservices/v1?language={}&format=detailed",
+        region, mode, locale
+    );
+
+    let mut request = url.as_str().into_client_request().map_err(|e| e.to_string())?;
+    let headers = request.headers_mut();
+    headers.insert(
+        "Ocp-Apim-Subscription-Key",
+        settings
+            .azure_speech_key
+            .parse()
+            .map_err(|_| "Azure Speech key contains invalid characters".to_string())?,
+    );
+    headers.insert("X-ConnectionId", new_id().parse().unwrap());
+
+    log::info!("Connecting to Azure Speech: {}", url);
+    let timeout = Duration::from_millis(settings.connect_timeout_ms + settings.handshake_timeout_ms);
+    let connected = tokio::time::timeout(timeout, tokio_tungstenite::connect_async(request))
+        .await
+        .map_err(|_| format!("Connection timed out after {:?}", timeout))?;
+    let (ws_stream, _) = match connected {
+        Ok(connection) => connection,
+        // A bad key or region is only reported as the handshake's HTTP status
+        Err(tokio_tungstenite::tungstenite::Error::Http(response))
+            if matches!(response.status().as_u16(), 401 | 403) =>
+        {
+            let message = format!("Azure rejected the key for region '{}' ({})", region, response.status());
+            log::error!("{}", message);
+            app.emit(
+                "auth-error",
+                ProviderError { code: response.status().as_u16().to_string(), message: message.clone() },
+            )
+            .ok();
+            return Err(message);
+        }
+        Err(e) => {
+            let err_msg = format!("WebSocket connection failed: {}", e);
+            log::error!("{}", err_msg);
+            return Err(err_msg);
+        }
+    };
+    log::info!("Connected to Azure Speech ({})", locale);
+    let (mut ws_write, mut ws_read) = ws_stream.split();
+
+    // One turn per session; every message of it shares the request id
+    let request_id = new_id();
+    let config = serde_json::json!({
+        "context": {
+            "system": { "name": "SpeechSDK", "version": "1.0.0", "build": "Rust" },
+            "os": { "platform": std::env::consts::OS, "name": "desktop-dictate", "version": env!("CARGO_PKG_VERSION") },
+            "audio": { "source": { "type": "Microphones", "samplerate": SAMPLE_RATE, "bitspersample": 16, "channelcount": 1 } },
+        },
+        "recognition": mode,
+    });
+    ws_write
+        .send(Message::Text(text_message("speech.config", &request_id, &config)))
+        .await
+        .map_err(|e| format!("Failed to send speech config: {}", e))?;
+    // A phrase list is the closest thing to Soniox context terms
+    let terms = settings.context_terms();
+    if !terms.is_empty() {
+        let items: Vec<_> = terms.iter().map(|term| serde_json::json!({ "Text": term })).collect();
+        let context = serde_json::json!({ "dgi": { "Groups": [{ "Type": "Generic", "Items": items }] } });
+        ws_write
+            .send(Message::Text(text_message("speech.context", &request_id, &context)))
+            .await
+            .map_err(|e| format!("Failed to send speech context: {}", e))?;
+    }
+
+    let mut output = crate::output::Utterances::new(settings.clone(), app.clone(), target_window_id);
+    output.begin().await;
+    let mut wav_header_sent = false;
+    let mut audio_closed = false;
+    let mut end_sent = false;
+
+    let finish_timeout = tokio::time::sleep(Duration::from_secs(3600));
+    tokio::pin!(finish_timeout);
+
+    loop {
+        if stop_signal.is_cancelled() && !end_sent {
+            audio_closed = true;
+        }
+        if audio_closed && !end_sent {
+            // An empty audio message ends the stream; the turn ends once the
+            // last phrase is recognized
+            ws_write.send(Message::Binary(audio_message(&request_id, &[]))).await.ok();
+            end_sent = true;
+            finish_timeout.as_mut().reset(Instant::now() + Duration::from_secs(5));
+        }
+
+        tokio::select! {
+            _ = stop_signal.cancelled(), if !audio_closed => {}
+            chunk = audio_rx.recv(), if !audio_closed => match chunk {
+                Some(chunk) => {
+                    let payload = if wav_header_sent {
+                        chunk.data.clone()
+                    } else {
+                        wav_header_sent = true;
+                        let mut payload = wav_header();
+                        payload.extend_from_slice(&chunk.data);
+                        payload
+                    };
+                    if let Err(e) = ws_write.send(Message::Binary(audio_message(&request_id, &payload))).await {
+                        log::error!("WebSocket send failed: {}", e);
+                    } else {
+                        crate::metrics::add_audio_bytes(chunk.data.len());
+                    }
+                }
+                None => audio_closed = true,
+            },
+            _ = &mut finish_timeout, if end_sent => {
+                log::warn!("Timeout waiting for final phrases from Azure");
+                break;
+            }
+            msg = ws_read.next() => match msg {
+                Some(Ok(Message::Text(text))) => {
+                    let (path, body) = parse_text_message(&text);
+                    let event: serde_json::Value = serde_json::from_str(body).unwrap_or_default();
+                    match path.as_str() {
+                        // Non-final text of the phrase being spoken
+                        "speech.hypothesis" | "speech.fragment" => {
+                            if let Some(text) = event.get("Text").and_then(|t| t.as_str()) {
+                                app.emit("partial-text", text.to_string()).ok();
+                            }
+                        }
+                        // Final text of one phrase
+                        "speech.phrase" => {
+                            let status = event.get("RecognitionStatus").and_then(|s| s.as_str()).unwrap_or_default();
+                            match status {
+                                "Success" => output.deliver(&phrase_text(&event, punctuation)).await,
+                                // Silence or noise only; nothing to type
+                                "NoMatch" | "InitialSilenceTimeout" | "BabbleTimeout" | "EndOfDictation" => {}
+                                _ => {
+                                    log::error!("Azure recognition failed: {}", status);
+                                    crate::console::record(&app, "error", format!("Azure recognition failed: {}", status));
+                                    let message = crate::i18n::t_args("azure-recognition-failed", &[("status", status.to_string())]);
+                                    app.emit("transcription-error", message).ok();
+                                }
+                            }
+                        }
+                        "turn.end" => {
+                            log::info!("Azure turn ended");
+                            break;
+                        }
+                        _ => {}
+                    }
+                }
+                Some(Ok(Message::Close(frame))) => {
+                    // Errors after the handshake arrive as close reasons
+                    if let Some(frame) = frame.filter(|f| !f.reason.is_empty()) {
+                        log::error!("Azure closed the connection: {}", frame.reason);
+                        crate::console::record(&app, "error", format!("Azure closed the connection: {}", frame.reason));
+                        app.emit("transcription-error", frame.reason.to_string()).ok();
+                    }
+                    break;
+                }
+                None => {
+                    log::info!("Azure WebSocket closed");
+                    break;
+                }
+                Some(Err(e)) => {
+                    log::error!("WebSocket error: {}", e);
+                    crate::console::record(&app, "error", format!("WebSocket error: {}", e));
+                    app.emit("transcription-error", e.to_string()).ok();
+                    break;
+                }
+                _ => {}
+            },
+        }
+    }
+
+    ws_write.close().await.ok();
+    output.finish();
+    Ok(())
+}
+
+/// Azure locale for each language offered as a hint, its most common
+/// speech-to-text region. Azure has no Belarusian model.
+const LOCALES: &[(&str, &str)] = &[
+    ("af", "af-ZA"),
+    ("sq", "sq-AL"),
+    ("ar", "ar-SA"),
+    ("az", "az-AZ"),
+    ("eu", "eu-ES"),
+    ("bn", "bn-IN"),
+    ("bs", "bs-BA"),
+    ("bg", "bg-BG"),
+    ("ca", "ca-ES"),
+    ("zh", "zh-CN"),
+    ("hr", "hr-HR"),
+    ("cs", "cs-CZ"),
+    ("da", "da-DK"),
+    ("nl", "nl-NL"),
+    ("en", "en-US"),
+    ("et", "et-EE"),
+    ("fi", "fi-FI"),
+    ("fr", "fr-FR"),
+    ("gl", "gl-ES"),
+    ("de", "de-DE"),
+    ("el", "el-GR"),
+    ("gu", "gu-IN"),
+    ("he", "he-IL"),
+    ("hi", "hi-IN"),
+    ("hu", "hu-HU"),
+    ("id", "id-ID"),
+    ("it", "it-IT"),
+    ("ja", "ja-JP"),
+    ("kn", "kn-IN"),
+    ("kk", "kk-KZ"),
+    ("ko", "ko-KR"),
+    ("lv", "lv-LV"),
+    ("lt", "lt-LT"),
+    ("mk", "mk-MK"),
+    ("ms", "ms-MY"),
+    ("ml", "ml-IN"),
+    ("mr", "mr-IN"),
+    ("no", "nb-NO"),
+    ("nb", "nb-NO"),
+    ("fa", "fa-IR"),
+    ("pl", "pl-PL"),
+    ("pt", "pt-BR"),
+    ("pa", "pa-IN"),
+    ("ro", "ro-RO"),
+    ("ru", "ru-RU"),
+    ("sr", "sr-RS"),
+    ("sk", "sk-SK"),
+    ("sl", "sl-SI"),
+    ("es", "es-ES"),
+    ("sw", "sw-KE"),
+    ("sv", "sv-SE"),
+    ("tl", "fil-PH"),
+    ("ta", "ta-IN"),
+    ("te", "te-IN"),
+    ("th", "th-TH"),
+    ("tr", "tr-TR"),
+    ("uk", "uk-UA"),
+    ("ur", "ur-IN"),
+    ("vi", "vi-VN"),
+    ("cy", "cy-GB"),
+];
+
+/// Azure wants a full locale ("en-US"); language hints are usually bare
+/// codes. Full locales pass through, and so do codes missing from the
+/// table, for Azure to reject as unsupported.
+fn azure_locale(hint: &str) -> String {
+    if hint.contains('-') {
+        return hint.to_string();
+    }
+    match LOCALES.iter().find(|(code, _)| *code == hint) {
+        Some((_, locale)) => locale.to_string(),
+        None => {
+            log::warn!("No Azure locale known for '{}'", hint);
+            hint.to_string()
+        }
+    }
+}
+
+/// Display text of a `speech.phrase`, from the detailed best result if the
+/// top-level field is missing. With punctuation off, the best result's
+/// normalized words (ITN: digits, but no punctuation or capitals).
+fn phrase_text(event: &serde_json::Value, punctuation: crate::ProviderPunctuation) -> String {
+    if punctuation == crate::ProviderPunctuation::Off {
+        if let Some(text) = event.pointer("/NBest/0/ITN").and_then(|t| t.as_str()) {
+            return text.to_string();
+        }
+    }
+    event
+        .get("DisplayText")
+        .and_then(|t| t.as_str())
+        .or_else(|| event.pointer("/NBest/0/Display").and_then(|t| t.as_str()))
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn text_message(path: &str, request_id: &str, body: &serde_json::Value) -> String {
+    format!(
+        "Path: {}\r\nX-RequestId: {}\r\nX-Timestamp: {}\r\nContent-Type: application/json\r\n\r\n{}",
+        path,
+        request_id,
+        timestamp(),
+        body
+    )
+}
+
+/// Binary audio message: 2-byte big-endian header length, headers, audio.
+fn audio_message(request_id: &str, audio: &[u8]) -> Vec<u8> {
+    let headers = format!(
+        "Path: audio\r\nX-RequestId: {}\r\nX-Timestamp: {}\r\nContent-Type: audio/x-wav\r\n",
+        request_id,
+        timestamp()
+    );
+    let mut message = Vec::with_capacity(2 + headers.len() + audio.len());
+    message.extend_from_slice(&(headers.len() as u16).to_be_bytes());
+    message.extend_from_slice(headers.as_bytes());
+    message.extend_from_slice(audio);
+    message
+}
+
+/// Split a server text message into its `Path` header and body.
+fn parse_text_message(text: &str) -> (String, &str) {
+    let (headers, body) = text.split_once("\r\n\r\n").unwrap_or((text, ""));
+    let path = headers
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("path"))
+        .map(|(_, value)| value.trim().to_lowercase())
+        .unwrap_or_default();
+    (path, body)
+}
+
+/// 44-byte header of an open-ended 16 kHz mono pcm16 WAV stream.
+fn wav_header() -> Vec<u8> {
+    let byte_rate = SAMPLE_RATE * 2;
+    let mut header = Vec::with_capacity(44);
+    header.extend_from_slice(b"RIFF");
+    header.extend_from_slice(&0u32.to_le_bytes());
+    header.extend_from_slice(b"WAVEfmt ");
+    header.extend_from_slice(&16u32.to_le_bytes());
+    header.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    header.extend_from_slice(&1u16.to_le_bytes()); // mono
+    header.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    header.extend_from_slice(&byte_rate.to_le_bytes());
+    header.extend_from_slice(&2u16.to_le_bytes()); // block align
+    header.extend_from_slice(&16u16.to_le_bytes()); // bits per sample
+    header.extend_from_slice(b"data");
+    header.extend_from_slice(&0u32.to_le_bytes());
+    header
+}
+
+/// 32 hex digits without dashes, the id format the service expects.
+fn new_id() -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or_default();
+    format!("{:016x}{:08x}{:08x}", nanos, std::process::id(), COUNTER.fetch_add(1, Ordering::Relaxed))
+}
+
+/// Current UTC time as ISO 8601 with milliseconds.
+fn timestamp() -> String {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default();
+    let secs = now.as_secs();
+    let (days, rem) = (secs / 86_400, secs % 86_400);
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days as i64 + 719_468;
+    let era = z / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60,
+        now.subsec_millis()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_bare_codes_to_locales() {
+        assert_eq!(azure_locale("en"), "en-US");
+        assert_eq!(azure_locale("no"), "nb-NO");
+        assert_eq!(azure_locale("tl"), "fil-PH");
+        assert_eq!(azure_locale("cy"), "cy-GB");
+        assert_eq!(azure_locale("en-GB"), "en-GB");
+        assert_eq!(azure_locale("be"), "be");
+    }
+}
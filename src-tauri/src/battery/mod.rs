@@ -0,0 +1,64 @@
+//! Battery saver: detecting battery power and easing off while on it.
+//!
+//! When the setting is on and the machine runs on battery, a session's
+//! settings copy is adjusted before it starts: a slower live preview and
+//! µ-law audio upload. The overlay drops its animation on its own.
+
+// Live preview interval while saving battery
+const BATTERY_PARTIAL_INTERVAL_MS: u64 = 500;
+
+/// Whether the machine currently runs on battery. Desktops and platforms
+/// without a supported power source query report `false`.
+pub fn on_battery() -> bool {
+    #[cfg(target_os = "linux")]
+    {
+        // Any online mains adapter means we're plugged in; otherwise a
+        // discharging battery means we're not
+        let Ok(entries) = std::fs::read_dir("/sys/class/power_supply") else {
+            return false;
+        };
+        let read = |path: std::path::PathBuf| std::fs::read_to_string(path).unwrap_or_default().trim().to_string();
+        let mut discharging = false;
+        for entry in entries.flatten() {
+            let dir = entry.path();
+            match read(dir.join("type")).as_str() {
+                "Mains" | "USB" if read(dir.join("online")) == "1" => return false,
+                "Battery" if read(dir.join("status")) == "Discharging" => discharging = true,
+                _ => {}
+            }
+        }
+        discharging
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        std::process::Command::new("pmset")
+            .args(["-g", "batt"])
+            .output()
+            .map(|out| String::from_utf8_lossy(&out.stdout).contains("'Battery Power'"))
+            .unwrap_or(false)
+    }
+
+    #[cfg(windows)]
+    {
+        use windows_sys::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+        let mut status: SYSTEM_POWER_STATUS = unsafe { std::mem::zeroed() };
+        // ACLineStatus: 0 offline, 1 online, 255 unknown
+        unsafe { GetSystemPowerStatus(&mut status) } != 0 && status.ACLineStatus == 0
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+    {
+        false
+    }
+}
+
+/// Trade responsiveness for battery life in a session's settings copy.
+pub fn reduce_for_battery(settings: &mut crate::AppSettings) {
+    settings.partial_text_interval_ms = settings.partial_text_interval_ms.max(BATTERY_PARTIAL_INTERVAL_MS);
+    settings.compressed_upload = true;
+    log::info!(
+        "On battery: live preview every {} ms, overlay animation off, µ-law upload",
+        settings.partial_text_interval_ms
+    );
+}
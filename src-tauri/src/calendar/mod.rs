@@ -0,0 +1,352 @@
+//! Meeting titles for history entries from an iCalendar (ICS) feed.
+//!
+//! `calendar_ics` is a file path or an `http(s)://`/`webcal://` URL. The
+//! feed is re-read when a session starts (at most every `REFRESH_AFTER`),
+//! and a saved session that overlaps an event is titled with the event's
+//! summary; with several candidates the longest overlap wins.
+//!
+//! Only what dictation needs is understood: `SUMMARY`, `DTSTART`, `DTEND`
+//! or `DURATION`, and daily or weekly `RRULE`s (`INTERVAL`, `BYDAY`,
+//! `UNTIL`). All-day events are skipped. Times with a `TZID` or without
+//! a zone are taken as this machine's local time, and their recurrences
+//! keep the same wall-clock time across daylight saving changes.
+
+use chrono::{DateTime, Datelike, Days, Local, LocalResult, NaiveDateTime, TimeDelta, TimeZone};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+const REFRESH_AFTER: Duration = Duration::from_secs(15 * 60);
+const DAY: u64 = 86_400;
+
+#[derive(Debug, Clone)]
+struct Event {
+    summary: String,
+    start: u64,
+    end: u64,
+    /// `start` as wall-clock time in `zone`, which recurrences repeat
+    wall_start: NaiveDateTime,
+    zone: Zone,
+    repeat: Option<Repeat>,
+}
+
+/// The zone an event's wall-clock times are in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Zone {
+    Utc,
+    Local,
+}
+
+impl Zone {
+    fn to_unix(self, wall: NaiveDateTime) -> Option<u64> {
+        let seconds = match self {
+            Zone::Utc => wall.and_utc().timestamp(),
+            Zone::Local => match Local.from_local_datetime(&wall) {
+                LocalResult::Single(time) | LocalResult::Ambiguous(time, _) => time.timestamp(),
+                // Skipped by a daylight saving change; clocks read an hour later
+                LocalResult::None => Local.from_local_datetime(&(wall + TimeDelta::hours(1))).earliest()?.timestamp(),
+            },
+        };
+        u64::try_from(seconds).ok()
+    }
+
+    fn to_wall(self, unix: u64) -> Option<NaiveDateTime> {
+        let utc = DateTime::from_timestamp(i64::try_from(unix).ok()?, 0)?;
+        Some(match self {
+            Zone::Utc => utc.naive_utc(),
+            Zone::Local => utc.with_timezone(&Local).naive_local(),
+        })
+    }
+}
+
+/// Recurrence as whole-day offsets within a period of `step_days`.
+#[derive(Debug, Clone)]
+struct Repeat {
+    step_days: u64,
+    offsets: Vec<u64>,
+    until: Option<u64>,
+}
+
+/// Events of the last read feed, with its source and when it was read.
+static CACHE: Mutex<Option<(String, Instant, Vec<Event>)>> = Mutex::new(None);
+
+/// Re-read `source` unless it was read recently. Errors are logged; the
+/// previous events stay in use.
+pub async fn refresh(source: &str) {
+    let source = source.trim();
+    let fresh = CACHE
+        .lock()
+        .unwrap()
+        .as_ref()
+        .is_some_and(|(cached, read_at, _)| cached == source && read_at.elapsed() < REFRESH_AFTER);
+    if source.is_empty() || fresh {
+        return;
+    }
+    match read(source).await {
+        Ok(text) => {
+            let events = parse(&text);
+            log::info!("Calendar: {} events from {}", events.len(), source);
+            *CACHE.lock().unwrap() = Some((source.to_string(), Instant::now(), events));
+        }
+        Err(e) => log::warn!("Calendar: {}", e),
+    }
+}
+
+async fn read(source: &str) -> Result<String, String> {
+    let url = match source.strip_prefix("webcal://") {
+        Some(rest) => format!("https://{}", rest),
+        None => source.to_string(),
+    };
+    if url.starts_with("http://") || url.starts_with("https://") {
+        reqwest::get(&url)
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| format!("Failed to fetch {}: {}", url, e))?
+            .text()
+            .await
+            .map_err(|e| format!("Failed to read {}: {}", url, e))
+    } else {
+        tokio::fs::read_to_string(source)
+            .await
+            .map_err(|e| format!("Failed to read {}: {}", source, e))
+    }
+}
+
+/// Summary of the event overlapping `start..end` (unix seconds) the most.
+pub fn title_for(start: u64, end: u64) -> Option<String> {
+    let cache = CACHE.lock().unwrap();
+    let (_, _, events) = cache.as_ref()?;
+    events
+        .iter()
+        .filter_map(|event| Some((overlap(event, start, end.max(start + 1))?, event)))
+        .max_by_key(|(seconds, _)| *seconds)
+        .map(|(_, event)| event.summary.clone())
+}
+
+/// Seconds `event` (or its nearest occurrence) overlaps `start..end`.
+fn overlap(event: &Event, start: u64, end: u64) -> Option<u64> {
+    let length = event.end.saturating_sub(event.start);
+    let shared = |occurrence: u64| {
+        let from = occurrence.max(start);
+        let to = (occurrence + length).min(end);
+        (to > from).then(|| to - from)
+    };
+    let Some(repeat) = &event.repeat else { return shared(event.start) };
+    if start + length < event.start {
+        return None;
+    }
+    // Periods are counted in the event's wall-clock days, so a 09:00
+    // meeting stays at 09:00 on both sides of a daylight saving change
+    let days = (event.zone.to_wall(start)?.date() - event.wall_start.date()).num_days();
+    let n = u64::try_from(days).unwrap_or(0) / repeat.step_days;
+    // An occurrence from the previous period can still be running
+    [n.saturating_sub(1), n]
+        .into_iter()
+        .flat_map(|n| repeat.offsets.iter().map(move |offset| n * repeat.step_days + offset))
+        .filter_map(|days| event.zone.to_unix(event.wall_start.checked_add_days(Days::new(days))?))
+        .filter(|&occurrence| repeat.until.is_none_or(|until| occurrence <= until))
+        .filter_map(shared)
+        .max()
+}
+
+fn parse(ics: &str) -> Vec<Event> {
+    // Long lines are folded: a continuation starts with a space or tab
+    let mut lines: Vec<String> = Vec::new();
+    for line in ics.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(line.to_string()),
+        }
+    }
+
+    let mut events = Vec::new();
+    let mut current: Option<Vec<(String, String)>> = None;
+    for line in lines {
+        match line.trim_end() {
+            "BEGIN:VEVENT" => current = Some(Vec::new()),
+            "END:VEVENT" => {
+                if let Some(event) = current.take().and_then(|fields| to_event(&fields)) {
+                    events.push(event);
+                }
+            }
+            line => {
+                if let (Some(fields), Some((name, value))) = (current.as_mut(), line.split_once(':')) {
+                    fields.push((name.to_string(), value.to_string()));
+                }
+            }
+        }
+    }
+    events
+}
+
+fn to_event(fields: &[(String, String)]) -> Option<Event> {
+    // Property names may carry parameters: `DTSTART;TZID=Europe/Berlin`
+    let field = |key: &str| {
+        fields
+            .iter()
+            .find(|(name, _)| name.split(';').next() == Some(key))
+            .map(|(name, value)| (name.as_str(), value.trim()))
+    };
+    let summary = field("SUMMARY")?.1.replace("\\,", ",").replace("\\;", ";").replace("\\n", " ");
+    let (wall_start, zone) = parse_time(field("DTSTART")?)?;
+    let start = zone.to_unix(wall_start)?;
+    let end = match field("DTEND") {
+        Some(dtend) => {
+            let (wall, zone) = parse_time(dtend)?;
+            zone.to_unix(wall)?
+        }
+        None => start + field("DURATION").and_then(|(_, v)| parse_duration(v)).unwrap_or(3600),
+    };
+    let repeat = field("RRULE").and_then(|(_, rule)| parse_rule(rule, wall_start));
+    Some(Event { summary, start, end, wall_start, zone, repeat })
+}
+
+/// `20261015T090000Z`, or local `20261015T090000`. All-day dates are None.
+fn parse_time((name, value): (&str, &str)) -> Option<(NaiveDateTime, Zone)> {
+    if name.contains("VALUE=DATE") && !name.contains("VALUE=DATE-TIME") {
+        return None;
+    }
+    let (value, zone) = match value.strip_suffix('Z') {
+        Some(value) => (value, Zone::Utc),
+        None => (value, Zone::Local),
+    };
+    let wall = NaiveDateTime::parse_from_str(value.get(..15)?, "%Y%m%dT%H%M%S").ok()?;
+    Some((wall, zone))
+}
+
+/// `PT1H30M`, `P1D`, ... as seconds.
+fn parse_duration(value: &str) -> Option<u64> {
+    let mut seconds = 0;
+    let mut number = String::new();
+    for c in value.trim_start_matches(['+', 'P']).chars() {
+        match c {
+            '0'..='9' => number.push(c),
+            'T' => {}
+            unit => {
+                let n: u64 = number.parse().ok()?;
+                number.clear();
+                seconds += n * match unit {
+                    'W' => 7 * DAY,
+                    'D' => DAY,
+                    'H' => 3600,
+                    'M' => 60,
+                    'S' => 1,
+                    _ => return None,
+                };
+            }
+        }
+    }
+    Some(seconds)
+}
+
+fn parse_rule(rule: &str, wall_start: NaiveDateTime) -> Option<Repeat> {
+    let part = |key: &str| {
+        rule.split(';')
+            .find_map(|p| p.strip_prefix(key)?.strip_prefix('='))
+    };
+    let interval: u64 = part("INTERVAL").and_then(|i| i.parse().ok()).unwrap_or(1).max(1);
+    // UNTIL is a date-time, or a date meaning the end of that day
+    let until = part("UNTIL").and_then(|u| {
+        let (wall, zone) = if u.contains('T') {
+            parse_time(("UNTIL", u))
+        } else {
+            parse_time(("UNTIL", &format!("{}T235959", u)))
+        }?;
+        zone.to_unix(wall)
+    });
+    match part("FREQ")? {
+        "DAILY" => Some(Repeat { step_days: interval, offsets: vec![0], until }),
+        "WEEKLY" => {
+            // 0 = Sunday as in BYDAY order below
+            let start_weekday = wall_start.weekday().num_days_from_sunday() as i64;
+            let offsets = match part("BYDAY") {
+                Some(days) => days
+                    .split(',')
+                    .filter_map(|d| ["SU", "MO", "TU", "WE", "TH", "FR", "SA"].iter().position(|&w| d.ends_with(w)))
+                    .map(|weekday| (weekday as i64 - start_weekday).rem_euclid(7) as u64)
+                    .collect(),
+                None => vec![0],
+            };
+            Some(Repeat { step_days: 7 * interval, offsets, until })
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 2026-10-15 09:00:00 UTC
+    const STANDUP: u64 = 1_792_054_800;
+
+    fn ics(event: &str) -> String {
+        format!("BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\n{}\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n", event.replace('\n', "\r\n"))
+    }
+
+    #[test]
+    fn parses_utc_event_with_folded_summary() {
+        let events = parse(&ics("SUMMARY:Weekly sync\\, design\n  review\nDTSTART:20261015T090000Z\nDTEND:20261015T093000Z"));
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].summary, "Weekly sync, design review");
+        assert_eq!((events[0].start, events[0].end), (STANDUP, STANDUP + 1800));
+        assert!(events[0].repeat.is_none());
+    }
+
+    #[test]
+    fn duration_stands_in_for_dtend() {
+        let events = parse(&ics("SUMMARY:Standup\nDTSTART:20261015T090000Z\nDURATION:PT15M"));
+        assert_eq!(events[0].end, STANDUP + 900);
+    }
+
+    #[test]
+    fn skips_all_day_events() {
+        assert!(parse(&ics("SUMMARY:Holiday\nDTSTART;VALUE=DATE:20261015")).is_empty());
+    }
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(parse_duration("PT1H30M"), Some(5400));
+        assert_eq!(parse_duration("P1DT2S"), Some(DAY + 2));
+        assert_eq!(parse_duration("P1W"), Some(7 * DAY));
+        assert_eq!(parse_duration("PT5X"), None);
+    }
+
+    #[test]
+    fn daily_rule_overlaps_later_occurrences_until_its_end() {
+        let events = parse(&ics(
+            "SUMMARY:Standup\nDTSTART:20261015T090000Z\nDURATION:PT15M\nRRULE:FREQ=DAILY;INTERVAL=2;UNTIL=20261019T235959Z",
+        ));
+        let event = &events[0];
+        // Oct 17, 09:10 to 09:20: five minutes of the occurrence
+        assert_eq!(overlap(event, STANDUP + 2 * DAY + 600, STANDUP + 2 * DAY + 1200), Some(300));
+        // Oct 16 has no occurrence with an interval of 2
+        assert_eq!(overlap(event, STANDUP + DAY, STANDUP + DAY + 600), None);
+        // Oct 21 is past UNTIL
+        assert_eq!(overlap(event, STANDUP + 6 * DAY, STANDUP + 6 * DAY + 600), None);
+        // Before the first occurrence
+        assert_eq!(overlap(event, STANDUP - DAY, STANDUP - DAY + 600), None);
+    }
+
+    #[test]
+    fn local_rule_keeps_its_wall_clock_time() {
+        let events = parse(&ics("SUMMARY:Standup\nDTSTART:20260115T090000\nDURATION:PT15M\nRRULE:FREQ=WEEKLY;BYDAY=TH"));
+        let event = &events[0];
+        assert_eq!(event.zone, Zone::Local);
+        // Half a year later, across any daylight saving change, still 09:00 local
+        let july = NaiveDateTime::parse_from_str("20260716T090500", "%Y%m%dT%H%M%S").unwrap();
+        let start = Zone::Local.to_unix(july).unwrap();
+        assert_eq!(overlap(event, start, start + 300), Some(300));
+        assert_eq!(overlap(event, start + 3600, start + 3900), None);
+    }
+
+    #[test]
+    fn weekly_rule_expands_byday() {
+        // 2026-10-15 is a Thursday
+        let events = parse(&ics("SUMMARY:Sync\nDTSTART:20261015T090000Z\nDURATION:PT30M\nRRULE:FREQ=WEEKLY;BYDAY=TH,MO"));
+        let event = &events[0];
+        // Monday Oct 19
+        assert_eq!(overlap(event, STANDUP + 4 * DAY, STANDUP + 4 * DAY + 600), Some(600));
+        // Tuesday Oct 20
+        assert_eq!(overlap(event, STANDUP + 5 * DAY, STANDUP + 5 * DAY + 600), None);
+    }
+}
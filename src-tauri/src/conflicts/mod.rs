@@ -0,0 +1,236 @@
+//! Startup check for software that gets in the way of dictation.
+//!
+//! Other dictation apps may type into the same window or hold the
+//! microphone, key remappers (AutoHotkey, keyd, ...) can grab the hotkey
+//! before we see it, and an installed but stopped `ydotoold` silently
+//! pushes Wayland typing onto the clipboard fallback. None of these fail
+//! loudly, so findings are kept for the UI to show as warnings and pushed
+//! as `conflicts-detected`.
+
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter};
+
+/// Something running (or not running) that explains a silent failure.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct Conflict {
+    /// "dictation", "hotkey" or "typing"
+    pub kind: &'static str,
+    pub name: String,
+    /// What goes wrong and what to do about it
+    pub message: String,
+}
+
+/// Known competing programs: (process name without ".exe", display name, kind).
+const KNOWN_PROGRAMS: &[(&str, &str, &str)] = &[
+    ("natspeak", "Dragon NaturallySpeaking", "dictation"),
+    ("nerd-dictation", "nerd-dictation", "dictation"),
+    ("talon", "Talon", "dictation"),
+    ("superwhisper", "superwhisper", "dictation"),
+    // The macOS and Windows executables keep the space ("Wispr Flow.exe");
+    // Linux command lines are split on whitespace, so only a dashed name works
+    ("wispr flow", "Wispr Flow", "dictation"),
+    ("wispr-flow", "Wispr Flow", "dictation"),
+    ("numen", "Numen", "dictation"),
+    ("autohotkey", "AutoHotkey", "hotkey"),
+    ("autohotkey64", "AutoHotkey", "hotkey"),
+    ("autohotkeyu64", "AutoHotkey", "hotkey"),
+    ("autohotkeyux", "AutoHotkey", "hotkey"),
+    ("karabiner_grabber", "Karabiner-Elements", "hotkey"),
+    ("xbindkeys", "xbindkeys", "hotkey"),
+    ("sxhkd", "sxhkd", "hotkey"),
+    ("keyd", "keyd", "hotkey"),
+    ("kanata", "kanata", "hotkey"),
+    ("kmonad", "KMonad", "hotkey"),
+    ("input-remapper-service", "Input Remapper", "hotkey"),
+];
+
+static CONFLICTS: Mutex<Vec<Conflict>> = Mutex::new(Vec::new());
+/// The hotkey named by the current "could not be registered" warning
+static TAKEN_HOTKEY: Mutex<Option<String>> = Mutex::new(None);
+
+/// Look for conflicting software in the background and report what was
+/// found. `hotkey` is named in the warnings about key remappers.
+pub fn check(app: &AppHandle, hotkey: &str) {
+    let app = app.clone();
+    let hotkey = hotkey.to_string();
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut found = running_conflicts(&hotkey);
+        found.extend(ydotoold_stopped());
+        if found.is_empty() {
+            return;
+        }
+        for conflict in &found {
+            log::warn!("Possible conflict: {}", conflict.message);
+            crate::console::record(&app, "conflict", conflict.message.clone());
+        }
+        CONFLICTS.lock().unwrap().extend(found);
+        app.emit("conflicts-detected", conflicts()).ok();
+    });
+}
+
+/// Record that registering the hotkey failed, which almost always means
+/// another program holds it. Replaces the warning about an earlier hotkey.
+pub fn hotkey_taken(app: &AppHandle, hotkey: &str, error: &str) {
+    let conflict = Conflict {
+        kind: "hotkey",
+        name: hotkey.to_string(),
+        message: crate::i18n::t_args(
+            "conflict-hotkey-taken",
+            &[("hotkey", hotkey.to_string()), ("error", error.to_string())],
+        ),
+    };
+    crate::console::record(app, "conflict", conflict.message.clone());
+    {
+        let mut conflicts = CONFLICTS.lock().unwrap();
+        let mut taken = TAKEN_HOTKEY.lock().unwrap();
+        if let Some(previous) = taken.replace(hotkey.to_string()) {
+            conflicts.retain(|c| !(c.kind == "hotkey" && c.name == previous));
+        }
+        conflicts.push(conflict);
+    }
+    app.emit("conflicts-detected", conflicts()).ok();
+}
+
+/// The hotkey registered after all: drop the warning that it was taken.
+pub fn hotkey_registered(app: &AppHandle) {
+    let Some(previous) = TAKEN_HOTKEY.lock().unwrap().take() else {
+        return;
+    };
+    CONFLICTS.lock().unwrap().retain(|c| !(c.kind == "hotkey" && c.name == previous));
+    app.emit("conflicts-detected", conflicts()).ok();
+}
+
+/// Conflicts found since startup that the user hasn't dismissed yet.
+pub fn conflicts() -> Vec<Conflict> {
+    CONFLICTS.lock().unwrap().clone()
+}
+
+pub fn dismiss() {
+    CONFLICTS.lock().unwrap().clear();
+    TAKEN_HOTKEY.lock().unwrap().take();
+}
+
+fn running_conflicts(hotkey: &str) -> Vec<Conflict> {
+    let running = running_programs();
+    let mut found: Vec<Conflict> = Vec::new();
+    for &(process, name, kind) in KNOWN_PROGRAMS {
+        if !running.iter().any(|p| p == process) || found.iter().any(|c| c.name == name) {
+            continue;
+        }
+        let message = match kind {
+            "hotkey" => crate::i18n::t_args(
+                "conflict-hotkey-tool",
+                &[("name", name.to_string()), ("hotkey", hotkey.to_string())],
+            ),
+            _ => crate::i18n::t_args("conflict-dictation-app", &[("name", name.to_string())]),
+        };
+        found.push(Conflict { kind, name: name.to_string(), message });
+    }
+    found
+}
+
+#[cfg(target_os = "linux")]
+fn ydotoold_stopped() -> Option<Conflict> {
+    if crate::typer::session_type() != "wayland" {
+        return None;
+    }
+    let daemon = crate::tooling::check().ydotoold?;
+    (!daemon.running).then(|| Conflict {
+        kind: "typing",
+        name: "ydotoold".to_string(),
+        message: crate::i18n::t("conflict-ydotoold-stopped"),
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+fn ydotoold_stopped() -> Option<Conflict> {
+    None
+}
+
+/// Lowercased executable names of running processes, without ".exe".
+#[cfg(target_os = "linux")]
+pub fn running_programs() -> Vec<String> {
+    command_lines("ps", &["-A", "-o", "args="])
+        .iter()
+        .flat_map(|line| command_programs(line))
+        .collect()
+}
+
+/// The program of a command line, and the script it runs when the program
+/// is an interpreter: script-based tools (nerd-dictation, Input Remapper)
+/// show up as "python3 /usr/bin/nerd-dictation". Arguments of anything
+/// else ("vim notes/talon") are not programs.
+#[cfg(target_os = "linux")]
+fn command_programs(line: &str) -> Vec<String> {
+    const INTERPRETERS: &[&str] = &["python", "perl", "ruby", "node", "sh", "bash"];
+    let mut args = line.split_whitespace();
+    let Some(program) = args.next().map(program_name) else {
+        return Vec::new();
+    };
+    let interpreted = INTERPRETERS.iter().any(|i| {
+        program
+            .strip_prefix(i)
+            .is_some_and(|version| version.chars().all(|c| c.is_ascii_digit() || c == '.'))
+    });
+    let script = if interpreted {
+        args.find(|arg| !arg.starts_with('-')).map(program_name)
+    } else {
+        None
+    };
+    std::iter::once(program).chain(script).collect()
+}
+
+#[cfg(target_os = "macos")]
+pub fn running_programs() -> Vec<String> {
+    // Full executable paths, which may contain spaces ("Wispr Flow")
+    command_lines("ps", &["-A", "-o", "comm="])
+        .iter()
+        .map(|line| program_name(line.trim()))
+        .collect()
+}
+
+#[cfg(windows)]
+pub fn running_programs() -> Vec<String> {
+    // "Image Name","PID",... one process per line
+    command_lines("tasklist", &["/fo", "csv", "/nh"])
+        .iter()
+        .filter_map(|line| line.split(',').next())
+        .map(|image| program_name(image.trim_matches('"')))
+        .collect()
+}
+
+fn program_name(path: &str) -> String {
+    let name = path.rsplit(['/', '\\']).next().unwrap_or(path).to_lowercase();
+    name.strip_suffix(".exe").map(str::to_string).unwrap_or(name)
+}
+
+fn command_lines(program: &str, args: &[&str]) -> Vec<String> {
+    match std::process::Command::new(program).args(args).output() {
+        Ok(output) if output.status.success() => {
+            String::from_utf8_lossy(&output.stdout).lines().map(str::to_string).collect()
+        }
+        Ok(output) => {
+            log::debug!("{} exited with status {}", program, output.status);
+            Vec::new()
+        }
+        Err(e) => {
+            log::debug!("Could not list processes with {}: {}", program, e);
+            Vec::new()
+        }
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn looks_at_the_script_of_an_interpreter_only() {
+        assert_eq!(command_programs("/usr/bin/python3.11 -u /usr/bin/nerd-dictation begin"), ["python3.11", "nerd-dictation"]);
+        assert_eq!(command_programs("/usr/bin/keyd"), ["keyd"]);
+        assert_eq!(command_programs("vim talon"), ["vim"]);
+        assert_eq!(command_programs("pythonista talon"), ["pythonista"]);
+        assert!(command_programs("").is_empty());
+        assert_eq!(command_programs("/opt/wispr-flow/wispr-flow --hidden"), ["wispr-flow"]);
+    }
+}
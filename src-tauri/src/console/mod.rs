@@ -0,0 +1,47 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter};
+
+// Enough to cover a few sessions without growing unbounded
+const MAX_EVENTS: usize = 500;
+
+static EVENTS: Mutex<VecDeque<ConsoleEvent>> = Mutex::new(VecDeque::new());
+
+/// One line of the live dictation console.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ConsoleEvent {
+    /// Unix time in milliseconds
+    pub timestamp_ms: u64,
+    /// "session", "token", "typed", "typing-error" or "error"
+    pub kind: String,
+    pub message: String,
+}
+
+/// Record an event in the ring buffer and push it to the main window.
+pub fn record(app: &AppHandle, kind: &str, message: impl Into<String>) {
+    let event = ConsoleEvent {
+        timestamp_ms: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64,
+        kind: kind.to_string(),
+        message: message.into(),
+    };
+
+    {
+        let mut events = EVENTS.lock().unwrap();
+        if events.len() == MAX_EVENTS {
+            events.pop_front();
+        }
+        events.push_back(event.clone());
+    }
+
+    app.emit("console-event", event).ok();
+}
+
+/// Most recent events, oldest first. `limit` caps the count from the end.
+pub fn recent(limit: Option<usize>) -> Vec<ConsoleEvent> {
+    let events = EVENTS.lock().unwrap();
+    let skip = limit.map_or(0, |n| events.len().saturating_sub(n));
+    events.iter().skip(skip).cloned().collect()
+}
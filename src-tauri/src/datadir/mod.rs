@@ -0,0 +1,60 @@
+//! Where the stores, recordings, journal, models and plugins live.
+//!
+//! Defaults to Tauri's app data directory. `--config-dir <path>` (or the
+//! `DESKTOP_DICTATE_CONFIG_DIR` environment variable) moves everything into
+//! another directory, for portable installs next to the executable or for
+//! running isolated instances side by side while testing.
+
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use tauri::{AppHandle, Manager};
+
+const ENV_VAR: &str = "DESKTOP_DICTATE_CONFIG_DIR";
+
+static OVERRIDE: OnceLock<Option<PathBuf>> = OnceLock::new();
+
+/// The directory given on the command line or in the environment, if any.
+/// The flag wins over the variable. Relative paths are taken from the
+/// current directory at startup.
+pub fn override_dir() -> Option<&'static PathBuf> {
+    OVERRIDE
+        .get_or_init(|| {
+            let mut args = std::env::args().skip(1);
+            let mut from_args = None;
+            while let Some(arg) = args.next() {
+                if arg == "--config-dir" {
+                    from_args = args.next();
+                } else if let Some(value) = arg.strip_prefix("--config-dir=") {
+                    from_args = Some(value.to_string());
+                }
+            }
+            let dir = from_args
+                .or_else(|| std::env::var(ENV_VAR).ok())
+                .filter(|dir| !dir.trim().is_empty())
+                .map(PathBuf::from)?;
+            Some(std::path::absolute(&dir).unwrap_or(dir))
+        })
+        .as_ref()
+}
+
+/// The data directory in use.
+pub fn dir(app: &AppHandle) -> Result<PathBuf, String> {
+    match override_dir() {
+        Some(dir) => Ok(dir.clone()),
+        None => app
+            .path()
+            .app_data_dir()
+            .map_err(|e| format!("No app data directory: {}", e)),
+    }
+}
+
+/// Create the override directory and log where data goes. Call once at
+/// startup, before any store is opened.
+pub fn init() {
+    if let Some(dir) = override_dir() {
+        match std::fs::create_dir_all(dir) {
+            Ok(()) => log::info!("Using data directory {:?}", dir),
+            Err(e) => log::error!("Cannot create data directory {:?}: {}", dir, e),
+        }
+    }
+}
@@ -0,0 +1,224 @@
+//! Lower other applications' playback while a session is recording, so
+//! music or videos don't bleed into the open microphone.
+//!
+//! On Linux this adjusts PulseAudio/PipeWire sink-input (per-stream)
+//! volumes through `pactl`; on Windows the volume of every other process's
+//! audio session on the default output device (`ISimpleAudioVolume`).
+//! Other platforms log that ducking is unsupported.
+//!
+//! Each session gets a token from `begin`. Ducking runs on a blocking
+//! thread and can finish after the session ended, and a session's cleanup
+//! can run after the next session started; the token keeps a late duck or
+//! restore from touching another session's volumes.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+/// A ducked stream and the volume to restore
+#[cfg(target_os = "linux")]
+type Saved = (u32, u64); // sink input id, raw volume
+#[cfg(windows)]
+type Saved = (u32, f32); // process id, session volume
+#[cfg(not(any(target_os = "linux", windows)))]
+type Saved = ();
+
+struct Ducking {
+    /// Session allowed to duck: the latest one, until it restores
+    active: u64,
+    /// Session the saved volumes belong to
+    owner: u64,
+    saved: Vec<Saved>,
+}
+
+static NEXT_SESSION: AtomicU64 = AtomicU64::new(1);
+static STATE: Mutex<Ducking> = Mutex::new(Ducking { active: 0, owner: 0, saved: Vec::new() });
+
+/// Start a session's ducking and return its token. Streams still ducked by
+/// the previous session stay ducked and now belong to this one.
+pub fn begin() -> u64 {
+    let session = NEXT_SESSION.fetch_add(1, Ordering::Relaxed);
+    let mut state = STATE.lock().unwrap();
+    state.active = session;
+    if !state.saved.is_empty() {
+        state.owner = session;
+    }
+    session
+}
+
+/// Scale every playback stream to `percent` of its current volume
+/// (0 mutes). Does nothing if streams are already ducked, or if `session`
+/// already ended or was superseded.
+pub fn duck(session: u64, percent: u8) {
+    let mut state = STATE.lock().unwrap();
+    if state.active != session || !state.saved.is_empty() {
+        return;
+    }
+    let saved = duck_streams(percent);
+    log::info!("Ducked {} playback stream(s) to {}%", saved.len(), percent);
+    state.owner = session;
+    state.saved = saved;
+}
+
+/// Put streams ducked for `session` back to their previous volume. Streams
+/// that ended in the meantime are skipped.
+pub fn restore(session: u64) {
+    let mut state = STATE.lock().unwrap();
+    if state.active == session {
+        state.active = 0;
+    }
+    if state.owner != session || state.saved.is_empty() {
+        return;
+    }
+    let saved = std::mem::take(&mut state.saved);
+    restore_streams(&saved);
+    log::info!("Restored {} playback stream(s)", saved.len());
+}
+
+/// Restore the streams of the latest session.
+pub fn restore_current() {
+    let session = STATE.lock().unwrap().active;
+    if session != 0 {
+        restore(session);
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn duck_streams(percent: u8) -> Vec<Saved> {
+    sink_input_volumes()
+        .into_iter()
+        .filter(|&(id, volume)| set_sink_input_volume(id, volume * percent.min(100) as u64 / 100))
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+fn restore_streams(saved: &[Saved]) {
+    for (id, volume) in saved {
+        set_sink_input_volume(*id, *volume);
+    }
+}
+
+#[cfg(windows)]
+fn duck_streams(percent: u8) -> Vec<Saved> {
+    let sessions = match windows_audio::sessions() {
+        Ok(sessions) => sessions,
+        Err(e) => {
+            log::warn!("Cannot list audio sessions, not ducking media: {}", e);
+            return Vec::new();
+        }
+    };
+    sessions
+        .into_iter()
+        .filter_map(|(pid, volume)| {
+            let level = windows_audio::volume(&volume)?;
+            windows_audio::set_volume(&volume, level * percent.min(100) as f32 / 100.0).then_some((pid, level))
+        })
+        .collect()
+}
+
+#[cfg(windows)]
+fn restore_streams(saved: &[Saved]) {
+    let sessions = match windows_audio::sessions() {
+        Ok(sessions) => sessions,
+        Err(e) => return log::warn!("Cannot list audio sessions, not restoring media: {}", e),
+    };
+    for (pid, level) in saved {
+        for (_, volume) in sessions.iter().filter(|(p, _)| p == pid) {
+            windows_audio::set_volume(volume, *level);
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+fn duck_streams(percent: u8) -> Vec<Saved> {
+    log::info!("Media ducking to {}% is not supported on this platform", percent);
+    Vec::new()
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+fn restore_streams(_saved: &[Saved]) {}
+
+/// Parse `pactl list sink-inputs` into (id, raw volume of the first channel).
+#[cfg(target_os = "linux")]
+fn sink_input_volumes() -> Vec<(u32, u64)> {
+    let output = match std::process::Command::new("pactl").args(["list", "sink-inputs"]).output() {
+        Ok(out) if out.status.success() => String::from_utf8_lossy(&out.stdout).into_owned(),
+        Ok(_) | Err(_) => {
+            log::warn!("pactl unavailable, cannot duck media");
+            return Vec::new();
+        }
+    };
+
+    let mut streams = Vec::new();
+    let mut current: Option<u32> = None;
+    for line in output.lines() {
+        let line = line.trim();
+        if let Some(id) = line.strip_prefix("Sink Input #") {
+            current = id.trim().parse().ok();
+        } else if let (Some(id), Some(rest)) = (current, line.strip_prefix("Volume:")) {
+            // "front-left: 65536 / 100% / 0.00 dB,   front-right: ..."
+            let raw = rest
+                .split(':')
+                .nth(1)
+                .and_then(|v| v.split('/').next())
+                .and_then(|v| v.trim().parse::<u64>().ok());
+            if let Some(raw) = raw {
+                streams.push((id, raw));
+            }
+            current = None;
+        }
+    }
+    streams
+}
+
+#[cfg(target_os = "linux")]
+fn set_sink_input_volume(id: u32, volume: u64) -> bool {
+    std::process::Command::new("pactl")
+        .args(["set-sink-input-volume", &id.to_string(), &volume.to_string()])
+        .status()
+        .map(|s| s.success())
+        .unwrap_or(false)
+}
+
+/// Per-process audio sessions of the default output device (Core Audio).
+#[cfg(windows)]
+mod windows_audio {
+    use windows::core::Interface;
+    use windows::Win32::Media::Audio::{
+        eMultimedia, eRender, IAudioSessionControl2, IAudioSessionManager2, IMMDeviceEnumerator,
+        ISimpleAudioVolume, MMDeviceEnumerator,
+    };
+    use windows::Win32::System::Com::{CoCreateInstance, CoInitializeEx, CLSCTX_ALL, COINIT_MULTITHREADED};
+
+    /// (process id, volume control) of every playback session except our
+    /// own and the system sounds.
+    pub fn sessions() -> windows::core::Result<Vec<(u32, ISimpleAudioVolume)>> {
+        unsafe {
+            // Already initialized on this thread is fine too
+            let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+            let enumerator: IMMDeviceEnumerator = CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL)?;
+            let device = enumerator.GetDefaultAudioEndpoint(eRender, eMultimedia)?;
+            let manager: IAudioSessionManager2 = device.Activate(CLSCTX_ALL, None)?;
+            let list = manager.GetSessionEnumerator()?;
+
+            let own = std::process::id();
+            let mut sessions = Vec::new();
+            for i in 0..list.GetCount()? {
+                let control = list.GetSession(i)?;
+                let pid = control.cast::<IAudioSessionControl2>()?.GetProcessId().unwrap_or(0);
+                if pid == 0 || pid == own {
+                    continue;
+                }
+                sessions.push((pid, control.cast::<ISimpleAudioVolume>()?));
+            }
+            Ok(sessions)
+        }
+    }
+
+    pub fn volume(volume: &ISimpleAudioVolume) -> Option<f32> {
+        unsafe { volume.GetMasterVolume() }.ok()
+    }
+
+    pub fn set_volume(volume: &ISimpleAudioVolume, level: f32) -> bool {
+        unsafe { volume.SetMasterVolume(level.clamp(0.0, 1.0), std::ptr::null()) }.is_ok()
+    }
+}
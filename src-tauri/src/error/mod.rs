@@ -0,0 +1,71 @@
+//! Session errors by cause, so the UI can point at the fix (settings,
+//! billing, network, microphone, typing tools) instead of showing the raw
+//! provider or OS message.
+//!
+//! Serialized as `{"kind": "audio-device", "message": "..."}` in the
+//! `recording-error` and `transcription-error` events.
+
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(tag = "kind", content = "message", rename_all = "kebab-case")]
+pub enum TranscriptionError {
+    /// The provider rejected the API key
+    Auth(String),
+    /// Out of balance or over a usage limit
+    Quota(String),
+    /// The provider could not be reached or the connection dropped
+    Network(String),
+    /// No microphone, or it could not be opened or went away
+    AudioDevice(String),
+    /// Text could not be inserted into the target window
+    Typing(String),
+    /// The hinted or restricted language is not supported
+    UnsupportedLanguage(String),
+    /// Anything else the provider reported, e.g. an invalid request
+    Provider(String),
+}
+
+impl TranscriptionError {
+    /// Classify an error code and message reported by a provider.
+    pub fn from_provider(code: &str, message: &str) -> Self {
+        let msg = message.to_lowercase();
+        let text = format!("{} - {}", code, message);
+        match code {
+            "401" | "403" => Self::Auth(text),
+            "402" => Self::Quota(text),
+            "429" if msg.contains("quota") || msg.contains("limit") => Self::Quota(text),
+            _ if msg.contains("api key") || msg.contains("unauthorized") => Self::Auth(text),
+            _ if msg.contains("quota") || msg.contains("balance") => Self::Quota(text),
+            _ if msg.contains("language") && (msg.contains("unsupported") || msg.contains("not supported")) => {
+                Self::UnsupportedLanguage(text)
+            }
+            _ => Self::Provider(text),
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            Self::Auth(m)
+            | Self::Quota(m)
+            | Self::Network(m)
+            | Self::AudioDevice(m)
+            | Self::Typing(m)
+            | Self::UnsupportedLanguage(m)
+            | Self::Provider(m) => m,
+        }
+    }
+}
+
+impl fmt::Display for TranscriptionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+/// Commands still report plain strings to the frontend.
+impl From<TranscriptionError> for String {
+    fn from(error: TranscriptionError) -> Self {
+        error.message().to_string()
+    }
+}
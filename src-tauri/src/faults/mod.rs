@@ -0,0 +1,93 @@
+//! Failure injection for QA.
+//!
+//! In debug builds, or with `DICTATE_DEBUG_FAULTS=1` in the environment,
+//! the command palette lists hidden `debug_fault:<name>` actions. Each arms
+//! a simulated failure that fires once, at the next point where the real
+//! failure could happen:
+//!
+//! - `provider_error`: the next session fails instead of reaching the provider
+//! - `typing_failure`: the next insertion fails (arm twice to fail two)
+//! - `device_loss`: the microphone of the running or next session stops
+//!   delivering audio, as if it was unplugged
+//!
+//! `debug_fault:clear` disarms everything. This exercises notifications,
+//! state resets and the overlay without breaking hardware or networks.
+
+use std::sync::atomic::{AtomicU32, Ordering};
+
+const ENV_VAR: &str = "DICTATE_DEBUG_FAULTS";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Fault {
+    ProviderError,
+    TypingFailure,
+    DeviceLoss,
+}
+
+pub const ALL: [Fault; 3] = [Fault::ProviderError, Fault::TypingFailure, Fault::DeviceLoss];
+
+// How many times each fault is still armed
+static PROVIDER_ERROR: AtomicU32 = AtomicU32::new(0);
+static TYPING_FAILURE: AtomicU32 = AtomicU32::new(0);
+static DEVICE_LOSS: AtomicU32 = AtomicU32::new(0);
+
+impl Fault {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::ProviderError => "provider_error",
+            Self::TypingFailure => "typing_failure",
+            Self::DeviceLoss => "device_loss",
+        }
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Self::ProviderError => "Fail the next session's provider connection",
+            Self::TypingFailure => "Fail the next text insertion",
+            Self::DeviceLoss => "Lose the microphone",
+        }
+    }
+
+    pub fn parse(name: &str) -> Result<Self, String> {
+        ALL.into_iter()
+            .find(|fault| fault.name() == name)
+            .ok_or_else(|| format!("Unknown debug fault '{}'", name))
+    }
+
+    fn counter(self) -> &'static AtomicU32 {
+        match self {
+            Self::ProviderError => &PROVIDER_ERROR,
+            Self::TypingFailure => &TYPING_FAILURE,
+            Self::DeviceLoss => &DEVICE_LOSS,
+        }
+    }
+}
+
+/// Whether the debug actions are available.
+pub fn enabled() -> bool {
+    cfg!(debug_assertions) || std::env::var(ENV_VAR).is_ok_and(|v| v == "1")
+}
+
+pub fn arm(fault: Fault) {
+    let armed = fault.counter().fetch_add(1, Ordering::SeqCst) + 1;
+    log::warn!("Debug fault '{}' armed ({} pending)", fault.name(), armed);
+}
+
+pub fn clear() {
+    for fault in ALL {
+        fault.counter().store(0, Ordering::SeqCst);
+    }
+    log::warn!("Debug faults cleared");
+}
+
+/// Consume one arming of `fault`; true if the caller should fail now.
+pub fn fire(fault: Fault) -> bool {
+    let fired = fault
+        .counter()
+        .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+        .is_ok();
+    if fired {
+        log::warn!("Injecting debug fault '{}'", fault.name());
+    }
+    fired
+}
@@ -0,0 +1,137 @@
+//! Watch mode: transcribe recordings dropped into a folder.
+//!
+//! New WAV, MP3 and OGG files in `watch_folder` (e.g. phone voice memos
+//! synced with Syncthing) are transcribed one at a time like
+//! `transcribe_file` and added to history; with `watch_folder_write_text`
+//! the transcript is also written next to the recording as `<name>.txt`.
+//! Files already there when watching starts are left alone. A file is only
+//! picked up once its size stops changing, since sync tools and recorders
+//! write in several steps.
+
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::sync::mpsc;
+
+const AUDIO_EXTENSIONS: [&str; 3] = ["wav", "mp3", "ogg"];
+// How often a new file's size is checked until it stops growing
+const SETTLE_INTERVAL: Duration = Duration::from_secs(2);
+
+/// The running watcher; replacing or dropping it stops the old one.
+static WATCHER: Mutex<Option<(PathBuf, RecommendedWatcher)>> = Mutex::new(None);
+
+/// Start, restart or stop watching to match `watch_folder`. Called at
+/// startup and whenever settings are saved.
+pub fn sync(app: &AppHandle) {
+    let folder = {
+        let state = app.state::<crate::AppState>();
+        let settings = state.settings.lock().unwrap();
+        settings
+            .watch_folder
+            .as_deref()
+            .map(str::trim)
+            .filter(|f| !f.is_empty())
+            .map(PathBuf::from)
+    };
+
+    let mut current = WATCHER.lock().unwrap();
+    if current.as_ref().map(|(path, _)| path) == folder.as_ref() {
+        return;
+    }
+    *current = None;
+    let Some(folder) = folder else { return };
+    match start(app.clone(), &folder) {
+        Ok(watcher) => {
+            log::info!("Watching {:?} for new recordings", folder);
+            *current = Some((folder, watcher));
+        }
+        Err(e) => {
+            log::error!("Cannot watch {:?}: {}", folder, e);
+            crate::console::record(app, "error", format!("Watch folder: {}", e));
+        }
+    }
+}
+
+fn start(app: AppHandle, folder: &Path) -> Result<RecommendedWatcher, String> {
+    if !folder.is_dir() {
+        return Err(format!("{:?} is not a folder", folder));
+    }
+    let (tx, rx) = mpsc::unbounded_channel::<PathBuf>();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| match event {
+        Ok(event) if is_new_file(&event.kind) => {
+            for path in event.paths.into_iter().filter(|p| is_audio(p)) {
+                tx.send(path).ok();
+            }
+        }
+        Ok(_) => {}
+        Err(e) => log::warn!("Watch folder error: {}", e),
+    })
+    .map_err(|e| e.to_string())?;
+    watcher
+        .watch(folder, RecursiveMode::NonRecursive)
+        .map_err(|e| e.to_string())?;
+    tauri::async_runtime::spawn(process(app, rx));
+    Ok(watcher)
+}
+
+/// Sync tools write to a temp file and rename it, so renames count as new files.
+fn is_new_file(kind: &EventKind) -> bool {
+    matches!(kind, EventKind::Create(_) | EventKind::Modify(notify::event::ModifyKind::Name(_)))
+}
+
+fn is_audio(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| AUDIO_EXTENSIONS.contains(&e.to_ascii_lowercase().as_str()))
+}
+
+/// Transcribe queued files in order until the watcher is dropped.
+async fn process(app: AppHandle, mut rx: mpsc::UnboundedReceiver<PathBuf>) {
+    let mut done: HashSet<PathBuf> = HashSet::new();
+    while let Some(path) = rx.recv().await {
+        // A file can be reported several times while it is written
+        if done.contains(&path) || !wait_until_settled(&path).await {
+            continue;
+        }
+        done.insert(path.clone());
+
+        let file = path.to_string_lossy().into_owned();
+        log::info!("Watch folder: transcribing {:?}", path);
+        match crate::transcribe_file_to_history(&app, &file).await {
+            Ok(text) => {
+                crate::console::record(&app, "session", format!("Transcribed {}", file));
+                let write_text = app.state::<crate::AppState>().settings.lock().unwrap().watch_folder_write_text;
+                if write_text {
+                    let txt = path.with_extension("txt");
+                    if let Err(e) = tokio::fs::write(&txt, format!("{}\n", text)).await {
+                        log::error!("Failed to write {:?}: {}", txt, e);
+                    }
+                }
+            }
+            Err(e) => {
+                log::error!("Watch folder: {}", e);
+                crate::console::record(&app, "error", format!("Watch folder: {}", e));
+            }
+        }
+    }
+}
+
+/// Wait until `path` exists with the same non-zero size twice in a row.
+/// False if it disappeared (a temp file that was renamed away).
+async fn wait_until_settled(path: &Path) -> bool {
+    let mut last = None;
+    loop {
+        let size = match tokio::fs::metadata(path).await {
+            Ok(meta) => meta.len(),
+            Err(_) => return false,
+        };
+        if size > 0 && last == Some(size) {
+            return true;
+        }
+        last = Some(size);
+        tokio::time::sleep(SETTLE_INTERVAL).await;
+    }
+}
@@ -0,0 +1,80 @@
+//! Headless mode (`headless` feature) for kiosks and minimal installs.
+//!
+//! Only the tray, the hotkeys and the transcription pipeline run; the main
+//! settings window is removed from the config before startup, so its
+//! webview is never created. Settings come from a TOML file with the same
+//! keys as `settings.json` (`--config <path>`, default `config.toml` in the
+//! `--config-dir` directory if one is given, else
+//! `$XDG_CONFIG_HOME/desktop-dictate/config.toml`) plus any number of
+//! `--set key=value` overrides. Missing keys keep their defaults.
+
+use std::path::PathBuf;
+
+/// Settings from the config file and command line.
+pub fn load_settings() -> Result<crate::AppSettings, String> {
+    let mut path = default_config_path();
+    let mut overrides = Vec::new();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--config" => path = args.next().map(PathBuf::from).ok_or("--config needs a file path")?,
+            "--set" => overrides.push(args.next().ok_or("--set needs a key=value argument")?),
+            _ => {}
+        }
+    }
+
+    let mut settings = match std::fs::read_to_string(&path) {
+        Ok(text) => toml::from_str(&text).map_err(|e| format!("Invalid config file {:?}: {}", path, e))?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            log::warn!("No config file at {:?}, using defaults", path);
+            crate::AppSettings::default()
+        }
+        Err(e) => return Err(format!("Failed to read config file {:?}: {}", path, e)),
+    };
+    for arg in &overrides {
+        settings = apply_override(settings, arg)?;
+    }
+    log::info!("Headless mode, settings from {:?}", path);
+    Ok(settings)
+}
+
+/// Drop the main window from the app config so it is never built.
+pub fn remove_main_window<R: tauri::Runtime>(context: &mut tauri::Context<R>) {
+    context.config_mut().app.windows.retain(|w| w.label != "main");
+}
+
+fn default_config_path() -> PathBuf {
+    // A portable or isolated instance keeps its config with its data
+    if let Some(dir) = crate::datadir::override_dir() {
+        return dir.join("config.toml");
+    }
+    std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .unwrap_or_default()
+        .join("desktop-dictate")
+        .join("config.toml")
+}
+
+/// Apply one `key=value` override. The value is read as a TOML value
+/// (`true`, `500`, `["en", "de"]`) and taken as a plain string otherwise.
+fn apply_override(settings: crate::AppSettings, arg: &str) -> Result<crate::AppSettings, String> {
+    let (key, raw) = arg
+        .split_once('=')
+        .ok_or_else(|| format!("Invalid --set '{}', expected key=value", arg))?;
+    let key = key.trim();
+    let value = toml::from_str::<toml::Table>(&format!("v = {}", raw))
+        .ok()
+        .and_then(|mut table| table.remove("v"))
+        .map(|v| serde_json::to_value(v).map_err(|e| e.to_string()))
+        .transpose()?
+        .unwrap_or_else(|| serde_json::Value::String(raw.to_string()));
+
+    let mut json = serde_json::to_value(&settings).map_err(|e| e.to_string())?;
+    let fields = json.as_object_mut().ok_or("Settings are not an object")?;
+    if !fields.contains_key(key) {
+        return Err(format!("Unknown setting '{}'", key));
+    }
+    fields.insert(key.to_string(), value);
+    serde_json::from_value(json).map_err(|e| format!("Invalid value for '{}': {}", key, e))
+}
@@ -0,0 +1,94 @@
+//! Translations for user-facing strings generated in the backend (errors,
+//! warnings, tray menu, command palette titles). Messages live in Fluent files under `locales/`
+//! and are compiled into the binary. The language follows the
+//! `ui_language` setting; keys missing from a locale fall back to English.
+//! Each locale is parsed into a bundle once, on first use.
+
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource, FluentValue};
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use unic_langid::LanguageIdentifier;
+
+const FALLBACK_LANGUAGE: &str = "en";
+
+/// (language, Fluent source) for every bundled locale.
+const LOCALES: &[(&str, &str)] = &[
+    ("en", include_str!("../../locales/en.ftl")),
+    ("de", include_str!("../../locales/de.ftl")),
+    ("tr", include_str!("../../locales/tr.ftl")),
+];
+
+static LANGUAGE: Mutex<String> = Mutex::new(String::new());
+static BUNDLES: OnceLock<HashMap<&'static str, FluentBundle<FluentResource>>> = OnceLock::new();
+
+/// Switch the language used by `t` / `t_args`. Unknown languages use English.
+pub fn set_language(language: &str) {
+    let language = if LOCALES.iter().any(|(l, _)| *l == language) {
+        language
+    } else {
+        log::warn!("No translations for '{}', using {}", language, FALLBACK_LANGUAGE);
+        FALLBACK_LANGUAGE
+    };
+    *LANGUAGE.lock().unwrap() = language.to_string();
+}
+
+/// The message for `key` in the current language.
+pub fn t(key: &str) -> String {
+    t_args(key, &[])
+}
+
+/// The message for `key` in the current language, with `$name` placeables
+/// filled from `args`.
+pub fn t_args(key: &str, args: &[(&str, String)]) -> String {
+    let language = LANGUAGE.lock().unwrap().clone();
+    let mut fluent_args = FluentArgs::new();
+    for (name, value) in args {
+        fluent_args.set(*name, FluentValue::from(value.clone()));
+    }
+
+    [language.as_str(), FALLBACK_LANGUAGE]
+        .iter()
+        .find_map(|l| format(l, key, &fluent_args))
+        .unwrap_or_else(|| {
+            log::warn!("Missing translation for '{}'", key);
+            key.to_string()
+        })
+}
+
+/// Parsed bundles of every locale that loads.
+fn bundles() -> &'static HashMap<&'static str, FluentBundle<FluentResource>> {
+    BUNDLES.get_or_init(|| {
+        LOCALES
+            .iter()
+            .filter_map(|(language, source)| match bundle(language, source) {
+                Ok(bundle) => Some((*language, bundle)),
+                Err(e) => {
+                    log::error!("Failed to load the {} translations: {}", language, e);
+                    None
+                }
+            })
+            .collect()
+    })
+}
+
+fn bundle(language: &str, source: &str) -> Result<FluentBundle<FluentResource>, String> {
+    let resource = FluentResource::try_new(source.to_string()).map_err(|(_, errors)| format!("{:?}", errors))?;
+    let langid: LanguageIdentifier = language.parse().map_err(|e| format!("{:?}", e))?;
+    let mut bundle = FluentBundle::new_concurrent(vec![langid]);
+    // The UI renders plain text; Unicode isolation marks would show up as junk
+    bundle.set_use_isolating(false);
+    bundle.add_resource(resource).map_err(|errors| format!("{:?}", errors))?;
+    Ok(bundle)
+}
+
+fn format(language: &str, key: &str, args: &FluentArgs) -> Option<String> {
+    let bundle = bundles().get(language)?;
+    let pattern = bundle.get_message(key)?.value()?;
+    let mut errors = Vec::new();
+    let text = bundle.format_pattern(pattern, Some(args), &mut errors);
+    if !errors.is_empty() {
+        log::warn!("Errors formatting '{}' ({}): {:?}", key, language, errors);
+    }
+    Some(text.into_owned())
+}
@@ -0,0 +1,168 @@
+//! Import transcripts from other dictation tools into history.
+//!
+//! Supported sources:
+//! - `talon`: a Talon log; lines with a `YYYY-MM-DD HH:MM:SS` timestamp and
+//!   a recognized phrase after the `[~]` marker become one entry each
+//! - `nerd-dictation`: text captured from `nerd-dictation --output STDOUT`,
+//!   one utterance per line, dated by the file's modification time
+//! - `text-folder`: a folder of `.txt` files, one entry per file, dated by
+//!   modification time
+//!
+//! Entries already in history (same time and text) are skipped, the rest
+//! are merged in by time. Imported entries don't count against the history
+//! size limit, so a large archive is kept whole.
+
+use serde::Serialize;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Talon,
+    NerdDictation,
+    TextFolder,
+}
+
+impl Format {
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "talon" => Ok(Self::Talon),
+            "nerd-dictation" => Ok(Self::NerdDictation),
+            "text-folder" => Ok(Self::TextFolder),
+            other => Err(format!(
+                "Unknown import format '{}' (expected talon, nerd-dictation or text-folder)",
+                other
+            )),
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Talon => "talon",
+            Self::NerdDictation => "nerd-dictation",
+            Self::TextFolder => "text-folder",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ImportReport {
+    /// Entries found in the source
+    pub found: usize,
+    /// Entries added to history
+    pub imported: usize,
+    /// Entries already in history
+    pub duplicates: usize,
+}
+
+/// Read `path` in `format` as (timestamp, text) pairs, oldest first.
+pub fn read(path: &Path, format: Format) -> Result<Vec<(u64, String)>, String> {
+    let mut found = match format {
+        Format::Talon => read_talon(&read_text(path)?),
+        Format::NerdDictation => {
+            let timestamp = modified(path);
+            read_text(path)?
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(|line| (timestamp, line.to_string()))
+                .collect()
+        }
+        Format::TextFolder => read_text_folder(path)?,
+    };
+    // Stable, so lines sharing a file timestamp keep their order
+    found.sort_by_key(|(timestamp, _)| *timestamp);
+    log::info!("Read {} entries from {:?} ({})", found.len(), path, format.label());
+    Ok(found)
+}
+
+/// Merge imported entries into `entries` (newest first).
+pub fn merge(entries: &mut Vec<crate::TranscriptionEntry>, found: Vec<(u64, String)>) -> ImportReport {
+    let mut report = ImportReport { found: found.len(), ..Default::default() };
+    for (i, (timestamp, text)) in found.into_iter().enumerate() {
+        if entries.iter().any(|e| e.timestamp == timestamp && e.text == text) {
+            report.duplicates += 1;
+            continue;
+        }
+        entries.push(crate::TranscriptionEntry {
+            id: format!("import-{}-{}", timestamp, i),
+            text,
+            timestamp,
+            language: String::new(),
+            audio_path: None,
+            words: None,
+            comparison: None,
+            app: None,
+            title: None,
+            source: crate::EntrySource::Import,
+            merged: false,
+        });
+        report.imported += 1;
+    }
+    entries.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    report
+}
+
+fn read_text(path: &Path) -> Result<String, String> {
+    let bytes = std::fs::read(path).map_err(|e| format!("Failed to read {:?}: {}", path, e))?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+fn read_talon(log: &str) -> Vec<(u64, String)> {
+    log.lines()
+        .filter_map(|line| {
+            let timestamp = parse_datetime(line.get(..19)?)?;
+            let (_, phrase) = line.split_once("[~]")?;
+            let phrase = phrase.trim();
+            (!phrase.is_empty()).then(|| (timestamp, phrase.to_string()))
+        })
+        .collect()
+}
+
+fn read_text_folder(dir: &Path) -> Result<Vec<(u64, String)>, String> {
+    let entries = std::fs::read_dir(dir).map_err(|e| format!("Failed to read folder {:?}: {}", dir, e))?;
+    let mut found = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("txt") {
+            continue;
+        }
+        let text = read_text(&path)?;
+        let text = text.trim();
+        if !text.is_empty() {
+            found.push((modified(&path), text.to_string()));
+        }
+    }
+    Ok(found)
+}
+
+fn modified(path: &Path) -> u64 {
+    std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map_or_else(crate::unix_now, |d| d.as_secs())
+}
+
+/// `YYYY-MM-DD HH:MM:SS` as unix seconds. Logs carry no zone; local time
+/// is taken as UTC, which only shifts imported entries by the offset.
+pub fn parse_datetime(s: &str) -> Option<u64> {
+    let num = |range: std::ops::Range<usize>| s.get(range)?.parse::<i64>().ok();
+    if s.len() != 19 || s.get(4..5) != Some("-") || s.get(7..8) != Some("-") || s.get(13..14) != Some(":") {
+        return None;
+    }
+    let (year, month, day) = (num(0..4)?, num(5..7)?, num(8..10)?);
+    let (hour, minute, second) = (num(11..13)?, num(14..16)?, num(17..19)?);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+    // Days since 1970-01-01 (Howard Hinnant's days_from_civil)
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146_097 + doe - 719_468;
+    u64::try_from(days * 86_400 + hour * 3600 + minute * 60 + second).ok()
+}
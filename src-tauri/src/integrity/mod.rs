@@ -0,0 +1,148 @@
+//! Startup integrity checks for the JSON stores.
+//!
+//! The store plugin silently starts empty when a file fails to parse, and the
+//! next save then overwrites whatever was left. Before any store is opened we
+//! validate each file: good files are rotated into `<name>.bak` /
+//! `<name>.bak.1`, corrupted ones are moved aside and replaced with the newest
+//! valid backup. Problems are kept for the UI to show as a recovery notice.
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::AppHandle;
+
+/// Backup generations kept per store: `.bak`, `.bak.1`, ...
+const BACKUP_GENERATIONS: usize = 2;
+
+/// A store that failed validation at startup.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct StoreIssue {
+    pub store: String,
+    pub error: String,
+    /// Backup the store was restored from; None if it starts empty
+    pub restored_from: Option<String>,
+    /// Where the corrupted file was moved
+    pub corrupt_copy: Option<String>,
+}
+
+static ISSUES: Mutex<Vec<StoreIssue>> = Mutex::new(Vec::new());
+
+/// Check every store file. Must run before the stores are first opened.
+pub fn check_stores(app: &AppHandle) {
+    let dir = match crate::datadir::dir(app) {
+        Ok(dir) => dir,
+        Err(e) => {
+            log::warn!("No app data dir, skipping store integrity checks: {}", e);
+            return;
+        }
+    };
+
+    let stores: [(&str, fn(&serde_json::Value) -> Result<(), String>); 2] = [
+        (crate::STORE_PATH, |v| {
+            match v.get("settings") {
+                Some(settings) => serde_json::from_value::<crate::AppSettings>(settings.clone())
+                    .map(|_| ())
+                    .map_err(|e| e.to_string()),
+                None => Ok(()),
+            }
+        }),
+        (crate::TRANSCRIPTIONS_STORE_PATH, |v| {
+            match v.get("entries") {
+                Some(entries) => serde_json::from_value::<Vec<crate::TranscriptionEntry>>(entries.clone())
+                    .map(|_| ())
+                    .map_err(|e| e.to_string()),
+                None => Ok(()),
+            }
+        }),
+    ];
+
+    for (name, validate) in stores {
+        if let Some(issue) = check_store(&dir.join(name), name, validate) {
+            log::error!("Store {} was corrupted: {}", name, issue.error);
+            ISSUES.lock().unwrap().push(issue);
+        }
+    }
+}
+
+/// Problems found at startup that the user hasn't dismissed yet.
+pub fn issues() -> Vec<StoreIssue> {
+    ISSUES.lock().unwrap().clone()
+}
+
+pub fn dismiss() {
+    ISSUES.lock().unwrap().clear();
+}
+
+fn validate_file(path: &Path, validate: fn(&serde_json::Value) -> Result<(), String>) -> Result<(), String> {
+    let bytes = std::fs::read(path).map_err(|e| e.to_string())?;
+    let value: serde_json::Value = serde_json::from_slice(&bytes).map_err(|e| e.to_string())?;
+    if !value.is_object() {
+        return Err("not a JSON object".to_string());
+    }
+    validate(&value)
+}
+
+fn backup_path(path: &Path, generation: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    if generation == 0 {
+        name.push(".bak");
+    } else {
+        name.push(format!(".bak.{}", generation));
+    }
+    PathBuf::from(name)
+}
+
+fn check_store(
+    path: &Path,
+    name: &str,
+    validate: fn(&serde_json::Value) -> Result<(), String>,
+) -> Option<StoreIssue> {
+    if !path.exists() {
+        return None;
+    }
+
+    let error = match validate_file(path, validate) {
+        Ok(()) => {
+            rotate_backups(path);
+            return None;
+        }
+        Err(e) => e,
+    };
+
+    // Keep the broken file for manual recovery instead of letting the next
+    // save overwrite it
+    let mut corrupt = path.as_os_str().to_owned();
+    corrupt.push(format!(".corrupt-{}", crate::unix_now()));
+    let corrupt = PathBuf::from(corrupt);
+    let corrupt_copy = match std::fs::rename(path, &corrupt) {
+        Ok(()) => Some(corrupt.to_string_lossy().into_owned()),
+        Err(e) => {
+            log::error!("Could not move corrupted {:?} aside: {}", path, e);
+            None
+        }
+    };
+
+    let restored_from = (0..BACKUP_GENERATIONS)
+        .map(|g| backup_path(path, g))
+        .find(|backup| validate_file(backup, validate).is_ok() && std::fs::copy(backup, path).is_ok())
+        .map(|backup| backup.to_string_lossy().into_owned());
+
+    Some(StoreIssue {
+        store: name.to_string(),
+        error,
+        restored_from,
+        corrupt_copy,
+    })
+}
+
+/// Shift `.bak` -> `.bak.1` -> ... and copy the current (valid) file to `.bak`.
+fn rotate_backups(path: &Path) {
+    for generation in (1..BACKUP_GENERATIONS).rev() {
+        let older = backup_path(path, generation - 1);
+        if older.exists() {
+            let _ = std::fs::rename(&older, backup_path(path, generation));
+        }
+    }
+    if let Err(e) = std::fs::copy(path, backup_path(path, 0)) {
+        log::warn!("Could not back up {:?}: {}", path, e);
+    }
+}
@@ -0,0 +1,162 @@
+//! Audit journal of typed text.
+//!
+//! With `audit_journal` on, each session gets a JSON Lines file under
+//! `journal/` in the app data directory with one record per keyboard
+//! action: exactly the text that was inserted (history keeps the session
+//! text, which may be merged with other sessions or edited later), when,
+//! through which backend (`xclip-paste`, `ydotool`, `enigo`, ...) and into
+//! which target. Erasures by provisional typing are recorded as well.
+//! Records are appended as they happen, so a crash loses nothing.
+//!
+//! `export` joins all sessions into one CSV file.
+
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalRecord {
+    /// Session id, the same as its history entry's
+    pub session: String,
+    /// Unix milliseconds
+    pub timestamp_ms: u64,
+    /// "insert", or "erase" (text is then the number of characters)
+    pub action: String,
+    pub backend: String,
+    /// Target id the text was sent to ("active", "window=...", ...)
+    pub target: String,
+    /// Application the session dictated into, when known
+    pub app: Option<String>,
+    pub text: String,
+}
+
+struct OpenJournal {
+    session: String,
+    app: Option<String>,
+    file: std::fs::File,
+}
+
+/// The journal insertions are currently written to.
+static CURRENT: Mutex<Option<OpenJournal>> = Mutex::new(None);
+
+fn journal_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    crate::datadir::dir(app).map(|dir| dir.join("journal"))
+}
+
+/// Start journaling session `session`, or stop journaling when the setting
+/// is off. Insertions keep going to a session's journal until the next
+/// one begins, since typing can finish after recording stopped.
+pub fn begin(app: &AppHandle, session: &str, target_app: Option<&crate::typer::FocusedApp>) {
+    let enabled = app.state::<crate::AppState>().settings.lock().unwrap().audit_journal;
+    let mut current = CURRENT.lock().unwrap();
+    *current = None;
+    if !enabled {
+        return;
+    }
+    let opened = journal_dir(app).and_then(|dir| {
+        std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {:?}: {}", dir, e))?;
+        let path = dir.join(format!("{}.jsonl", session));
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|e| format!("Failed to open {:?}: {}", path, e))
+    });
+    match opened {
+        Ok(file) => {
+            *current = Some(OpenJournal {
+                session: session.to_string(),
+                app: target_app.map(|a| a.app_id.clone()).filter(|id| !id.is_empty()),
+                file,
+            })
+        }
+        Err(e) => {
+            log::error!("Audit journal: {}", e);
+            crate::console::record(app, "error", format!("Audit journal: {}", e));
+        }
+    }
+}
+
+/// Append a record to the current session's journal, if journaling.
+pub fn record(action: &str, backend: &str, target: &str, text: &str) {
+    let mut current = CURRENT.lock().unwrap();
+    let Some(journal) = current.as_mut() else { return };
+    let record = JournalRecord {
+        session: journal.session.clone(),
+        timestamp_ms: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64,
+        action: action.to_string(),
+        backend: backend.to_string(),
+        target: target.to_string(),
+        app: journal.app.clone(),
+        text: text.to_string(),
+    };
+    let line = match serde_json::to_string(&record) {
+        Ok(line) => line,
+        Err(e) => return log::warn!("Audit journal: {}", e),
+    };
+    if let Err(e) = writeln!(journal.file, "{}", line) {
+        log::warn!("Audit journal write failed: {}", e);
+    }
+}
+
+/// All records of all sessions, oldest first.
+fn read_all(dir: &Path) -> Result<Vec<JournalRecord>, String> {
+    let mut files: Vec<PathBuf> = match std::fs::read_dir(dir) {
+        Ok(entries) => entries
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|e| e == "jsonl"))
+            .collect(),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+        Err(e) => return Err(format!("Failed to read {:?}: {}", dir, e)),
+    };
+    files.sort();
+
+    let mut records = Vec::new();
+    for file in files {
+        let text = std::fs::read_to_string(&file).map_err(|e| format!("Failed to read {:?}: {}", file, e))?;
+        for line in text.lines().filter(|l| !l.trim().is_empty()) {
+            match serde_json::from_str::<JournalRecord>(line) {
+                Ok(record) => records.push(record),
+                // A line cut off by a crash; the rest of the file is fine
+                Err(e) => log::warn!("Skipping unreadable journal line in {:?}: {}", file, e),
+            }
+        }
+    }
+    records.sort_by_key(|r| r.timestamp_ms);
+    Ok(records)
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Write every journaled record to `path` as CSV. Returns the record count.
+pub fn export(app: &AppHandle, path: &Path) -> Result<usize, String> {
+    let records = read_all(&journal_dir(app)?)?;
+    let mut out = String::from("timestamp_ms,session,action,backend,target,app,text\n");
+    for r in &records {
+        let fields = [
+            r.timestamp_ms.to_string(),
+            r.session.clone(),
+            r.action.clone(),
+            r.backend.clone(),
+            r.target.clone(),
+            r.app.clone().unwrap_or_default(),
+            r.text.clone(),
+        ];
+        out.push_str(&fields.iter().map(|f| csv_field(f)).collect::<Vec<_>>().join(","));
+        out.push('\n');
+    }
+    std::fs::write(path, out).map_err(|e| format!("Failed to write {:?}: {}", path, e))?;
+    Ok(records.len())
+}
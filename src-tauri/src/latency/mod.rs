@@ -0,0 +1,96 @@
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+// PCM s16le mono at 16 kHz: 2 bytes per sample, 16 samples per millisecond
+const BYTES_PER_MS: u64 = 32;
+
+/// Collects per-session latency samples for the dictation pipeline:
+/// - capture -> send: audio callback until the chunk is queued on the WebSocket
+/// - provider round-trip: audio sent until Soniox finalizes tokens covering it
+/// - insertion: final text received until the typer has inserted it
+#[derive(Default)]
+pub struct LatencyTracker {
+    capture_to_send: Vec<Duration>,
+    provider_round_trip: Vec<Duration>,
+    insertion: Vec<Duration>,
+    // (audio position in ms at the end of the chunk, time the chunk was sent)
+    sent_audio: Vec<(u64, Instant)>,
+    sent_audio_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LatencySummary {
+    pub samples: usize,
+    pub min_ms: f64,
+    pub avg_ms: f64,
+    pub p95_ms: f64,
+    pub max_ms: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LatencyReport {
+    pub capture_to_send: LatencySummary,
+    pub provider_round_trip: LatencySummary,
+    pub insertion: LatencySummary,
+}
+
+impl LatencyTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record an audio chunk captured at `captured_at` being sent now.
+    pub fn record_send(&mut self, captured_at: Instant, bytes: usize) {
+        let now = Instant::now();
+        self.capture_to_send.push(now.duration_since(captured_at));
+        self.sent_audio_ms += bytes as u64 / BYTES_PER_MS;
+        self.sent_audio.push((self.sent_audio_ms, now));
+    }
+
+    /// Record that Soniox finalized audio up to `end_ms` (stream time).
+    pub fn record_final(&mut self, end_ms: u64) {
+        let idx = self.sent_audio.partition_point(|(ms, _)| *ms < end_ms);
+        if let Some((_, sent_at)) = self.sent_audio.get(idx) {
+            self.provider_round_trip.push(sent_at.elapsed());
+        }
+        // Audio before this point will never be referenced again
+        self.sent_audio.drain(..idx);
+    }
+
+    /// Record text enqueued at `enqueued_at` having been inserted now.
+    pub fn record_insertion(&mut self, enqueued_at: Instant) {
+        self.insertion.push(enqueued_at.elapsed());
+    }
+
+    pub fn report(&self) -> LatencyReport {
+        LatencyReport {
+            capture_to_send: summarize(&self.capture_to_send),
+            provider_round_trip: summarize(&self.provider_round_trip),
+            insertion: summarize(&self.insertion),
+        }
+    }
+}
+
+fn summarize(samples: &[Duration]) -> LatencySummary {
+    if samples.is_empty() {
+        return LatencySummary {
+            samples: 0,
+            min_ms: 0.0,
+            avg_ms: 0.0,
+            p95_ms: 0.0,
+            max_ms: 0.0,
+        };
+    }
+
+    let mut ms: Vec<f64> = samples.iter().map(|d| d.as_secs_f64() * 1000.0).collect();
+    ms.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+
+    let p95_idx = ((ms.len() as f64 * 0.95).ceil() as usize).saturating_sub(1);
+    LatencySummary {
+        samples: ms.len(),
+        min_ms: ms[0],
+        avg_ms: ms.iter().sum::<f64>() / ms.len() as f64,
+        p95_ms: ms[p95_idx.min(ms.len() - 1)],
+        max_ms: ms[ms.len() - 1],
+    }
+}
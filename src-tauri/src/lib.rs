@@ -5,58 +5,703 @@ use tauri::{
     AppHandle, Emitter, Manager,
 };
 use tauri_plugin_global_shortcut::GlobalShortcutExt;
-use tauri_plugin_store::StoreExt;
 
+mod accuracy;
+mod actions;
+mod analytics;
 mod audio;
+mod azure;
+mod battery;
+mod calendar;
+mod conflicts;
+mod console;
+mod datadir;
+mod ducking;
+mod error;
+mod faults;
+mod folderwatch;
+#[cfg(feature = "headless")]
+mod headless;
+mod i18n;
+mod importer;
+mod integrity;
+mod journal;
+mod latency;
+mod metrics;
+mod native_overlay;
+mod openai;
+mod output;
+mod pending;
+mod persist;
+mod plugins;
+mod power;
+mod rewrite;
+mod screenshare;
+mod scripting;
 mod soniox;
+mod statusbar;
+mod stop;
+mod subtitles;
+mod text;
+mod tooling;
 mod typer;
+mod usage;
+mod vosk;
+mod voicecommands;
+mod whisper;
+mod watchdog;
 
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 use tokio::task::JoinHandle;
 
 const STORE_PATH: &str = "settings.json";
+const TRAY_ID: &str = "main";
+// `tray_left_click` value that opens the tray menu instead of running an action
+const TRAY_MENU_ACTION: &str = "menu";
 
 pub struct AppState {
     pub is_recording: Arc<AtomicBool>,
-    pub stop_signal: Arc<AtomicBool>,
+    /// Stop and cancel tokens of the current session
+    pub session_tokens: Mutex<stop::SessionTokens>,
     pub settings: Mutex<AppSettings>,
     pub recording_task: Mutex<Option<JoinHandle<()>>>,
     pub target_window_id: Mutex<Option<String>>,
+    /// Unix time (secs) the current/last session started, for history merging
+    pub session_started_at: Mutex<Option<u64>>,
+    /// Id of the current/last session, used to link history to recordings
+    pub session_id: Mutex<Option<String>>,
+    /// WAV recording of the last finished session, until saved to history
+    pub last_session_audio: Mutex<Option<String>>,
+    /// Word timings of the last finished session, until saved to history
+    pub last_session_words: Mutex<Option<Vec<WordTiming>>>,
+    /// Languages Soniox detected in the last finished session, most used first
+    pub last_session_languages: Mutex<Option<Vec<String>>>,
+    /// Language hints forced by the action that started the current/last
+    /// session, replacing the configured ones
+    pub session_language_hints: Mutex<Option<Vec<String>>>,
+    /// Comparison-stream transcript of the last finished session
+    pub last_session_comparison: Mutex<Option<ComparisonTranscript>>,
+    /// Text of the last finished session, until saved to history
+    pub last_session_text: Mutex<Option<String>>,
+    /// Session id and text last saved to history, to drop a double delivery
+    pub last_saved_session: Mutex<Option<(String, String)>>,
+    /// Stop flag of the active history playback, if any
+    pub playback_stop: Mutex<Option<Arc<AtomicBool>>>,
+    /// Last accepted hotkey press, for the toggle cooldown
+    pub last_hotkey_press: Mutex<Option<std::time::Instant>>,
+    /// Geometry of the captured target window, when the platform exposes it
+    pub target_window_geometry: Mutex<Option<typer::WindowGeometry>>,
+    /// The current session runs in battery saver mode
+    pub battery_saving: AtomicBool,
+    /// The current session only shows its text for review (screen sharing)
+    pub review_mode: AtomicBool,
+    /// App focused when the current/last session started
+    pub target_app: Mutex<Option<typer::FocusedApp>>,
+    /// Sessions are neither saved to history nor recorded while set
+    pub incognito: AtomicBool,
+    /// Clipboard text the current session's instruction rewrites
+    pub rewrite_source: Mutex<Option<String>>,
+}
+
+#[derive(Clone, Copy, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverlayPlacement {
+    /// Centered at the top of the active monitor
+    #[default]
+    ScreenTop,
+    /// Centered along the bottom edge of the target window
+    NearTarget,
+}
+
+/// Named set of settings that override the global ones while active.
+#[derive(Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct Profile {
+    /// Replaces the global text processor list when set
+    pub text_processors: Option<Vec<text::pipeline::ProcessorConfig>>,
+    /// Key into `credentials` billed while this profile is active
+    pub credential: Option<String>,
+    /// App ids (bundle id or executable name) that switch to this profile
+    /// for sessions started while they are focused
+    pub apps: Vec<String>,
+    /// Typed when a session starts, with `{date}`-style placeholders
+    /// (see `text::template`)
+    pub template: Option<String>,
+    /// Replaces `translation_target_language` when set: speech in any
+    /// language comes out in this one
+    pub output_language: Option<String>,
+    /// Replace `clipboard_settle_ms` / `clipboard_read_ms` for this
+    /// profile's apps
+    pub clipboard_settle_ms: Option<u64>,
+    pub clipboard_read_ms: Option<u64>,
+}
+
+/// Named Soniox API key, so dictation can be billed to different accounts.
+#[derive(Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct Credential {
+    pub api_key: String,
+}
+
+/// Extra hotkey that dictates into the focused window of another X display
+/// (multi-head or multi-seat setups).
+#[derive(Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct Seat {
+    pub name: String,
+    pub hotkey: String,
+    /// X display whose active window receives the text, e.g. ":1" or ":0.1"
+    pub display: String,
+}
+
+/// Extra hotkey that runs a named action, e.g. `start:lang=tr`.
+#[derive(Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct ActionHotkey {
+    pub hotkey: String,
+    /// Action name as listed by `list_actions`, with optional parameters
+    pub action: String,
+}
+
+/// Second recognition stream fed the same audio, to compare accuracy
+/// side by side in history. Only the main stream is typed. Both streams
+/// are Soniox; other providers ignore this.
+#[derive(Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct ComparisonSettings {
+    pub enabled: bool,
+    /// Soniox model for the comparison stream; empty uses the default model
+    pub model: String,
+    /// Key into `credentials`; None uses the active key
+    pub credential: Option<String>,
+    /// Replaces `language_hints` for the comparison stream when set
+    pub language_hints: Option<Vec<String>>,
+}
+
+/// Per-provider network workarounds (e.g. VPNs that break IPv6 WebSockets).
+#[derive(Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct NetworkOverrides {
+    /// Only connect over IPv4
+    pub force_ipv4: bool,
+    /// Connect to this IP instead of resolving the provider host
+    pub resolve_to: Option<String>,
+    /// Hostname used for TLS SNI and the Host header
+    pub sni_host: Option<String>,
+}
+
+#[derive(Clone, Copy, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TranscriptionProvider {
+    /// Soniox real-time API (needs an API key and network)
+    #[default]
+    Soniox,
+    /// OpenAI Realtime transcription API
+    #[serde(rename = "openai")]
+    OpenAi,
+    /// Azure Speech-to-Text (key and region)
+    Azure,
+    /// Local whisper.cpp model; needs the `whisper` build feature
+    Whisper,
+    /// Local Vosk model; needs the `vosk` build feature
+    Vosk,
+}
+
+#[derive(Clone, Copy, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverlayBackend {
+    /// Transparent webview window (overlay.html)
+    #[default]
+    Webview,
+    /// GTK window on Linux, NSPanel on macOS; Windows uses the webview
+    Native,
+}
+
+/// Bundles of latency/accuracy settings, for users who would rather not
+/// tune each one. `Custom` leaves them as configured.
+#[derive(Clone, Copy, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QualityPreset {
+    #[default]
+    Custom,
+    /// Audio sent as captured, no preview throttling, provisional words
+    /// finalized after a second, a quick speech detector, the previous
+    /// Soniox real-time model and a short finish wait
+    Fast,
+    /// The default timings, with 100 ms audio blocks
+    Balanced,
+    /// No finalizing at pauses (the model sees whole sessions), larger
+    /// audio blocks, a speech detector that keeps quiet speech and a long
+    /// finish wait
+    Accurate,
+}
+
+impl QualityPreset {
+    /// Overwrite the settings this preset controls. Opt-in behaviour such
+    /// as provisional typing and the models the user picked are left alone.
+    fn apply(self, settings: &mut AppSettings) {
+        let (endpoint_detection, partial_text_interval_ms, finish_timeout_ms, audio_chunk_ms, speech_threshold, non_final_flush_ms) =
+            match self {
+                Self::Custom => return,
+                Self::Fast => (true, 0, 2000, 0, 800, 1000),
+                Self::Balanced => (true, 100, 5000, 100, 500, 0),
+                Self::Accurate => (false, 250, 8000, 200, 300, 0),
+            };
+        settings.endpoint_detection = endpoint_detection;
+        settings.partial_text_interval_ms = partial_text_interval_ms;
+        settings.finish_timeout_ms = finish_timeout_ms;
+        settings.audio_chunk_ms = audio_chunk_ms;
+        settings.speech_threshold = speech_threshold;
+        settings.non_final_flush_ms = non_final_flush_ms;
+    }
+
+    /// Soniox model variant of this preset, used unless `soniox_model` is set.
+    pub fn soniox_model(self) -> Option<&'static str> {
+        match self {
+            Self::Fast => Some("stt-rt-v3"),
+            Self::Custom | Self::Balanced | Self::Accurate => None,
+        }
+    }
+}
+
+/// How the provider itself punctuates. Only Azure offers a choice; Soniox,
+/// OpenAI and Whisper always punctuate and Vosk never does.
+#[derive(Clone, Copy, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProviderPunctuation {
+    /// Punctuation and capitalization inferred by the provider
+    #[default]
+    Automatic,
+    /// Spoken punctuation ("comma", "question mark") written as symbols
+    /// (Azure dictation mode)
+    Spoken,
+    /// Raw words without punctuation or capitalization
+    Off,
 }
 
 #[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 pub struct AppSettings {
+    pub provider: TranscriptionProvider,
+    /// Overrides the settings it bundles (see `QualityPreset::apply`)
+    pub quality_preset: QualityPreset,
+    /// ggml model file used by the Whisper provider
+    pub whisper_model_path: Option<String>,
+    /// Downloaded model (directory name) used by the Vosk provider
+    pub vosk_model: String,
+    pub openai_api_key: String,
+    /// Transcription model for the OpenAI provider
+    pub openai_model: String,
+    /// OpenAI-compatible API base for the clipboard rewrite action
+    pub rewrite_endpoint: String,
+    /// Chat model for the clipboard rewrite action
+    pub rewrite_model: String,
+    /// Key for `rewrite_endpoint`; empty uses the OpenAI API key
+    pub rewrite_api_key: String,
+    pub azure_speech_key: String,
+    /// Azure resource region, e.g. "westeurope"
+    pub azure_region: String,
+    pub provider_punctuation: ProviderPunctuation,
     pub api_key: String,
     pub hotkey: String,
+    /// Language of backend-generated messages ("en", "de", "tr")
+    pub ui_language: String,
+    /// Action run by a left click on the tray icon (see `list_actions`),
+    /// or "menu" to open the tray menu
+    pub tray_left_click: String,
     pub language_hints: Vec<String>,
     pub language_restrictions: Option<Vec<String>>,
+    /// Soniox one-way translation: type the speech translated into this
+    /// language instead of the transcript. None types what was said
+    pub translation_target_language: Option<String>,
+    /// WAV file streamed instead of the microphone (development only)
+    pub audio_fixture_path: Option<String>,
+    /// Append trailing non-final text to the history entry when a session
+    /// ends before Soniox finalizes it.
+    pub include_partial_in_history: bool,
+    /// Merge a session into the previous history entry when it started within
+    /// this many seconds of the previous one ending. 0 disables merging.
+    pub history_merge_window_secs: u64,
+    /// Stop recording after this many seconds without speech. 0 keeps
+    /// recording until stopped.
+    pub auto_stop_silence_secs: u64,
+    /// Additional hotkeys that target other displays; Linux X11 only
+    pub seats: Vec<Seat>,
+    /// Additional hotkeys bound to actions instead of the plain toggle
+    pub action_hotkeys: Vec<ActionHotkey>,
+    /// Hotkey presses within this many ms of the last accepted one are ignored
+    pub hotkey_cooldown_ms: u64,
+    /// Minimum interval between `partial-text` events; 0 emits on every message
+    pub partial_text_interval_ms: u64,
+    /// Let Soniox finalize tokens at pauses in speech instead of only at the end
+    pub endpoint_detection: bool,
+    /// Soniox real-time model; empty uses the quality preset's or the default
+    pub soniox_model: String,
+    /// Send microphone audio in blocks of at least this many ms; 0 sends
+    /// each capture buffer as it arrives
+    pub audio_chunk_ms: u64,
+    /// Chunk RMS (s16 scale) above which the input counts as speech, for
+    /// the speaking indicator and auto-stop; higher ignores quieter sound
+    pub speech_threshold: u32,
+    /// Ask Soniox to finalize provisional words once they have waited this
+    /// long; 0 leaves finalizing to the provider
+    pub non_final_flush_ms: u64,
+    /// How long to wait for final tokens after recording stops
+    pub finish_timeout_ms: u64,
+    /// Continue a Soniox session on a new stream after this many minutes,
+    /// ahead of the provider's stream duration limit; 0 never rolls over
+    pub session_rollover_mins: u64,
+    /// Longer insertions are typed in pieces of this many characters; 0
+    /// inserts everything at once
+    pub insert_chunk_chars: usize,
+    /// Pause between the pieces of a split insertion
+    pub insert_chunk_delay_ms: u64,
+    /// Insertions longer than this many characters are always split and
+    /// paced, since one huge paste can hang slow apps; 0 turns this off
+    pub large_insert_chars: usize,
+    /// Pause between filling the clipboard and the paste shortcut
+    pub clipboard_settle_ms: u64,
+    /// Pause after the paste shortcut before the previous clipboard is
+    /// restored; slow apps (Electron over RDP) need more
+    pub clipboard_read_ms: u64,
+    /// Emit every Soniox response's tokens, unfiltered, as `soniox-raw`
+    /// for diagnosing token revisions and control tokens
+    pub debug_raw_tokens: bool,
+    pub overlay_placement: OverlayPlacement,
+    pub overlay_backend: OverlayBackend,
+    /// TCP connect timeout (includes DNS resolution)
+    pub connect_timeout_ms: u64,
+    /// TLS + WebSocket upgrade timeout
+    pub handshake_timeout_ms: u64,
+    /// Extra connect attempts on transient DNS/TCP/TLS failures
+    pub connect_retries: u32,
+    /// Hold an idle Soniox connection between sessions
+    pub keep_connection_warm: bool,
+    /// USD per hour of Soniox audio, for the usage estimate
+    pub soniox_price_per_hour: f64,
+    /// Send the end of the previous session (if recent) as Soniox context
+    pub context_carry_over: bool,
+    /// Soniox-compatible WebSocket URL replacing the public API (self-hosted
+    /// or regional endpoints)
+    pub soniox_endpoint: Option<String>,
+    /// Network overrides keyed by provider id ("soniox", "openai")
+    pub network_overrides: std::collections::HashMap<String, NetworkOverrides>,
+    /// Names and terms the provider gets wrong; sent as recognition context
+    /// and corrected locally before the text processors run
+    pub pronunciations: Vec<text::pipeline::Pronunciation>,
+    /// Domain terms (names, jargon) sent as recognition context
+    pub vocabulary: Vec<String>,
+    /// Ordered post-processing applied to finalized text before output
+    pub text_processors: Vec<text::pipeline::ProcessorConfig>,
+    /// Convert smart quotes, dashes and special spaces to ASCII before typing
+    pub plain_ascii_output: bool,
+    /// Paste text containing Markdown (bold, lists) as HTML plus plain text
+    pub rich_text_paste: bool,
+    /// Journal every insertion (text, time, backend, target) per session
+    pub audit_journal: bool,
+    /// Count sessions per provider locally for the analytics view
+    pub local_analytics: bool,
+    /// Type non-final text right away and correct it when it changes (Soniox)
+    pub provisional_typing: bool,
+    /// Diagnostic second stream whose transcript is saved next to the typed one
+    pub comparison: ComparisonSettings,
+    pub profiles: std::collections::HashMap<String, Profile>,
+    /// Key into `profiles`; None uses the global settings only
+    pub active_profile: Option<String>,
+    /// Named API keys ("personal", "work", ...)
+    pub credentials: std::collections::HashMap<String, Credential>,
+    /// Key into `credentials`; None uses `api_key`
+    pub active_credential: Option<String>,
+    /// Input device name from `list_audio_devices`; None uses the system default
+    pub selected_device: Option<String>,
+    /// Ordered DSP applied to microphone audio before it is sent
+    pub audio_effects: Vec<audio::dsp::EffectConfig>,
+    /// On battery, slow down live previews, stop the overlay animation and
+    /// upload compressed audio
+    pub battery_saver: bool,
+    /// Upload Soniox audio as 8-bit µ-law instead of 16-bit PCM; set for a
+    /// session by the battery saver, never stored
+    #[serde(skip)]
+    pub compressed_upload: bool,
+    /// While the screen seems to be shared or recorded, keep the text in
+    /// the main window instead of typing it
+    pub review_while_sharing: bool,
+    /// Bring the window focused at session start back to the front before
+    /// each insertion; off lets text follow focus
+    pub refocus_target: bool,
+    /// Lower other applications' playback while recording
+    pub duck_media: bool,
+    /// Volume other applications are lowered to, in percent (0 mutes)
+    pub duck_volume_percent: u8,
+    /// Keep a WAV recording of each session, linked from its history entry
+    pub save_session_audio: bool,
+    /// ICS file or URL; sessions overlapping an event are titled after it
+    pub calendar_ics: Option<String>,
+    /// Folder whose new recordings are transcribed into history
+    pub watch_folder: Option<String>,
+    /// Also write each watch folder transcript next to its recording
+    pub watch_folder_write_text: bool,
+    /// Listen for voice commands with Vosk while idle; keeps the mic open
+    pub voice_commands: bool,
+    /// Spoken phrase -> action name (as in `invoke_action`)
+    pub voice_command_phrases: std::collections::HashMap<String, String>,
+    /// Destroy the main/overlay webviews when hidden and recreate them on
+    /// demand, trading a little show latency for lower idle memory.
+    pub unload_hidden_windows: bool,
+    /// Port for the local Prometheus `/metrics` endpoint; disabled when None.
+    /// Read at startup only.
+    pub metrics_port: Option<u16>,
 }
 
 const TRANSCRIPTIONS_STORE_PATH: &str = "transcriptions.json";
+// Dictated and file entries kept in history, newest first
+const HISTORY_LIMIT: usize = 100;
+// Suffix for file entry ids, so files finishing in the same second differ
+static FILE_ENTRY_SEQ: AtomicU64 = AtomicU64::new(0);
+
+/// Drop the oldest dictated and file entries beyond `HISTORY_LIMIT`.
+/// Imported archives don't count against the limit and are kept whole.
+fn trim_history(entries: &mut Vec<TranscriptionEntry>) {
+    let mut kept = 0;
+    entries.retain(|e| {
+        if e.source == EntrySource::Import {
+            return true;
+        }
+        kept += 1;
+        kept <= HISTORY_LIMIT
+    });
+}
 
 #[derive(Clone, serde::Serialize, serde::Deserialize)]
 pub struct TranscriptionEntry {
+    /// Session id; empty for entries saved before ids existed
+    #[serde(default)]
+    pub id: String,
     pub text: String,
     pub timestamp: u64,
     pub language: String,
+    /// WAV recording of the session, when session audio capture is enabled
+    #[serde(default)]
+    pub audio_path: Option<String>,
+    /// Word offsets into `audio_path`, when the provider sent timestamps
+    #[serde(default)]
+    pub words: Option<Vec<WordTiming>>,
+    /// What the comparison stream heard, when comparison mode was on
+    #[serde(default)]
+    pub comparison: Option<ComparisonTranscript>,
+    /// App the session dictated into, when it could be detected
+    #[serde(default)]
+    pub app: Option<typer::FocusedApp>,
+    /// Calendar event the session overlapped, when a calendar is set
+    #[serde(default)]
+    pub title: Option<String>,
+    /// Which pipeline produced the entry; older entries are live dictations
+    #[serde(default)]
+    pub source: EntrySource,
+    /// Later sessions were folded in by `history_merge_window_secs`, so
+    /// `audio_path` only covers the first of them
+    #[serde(default)]
+    pub merged: bool,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntrySource {
+    /// A dictation session
+    #[default]
+    Live,
+    /// `transcribe_file` or the watched folder
+    File,
+    /// Brought in from another tool by `import_history`
+    Import,
+}
+
+/// Transcript of the comparison stream for one session.
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct ComparisonTranscript {
+    /// Which configuration produced it (the model name)
+    pub label: String,
+    pub text: String,
+}
+
+/// A recognized word and where it was spoken in the session audio.
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct WordTiming {
+    pub word: String,
+    pub start_ms: u64,
+    pub end_ms: u64,
+    /// Language the provider tagged the word with, if it tags languages
+    #[serde(default)]
+    pub language: Option<String>,
 }
 
 impl Default for AppSettings {
     fn default() -> Self {
         Self {
+            provider: TranscriptionProvider::Soniox,
+            quality_preset: QualityPreset::Custom,
+            whisper_model_path: None,
+            vosk_model: String::new(),
+            openai_api_key: String::new(),
+            openai_model: "gpt-4o-transcribe".to_string(),
+            rewrite_endpoint: "https://api.openai.com/v1".to_string(),
+            rewrite_model: "gpt-4o-mini".to_string(),
+            rewrite_api_key: String::new(),
+            azure_speech_key: String::new(),
+            azure_region: String::new(),
+            provider_punctuation: ProviderPunctuation::Automatic,
             api_key: String::new(),
             hotkey: "Insert".to_string(),
+            ui_language: "en".to_string(),
+            tray_left_click: "show_window".to_string(),
             language_hints: vec!["en".to_string()],
             language_restrictions: None,
+            translation_target_language: None,
+            audio_fixture_path: None,
+            include_partial_in_history: false,
+            history_merge_window_secs: 0,
+            auto_stop_silence_secs: 0,
+            seats: Vec::new(),
+            action_hotkeys: Vec::new(),
+            hotkey_cooldown_ms: 300,
+            partial_text_interval_ms: 100,
+            endpoint_detection: true,
+            soniox_model: String::new(),
+            audio_chunk_ms: 0,
+            speech_threshold: 500,
+            non_final_flush_ms: 0,
+            finish_timeout_ms: 5000,
+            session_rollover_mins: 55,
+            insert_chunk_chars: 2000,
+            insert_chunk_delay_ms: 50,
+            large_insert_chars: 8000,
+            clipboard_settle_ms: 30,
+            clipboard_read_ms: 150,
+            debug_raw_tokens: false,
+            overlay_placement: OverlayPlacement::ScreenTop,
+            overlay_backend: OverlayBackend::Webview,
+            connect_timeout_ms: 5000,
+            handshake_timeout_ms: 10000,
+            connect_retries: 2,
+            keep_connection_warm: false,
+            soniox_price_per_hour: 0.12,
+            context_carry_over: false,
+            soniox_endpoint: None,
+            network_overrides: std::collections::HashMap::new(),
+            pronunciations: Vec::new(),
+            vocabulary: Vec::new(),
+            text_processors: Vec::new(),
+            plain_ascii_output: false,
+            rich_text_paste: false,
+            audit_journal: false,
+            local_analytics: false,
+            provisional_typing: false,
+            comparison: ComparisonSettings::default(),
+            profiles: std::collections::HashMap::new(),
+            active_profile: None,
+            credentials: std::collections::HashMap::new(),
+            active_credential: None,
+            selected_device: None,
+            audio_effects: Vec::new(),
+            battery_saver: false,
+            compressed_upload: false,
+            review_while_sharing: false,
+            refocus_target: false,
+            duck_media: false,
+            duck_volume_percent: 20,
+            save_session_audio: false,
+            calendar_ics: None,
+            watch_folder: None,
+            watch_folder_write_text: false,
+            voice_commands: false,
+            voice_command_phrases: [("start dictation", "start"), ("stop dictation", "stop"), ("open settings", "show_window")]
+                .into_iter()
+                .map(|(phrase, action)| (phrase.to_string(), action.to_string()))
+                .collect(),
+            unload_hidden_windows: false,
+            metrics_port: None,
         }
     }
 }
 
+impl AppSettings {
+    fn active_profile(&self) -> Option<&Profile> {
+        self.active_profile.as_ref().and_then(|name| self.profiles.get(name))
+    }
+
+    /// API key in effect: the active profile's credential, then the
+    /// selected credential, then the plain `api_key`.
+    pub fn effective_api_key(&self) -> &str {
+        self.active_profile()
+            .and_then(|p| p.credential.as_ref())
+            .or(self.active_credential.as_ref())
+            .and_then(|name| self.credentials.get(name))
+            .map_or(self.api_key.as_str(), |c| c.api_key.as_str())
+    }
+
+    /// Text processors in effect, honouring the active profile's override.
+    pub fn effective_text_processors(&self) -> &[text::pipeline::ProcessorConfig] {
+        self.active_profile()
+            .and_then(|p| p.text_processors.as_deref())
+            .unwrap_or(&self.text_processors)
+    }
+
+    /// Language to translate into: the active profile's output language,
+    /// then `translation_target_language`. None types what was said.
+    pub fn effective_translation_target(&self) -> Option<&str> {
+        self.active_profile()
+            .and_then(|p| p.output_language.as_deref())
+            .or(self.translation_target_language.as_deref())
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+    }
+
+    /// Clipboard paste delays in effect, honouring the active profile.
+    pub fn effective_clipboard_delays(&self) -> typer::ClipboardDelays {
+        let profile = self.active_profile();
+        typer::ClipboardDelays {
+            settle: std::time::Duration::from_millis(profile.and_then(|p| p.clipboard_settle_ms).unwrap_or(self.clipboard_settle_ms)),
+            read: std::time::Duration::from_millis(profile.and_then(|p| p.clipboard_read_ms).unwrap_or(self.clipboard_read_ms)),
+        }
+    }
+
+    /// The active profile's session template, expanded for now.
+    pub fn session_preamble(&self) -> Option<String> {
+        self.active_profile()
+            .and_then(|p| p.template.as_deref())
+            .filter(|t| !t.is_empty())
+            .map(text::template::expand)
+    }
+
+    /// Vocabulary plus pronunciation words, without duplicates, for the
+    /// provider's recognition context.
+    pub fn context_terms(&self) -> Vec<String> {
+        let mut terms: Vec<String> = Vec::new();
+        let words = self.vocabulary.iter().map(|t| t.trim()).chain(self.pronunciations.iter().map(|p| p.word.trim()));
+        for word in words {
+            if !word.is_empty() && !terms.iter().any(|t| t == word) {
+                terms.push(word.to_string());
+            }
+        }
+        terms
+    }
+
+    /// Name of the profile that lists `app_id` in its apps, if any.
+    pub fn profile_for_app(&self, app_id: &str) -> Option<&str> {
+        self.profiles
+            .iter()
+            .find(|(_, p)| p.apps.iter().any(|a| a.eq_ignore_ascii_case(app_id)))
+            .map(|(name, _)| name.as_str())
+    }
+}
+
 fn load_settings_from_store(app: &AppHandle) -> AppSettings {
-    if let Ok(store) = app.store(STORE_PATH) {
+    if let Ok(store) = persist::store(app, STORE_PATH) {
         if let Some(settings_json) = store.get("settings") {
-            if let Ok(settings) = serde_json::from_value::<AppSettings>(settings_json) {
+            if let Ok(mut settings) = serde_json::from_value::<AppSettings>(settings_json) {
+                // settings.json may have been edited by hand
+                settings.quality_preset.apply(&mut settings);
                 return settings;
             }
         }
@@ -65,10 +710,10 @@ fn load_settings_from_store(app: &AppHandle) -> AppSettings {
 }
 
 fn save_settings_to_store(app: &AppHandle, settings: &AppSettings) -> Result<(), String> {
-    let store = app.store(STORE_PATH).map_err(|e| e.to_string())?;
+    let store = persist::store(app, STORE_PATH)?;
     let settings_json = serde_json::to_value(settings).map_err(|e| e.to_string())?;
     store.set("settings", settings_json);
-    store.save().map_err(|e| e.to_string())?;
+    persist::schedule(app, STORE_PATH);
     Ok(())
 }
 
@@ -81,45 +726,344 @@ fn get_settings(state: tauri::State<AppState>) -> AppSettings {
 async fn save_settings(
     app: AppHandle,
     state: tauri::State<'_, AppState>, 
-    settings: AppSettings
+    mut settings: AppSettings
 ) -> Result<(), String> {
-    let old_hotkey = {
+    settings.quality_preset.apply(&mut settings);
+    let (old_hotkey, old_seats, old_action_hotkeys) = {
         let s = state.settings.lock().unwrap();
-        s.hotkey.clone()
+        (s.hotkey.clone(), s.seats.clone(), s.action_hotkeys.clone())
     };
     
     {
         let mut s = state.settings.lock().unwrap();
         *s = settings.clone();
     }
+    i18n::set_language(&settings.ui_language);
+    if let Some(tray) = app.tray_by_id(TRAY_ID) {
+        tray.set_show_menu_on_left_click(settings.tray_left_click == TRAY_MENU_ACTION).ok();
+    }
     
     save_settings_to_store(&app, &settings)?;
+    folderwatch::sync(&app);
+    voicecommands::sync(&app);
+    soniox::warm::keep_warm(&app);
     
     // Re-register hotkey if it changed
-    if old_hotkey != settings.hotkey {
+    if old_hotkey != settings.hotkey || old_seats != settings.seats || old_action_hotkeys != settings.action_hotkeys {
         log::info!("Hotkey changed from '{}' to '{}', re-registering...", old_hotkey, settings.hotkey);
-        let gs = app.global_shortcut();
-        gs.unregister_all().ok();
-        let new_shortcut: tauri_plugin_global_shortcut::Shortcut = settings.hotkey.parse()
-            .map_err(|e| format!("Invalid hotkey '{}': {:?}", settings.hotkey, e))?;
-        // Must use on_shortcut (not register) so the callback is attached
-        gs.on_shortcut(new_shortcut, move |app_handle, _shortcut, event| {
-            if event.state == tauri_plugin_global_shortcut::ShortcutState::Pressed {
-                let handle = app_handle.clone();
-                tauri::async_runtime::spawn(async move {
-                    let state: tauri::State<'_, AppState> = handle.state();
-                    if let Err(e) = toggle_recording(handle.clone(), state).await {
-                        log::error!("Hotkey toggle failed: {}", e);
-                    }
-                });
-            }
-        }).map_err(|e| format!("Failed to register hotkey: {}", e))?;
-        log::info!("Re-registered hotkey '{}' with handler", settings.hotkey);
+        register_hotkey(&app)?;
     }
     
     Ok(())
 }
 
+/// Run the action configured for a left click on the tray icon.
+fn on_tray_left_click(app: &AppHandle) {
+    let action = app.state::<AppState>().settings.lock().unwrap().tray_left_click.clone();
+    // The menu itself is opened by the tray; nothing to run
+    if action == TRAY_MENU_ACTION {
+        return;
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = actions::invoke(&app, &action).await {
+            log::error!("Tray click action '{}' failed: {}", action, e);
+        }
+    });
+}
+
+/// (Re-)register the configured global hotkey, replacing any previous one.
+fn register_hotkey(app: &AppHandle) -> Result<(), String> {
+    let hotkey = app.state::<AppState>().settings.lock().unwrap().hotkey.clone();
+    app.global_shortcut().unregister_all().ok();
+    register_main_hotkey(app, &hotkey)?;
+    log::info!("Registered hotkey '{}' with handler", hotkey);
+    conflicts::hotkey_registered(app);
+    register_seat_hotkeys(app);
+    register_action_hotkeys(app);
+    Ok(())
+}
+
+/// Register the main hotkey alone; the caller unregisters it first.
+fn register_main_hotkey(app: &AppHandle, hotkey: &str) -> Result<(), String> {
+    let shortcut: tauri_plugin_global_shortcut::Shortcut = hotkey.parse()
+        .map_err(|e| format!("Invalid hotkey '{}': {:?}", hotkey, e))?;
+    // Must use on_shortcut (not register) so the callback is attached
+    app.global_shortcut().on_shortcut(shortcut, move |app_handle, _shortcut, event| {
+        if event.state == tauri_plugin_global_shortcut::ShortcutState::Pressed {
+            on_hotkey_pressed(app_handle, None);
+        }
+    }).map_err(|e| {
+        conflicts::hotkey_taken(app, hotkey, &e.to_string());
+        format!("Failed to register hotkey: {}", e)
+    })
+}
+
+/// Register the hotkey of every configured seat. A bad seat hotkey is
+/// logged and skipped so it can't take the main hotkey down with it.
+fn register_seat_hotkeys(app: &AppHandle) {
+    let seats = app.state::<AppState>().settings.lock().unwrap().seats.clone();
+    let gs = app.global_shortcut();
+    for seat in seats {
+        let shortcut: tauri_plugin_global_shortcut::Shortcut = match seat.hotkey.parse() {
+            Ok(shortcut) => shortcut,
+            Err(e) => {
+                log::warn!("Invalid hotkey '{}' for seat '{}': {:?}", seat.hotkey, seat.name, e);
+                continue;
+            }
+        };
+        let name = seat.name.clone();
+        match gs.on_shortcut(shortcut, move |app_handle, _shortcut, event| {
+            if event.state == tauri_plugin_global_shortcut::ShortcutState::Pressed {
+                on_hotkey_pressed(app_handle, Some(name.clone()));
+            }
+        }) {
+            Ok(_) => log::info!("Registered hotkey '{}' for seat '{}' ({})", seat.hotkey, seat.name, seat.display),
+            Err(e) => log::warn!("Could not register hotkey '{}' for seat '{}': {}", seat.hotkey, seat.name, e),
+        }
+    }
+}
+
+/// Register the configured action hotkeys, skipping bad ones like seats do.
+fn register_action_hotkeys(app: &AppHandle) {
+    let bindings = app.state::<AppState>().settings.lock().unwrap().action_hotkeys.clone();
+    let gs = app.global_shortcut();
+    for binding in bindings {
+        let shortcut: tauri_plugin_global_shortcut::Shortcut = match binding.hotkey.parse() {
+            Ok(shortcut) => shortcut,
+            Err(e) => {
+                log::warn!("Invalid hotkey '{}' for action '{}': {:?}", binding.hotkey, binding.action, e);
+                continue;
+            }
+        };
+        let action = binding.action.clone();
+        match gs.on_shortcut(shortcut, move |app_handle, _shortcut, event| {
+            if event.state != tauri_plugin_global_shortcut::ShortcutState::Pressed || !accept_hotkey_press(app_handle) {
+                return;
+            }
+            let app_handle = app_handle.clone();
+            let action = action.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = actions::invoke(&app_handle, &action).await {
+                    log::error!("Hotkey action '{}' failed: {}", action, e);
+                }
+            });
+        }) {
+            Ok(_) => log::info!("Registered hotkey '{}' for action '{}'", binding.hotkey, binding.action),
+            Err(e) => log::warn!("Could not register hotkey '{}' for action '{}': {}", binding.hotkey, binding.action, e),
+        }
+    }
+}
+
+/// Quick switch between named credentials; `None` returns to the plain API key.
+#[tauri::command]
+fn switch_credential(app: AppHandle, name: Option<String>) -> Result<AppSettings, String> {
+    set_active_credential(&app, name)
+}
+
+fn set_active_credential(app: &AppHandle, name: Option<String>) -> Result<AppSettings, String> {
+    let settings = {
+        let state = app.state::<AppState>();
+        let mut s = state.settings.lock().unwrap();
+        if let Some(name) = &name {
+            if !s.credentials.contains_key(name) {
+                return Err(i18n::t_args("unknown-credential", &[("name", name.clone())]));
+            }
+        }
+        s.active_credential = name;
+        s.clone()
+    };
+    save_settings_to_store(app, &settings)?;
+    log::info!("Switched credential to '{}'", settings.active_credential.as_deref().unwrap_or("default"));
+    Ok(settings)
+}
+
+/// Activate a profile by name; `None` goes back to the global settings.
+fn set_active_profile(app: &AppHandle, name: Option<String>) -> Result<AppSettings, String> {
+    let settings = {
+        let state = app.state::<AppState>();
+        let mut s = state.settings.lock().unwrap();
+        if let Some(name) = &name {
+            if !s.profiles.contains_key(name) {
+                return Err(i18n::t_args("unknown-profile", &[("name", name.clone())]));
+            }
+        }
+        s.active_profile = name;
+        s.clone()
+    };
+    save_settings_to_store(app, &settings)?;
+    log::info!("Switched profile to '{}'", settings.active_profile.as_deref().unwrap_or("default"));
+    Ok(settings)
+}
+
+/// Actions available to the command palette right now.
+#[tauri::command]
+fn list_actions(app: AppHandle) -> Vec<actions::ActionInfo> {
+    actions::list(&app)
+}
+
+/// Run a command palette action by name (see `list_actions`).
+#[tauri::command]
+async fn invoke_action(app: AppHandle, name: String) -> Result<(), String> {
+    actions::invoke(&app, &name).await
+}
+
+#[tauri::command]
+async fn probe_provider_latency(state: tauri::State<'_, AppState>) -> Result<Vec<soniox::EndpointProbe>, String> {
+    let settings = state.settings.lock().unwrap().clone();
+    Ok(soniox::probe_latency(&settings).await)
+}
+
+/// Transcribe a WAV file with the current settings and score it against
+/// `reference_text` (word and character error rates).
+#[tauri::command]
+async fn evaluate_accuracy(
+    state: tauri::State<'_, AppState>,
+    audio_file: String,
+    reference_text: String,
+) -> Result<accuracy::AccuracyReport, String> {
+    let settings = state.settings.lock().unwrap().clone();
+    accuracy::evaluate(settings, &audio_file, &reference_text).await
+}
+
+/// Input devices for the microphone picker, the system default first.
+#[tauri::command]
+async fn list_audio_devices() -> Vec<audio::AudioDevice> {
+    tauri::async_runtime::spawn_blocking(audio::list_input_devices).await.unwrap_or_default()
+}
+
+/// Whether screen sharing can be detected here, to offer the setting.
+#[tauri::command]
+fn screen_share_detection_supported() -> bool {
+    screenshare::SUPPORTED
+}
+
+/// Which external typing tools are installed and which are missing.
+#[tauri::command]
+async fn get_typing_dependencies() -> tooling::DependencyReport {
+    tauri::async_runtime::spawn_blocking(tooling::check).await.unwrap_or_default()
+}
+
+/// Install one typing tool's package (after a polkit prompt).
+#[tauri::command]
+async fn install_typing_tool(name: String) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || tooling::install(&name))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Install and start a systemd user unit for `ydotoold`.
+#[tauri::command]
+async fn setup_ydotoold_service() -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(tooling::setup_ydotoold)
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Vosk models already downloaded to the app data directory.
+#[tauri::command]
+fn list_models(app: AppHandle) -> Vec<String> {
+    vosk::installed_models(&app)
+}
+
+/// Download a Vosk model by its published name (e.g.
+/// "vosk-model-small-en-us-0.15"), emitting `model-download-progress`.
+#[tauri::command]
+async fn download_model(app: AppHandle, name: String) -> Result<(), String> {
+    vosk::download_model(&app, name.trim()).await
+}
+
+/// Whether the current session saves battery; the overlay then stays static.
+#[tauri::command]
+fn is_battery_saving(state: tauri::State<AppState>) -> bool {
+    state.battery_saving.load(Ordering::SeqCst)
+}
+
+/// Last connection, last error and API key validity, for the status bar.
+/// Changes are also pushed as `provider-status` events.
+#[tauri::command]
+fn get_provider_status(state: tauri::State<AppState>) -> soniox::status::ProviderStatus {
+    soniox::status::current(state.settings.lock().unwrap().effective_api_key())
+}
+
+/// Audio sent to each provider per day, and this month's Soniox estimate.
+#[tauri::command]
+fn get_usage_stats(app: AppHandle, state: tauri::State<AppState>) -> Result<usage::UsageStats, String> {
+    let price = state.settings.lock().unwrap().soniox_price_per_hour;
+    usage::stats(&app, price)
+}
+
+/// Local dictation habits, when `local_analytics` is on.
+#[tauri::command]
+fn get_analytics(app: AppHandle, state: tauri::State<AppState>) -> Result<analytics::Analytics, String> {
+    if !state.settings.lock().unwrap().local_analytics {
+        return Err(i18n::t("analytics-off"));
+    }
+    analytics::compute(&app, &get_transcriptions(app.clone()))
+}
+
+#[tauri::command]
+fn get_recent_events(limit: Option<usize>) -> Vec<console::ConsoleEvent> {
+    console::recent(limit)
+}
+
+/// Stores found corrupted at startup, for the recovery notice.
+#[tauri::command]
+fn get_store_issues() -> Vec<integrity::StoreIssue> {
+    integrity::issues()
+}
+
+#[tauri::command]
+fn dismiss_store_issues() {
+    integrity::dismiss();
+}
+
+/// Text a crash left queued but untyped, for the recovery notice.
+#[tauri::command]
+fn get_unsent_insertions() -> Vec<pending::PendingInsertion> {
+    pending::recovered()
+}
+
+/// Type the text a crash left untyped, once, on the user's request. The
+/// main window is hidden first so the text goes to the window behind it.
+/// Returns the number of characters typed.
+#[tauri::command]
+async fn replay_unsent_insertions(app: AppHandle) -> Result<usize, String> {
+    let text: String = pending::take_recovered(&app).into_iter().map(|i| i.text).collect();
+    let typed = text.chars().count();
+    if typed == 0 {
+        return Ok(0);
+    }
+    if let Some(window) = app.get_webview_window("main") {
+        window.hide().ok();
+    }
+    tauri::async_runtime::spawn_blocking(move || {
+        // Give the window manager a moment to move focus back
+        std::thread::sleep(std::time::Duration::from_millis(300));
+        typer::type_text(&text, "active")
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    console::record(&app, "recovery", format!("Replayed {} unsent characters", typed));
+    Ok(typed)
+}
+
+#[tauri::command]
+fn discard_unsent_insertions(app: AppHandle) {
+    pending::take_recovered(&app);
+}
+
+/// Conflicting software found at startup, for the warning banner.
+#[tauri::command]
+fn get_conflicts() -> Vec<conflicts::Conflict> {
+    conflicts::conflicts()
+}
+
+#[tauri::command]
+fn dismiss_conflicts() {
+    conflicts::dismiss();
+}
+
 #[tauri::command]
 fn get_recording_state(state: tauri::State<AppState>) -> bool {
     state.is_recording.load(Ordering::SeqCst)
@@ -127,7 +1071,7 @@ fn get_recording_state(state: tauri::State<AppState>) -> bool {
 
 #[tauri::command]
 fn get_transcriptions(app: AppHandle) -> Vec<TranscriptionEntry> {
-    if let Ok(store) = app.store(TRANSCRIPTIONS_STORE_PATH) {
+    if let Ok(store) = persist::store(&app, TRANSCRIPTIONS_STORE_PATH) {
         if let Some(entries_json) = store.get("entries") {
             if let Ok(entries) = serde_json::from_value::<Vec<TranscriptionEntry>>(entries_json) {
                 return entries;
@@ -137,60 +1081,565 @@ fn get_transcriptions(app: AppHandle) -> Vec<TranscriptionEntry> {
     Vec::new()
 }
 
+/// One page of history plus the number of entries matching the filter.
+#[derive(serde::Serialize)]
+pub struct TranscriptionPage {
+    pub entries: Vec<TranscriptionEntry>,
+    pub total: usize,
+}
+
+/// Page through history, newest first, optionally keeping only entries
+/// whose text or title contains `filter` (case-insensitive) and that came
+/// from `source`.
 #[tauri::command]
-fn save_transcription(app: AppHandle, text: String, language_hints: Vec<String>) -> Result<(), String> {
-    let store = app.store(TRANSCRIPTIONS_STORE_PATH).map_err(|e| e.to_string())?;
+fn get_transcriptions_page(
+    app: AppHandle,
+    offset: usize,
+    limit: usize,
+    filter: Option<String>,
+    source: Option<EntrySource>,
+) -> TranscriptionPage {
+    let filter = filter.map(|f| f.trim().to_lowercase()).filter(|f| !f.is_empty());
+    let matching: Vec<TranscriptionEntry> = get_transcriptions(app)
+        .into_iter()
+        .filter(|e| source.is_none_or(|s| e.source == s))
+        .filter(|e| {
+            filter.as_ref().is_none_or(|f| {
+                e.text.to_lowercase().contains(f) || e.title.as_ref().is_some_and(|t| t.to_lowercase().contains(f))
+            })
+        })
+        .collect();
+    let total = matching.len();
+    TranscriptionPage {
+        entries: matching.into_iter().skip(offset).take(limit).collect(),
+        total,
+    }
+}
+
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Save the text the finished session left in `last_session_text` to
+/// history. Runs in the backend so sessions are kept without a main window
+/// (unloaded, or headless builds).
+pub fn save_session_history(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let Some(text) = state.last_session_text.lock().unwrap().take() else { return };
+    let cancelled = state.session_tokens.lock().unwrap().cancel.is_cancelled();
+    if cancelled || state.incognito.load(Ordering::SeqCst) {
+        log::info!("Session not saved to history (cancelled or incognito)");
+        if let Some(path) = state.last_session_audio.lock().unwrap().take() {
+            std::fs::remove_file(&path).ok();
+        }
+        return;
+    }
+    if let Some(source) = state.rewrite_source.lock().unwrap().take() {
+        rewrite::finish(app, source, text);
+        return;
+    }
+    let text = tidy_session_text(&text);
+    if text.is_empty() {
+        return;
+    }
+    let language_hints = state.settings.lock().unwrap().language_hints.clone();
+    match save_transcription(app, &state, text, language_hints) {
+        Ok(()) => {
+            app.emit("history-changed", ()).ok();
+        }
+        Err(e) => log::error!("Failed to save transcription: {}", e),
+    }
+}
+
+/// Collapse runs of whitespace and drop spaces before punctuation.
+fn tidy_session_text(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for word in text.split_whitespace() {
+        if !out.is_empty() && !word.starts_with(['.', ',', '!', '?', ';', ':']) {
+            out.push(' ');
+        }
+        out.push_str(word);
+    }
+    out
+}
+
+fn save_transcription(app: &AppHandle, state: &AppState, text: String, language_hints: Vec<String>) -> Result<(), String> {
+    let store = persist::store(app, TRANSCRIPTIONS_STORE_PATH)?;
     let mut entries: Vec<TranscriptionEntry> = store
         .get("entries")
         .and_then(|v| serde_json::from_value(v).ok())
         .unwrap_or_default();
 
-    let entry = TranscriptionEntry {
-        text,
-        timestamp: std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_secs(),
-        language: language_hints.join(","),
+    // The same session delivered twice is saved once; a new session with
+    // the same words is a real repeat and is kept
+    let session_id = state.session_id.lock().unwrap().clone();
+    if let Some(id) = &session_id {
+        let mut last_saved = state.last_saved_session.lock().unwrap();
+        if last_saved.as_ref().is_some_and(|(last_id, last_text)| last_id == id && *last_text == text) {
+            log::info!("Session {} was already saved to history", id);
+            return Ok(());
+        }
+        *last_saved = Some((id.clone(), text.clone()));
+    }
+
+    let now = unix_now();
+    let (merge_window, use_calendar) = {
+        let settings = state.settings.lock().unwrap();
+        (settings.history_merge_window_secs, settings.calendar_ics.is_some())
     };
-    entries.insert(0, entry);
-    // Keep last 100 entries
-    entries.truncate(100);
+    let started_at = state.session_started_at.lock().unwrap().unwrap_or(now);
+    let title = if use_calendar { calendar::title_for(started_at, now) } else { None };
+
+    // Push-to-talk produces many tiny fragments; fold a session into the
+    // previous entry if it started shortly after that one ended.
+    let merge_into_previous = merge_window > 0
+        && entries
+            .first()
+            .is_some_and(|prev| {
+                prev.source == EntrySource::Live && started_at.saturating_sub(prev.timestamp) <= merge_window
+            });
+
+    let audio_path = state.last_session_audio.lock().unwrap().take();
+    let words = state.last_session_words.lock().unwrap().take();
+    // What was actually spoken, falling back to the hints for providers
+    // that don't tag languages
+    let language_hints = state.session_language_hints.lock().unwrap().clone().unwrap_or(language_hints);
+    let language = state
+        .last_session_languages
+        .lock()
+        .unwrap()
+        .take()
+        .map_or_else(|| language_hints.join(","), |detected| detected.join(","));
+    let comparison = state.last_session_comparison.lock().unwrap().take();
+    let target_app = state.target_app.lock().unwrap().clone();
+
+    if merge_into_previous {
+        let prev = &mut entries[0];
+        prev.text = format!("{} {}", prev.text, text);
+        prev.timestamp = now;
+        prev.merged = true;
+        // A merged entry keeps the recording of its first session, and
+        // with it the word timings that point into that recording
+        if prev.audio_path.is_none() {
+            prev.audio_path = audio_path;
+            prev.words = words;
+        }
+        if prev.title.is_none() {
+            prev.title = title;
+        }
+        for detected in language.split(',').filter(|l| !l.is_empty()) {
+            if prev.language.is_empty() {
+                prev.language = detected.to_string();
+            } else if !prev.language.split(',').any(|l| l == detected) {
+                prev.language.push(',');
+                prev.language.push_str(detected);
+            }
+        }
+        match (&mut prev.comparison, comparison) {
+            (Some(prev_cmp), Some(cmp)) => prev_cmp.text = format!("{} {}", prev_cmp.text, cmp.text),
+            (prev_cmp @ None, cmp) => *prev_cmp = cmp,
+            _ => {}
+        }
+    } else {
+        let entry = TranscriptionEntry {
+            id: session_id.unwrap_or_else(|| now.to_string()),
+            text,
+            timestamp: now,
+            language,
+            audio_path,
+            words,
+            comparison,
+            app: target_app,
+            title,
+            source: EntrySource::Live,
+            merged: false,
+        };
+        entries.insert(0, entry);
+    }
+    trim_history(&mut entries);
 
     let json = serde_json::to_value(&entries).map_err(|e| e.to_string())?;
     store.set("entries", json);
-    store.save().map_err(|e| e.to_string())?;
+    persist::schedule(app, TRANSCRIPTIONS_STORE_PATH);
     Ok(())
 }
 
+/// Transcribe an audio file (WAV, MP3, OGG) with Soniox and save the text
+/// to history. The file is streamed as fast as the connection accepts it.
+#[tauri::command]
+async fn transcribe_file(app: AppHandle, path: String) -> Result<String, String> {
+    transcribe_file_to_history(&app, &path).await
+}
+
+async fn transcribe_file_to_history(app: &AppHandle, path: &str) -> Result<String, String> {
+    let settings = app.state::<AppState>().settings.lock().unwrap().clone();
+    if settings.effective_api_key().is_empty() {
+        return Err(i18n::t("api-key-missing"));
+    }
+    let path = path.trim().to_string();
+    let file = path.clone();
+    let samples = tauri::async_runtime::spawn_blocking(move || audio::decode::load_file(&file))
+        .await
+        .map_err(|e| e.to_string())??;
+    if samples.is_empty() {
+        return Err(format!("'{}' contains no audio", path));
+    }
+
+    log::info!("Transcribing file '{}'", path);
+    let language = settings.language_hints.join(",");
+    let seconds = samples.len() as f64 / 16000.0;
+    let result = soniox::transcribe_samples(settings, samples).await;
+    // Billed whether or not anything was recognized
+    usage::record(app, TranscriptionProvider::Soniox, seconds);
+    let (text, words) = result?;
+    if text.trim().is_empty() {
+        return Err(i18n::t_args("no-speech-in-file", &[("path", path.to_string())]));
+    }
+
+    let store = persist::store(app, TRANSCRIPTIONS_STORE_PATH)?;
+    let mut entries: Vec<TranscriptionEntry> = store
+        .get("entries")
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default();
+    let now = unix_now();
+    entries.insert(
+        0,
+        TranscriptionEntry {
+            id: format!("file-{}-{}", now, FILE_ENTRY_SEQ.fetch_add(1, Ordering::Relaxed)),
+            text: text.clone(),
+            timestamp: now,
+            language,
+            audio_path: None,
+            words: (!words.is_empty()).then_some(words),
+            comparison: None,
+            app: None,
+            title: None,
+            source: EntrySource::File,
+            merged: false,
+        },
+    );
+    trim_history(&mut entries);
+    let json = serde_json::to_value(&entries).map_err(|e| e.to_string())?;
+    store.set("entries", json);
+    persist::schedule(app, TRANSCRIPTIONS_STORE_PATH);
+    app.emit("history-changed", ()).ok();
+    Ok(text)
+}
+
+/// Transcribe a session's saved recording again with Soniox's async file
+/// API, optionally with other language hints, and replace the history
+/// entry's text and word timings. Returns the updated entry.
+#[tauri::command]
+async fn retranscribe_session(
+    app: AppHandle,
+    id: String,
+    language_hints: Option<Vec<String>>,
+) -> Result<TranscriptionEntry, String> {
+    let mut settings = app.state::<AppState>().settings.lock().unwrap().clone();
+    if settings.effective_api_key().is_empty() {
+        return Err(i18n::t("api-key-missing"));
+    }
+    if let Some(hints) = language_hints {
+        settings.language_hints = hints;
+    }
+    let entry = get_transcriptions(app.clone())
+        .into_iter()
+        .find(|e| e.id == id)
+        .ok_or_else(|| format!("No history entry with id {}", id))?;
+    // The recording holds only the first of the merged sessions; its
+    // transcript would replace the text of the others
+    if entry.merged {
+        return Err(i18n::t("retranscribe-merged"));
+    }
+    let path = entry.audio_path.ok_or_else(|| "This session has no recording".to_string())?;
+
+    let wav = std::fs::read(&path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let seconds = hound::WavReader::new(std::io::Cursor::new(&wav))
+        .map(|r| r.duration() as f64 / r.spec().sample_rate as f64)
+        .map_err(|e| format!("'{}' is not a valid WAV file: {}", path, e))?;
+    let file_name = std::path::Path::new(&path)
+        .file_name()
+        .map_or_else(|| format!("{}.wav", id), |n| n.to_string_lossy().into_owned());
+
+    log::info!("Re-transcribing session {} ({:.1} s)", id, seconds);
+    let result = soniox::files::transcribe_wav(&settings, &file_name, wav).await;
+    usage::record(&app, TranscriptionProvider::Soniox, seconds);
+    let (text, words, languages) = result?;
+    if text.is_empty() {
+        return Err(i18n::t("no-speech-in-recording"));
+    }
+
+    // Re-read: history may have changed while the job ran
+    let store = persist::store(&app, TRANSCRIPTIONS_STORE_PATH)?;
+    let mut entries: Vec<TranscriptionEntry> = store
+        .get("entries")
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default();
+    let entry = entries
+        .iter_mut()
+        .find(|e| e.id == id)
+        .ok_or_else(|| format!("History entry {} was removed", id))?;
+    entry.text = text;
+    entry.words = (!words.is_empty()).then_some(words);
+    entry.language = if languages.is_empty() { settings.language_hints.join(",") } else { languages.join(",") };
+    let updated = entry.clone();
+
+    let json = serde_json::to_value(&entries).map_err(|e| e.to_string())?;
+    store.set("entries", json);
+    persist::schedule(&app, TRANSCRIPTIONS_STORE_PATH);
+    app.emit("history-changed", ()).ok();
+    Ok(updated)
+}
+
+/// Import transcripts from another dictation tool (`format`: "talon",
+/// "nerd-dictation" or "text-folder") into history.
+#[tauri::command]
+fn import_from(app: AppHandle, path: String, format: String) -> Result<importer::ImportReport, String> {
+    let format = importer::Format::parse(&format)?;
+    let found = importer::read(std::path::Path::new(path.trim()), format)?;
+
+    let store = persist::store(&app, TRANSCRIPTIONS_STORE_PATH)?;
+    let mut entries: Vec<TranscriptionEntry> = store
+        .get("entries")
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default();
+    let report = importer::merge(&mut entries, found);
+
+    let json = serde_json::to_value(&entries).map_err(|e| e.to_string())?;
+    store.set("entries", json);
+    persist::schedule(&app, TRANSCRIPTIONS_STORE_PATH);
+    app.emit("history-changed", ()).ok();
+    Ok(report)
+}
+
+/// Play a session's recording, optionally from `start_ms` (e.g. a clicked word).
+#[tauri::command]
+fn play_session_audio(
+    app: AppHandle,
+    state: tauri::State<AppState>,
+    id: String,
+    start_ms: Option<u64>,
+) -> Result<(), String> {
+    let entry = get_transcriptions(app)
+        .into_iter()
+        .find(|e| e.id == id)
+        .ok_or_else(|| format!("No history entry with id {}", id))?;
+    let path = entry
+        .audio_path
+        .ok_or_else(|| "This session has no recording".to_string())?;
+
+    // Only one playback at a time
+    stop_playback(state.clone());
+    let stop = Arc::new(AtomicBool::new(false));
+    audio::play_wav(&path, start_ms.unwrap_or(0), stop.clone())?;
+    *state.playback_stop.lock().unwrap() = Some(stop);
+    Ok(())
+}
+
+/// Write SRT or VTT subtitles for a history entry's word timings to `path`,
+/// or to the downloads folder when no path is given. Returns the file path.
+#[tauri::command]
+fn export_subtitles(app: AppHandle, id: String, format: String, path: Option<String>) -> Result<String, String> {
+    let format = subtitles::Format::parse(&format)?;
+    let entry = get_transcriptions(app.clone())
+        .into_iter()
+        .find(|e| e.id == id)
+        .ok_or_else(|| format!("No history entry with id {}", id))?;
+    let words = entry
+        .words
+        .filter(|w| !w.is_empty())
+        .ok_or_else(|| "This entry has no word timings".to_string())?;
+
+    let path = match path.as_deref().map(str::trim).filter(|p| !p.is_empty()) {
+        Some(path) => std::path::PathBuf::from(path),
+        None => app
+            .path()
+            .download_dir()
+            .map_err(|e| format!("No downloads folder: {}", e))?
+            .join(format!("dictation-{}.{}", id, format.extension())),
+    };
+    std::fs::write(&path, subtitles::render(&words, format))
+        .map_err(|e| format!("Failed to write {:?}: {}", path, e))?;
+    log::info!("Exported subtitles for {} words to {:?}", words.len(), path);
+    Ok(path.to_string_lossy().into_owned())
+}
+
+/// Write the transcription history as JSON to `path`, or to the downloads
+/// folder when no path is given. Returns the file path.
+#[tauri::command]
+fn export_history(app: AppHandle, path: Option<String>) -> Result<String, String> {
+    let path = match path.as_deref().map(str::trim).filter(|p| !p.is_empty()) {
+        Some(path) => std::path::PathBuf::from(path),
+        None => app
+            .path()
+            .download_dir()
+            .map_err(|e| format!("No downloads folder: {}", e))?
+            .join(format!("dictation-history-{}.json", unix_now())),
+    };
+    let entries = get_transcriptions(app);
+    let json = serde_json::to_vec_pretty(&entries).map_err(|e| e.to_string())?;
+    std::fs::write(&path, json).map_err(|e| format!("Failed to write {:?}: {}", path, e))?;
+    log::info!("Exported {} history entries to {:?}", entries.len(), path);
+    Ok(path.to_string_lossy().into_owned())
+}
+
+/// Write the audit journal of all sessions as CSV to `path`, or to the
+/// downloads folder when no path is given. Returns the file path.
+#[tauri::command]
+fn export_audit_journal(app: AppHandle, path: Option<String>) -> Result<String, String> {
+    let path = match path.as_deref().map(str::trim).filter(|p| !p.is_empty()) {
+        Some(path) => std::path::PathBuf::from(path),
+        None => app
+            .path()
+            .download_dir()
+            .map_err(|e| format!("No downloads folder: {}", e))?
+            .join(format!("dictation-journal-{}.csv", unix_now())),
+    };
+    let count = journal::export(&app, &path)?;
+    log::info!("Exported {} audit journal records to {:?}", count, path);
+    Ok(path.to_string_lossy().into_owned())
+}
+
+#[tauri::command]
+fn stop_playback(state: tauri::State<AppState>) {
+    if let Some(stop) = state.playback_stop.lock().unwrap().take() {
+        stop.store(true, Ordering::SeqCst);
+    }
+}
+
 #[tauri::command]
 fn clear_transcriptions(app: AppHandle) -> Result<(), String> {
-    let store = app.store(TRANSCRIPTIONS_STORE_PATH).map_err(|e| e.to_string())?;
+    let store = persist::store(&app, TRANSCRIPTIONS_STORE_PATH)?;
     let empty: Vec<TranscriptionEntry> = Vec::new();
     let json = serde_json::to_value(&empty).map_err(|e| e.to_string())?;
     store.set("entries", json);
-    store.save().map_err(|e| e.to_string())?;
+    persist::schedule(&app, TRANSCRIPTIONS_STORE_PATH);
+    app.emit("history-changed", ()).ok();
     Ok(())
 }
 
-fn show_overlay(app: &AppHandle) {
+fn unload_hidden_windows(app: &AppHandle) -> bool {
+    app.try_state::<AppState>()
+        .is_some_and(|s| s.settings.lock().unwrap().unload_hidden_windows)
+}
+
+/// Get a window by label, recreating it from `tauri.conf.json` if it was
+/// destroyed by the unload-when-hidden mode.
+fn get_or_create_window(app: &AppHandle, label: &str) -> Option<tauri::WebviewWindow> {
+    if let Some(window) = app.get_webview_window(label) {
+        return Some(window);
+    }
+
+    let config = app.config().app.windows.iter().find(|w| w.label == label)?.clone();
+    match tauri::WebviewWindowBuilder::from_config(app, &config).and_then(|b| b.build()) {
+        Ok(window) => {
+            log::info!("Recreated '{}' window", label);
+            if label == "main" {
+                attach_close_to_tray(&window);
+            }
+            Some(window)
+        }
+        Err(e) => {
+            log::error!("Failed to recreate '{}' window: {}", label, e);
+            None
+        }
+    }
+}
+
+/// Close to tray: intercept close on the main window and hide instead,
+/// or let it be destroyed when unloading hidden windows.
+fn attach_close_to_tray(window: &tauri::WebviewWindow) {
+    let window_clone = window.clone();
+    window.on_window_event(move |event| {
+        if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+            if unload_hidden_windows(window_clone.app_handle()) {
+                return;
+            }
+            api.prevent_close();
+            window_clone.hide().ok();
+        }
+    });
+}
+
+fn show_main_window(app: &AppHandle) {
+    if let Some(window) = get_or_create_window(app, "main") {
+        window.show().ok();
+        window.set_focus().ok();
+    }
+}
+
+/// Monitor the user is working on: the one under the cursor (a proxy for
+/// the focused window), falling back to the overlay's own and the primary.
+fn active_monitor(app: &AppHandle) -> Option<tauri::Monitor> {
+    if let Ok(cursor) = app.cursor_position() {
+        if let Ok(Some(monitor)) = app.monitor_from_point(cursor.x, cursor.y) {
+            return Some(monitor);
+        }
+    }
     if let Some(window) = app.get_webview_window("overlay") {
-        // Calculate centered horizontal position
-        let overlay_width = 200.0_f64;
-        let top_margin = 40.0_f64;
-        
-        let x = if let Ok(Some(monitor)) = window.current_monitor() {
-            let screen_width = monitor.size().width as f64 / monitor.scale_factor();
-            (screen_width - overlay_width) / 2.0
-        } else {
-            // Fallback: assume 1920px screen
-            (1920.0 - overlay_width) / 2.0
-        };
-        
+        if let Ok(Some(monitor)) = window.current_monitor() {
+            return Some(monitor);
+        }
+    }
+    app.primary_monitor().ok().flatten()
+}
+
+/// Overlay position in physical pixels, plus the scale factor of the
+/// monitor it lands on.
+fn overlay_position(app: &AppHandle) -> (tauri::PhysicalPosition<i32>, f64) {
+    // Calculate centered horizontal position
+    let overlay_width = 200.0_f64;
+    let overlay_height = 60.0_f64;
+    let top_margin = 40.0_f64;
+
+    let state: tauri::State<'_, AppState> = app.state();
+    let placement = state.settings.lock().unwrap().overlay_placement;
+    let target = *state.target_window_geometry.lock().unwrap();
+
+    // Work in physical pixels with the target monitor's own scale factor,
+    // so mixed-DPI multi-monitor setups (Windows per-monitor DPI) place
+    // the overlay correctly on secondary monitors too.
+    let (x, y, scale) = match (placement, target, active_monitor(app)) {
+        (OverlayPlacement::NearTarget, Some(target), Some(monitor)) => {
+            // Just inside the bottom edge of the target window, where input
+            // fields usually are; kept on screen for maximized windows.
+            let scale = monitor.scale_factor();
+            let x = target.x as f64 + (target.width as f64 - overlay_width * scale) / 2.0;
+            let y = target.y as f64 + target.height as f64 - (overlay_height + top_margin) * scale;
+            (x, y.max(target.y as f64), scale)
+        }
+        (_, _, Some(monitor)) => {
+            let scale = monitor.scale_factor();
+            let origin = monitor.position();
+            let x = origin.x as f64 + (monitor.size().width as f64 - overlay_width * scale) / 2.0;
+            let y = origin.y as f64 + top_margin * scale;
+            (x, y, scale)
+        }
+        // Fallback: assume 1920px screen
+        _ => ((1920.0 - overlay_width) / 2.0, top_margin, 1.0),
+    };
+
+    (tauri::PhysicalPosition::new(x.round() as i32, y.round() as i32), scale)
+}
+
+fn show_overlay(app: &AppHandle) {
+    let (position, scale) = overlay_position(app);
+
+    let backend = app.state::<AppState>().settings.lock().unwrap().overlay_backend;
+    if backend == OverlayBackend::Native && native_overlay::is_supported() {
+        let logical = position.to_logical::<f64>(scale);
+        native_overlay::show(app, logical.x.round() as i32, logical.y.round() as i32);
+        return;
+    }
+
+    if let Some(window) = get_or_create_window(app, "overlay") {
         // Set transparent background color to prevent black flash on Linux
         let _ = window.set_background_color(Some(tauri::webview::Color(0, 0, 0, 0)));
         
-        let _ = window.set_position(tauri::Position::Logical(tauri::LogicalPosition::new(x, top_margin)));
+        let _ = window.set_position(tauri::Position::Physical(position));
         
         // Small delay to let webview render before showing (prevents black border flash on Linux)
         #[cfg(target_os = "linux")]
@@ -201,88 +1650,267 @@ fn show_overlay(app: &AppHandle) {
     }
 }
 
+/// Platform-native "recording" indicator on the main window's taskbar/dock entry:
+/// - Windows: indeterminate taskbar progress
+/// - Linux: Unity LauncherEntry progress (docks that implement it)
+/// - macOS: dock badge
+fn set_recording_indicator(app: &AppHandle, recording: bool) {
+    if let Some(window) = app.get_webview_window("main") {
+        let status = if recording {
+            tauri::window::ProgressBarStatus::Indeterminate
+        } else {
+            tauri::window::ProgressBarStatus::None
+        };
+        let _ = window.set_progress_bar(tauri::window::ProgressBarState {
+            status: Some(status),
+            progress: None,
+        });
+
+        #[cfg(target_os = "macos")]
+        let _ = window.set_badge_label(if recording { Some("REC".to_string()) } else { None });
+    }
+}
+
 fn hide_overlay(app: &AppHandle) {
+    let backend = app.try_state::<AppState>().map(|s| s.settings.lock().unwrap().overlay_backend);
+    if backend == Some(OverlayBackend::Native) {
+        native_overlay::hide(app);
+    }
+
     if let Some(window) = app.get_webview_window("overlay") {
-        let _ = window.hide();
+        if unload_hidden_windows(app) {
+            let _ = window.destroy();
+        } else {
+            let _ = window.hide();
+        }
     }
 }
 
-async fn toggle_recording(app: AppHandle, state: tauri::State<'_, AppState>) -> Result<(), String> {
-    if state.is_recording.load(Ordering::SeqCst) {
-        // Stop recording
+/// Global shortcut handler. Debounces auto-repeat and accidental double
+/// presses so rapid on/off cycles can't race the session teardown.
+///
+/// `seat` names the seat whose hotkey was pressed; None for the main hotkey.
+fn on_hotkey_pressed(app: &AppHandle, seat: Option<String>) {
+    if !accept_hotkey_press(app) {
+        return;
+    }
+
+    let app_handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let state: tauri::State<'_, AppState> = app_handle.state();
+        if let Err(e) = toggle_recording(app_handle.clone(), state, seat, None).await {
+            log::error!("Hotkey toggle failed: {}", e);
+        }
+    });
+}
+
+/// Apply the cooldown shared by all hotkeys; false if this press is ignored.
+fn accept_hotkey_press(app: &AppHandle) -> bool {
+    let state: tauri::State<'_, AppState> = app.state();
+    let cooldown = std::time::Duration::from_millis(state.settings.lock().unwrap().hotkey_cooldown_ms);
+    let mut last = state.last_hotkey_press.lock().unwrap();
+    let now = std::time::Instant::now();
+    if let Some(prev) = *last {
+        if now.duration_since(prev) < cooldown {
+            log::debug!("Ignoring hotkey press within {:?} cooldown", cooldown);
+            return false;
+        }
+    }
+    *last = Some(now);
+    true
+}
+
+/// Start or stop a session. When starting, `seat` selects the display whose
+/// active window becomes the target and `language_hints` replaces the
+/// configured hints for this session; any hotkey stops a running session.
+async fn toggle_recording(
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+    seat: Option<String>,
+    language_hints: Option<Vec<String>>,
+) -> Result<(), String> {
+    // Claimed before anything awaits: a second press while the session is
+    // being set up stops it rather than starting another one
+    if state.is_recording.compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst).is_err() {
         log::info!("Hotkey: stopping recording");
-        state.stop_signal.store(true, Ordering::SeqCst);
+        stop_active_session(&app);
+        return Ok(());
+    }
+    log::info!("Hotkey: starting recording");
+    // Fresh tokens; the last session's stay cancelled
+    let tokens = stop::SessionTokens::new();
+    *state.session_tokens.lock().unwrap() = tokens.clone();
+
+    let mut settings = state.settings.lock().unwrap().clone();
+    
+    let missing_key = match settings.provider {
+        TranscriptionProvider::Soniox if settings.effective_api_key().is_empty() => Some("api-key-missing"),
+        TranscriptionProvider::OpenAi if settings.openai_api_key.is_empty() => Some("openai-key-missing"),
+        TranscriptionProvider::Azure if settings.azure_speech_key.is_empty() || settings.azure_region.is_empty() => {
+            Some("azure-key-missing")
+        }
+        _ => None,
+    };
+    if let Some(key) = missing_key {
+        log::error!("API key is empty");
+        let message = i18n::t(key);
+        app.emit("recording-error", error::TranscriptionError::Auth(message.clone())).ok();
         state.is_recording.store(false, Ordering::SeqCst);
-        hide_overlay(&app);
-        app.emit("recording-stopped", ()).ok();
+        return Err(message);
+    }
+    
+    let display = match &seat {
+        Some(name) => match settings.seats.iter().find(|s| &s.name == name) {
+            Some(seat) => Some(seat.display.clone()),
+            None => {
+                state.is_recording.store(false, Ordering::SeqCst);
+                return Err(i18n::t_args("unknown-seat", &[("name", name.clone())]));
+            }
+        },
+        None => None,
+    };
+
+    // CRITICAL: Capture the target window FIRST - before any UI changes
+    let target_app = if display.is_none() { typer::focused_app() } else { None };
+    let refocus_app = target_app.as_ref().filter(|_| settings.refocus_target);
+    let target_window_id = match typer::capture_focused_window_on(display.as_deref(), refocus_app) {
+        Ok(id) => id,
+        Err(e) => {
+            log::error!("Failed to capture target window: {}", e);
+            let message = i18n::t_args("target-window-failed", &[("error", e.clone())]);
+            app.emit("recording-error", error::TranscriptionError::Typing(message)).ok();
+            state.is_recording.store(false, Ordering::SeqCst);
+            return Err(e);
+        }
+    };
+    
+    {
+        let mut tw = state.target_window_id.lock().unwrap();
+        *tw = Some(target_window_id.clone());
+    }
+    *state.session_started_at.lock().unwrap() = Some(unix_now());
+    let session_id = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis()
+        .to_string();
+    *state.session_id.lock().unwrap() = Some(session_id.clone());
+    *state.last_session_text.lock().unwrap() = None;
+    *state.last_session_audio.lock().unwrap() = None;
+    *state.last_session_words.lock().unwrap() = None;
+    *state.last_session_languages.lock().unwrap() = None;
+    *state.last_session_comparison.lock().unwrap() = None;
+    // The overlay lives on our own display, so only place it near targets there
+    *state.target_window_geometry.lock().unwrap() = if display.is_none() {
+        typer::focused_window_geometry()
     } else {
-        // Start recording
-        log::info!("Hotkey: starting recording");
-        
-        let settings = state.settings.lock().unwrap().clone();
-        
-        if settings.api_key.is_empty() {
-            log::error!("API key is empty");
-            app.emit("recording-error", "API key not configured. Please set your Soniox API key in settings.").ok();
-            return Err("API key not configured".to_string());
+        None
+    };
+    eprintln!("DEBUG: Target window captured via hotkey: {}", target_window_id);
+
+    // A profile bound to the target app applies to this session only
+    if let Some(app) = &target_app {
+        if let Some(name) = settings.profile_for_app(&app.app_id).map(str::to_string) {
+            log::info!("Using profile '{}' for {}", name, app.app_id);
+            settings.active_profile = Some(name);
         }
+    }
+    // Applied after the profile so a language hotkey wins over it
+    if let Some(hints) = &language_hints {
+        log::info!("Using language hints {:?} for this session", hints);
+        settings.language_hints = hints.clone();
+    }
+    *state.session_language_hints.lock().unwrap() = language_hints;
+    typer::set_clipboard_delays(settings.effective_clipboard_delays());
+    journal::begin(&app, &session_id, target_app.as_ref());
+    *state.target_app.lock().unwrap() = target_app;
+    
+    // Pick up edits to user scripts without a restart
+    scripting::reload();
+    // Read ahead so the title is ready when the session is saved
+    if let Some(source) = settings.calendar_ics.clone() {
+        tauri::async_runtime::spawn(async move { calendar::refresh(&source).await });
+    }
+
+    // Connect while the microphone opens instead of after it
+    if settings.provider == TranscriptionProvider::Soniox {
+        soniox::warm::prepare(&app, &settings);
+    }
+
+    let battery_saving = settings.battery_saver && battery::on_battery();
+    if battery_saving {
+        battery::reduce_for_battery(&mut settings);
+    }
+    state.battery_saving.store(battery_saving, Ordering::SeqCst);
+
+    let sharing = if settings.review_while_sharing {
+        tauri::async_runtime::spawn_blocking(screenshare::detect).await.ok().flatten()
+    } else {
+        None
+    };
+    // A clipboard rewrite session only records the instruction
+    let rewriting = state.rewrite_source.lock().unwrap().is_some();
+    state.review_mode.store(sharing.is_some() || rewriting, Ordering::SeqCst);
+
+    // Stopped while detecting the screen share: nothing was started yet
+    if tokens.stop.is_cancelled() {
+        log::info!("Session stopped before recording started");
+        return Ok(());
+    }
+
+    // Show overlay AFTER capturing the target window
+    show_overlay(&app);
+    set_recording_indicator(&app, true);
+    let ducking_session = ducking::begin();
+    if settings.duck_media {
+        let percent = settings.duck_volume_percent;
+        tauri::async_runtime::spawn_blocking(move || ducking::duck(ducking_session, percent));
+    }
+    
+    app.emit("recording-started", ()).ok();
+    console::record(&app, "session", "Recording started");
+    if let Some(name) = sharing {
+        let message = i18n::t_args("review-mode-sharing", &[("name", name)]);
+        log::info!("{}", message);
+        console::record(&app, "session", message.clone());
+        app.emit("review-mode", message).ok();
+    }
+    
+    let stop_signal = tokens.stop;
+    let is_recording = state.is_recording.clone();
+    let app_clone = app.clone();
+    
+    metrics::inc_sessions();
+
+    // Spawn recording in a separate task
+    let handle = tokio::spawn(async move {
+        log::info!("Starting audio capture in background task...");
         
-        // CRITICAL: Capture the target window FIRST - before any UI changes
-        let target_window_id = match typer::capture_focused_window() {
-            Ok(id) => id,
+        match audio::start_audio_capture(settings, stop_signal.clone(), app_clone.clone(), target_window_id).await {
+            Ok(_) => log::info!("Audio capture completed successfully"),
             Err(e) => {
-                log::error!("Failed to capture target window: {}", e);
-                app.emit("recording-error", format!("Failed to capture target window: {}", e)).ok();
-                return Err(e);
+                log::error!("Audio capture failed: {}", e);
+                console::record(&app_clone, "error", format!("Audio capture failed: {}", e));
+                app_clone.emit("recording-error", e).ok();
             }
-        };
-        
-        {
-            let mut tw = state.target_window_id.lock().unwrap();
-            *tw = Some(target_window_id.clone());
         }
-        eprintln!("DEBUG: Target window captured via hotkey: {}", target_window_id);
-        
-        // Reset stop signal
-        state.stop_signal.store(false, Ordering::SeqCst);
-        state.is_recording.store(true, Ordering::SeqCst);
-        
-        // Show overlay AFTER capturing the target window
-        show_overlay(&app);
         
-        app.emit("recording-started", ()).ok();
-        
-        let stop_signal = state.stop_signal.clone();
-        let is_recording = state.is_recording.clone();
-        let api_key = settings.api_key.clone();
-        let language_hints = settings.language_hints.clone();
-        let language_restrictions = settings.language_restrictions.clone();
-        let app_clone = app.clone();
-        
-        // Spawn recording in a separate task
-        let handle = tokio::spawn(async move {
-            log::info!("Starting audio capture in background task...");
-            
-            match audio::start_audio_capture(api_key, language_hints, language_restrictions, stop_signal.clone(), app_clone.clone(), target_window_id).await {
-                Ok(_) => log::info!("Audio capture completed successfully"),
-                Err(e) => {
-                    log::error!("Audio capture failed: {}", e);
-                    app_clone.emit("recording-error", e).ok();
-                }
-            }
-            
-            is_recording.store(false, Ordering::SeqCst);
-            hide_overlay(&app_clone);
-            app_clone.emit("recording-stopped", ()).ok();
-        });
-        
-        // Store the handle
-        {
-            let mut task = state.recording_task.lock().unwrap();
-            *task = Some(handle);
-        }
-    }
+        is_recording.store(false, Ordering::SeqCst);
+        // Left over when the session ended without any text
+        app_clone.state::<AppState>().rewrite_source.lock().unwrap().take();
+        soniox::warm::keep_warm(&app_clone);
+        ducking::restore(ducking_session);
+        hide_overlay(&app_clone);
+        set_recording_indicator(&app_clone, false);
+        app_clone.emit("recording-stopped", ()).ok();
+    });
     
+    // Store the handle
+    {
+        let mut task = state.recording_task.lock().unwrap();
+        *task = Some(handle);
+    }
+
     Ok(())
 }
 
@@ -292,57 +1920,136 @@ async fn start_recording(
     state: tauri::State<'_, AppState>,
 ) -> Result<(), String> {
     log::info!("start_recording called from button");
-    toggle_recording(app, state).await
+    toggle_recording(app, state, None, None).await
 }
 
 #[tauri::command]
-fn stop_recording(
-    app: AppHandle,
-    state: tauri::State<AppState>
-) -> Result<(), String> {
+fn stop_recording(app: AppHandle) -> Result<(), String> {
     log::info!("stop_recording called");
-    state.stop_signal.store(true, Ordering::SeqCst);
-    state.is_recording.store(false, Ordering::SeqCst);
-    hide_overlay(&app);
-    app.emit("recording-stopped", ()).ok();
+    stop_active_session(&app);
     Ok(())
 }
 
+/// Signal the active session to finish and update the UI. The session task
+/// still delivers its final tokens. Returns false if nothing was recording.
+fn stop_active_session(app: &AppHandle) -> bool {
+    let state: tauri::State<'_, AppState> = app.state();
+    let was_recording = state.is_recording.swap(false, Ordering::SeqCst);
+    console::record(app, "session", "Recording stopped");
+    state.session_tokens.lock().unwrap().stop.cancel();
+    // Bring the media back right away rather than after the final tokens
+    ducking::restore_current();
+    hide_overlay(app);
+    set_recording_indicator(app, false);
+    app.emit("recording-stopped", ()).ok();
+    was_recording
+}
+
+/// Stop the active session without saving it to history. Text already
+/// typed stays. Returns false if nothing was recording.
+fn cancel_active_session(app: &AppHandle) -> bool {
+    let state: tauri::State<'_, AppState> = app.state();
+    if !state.is_recording.load(Ordering::SeqCst) {
+        return false;
+    }
+    // Also ends typing; the session's text is not saved
+    state.session_tokens.lock().unwrap().cancel.cancel();
+    console::record(app, "session", "Session cancelled");
+    stop_active_session(app)
+}
+
+/// Turn incognito mode on or off and tell the UI.
+fn set_incognito(app: &AppHandle, enabled: bool) {
+    app.state::<AppState>().incognito.store(enabled, Ordering::SeqCst);
+    log::info!("Incognito mode {}", if enabled { "on" } else { "off" });
+    app.emit("incognito-changed", enabled).ok();
+}
+
+/// `--status [--follow]` command line mode for status bar modules; see
+/// the `statusbar` module. Returns the process exit code.
+pub fn print_status(follow: bool) -> i32 {
+    statusbar::print(follow)
+}
+
 pub fn run() {
     env_logger::init();
+    datadir::init();
+
+    #[cfg(feature = "headless")]
+    let headless_settings = headless::load_settings().unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(2);
+    });
+    #[cfg_attr(not(feature = "headless"), allow(unused_mut))]
+    let mut context = tauri::generate_context!();
+    #[cfg(feature = "headless")]
+    headless::remove_main_window(&mut context);
 
     tauri::Builder::default()
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_store::Builder::new().build())
-        .setup(|app| {
+        .setup(move |app| {
+            // Before the first store access, which would cache a corrupted file as empty
+            integrity::check_stores(app.handle());
+            #[cfg(feature = "headless")]
+            let settings = headless_settings;
+            #[cfg(not(feature = "headless"))]
             let settings = load_settings_from_store(&app.handle());
+            i18n::set_language(&settings.ui_language);
             let hotkey_str = settings.hotkey.clone();
+            let tray_menu_on_left_click = settings.tray_left_click == TRAY_MENU_ACTION;
+
+            match datadir::dir(app.handle()) {
+                Ok(dir) => {
+                    plugins::init(dir.join("plugins"));
+                    scripting::init(dir.join("scripts"), dir.join("script-output"));
+                }
+                Err(e) => log::warn!("No app data dir, plugins and scripts disabled: {}", e),
+            }
+            pending::init(app.handle());
+
+            if let Some(port) = settings.metrics_port {
+                tauri::async_runtime::spawn(metrics::serve(port));
+            }
             
             let app_state = AppState {
                 is_recording: Arc::new(AtomicBool::new(false)),
-                stop_signal: Arc::new(AtomicBool::new(false)),
+                session_tokens: Mutex::new(stop::SessionTokens::new()),
                 settings: Mutex::new(settings),
                 recording_task: Mutex::new(None),
                 target_window_id: Mutex::new(None),
+                session_started_at: Mutex::new(None),
+                session_id: Mutex::new(None),
+                last_session_audio: Mutex::new(None),
+                last_session_words: Mutex::new(None),
+                last_session_languages: Mutex::new(None),
+                session_language_hints: Mutex::new(None),
+                last_session_comparison: Mutex::new(None),
+                last_session_text: Mutex::new(None),
+                last_saved_session: Mutex::new(None),
+                playback_stop: Mutex::new(None),
+                last_hotkey_press: Mutex::new(None),
+                target_window_geometry: Mutex::new(None),
+                battery_saving: AtomicBool::new(false),
+                review_mode: AtomicBool::new(false),
+                target_app: Mutex::new(None),
+                incognito: AtomicBool::new(false),
+                rewrite_source: Mutex::new(None),
             };
             
             app.manage(app_state);
+            power::spawn_watcher(app.handle().clone());
+            statusbar::init(app.handle());
+            folderwatch::sync(app.handle());
+            voicecommands::sync(app.handle());
+            soniox::warm::keep_warm(app.handle());
             
             // Hide overlay window initially
-            if let Some(window) = app.get_webview_window("overlay") {
-                let _ = window.hide();
-            }
+            hide_overlay(app.handle());
 
-            // Close to tray: intercept close on main window and hide instead
             if let Some(window) = app.get_webview_window("main") {
-                let window_clone = window.clone();
-                window.on_window_event(move |event| {
-                    if let tauri::WindowEvent::CloseRequested { api, .. } = event {
-                        api.prevent_close();
-                        window_clone.hide().ok();
-                    }
-                });
+                attach_close_to_tray(&window);
             }
             
             // Register global hotkey in Rust (no JS/webview focus change)
@@ -355,38 +2062,42 @@ pub fn run() {
                     
                     match gs.on_shortcut(hotkey_shortcut, |app, _shortcut, event| {
                         if event.state == tauri_plugin_global_shortcut::ShortcutState::Pressed {
-                            let app_handle = app.clone();
-                            tauri::async_runtime::spawn(async move {
-                                let state: tauri::State<'_, AppState> = app_handle.state();
-                                if let Err(e) = toggle_recording(app_handle.clone(), state).await {
-                                    log::error!("Hotkey toggle failed: {}", e);
-                                }
-                            });
+                            on_hotkey_pressed(app, None);
                         }
                     }) {
                         Ok(_) => log::info!("Global hotkey '{}' registered", hotkey_str),
-                        Err(e) => log::warn!("Could not register hotkey '{}': {}. You can change it in settings.", hotkey_str, e),
+                        Err(e) => {
+                            log::warn!("Could not register hotkey '{}': {}. You can change it in settings.", hotkey_str, e);
+                            conflicts::hotkey_taken(app.handle(), &hotkey_str, &e.to_string());
+                        }
                     }
                 }
                 Err(e) => log::warn!("Invalid hotkey '{}': {:?}. You can change it in settings.", hotkey_str, e),
             }
+            register_seat_hotkeys(app.handle());
+            register_action_hotkeys(app.handle());
+            watchdog::spawn(app.handle().clone());
+            conflicts::check(app.handle(), &hotkey_str);
             
-            let quit = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
-            let show = MenuItem::with_id(app, "show", "Show", true, None::<&str>)?;
-            let menu = Menu::with_items(app, &[&show, &quit])?;
+            let quit = MenuItem::with_id(app, "quit", i18n::t("tray-quit"), true, None::<&str>)?;
+            let show = MenuItem::with_id(app, "show", i18n::t("tray-show"), true, None::<&str>)?;
+            // Headless builds have no window to show
+            let menu = if cfg!(feature = "headless") {
+                Menu::with_items(app, &[&quit])?
+            } else {
+                Menu::with_items(app, &[&show, &quit])?
+            };
 
-            let _tray = TrayIconBuilder::new()
+            let _tray = TrayIconBuilder::with_id(TRAY_ID)
                 .menu(&menu)
+                .show_menu_on_left_click(tray_menu_on_left_click)
                 .tooltip("Desktop Dictate - Click to configure")
                 .on_menu_event(|app, event| match event.id.as_ref() {
                     "quit" => {
                         app.exit(0);
                     }
                     "show" => {
-                        if let Some(window) = app.get_webview_window("main") {
-                            window.show().ok();
-                            window.set_focus().ok();
-                        }
+                        show_main_window(app);
                     }
                     _ => {}
                 })
@@ -397,11 +2108,7 @@ pub fn run() {
                         ..
                     } = event
                     {
-                        let app = tray.app_handle();
-                        if let Some(window) = app.get_webview_window("main") {
-                            window.show().ok();
-                            window.set_focus().ok();
-                        }
+                        on_tray_left_click(tray.app_handle());
                     }
                 })
                 .build(app)?;
@@ -415,9 +2122,53 @@ pub fn run() {
             start_recording,
             stop_recording,
             get_transcriptions,
-            save_transcription,
+            get_transcriptions_page,
             clear_transcriptions,
+            import_from,
+            transcribe_file,
+            retranscribe_session,
+            probe_provider_latency,
+            evaluate_accuracy,
+            switch_credential,
+            list_actions,
+            invoke_action,
+            get_recent_events,
+            get_usage_stats,
+            get_analytics,
+            get_provider_status,
+            is_battery_saving,
+            list_models,
+            download_model,
+            list_audio_devices,
+            get_typing_dependencies,
+            screen_share_detection_supported,
+            install_typing_tool,
+            setup_ydotoold_service,
+            get_store_issues,
+            dismiss_store_issues,
+            get_unsent_insertions,
+            replay_unsent_insertions,
+            discard_unsent_insertions,
+            get_conflicts,
+            dismiss_conflicts,
+            play_session_audio,
+            stop_playback,
+            export_subtitles,
+            export_audit_journal,
+            export_history,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(context)
+        .expect("error while building tauri application")
+        .run(|app, event| match event {
+            // Stay resident in the tray when every window has been closed
+            // (or unloaded); only an explicit app.exit() quits.
+            tauri::RunEvent::ExitRequested { code: None, api, .. } => api.prevent_exit(),
+            // Don't lose changes still waiting out the write debounce
+            tauri::RunEvent::Exit => {
+                persist::flush(app);
+                statusbar::shutdown();
+                pending::shutdown();
+            }
+            _ => {}
+        });
 }
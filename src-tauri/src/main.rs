@@ -1,3 +1,10 @@
 fn main() {
+    // Status bar modules (Waybar, Polybar) query the running app's state
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.iter().any(|a| a == "--status") {
+        let follow = args.iter().any(|a| a == "--follow");
+        std::process::exit(desktop_dictate_app_lib::print_status(follow));
+    }
+
     desktop_dictate_app_lib::run();
 }
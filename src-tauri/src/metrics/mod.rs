@@ -0,0 +1,141 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+// PCM s16le mono at 16 kHz
+const BYTES_PER_SECOND: f64 = 32000.0;
+
+static SESSIONS_TOTAL: AtomicU64 = AtomicU64::new(0);
+static AUDIO_BYTES_STREAMED: AtomicU64 = AtomicU64::new(0);
+static TYPING_FAILURES_TOTAL: AtomicU64 = AtomicU64::new(0);
+static RECONNECTS_TOTAL: AtomicU64 = AtomicU64::new(0);
+// Sessions by capture path: passthrough, format conversion, resampling
+static CAPTURE_PATHS: [AtomicU64; 3] = [AtomicU64::new(0), AtomicU64::new(0), AtomicU64::new(0)];
+const CAPTURE_PATH_LABELS: [&str; 3] = ["passthrough", "convert", "resample"];
+
+pub fn inc_sessions() {
+    SESSIONS_TOTAL.fetch_add(1, Ordering::Relaxed);
+}
+
+pub fn add_audio_bytes(bytes: usize) {
+    AUDIO_BYTES_STREAMED.fetch_add(bytes as u64, Ordering::Relaxed);
+}
+
+pub fn inc_typing_failures() {
+    TYPING_FAILURES_TOTAL.fetch_add(1, Ordering::Relaxed);
+}
+
+pub fn inc_reconnects() {
+    RECONNECTS_TOTAL.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Text insertions that failed since the app started.
+pub fn typing_failures() -> u64 {
+    TYPING_FAILURES_TOTAL.load(Ordering::Relaxed)
+}
+
+/// Provider reconnection attempts since the app started.
+pub fn reconnects() -> u64 {
+    RECONNECTS_TOTAL.load(Ordering::Relaxed)
+}
+
+/// Count a session captured through `path` ("passthrough", "convert" or
+/// "resample").
+pub fn inc_capture_path(path: &str) {
+    if let Some(i) = CAPTURE_PATH_LABELS.iter().position(|&label| label == path) {
+        CAPTURE_PATHS[i].fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Render all counters in the Prometheus text exposition format.
+pub fn render() -> String {
+    let seconds = AUDIO_BYTES_STREAMED.load(Ordering::Relaxed) as f64 / BYTES_PER_SECOND;
+    let capture_paths: String = CAPTURE_PATH_LABELS
+        .iter()
+        .zip(&CAPTURE_PATHS)
+        .map(|(label, count)| {
+            format!("dictate_capture_sessions_total{{path=\"{}\"}} {}\n", label, count.load(Ordering::Relaxed))
+        })
+        .collect();
+    format!(
+        "# HELP dictate_sessions_total Dictation sessions started.\n\
+         # TYPE dictate_sessions_total counter\n\
+         dictate_sessions_total {}\n\
+         # HELP dictate_audio_streamed_seconds_total Seconds of audio streamed to the provider.\n\
+         # TYPE dictate_audio_streamed_seconds_total counter\n\
+         dictate_audio_streamed_seconds_total {:.3}\n\
+         # HELP dictate_typing_failures_total Text insertions that failed.\n\
+         # TYPE dictate_typing_failures_total counter\n\
+         dictate_typing_failures_total {}\n\
+         # HELP dictate_reconnects_total Provider reconnection attempts.\n\
+         # TYPE dictate_reconnects_total counter\n\
+         dictate_reconnects_total {}\n\
+         # HELP dictate_capture_sessions_total Microphone sessions by capture path (passthrough = device already 16 kHz mono i16).\n\
+         # TYPE dictate_capture_sessions_total counter\n\
+         {}",
+        SESSIONS_TOTAL.load(Ordering::Relaxed),
+        seconds,
+        TYPING_FAILURES_TOTAL.load(Ordering::Relaxed),
+        RECONNECTS_TOTAL.load(Ordering::Relaxed),
+        capture_paths,
+    )
+}
+
+/// Serve `GET /metrics` on 127.0.0.1:`port` until the app exits.
+///
+/// This is a deliberately tiny HTTP/1.0-style responder: one request per
+/// connection, no keep-alive. It only binds to loopback.
+pub async fn serve(port: u16) {
+    let listener = match TcpListener::bind(("127.0.0.1", port)).await {
+        Ok(l) => l,
+        Err(e) => {
+            log::warn!("Could not start metrics endpoint on port {}: {}", port, e);
+            return;
+        }
+    };
+    log::info!("Metrics endpoint listening on http://127.0.0.1:{}/metrics", port);
+
+    loop {
+        let (mut socket, _) = match listener.accept().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                log::warn!("Metrics endpoint accept failed: {}", e);
+                continue;
+            }
+        };
+
+        tokio::spawn(async move {
+            let mut buf = [0u8; 1024];
+            let n = match socket.read(&mut buf).await {
+                Ok(n) => n,
+                Err(_) => return,
+            };
+            let request = String::from_utf8_lossy(&buf[..n]);
+            let path = request
+                .lines()
+                .next()
+                .and_then(|line| {
+                    let mut parts = line.split_whitespace();
+                    match (parts.next(), parts.next()) {
+                        (Some("GET"), Some(path)) => Some(path),
+                        _ => None,
+                    }
+                })
+                .unwrap_or("");
+
+            let response = if path == "/metrics" {
+                let body = render();
+                format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                )
+            } else {
+                "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string()
+            };
+
+            let _ = socket.write_all(response.as_bytes()).await;
+            let _ = socket.shutdown().await;
+        });
+    }
+}
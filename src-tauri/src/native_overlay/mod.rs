@@ -0,0 +1,306 @@
+//! Lightweight recording indicator drawn natively instead of in a webview.
+//!
+//! Avoids the webview's GPU/compositing cost and the transparency glitches
+//! some Linux compositors show for transparent webview windows. On Linux it
+//! is a plain GTK window; with the `layer-shell` feature it uses a
+//! wlr-layer-shell surface on Wayland, which is the only way to position an
+//! always-on-top window there. On macOS it is a non-activating `NSPanel`
+//! that floats over full-screen apps on every Space without taking focus.
+//!
+//! Windows keeps the webview overlay.
+
+use tauri::AppHandle;
+
+/// Whether the native overlay can be used on this platform.
+pub fn is_supported() -> bool {
+    cfg!(any(target_os = "linux", target_os = "macos"))
+}
+
+#[cfg(target_os = "linux")]
+mod imp {
+    use gtk::prelude::*;
+    use std::cell::RefCell;
+
+    const WIDTH: i32 = 200;
+    const HEIGHT: i32 = 60;
+    const TOP_MARGIN: i32 = 40;
+
+    const CSS: &str = "
+        window { background-color: transparent; }
+        .pill { background-color: rgba(0, 0, 0, 0.65); border-radius: 24px; padding: 10px 16px; }
+        .dot { color: #ef4444; }
+        .dot.quiet { opacity: 0.35; }
+        .label { color: white; }
+    ";
+
+    thread_local! {
+        // GTK objects are not Send; they only ever live on the main thread
+        static WINDOW: RefCell<Option<gtk::Window>> = const { RefCell::new(None) };
+        static DOT: RefCell<Option<gtk::Label>> = const { RefCell::new(None) };
+    }
+
+    fn build_window() -> gtk::Window {
+        let window = gtk::Window::new(gtk::WindowType::Popup);
+        window.set_decorated(false);
+        window.set_keep_above(true);
+        window.set_accept_focus(false);
+        window.set_skip_taskbar_hint(true);
+        window.set_default_size(WIDTH, HEIGHT);
+        window.set_app_paintable(true);
+
+        if let Some(screen) = gtk::prelude::WidgetExt::screen(&window) {
+            if let Some(visual) = screen.rgba_visual() {
+                window.set_visual(Some(&visual));
+            }
+            let provider = gtk::CssProvider::new();
+            if provider.load_from_data(CSS.as_bytes()).is_ok() {
+                gtk::StyleContext::add_provider_for_screen(
+                    &screen,
+                    &provider,
+                    gtk::STYLE_PROVIDER_PRIORITY_APPLICATION,
+                );
+            }
+        }
+
+        let pill = gtk::Box::new(gtk::Orientation::Horizontal, 10);
+        pill.style_context().add_class("pill");
+        pill.set_halign(gtk::Align::Center);
+        pill.set_valign(gtk::Align::Center);
+
+        let dot = gtk::Label::new(Some("●"));
+        dot.style_context().add_class("dot");
+        dot.style_context().add_class("quiet");
+        DOT.with(|cell| *cell.borrow_mut() = Some(dot.clone()));
+        let label = gtk::Label::new(Some("Recording"));
+        label.style_context().add_class("label");
+        pill.add(&dot);
+        pill.add(&label);
+        window.add(&pill);
+
+        #[cfg(feature = "layer-shell")]
+        if gtk_layer_shell::is_supported() {
+            use gtk_layer_shell::{Edge, KeyboardMode, Layer, LayerShell};
+            window.init_layer_shell();
+            window.set_layer(Layer::Overlay);
+            window.set_keyboard_mode(KeyboardMode::None);
+            // Layer surfaces are positioned per output, not in global
+            // coordinates: anchor to the top edge, centered horizontally.
+            window.set_anchor(Edge::Top, true);
+            window.set_layer_shell_margin(Edge::Top, TOP_MARGIN);
+        }
+
+        window
+    }
+
+    pub fn show(x: i32, y: i32) {
+        WINDOW.with(|cell| {
+            let mut slot = cell.borrow_mut();
+            let window = slot.get_or_insert_with(build_window);
+
+            #[cfg(feature = "layer-shell")]
+            let is_layer = gtk_layer_shell::LayerShell::is_layer_window(window);
+            #[cfg(not(feature = "layer-shell"))]
+            let is_layer = false;
+
+            if !is_layer {
+                window.move_(x, y);
+            }
+            window.show_all();
+        });
+    }
+
+    /// Dim the dot while no speech is detected.
+    pub fn set_speaking(speaking: bool) {
+        DOT.with(|cell| {
+            if let Some(dot) = cell.borrow().as_ref() {
+                if speaking {
+                    dot.style_context().remove_class("quiet");
+                } else {
+                    dot.style_context().add_class("quiet");
+                }
+            }
+        });
+    }
+
+    pub fn hide() {
+        WINDOW.with(|cell| {
+            if let Some(window) = cell.borrow().as_ref() {
+                window.hide();
+            }
+        });
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod imp {
+    use objc::runtime::{Object, NO, YES};
+    use objc::{class, msg_send, sel, sel_impl};
+    use std::cell::Cell;
+
+    const WIDTH: f64 = 200.0;
+    const HEIGHT: f64 = 44.0;
+    const QUIET_ALPHA: f64 = 0.35;
+    /// NSWindowStyleMaskBorderless | NSWindowStyleMaskNonactivatingPanel
+    const STYLE_MASK: u64 = 1 << 7;
+    const BACKING_BUFFERED: u64 = 2;
+    /// NSStatusWindowLevel: above normal and floating windows
+    const STATUS_WINDOW_LEVEL: i64 = 25;
+    /// canJoinAllSpaces | stationary | ignoresCycle | fullScreenAuxiliary
+    const COLLECTION_BEHAVIOR: u64 = (1 << 0) | (1 << 4) | (1 << 6) | (1 << 8);
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct NSPoint {
+        x: f64,
+        y: f64,
+    }
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct NSSize {
+        width: f64,
+        height: f64,
+    }
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct NSRect {
+        origin: NSPoint,
+        size: NSSize,
+    }
+
+    fn rect(x: f64, y: f64, width: f64, height: f64) -> NSRect {
+        NSRect { origin: NSPoint { x, y }, size: NSSize { width, height } }
+    }
+
+    thread_local! {
+        // AppKit objects are main-thread only; both live as long as the app
+        static PANEL: Cell<*mut Object> = const { Cell::new(std::ptr::null_mut()) };
+        static DOT: Cell<*mut Object> = const { Cell::new(std::ptr::null_mut()) };
+    }
+
+    unsafe fn ns_string(text: &str) -> *mut Object {
+        let text = std::ffi::CString::new(text).unwrap_or_default();
+        msg_send![class!(NSString), stringWithUTF8String: text.as_ptr()]
+    }
+
+    unsafe fn label(text: &str, frame: NSRect, color: *mut Object) -> *mut Object {
+        let field: *mut Object = msg_send![class!(NSTextField), labelWithString: ns_string(text)];
+        let () = msg_send![field, setFrame: frame];
+        let () = msg_send![field, setTextColor: color];
+        field
+    }
+
+    unsafe fn build_panel() -> *mut Object {
+        let panel: *mut Object = msg_send![class!(NSPanel), alloc];
+        let panel: *mut Object = msg_send![panel,
+            initWithContentRect: rect(0.0, 0.0, WIDTH, HEIGHT)
+            styleMask: STYLE_MASK
+            backing: BACKING_BUFFERED
+            defer: NO];
+        let () = msg_send![panel, setLevel: STATUS_WINDOW_LEVEL];
+        let () = msg_send![panel, setCollectionBehavior: COLLECTION_BEHAVIOR];
+        let () = msg_send![panel, setFloatingPanel: YES];
+        let () = msg_send![panel, setBecomesKeyOnlyIfNeeded: YES];
+        let () = msg_send![panel, setHidesOnDeactivate: NO];
+        let () = msg_send![panel, setIgnoresMouseEvents: YES];
+        let () = msg_send![panel, setReleasedWhenClosed: NO];
+        let () = msg_send![panel, setOpaque: NO];
+        let () = msg_send![panel, setHasShadow: NO];
+        let clear: *mut Object = msg_send![class!(NSColor), clearColor];
+        let () = msg_send![panel, setBackgroundColor: clear];
+
+        // The pill is the content view's layer, rounded
+        let content: *mut Object = msg_send![panel, contentView];
+        let () = msg_send![content, setWantsLayer: YES];
+        let layer: *mut Object = msg_send![content, layer];
+        let pill: *mut Object = msg_send![class!(NSColor), colorWithCalibratedWhite: 0.0f64 alpha: 0.65f64];
+        let pill: *const std::ffi::c_void = msg_send![pill, CGColor];
+        let () = msg_send![layer, setBackgroundColor: pill];
+        let () = msg_send![layer, setCornerRadius: HEIGHT / 2.0];
+
+        let red: *mut Object = msg_send![class!(NSColor), systemRedColor];
+        let dot = label("●", rect(16.0, 12.0, 20.0, 20.0), red);
+        let () = msg_send![dot, setAlphaValue: QUIET_ALPHA];
+        let () = msg_send![content, addSubview: dot];
+        DOT.with(|cell| cell.set(dot));
+
+        let white: *mut Object = msg_send![class!(NSColor), whiteColor];
+        let text = label("Recording", rect(42.0, 12.0, WIDTH - 58.0, 20.0), white);
+        let () = msg_send![content, addSubview: text];
+        panel
+    }
+
+    pub fn show(x: i32, y: i32) {
+        PANEL.with(|cell| unsafe {
+            if cell.get().is_null() {
+                cell.set(build_panel());
+            }
+            let panel = cell.get();
+            // AppKit's y axis points up from the bottom of the primary screen
+            let screens: *mut Object = msg_send![class!(NSScreen), screens];
+            let primary: *mut Object = msg_send![screens, firstObject];
+            let height = if primary.is_null() {
+                0.0
+            } else {
+                let frame: NSRect = msg_send![primary, frame];
+                frame.size.height
+            };
+            let origin = NSPoint { x: x as f64, y: height - y as f64 - HEIGHT };
+            let () = msg_send![panel, setFrameOrigin: origin];
+            // Shown without activating the app or taking key focus
+            let () = msg_send![panel, orderFrontRegardless];
+        });
+    }
+
+    /// Dim the dot while no speech is detected.
+    pub fn set_speaking(speaking: bool) {
+        DOT.with(|cell| unsafe {
+            let dot = cell.get();
+            if !dot.is_null() {
+                let () = msg_send![dot, setAlphaValue: if speaking { 1.0 } else { QUIET_ALPHA }];
+            }
+        });
+    }
+
+    pub fn hide() {
+        PANEL.with(|cell| unsafe {
+            let panel = cell.get();
+            if !panel.is_null() {
+                let () = msg_send![panel, orderOut: std::ptr::null_mut::<Object>()];
+            }
+        });
+    }
+}
+
+/// Show the native indicator at logical screen coordinates.
+pub fn show(app: &AppHandle, x: i32, y: i32) {
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
+    if let Err(e) = app.run_on_main_thread(move || imp::show(x, y)) {
+        log::error!("Failed to show native overlay: {}", e);
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    let _ = (app, x, y);
+}
+
+/// Reflect the speech detector's state; a no-op until the indicator exists.
+pub fn set_speaking(app: &AppHandle, speaking: bool) {
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
+    if let Err(e) = app.run_on_main_thread(move || imp::set_speaking(speaking)) {
+        log::warn!("Failed to update native overlay: {}", e);
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    let _ = (app, speaking);
+}
+
+pub fn hide(app: &AppHandle) {
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
+    if let Err(e) = app.run_on_main_thread(imp::hide) {
+        log::error!("Failed to hide native overlay: {}", e);
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    let _ = app;
+}
@@ -0,0 +1,228 @@
+//! OpenAI Realtime transcription provider.
+//!
+//! Differences from Soniox that this module absorbs:
+//! - audio is 24 kHz pcm16, base64-encoded inside JSON
+//!   `input_audio_buffer.append` events instead of binary frames
+//! - the server's VAD commits the buffer into conversation items; each item
+//!   streams `...transcription.delta` events (used for the live preview) and
+//!   ends with one `...transcription.completed` transcript (the final text)
+//! - items may complete out of order, so transcripts are delivered in the
+//!   order their audio was committed
+
+use base64::Engine;
+use futures_util::{SinkExt, StreamExt};
+use serde::Serialize;
+use std::collections::VecDeque;
+use tauri::Emitter;
+use tokio::sync::mpsc;
+use tokio::time::{Duration, Instant};
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::audio::resample::Resampler;
+
+// Key for this provider in `AppSettings::network_overrides`
+const PROVIDER_ID: &str = "openai";
+const REALTIME_HOST: &str = "api.openai.com";
+const REALTIME_PATH: &str = "/v1/realtime?intent=transcription";
+// The realtime API only accepts 24 kHz mono pcm16
+const OPENAI_SAMPLE_RATE: u32 = 24000;
+const CAPTURE_SAMPLE_RATE: u32 = 16000;
+
+/// Payload for typed provider error events, as sent by the Soniox session.
+#[derive(Debug, Clone, Serialize)]
+struct ProviderError {
+    code: String,
+    message: String,
+}
+
+pub async fn connect_and_transcribe(
+    settings: crate::AppSettings,
+    stop_signal: tokio_util::sync::CancellationToken,
+    audio_rx: &mut mpsc::Receiver<crate::audio::AudioChunk>,
+    app: tauri::AppHandle,
+    target_window_id: String,
+) -> Result<(), String> {
+    // Same IP pin, IPv4-only and SNI overrides, timeouts and retries as Soniox
+    let network = settings.network_overrides.get(PROVIDER_ID).cloned().unwrap_or_default();
+    let host = network.sni_host.clone().unwrap_or_else(|| REALTIME_HOST.to_string());
+    let endpoint = crate::soniox::Endpoint {
+        url: format!("wss://{}{}", host, REALTIME_PATH),
+        host,
+        port: 443,
+        headers: vec![
+            ("Authorization", format!("Bearer {}", settings.openai_api_key)),
+            ("OpenAI-Beta", "realtime=v1".to_string()),
+        ],
+    };
+
+    log::info!("Connecting to OpenAI Realtime: {}", endpoint.url);
+    let ws_stream = crate::soniox::connect_with_retries(Some(&app), &endpoint, &network, &settings)
+        .await
+        .map_err(|e| {
+            log::error!("WebSocket connection failed: {}", e);
+            e.to_string()
+        })?;
+    log::info!("Connected to OpenAI Realtime");
+    let (mut ws_write, mut ws_read) = ws_stream.split();
+
+    let language = settings.language_hints.first().cloned();
+    // A prompt listing the terms is the closest thing to Soniox context terms
+    let prompt = settings.context_terms().join(", ");
+    let session = serde_json::json!({
+        "type": "transcription_session.update",
+        "session": {
+            "input_audio_format": "pcm16",
+            "input_audio_transcription": {
+                "model": settings.openai_model,
+                "language": language,
+                "prompt": prompt,
+            },
+            "turn_detection": { "type": "server_vad", "silence_duration_ms": 500 },
+        },
+    });
+    ws_write
+        .send(Message::Text(session.to_string()))
+        .await
+        .map_err(|e| format!("Failed to send session config: {}", e))?;
+
+    let mut output = crate::output::Utterances::new(settings.clone(), app.clone(), target_window_id);
+    output.begin().await;
+    // Committed items in audio order, with their transcript once completed
+    let mut items: VecDeque<(String, Option<String>)> = VecDeque::new();
+    // Live preview text of the item currently being transcribed
+    let mut preview = String::new();
+    // One resampler for the whole session, so chunk boundaries stay seamless
+    let mut resampler = Resampler::new(CAPTURE_SAMPLE_RATE, OPENAI_SAMPLE_RATE);
+    let mut audio_closed = false;
+    let mut end_sent = false;
+    // The final commit after the end signal was answered (committed or error)
+    let mut end_acknowledged = false;
+
+    let finish_timeout = tokio::time::sleep(Duration::from_secs(3600));
+    tokio::pin!(finish_timeout);
+
+    loop {
+        if stop_signal.is_cancelled() && !end_sent {
+            audio_closed = true;
+        }
+        if audio_closed && !end_sent {
+            // Flush whatever the server VAD has not committed yet
+            ws_write
+                .send(Message::Text(serde_json::json!({ "type": "input_audio_buffer.commit" }).to_string()))
+                .await
+                .ok();
+            end_sent = true;
+            finish_timeout.as_mut().reset(Instant::now() + Duration::from_secs(5));
+        }
+        if end_sent && end_acknowledged && items.is_empty() {
+            break;
+        }
+
+        tokio::select! {
+            _ = stop_signal.cancelled(), if !audio_closed => {}
+            chunk = audio_rx.recv(), if !audio_closed => match chunk {
+                Some(chunk) => {
+                    let append = serde_json::json!({
+                        "type": "input_audio_buffer.append",
+                        "audio": encode_audio(&mut resampler, &chunk.data),
+                    });
+                    if let Err(e) = ws_write.send(Message::Text(append.to_string())).await {
+                        log::error!("WebSocket send failed: {}", e);
+                    } else {
+                        crate::metrics::add_audio_bytes(chunk.data.len());
+                    }
+                }
+                None => audio_closed = true,
+            },
+            _ = &mut finish_timeout, if end_sent => {
+                log::warn!("Timeout waiting for final transcripts from OpenAI");
+                break;
+            }
+            msg = ws_read.next() => match msg {
+                Some(Ok(Message::Text(text))) => {
+                    let Ok(event) = serde_json::from_str::<serde_json::Value>(&text) else { continue };
+                    let str_field = |name: &str| event.get(name).and_then(|v| v.as_str()).unwrap_or_default().to_string();
+                    match event.get("type").and_then(|t| t.as_str()).unwrap_or_default() {
+                        "input_audio_buffer.committed" => {
+                            items.push_back((str_field("item_id"), None));
+                            if end_sent {
+                                end_acknowledged = true;
+                            }
+                        }
+                        "conversation.item.input_audio_transcription.delta" => {
+                            preview.push_str(&str_field("delta"));
+                            app.emit("partial-text", preview.clone()).ok();
+                        }
+                        "conversation.item.input_audio_transcription.completed" => {
+                            let item_id = str_field("item_id");
+                            match items.iter_mut().find(|(id, _)| *id == item_id) {
+                                Some(item) => item.1 = Some(str_field("transcript")),
+                                None => items.push_back((item_id, Some(str_field("transcript")))),
+                            }
+                            preview.clear();
+                            while let Some((_, Some(_))) = items.front() {
+                                if let Some((_, Some(transcript))) = items.pop_front() {
+                                    output.deliver(&transcript).await;
+                                }
+                            }
+                        }
+                        "error" => {
+                            let error = event.get("error").cloned().unwrap_or_default();
+                            let code = error.get("code").and_then(|c| c.as_str()).unwrap_or_default().to_string();
+                            let message = error.get("message").and_then(|m| m.as_str()).unwrap_or_default().to_string();
+                            // Committing an empty buffer at the end is expected
+                            if end_sent && code == "input_audio_buffer_commit_empty" {
+                                end_acknowledged = true;
+                                continue;
+                            }
+                            log::error!("OpenAI error: {} - {}", code, message);
+                            crate::console::record(&app, "error", format!("OpenAI error: {} - {}", code, message));
+                            if code.contains("api_key") {
+                                app.emit("auth-error", ProviderError { code, message }).ok();
+                                break;
+                            }
+                            app.emit("transcription-error", format!("{} - {}", code, message)).ok();
+                            if end_sent {
+                                end_acknowledged = true;
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                Some(Ok(Message::Close(_))) | None => {
+                    log::info!("OpenAI WebSocket closed");
+                    break;
+                }
+                Some(Err(e)) => {
+                    log::error!("WebSocket error: {}", e);
+                    crate::console::record(&app, "error", format!("WebSocket error: {}", e));
+                    app.emit("transcription-error", e.to_string()).ok();
+                    break;
+                }
+                _ => {}
+            },
+        }
+    }
+
+    // Keep transcripts that completed behind one that never did
+    for transcript in items.into_iter().filter_map(|(_, transcript)| transcript) {
+        output.deliver(&transcript).await;
+    }
+    ws_write.close().await.ok();
+    output.finish();
+    Ok(())
+}
+
+/// Resample the next chunk of 16 kHz pcm16 to 24 kHz and base64-encode it.
+fn encode_audio(resampler: &mut Resampler, pcm: &[u8]) -> String {
+    let samples: Vec<f32> = pcm
+        .chunks_exact(2)
+        .map(|b| i16::from_le_bytes([b[0], b[1]]) as f32 / 32768.0)
+        .collect();
+    let resampled = resampler.process(&samples);
+    let bytes: Vec<u8> = resampled
+        .iter()
+        .flat_map(|s| ((s.clamp(-1.0, 1.0) * 32767.0) as i16).to_le_bytes())
+        .collect();
+    base64::engine::general_purpose::STANDARD.encode(bytes)
+}
@@ -0,0 +1,185 @@
+//! Output side of providers that deliver whole utterances (Whisper, Vosk,
+//! OpenAI Realtime, Azure): pronunciation fixes, text processors, typing and
+//! the `transcribed-text` / `session-complete` events. The Soniox session
+//! has its own streaming typing worker with latency tracking.
+
+use std::sync::atomic::Ordering;
+use tauri::{Emitter, Manager};
+
+use crate::text::spelling::{Fed, SessionVocabulary};
+
+pub struct Utterances {
+    settings: crate::AppSettings,
+    app: tauri::AppHandle,
+    target_window_id: String,
+    language: Option<String>,
+    /// Everything delivered so far, for the history entry
+    accumulated: String,
+    duplicates: crate::typer::DuplicateFilter,
+    vocabulary: SessionVocabulary,
+    script_context: crate::scripting::SessionContext,
+    /// Screen sharing: nothing is typed, the text is only shown and saved
+    review_mode: bool,
+    /// Cancelled with the session; nothing more is typed
+    cancel: tokio_util::sync::CancellationToken,
+}
+
+impl Utterances {
+    pub fn new(settings: crate::AppSettings, app: tauri::AppHandle, target_window_id: String) -> Self {
+        let language = settings.language_hints.first().cloned();
+        let review_mode = app.state::<crate::AppState>().review_mode.load(Ordering::SeqCst);
+        let script_context = crate::scripting::SessionContext::current(&app);
+        let cancel = app.state::<crate::AppState>().session_tokens.lock().unwrap().cancel.clone();
+        Self {
+            settings,
+            app,
+            target_window_id,
+            language,
+            accumulated: String::new(),
+            duplicates: crate::typer::DuplicateFilter::new(),
+            vocabulary: SessionVocabulary::default(),
+            script_context,
+            review_mode,
+            cancel,
+        }
+    }
+
+    /// Type the active profile's session template, before anything is
+    /// dictated. It bypasses processors and is not part of the history entry.
+    pub async fn begin(&self) {
+        if let Some(preamble) = self.settings.session_preamble() {
+            self.insert(&preamble).await;
+        }
+    }
+
+    /// Process, type and record one finished utterance.
+    pub async fn deliver(&mut self, text: &str) {
+        let text = text.trim();
+        if text.is_empty() {
+            return;
+        }
+        crate::console::record(&self.app, "token", text);
+
+        // Utterances arrive without a leading space; separate them
+        let spaced = if self.accumulated.is_empty() { text.to_string() } else { format!(" {}", text) };
+        // Utterances are complete, so a "spell that" is never held back
+        let (spaced, spelled) = match self.vocabulary.feed(&spaced, true) {
+            Fed::Text(text) => (text, None),
+            Fed::Held => (String::new(), None),
+            Fed::Spelled(spelled) => (spelled.before, Some((spelled.word, spelled.after))),
+        };
+        let mut output = self.process(&spaced, &self.accumulated);
+        if let Some((word, after)) = spelled {
+            self.correct(&word, &mut output).await;
+            let previous = format!("{}{}", self.accumulated, output);
+            output.push_str(&self.process(&after, &previous));
+        }
+        if output.is_empty() {
+            return;
+        }
+        if !self.duplicates.admit(&output) {
+            log::warn!("Suppressed repeated final text: '{}'", output);
+            crate::console::record(&self.app, "duplicate", output);
+            return;
+        }
+        self.accumulated.push_str(&output);
+        crate::scripting::on_text(&output, &self.script_context);
+
+        self.insert(&output).await;
+        self.app.emit("transcribed-text", output).ok();
+    }
+
+    /// Known misrecognitions and words taught this session first, then the
+    /// configured post-processing pipeline.
+    fn process(&self, text: &str, previous: &str) -> String {
+        let corrected = crate::text::pipeline::apply_pronunciations(&self.settings.pronunciations, text);
+        let output = crate::text::pipeline::apply(
+            self.settings.effective_text_processors(),
+            &self.vocabulary.apply(&corrected),
+            &crate::text::pipeline::ProcessContext {
+                previous,
+                language: self.language.as_deref(),
+                end_of_utterance: true,
+            },
+        );
+        if self.settings.plain_ascii_output {
+            crate::text::to_plain_ascii(&output)
+        } else {
+            output
+        }
+    }
+
+    /// Put a spelled word in place of the word before "spell that", in
+    /// `output` or on screen.
+    async fn correct(&mut self, word: &str, output: &mut String) {
+        let Some(correction) = self.vocabulary.learn(word, &format!("{}{}", self.accumulated, output)) else {
+            return;
+        };
+        log::info!("Learned \"{}\" for this session", word);
+        crate::console::record(&self.app, "spelling", format!("Learned '{}'", word));
+        let Some((erase, text)) = correction.apply(&mut self.accumulated, output) else {
+            return;
+        };
+        if self.review_mode {
+            return;
+        }
+        let target = self.target_window_id.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            crate::typer::erase_chars(erase, &target)?;
+            crate::typer::type_text(&text, &target)
+        })
+        .await;
+        if let Ok(Err(e)) = result {
+            log::warn!("Failed to correct spelled word: {}", e);
+        }
+    }
+
+    /// Type `output` into the target window.
+    async fn insert(&self, output: &str) {
+        if self.review_mode || self.cancel.is_cancelled() {
+            return;
+        }
+        let seq = crate::pending::queue(&self.target_window_id, output);
+        let to_type = output.to_string();
+        let target = self.target_window_id.clone();
+        let rich_text_paste = self.settings.rich_text_paste;
+        let chunking = crate::typer::Chunking::from_settings(&self.settings);
+        let app = self.app.clone();
+        let cancel = self.cancel.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            if rich_text_paste && !chunking.is_large(&to_type) && crate::text::markdown::has_markup(&to_type) {
+                let html = crate::text::markdown::to_html(&to_type);
+                crate::typer::paste_rich_text(&to_type, &html, &target)
+            } else {
+                crate::typer::type_text_chunked(&to_type, &target, chunking, &cancel, |progress| {
+                    app.emit("insert-progress", progress).ok();
+                })
+            }
+        })
+        .await;
+        match result {
+            Ok(Ok(())) => crate::console::record(&self.app, "typed", output.to_string()),
+            Ok(Err(e)) => {
+                log::error!("Failed to type text: {}", e);
+                crate::metrics::inc_typing_failures();
+                crate::console::record(&self.app, "typing-error", format!("'{}': {}", output, e));
+            }
+            Err(e) => {
+                crate::metrics::inc_typing_failures();
+                crate::console::record(&self.app, "typing-error", format!("Typing task failed: {}", e));
+            }
+        }
+        crate::pending::done(seq);
+    }
+
+    /// End the session: hand the accumulated text to scripts and history.
+    pub fn finish(self) {
+        if !self.accumulated.is_empty() {
+            crate::scripting::on_session_complete(&self.accumulated, &self.script_context);
+            let state: tauri::State<'_, crate::AppState> = self.app.state();
+            *state.last_session_text.lock().unwrap() = Some(self.accumulated.clone());
+            self.app.emit("session-complete", self.accumulated).ok();
+        }
+        log::info!("Transcription ended");
+    }
+}
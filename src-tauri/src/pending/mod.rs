@@ -0,0 +1,209 @@
+//! Crash-safe record of text waiting to be typed.
+//!
+//! Every final insertion is appended to `pending-insertions.jsonl` with a
+//! sequence id before it is handed to the typer, and marked done once the
+//! typer is through with it. A writer thread does the writing and syncing,
+//! so a slow disk never holds up the provider's receive loop, and empties
+//! the file whenever nothing is outstanding, so dictated text doesn't pile
+//! up on disk. A clean shutdown deletes the file, so anything still queued
+//! there at startup was cut off by a crash. That text is never
+//! typed on its own (the user has likely re-dictated it, or moved on to
+//! another window); it is kept in `pending-insertions.recovered.json` until
+//! the user replays or discards it.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc;
+use std::sync::Mutex;
+use tauri::AppHandle;
+
+const JOURNAL_FILE: &str = "pending-insertions.jsonl";
+const RECOVERED_FILE: &str = "pending-insertions.recovered.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingInsertion {
+    pub seq: u64,
+    /// Target id the text was meant for
+    pub target: String,
+    pub text: String,
+    /// Unix seconds
+    pub queued_at: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum Record {
+    Queued(PendingInsertion),
+    Done { seq: u64 },
+}
+
+/// 0 is never handed out; it stands for "not journaled"
+static NEXT_SEQ: AtomicU64 = AtomicU64::new(1);
+static JOURNAL: Mutex<Option<Journal>> = Mutex::new(None);
+/// Left over from a crash, until replayed or discarded
+static RECOVERED: Mutex<Vec<PendingInsertion>> = Mutex::new(Vec::new());
+
+/// The open journal: records go to its writer thread.
+struct Journal {
+    path: PathBuf,
+    records: mpsc::Sender<Record>,
+    writer: std::thread::JoinHandle<()>,
+}
+
+/// Collect what a crashed run left untyped and start a fresh journal.
+pub fn init(app: &AppHandle) {
+    let dir = match crate::datadir::dir(app) {
+        Ok(dir) => dir,
+        Err(e) => return log::warn!("No app data dir, typing queue not journaled: {}", e),
+    };
+    let journal = dir.join(JOURNAL_FILE);
+
+    let mut recovered = read_recovered(&dir.join(RECOVERED_FILE));
+    let unsent = read_unsent(&journal);
+    if !unsent.is_empty() {
+        let message = format!("{} insertion(s) were still queued when the app last stopped", unsent.len());
+        log::warn!("{}", message);
+        crate::console::record(app, "recovery", message);
+        recovered.extend(unsent);
+        if let Err(e) = write_recovered(&dir.join(RECOVERED_FILE), &recovered) {
+            log::error!("Failed to keep unsent insertions: {}", e);
+        }
+    }
+    // Keep sequence ids unique across the recovered runs
+    if let Some(last) = recovered.iter().map(|i| i.seq).max() {
+        NEXT_SEQ.fetch_max(last + 1, Ordering::Relaxed);
+    }
+    *RECOVERED.lock().unwrap() = recovered;
+
+    // Appending, so emptying the file leaves nothing before the next record
+    let opened = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&journal)
+        .and_then(|file| file.set_len(0).map(|_| file))
+        .map_err(|e| format!("Failed to create {:?}: {}", journal, e));
+    let file = match opened {
+        Ok(file) => file,
+        Err(e) => return log::error!("{}", e),
+    };
+    let (records, received) = mpsc::channel();
+    let writer = std::thread::Builder::new()
+        .name("pending-journal".to_string())
+        .spawn(move || write_journal(file, received));
+    match writer {
+        Ok(writer) => *JOURNAL.lock().unwrap() = Some(Journal { path: journal, records, writer }),
+        Err(e) => log::error!("Failed to start the typing queue journal: {}", e),
+    }
+}
+
+/// Journal `text` as queued for `target`. Returns its sequence id for `done`.
+pub fn queue(target: &str, text: &str) -> u64 {
+    let seq = NEXT_SEQ.fetch_add(1, Ordering::Relaxed);
+    append(Record::Queued(PendingInsertion {
+        seq,
+        target: target.to_string(),
+        text: text.to_string(),
+        queued_at: crate::unix_now(),
+    }));
+    seq
+}
+
+/// Mark an insertion as handled, whether or not typing succeeded; a failed
+/// insertion was already reported and must not come back later.
+pub fn done(seq: u64) {
+    if seq != 0 {
+        append(Record::Done { seq });
+    }
+}
+
+/// Delete the journal on a clean shutdown: nothing in it needs recovering.
+pub fn shutdown() {
+    if let Some(journal) = JOURNAL.lock().unwrap().take() {
+        // Closing the channel ends the writer once it has caught up
+        drop(journal.records);
+        journal.writer.join().ok();
+        if let Err(e) = std::fs::remove_file(&journal.path) {
+            log::warn!("Failed to remove {:?}: {}", journal.path, e);
+        }
+    }
+}
+
+/// Insertions a crash left untyped, oldest first.
+pub fn recovered() -> Vec<PendingInsertion> {
+    RECOVERED.lock().unwrap().clone()
+}
+
+/// Forget the recovered insertions. Returns them for the caller to type.
+pub fn take_recovered(app: &AppHandle) -> Vec<PendingInsertion> {
+    if let Ok(dir) = crate::datadir::dir(app) {
+        let path = dir.join(RECOVERED_FILE);
+        if let Err(e) = std::fs::remove_file(&path) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                log::warn!("Failed to remove {:?}: {}", path, e);
+            }
+        }
+    }
+    std::mem::take(&mut *RECOVERED.lock().unwrap())
+}
+
+fn append(record: Record) {
+    if let Some(journal) = JOURNAL.lock().unwrap().as_ref() {
+        journal.records.send(record).ok();
+    }
+}
+
+/// Write records until the channel closes. Once every queued insertion is
+/// done the file is emptied instead: there is nothing left to recover.
+fn write_journal(mut file: std::fs::File, records: mpsc::Receiver<Record>) {
+    let mut outstanding: HashSet<u64> = HashSet::new();
+    for record in records {
+        match &record {
+            Record::Queued(insertion) => outstanding.insert(insertion.seq),
+            Record::Done { seq } => outstanding.remove(seq),
+        };
+        let written = if outstanding.is_empty() {
+            file.set_len(0)
+        } else {
+            match serde_json::to_string(&record) {
+                Ok(line) => writeln!(file, "{}", line),
+                Err(e) => {
+                    log::warn!("Typing queue journal: {}", e);
+                    continue;
+                }
+            }
+        };
+        // Synced right away: the point is to survive a crash
+        if let Err(e) = written.and_then(|_| file.sync_data()) {
+            log::warn!("Typing queue journal write failed: {}", e);
+        }
+    }
+}
+
+/// Queued records of `path` without a matching done record.
+fn read_unsent(path: &Path) -> Vec<PendingInsertion> {
+    let Ok(text) = std::fs::read_to_string(path) else { return Vec::new() };
+    let mut unsent: Vec<PendingInsertion> = Vec::new();
+    // A line cut off by the crash is skipped; the rest is still good
+    for record in text.lines().filter_map(|line| serde_json::from_str::<Record>(line).ok()) {
+        match record {
+            Record::Queued(insertion) => unsent.push(insertion),
+            Record::Done { seq } => unsent.retain(|i| i.seq != seq),
+        }
+    }
+    unsent
+}
+
+fn read_recovered(path: &Path) -> Vec<PendingInsertion> {
+    std::fs::read(path)
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .unwrap_or_default()
+}
+
+fn write_recovered(path: &Path, insertions: &[PendingInsertion]) -> Result<(), String> {
+    let bytes = serde_json::to_vec_pretty(insertions).map_err(|e| e.to_string())?;
+    std::fs::write(path, bytes).map_err(|e| format!("Failed to write {:?}: {}", path, e))
+}
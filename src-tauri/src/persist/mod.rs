@@ -0,0 +1,88 @@
+//! Debounced, atomic persistence for the JSON stores.
+//!
+//! Commands only update the in-memory store and mark it dirty. A single
+//! writer task coalesces bursts of changes and writes each dirty store to a
+//! temp file that is synced and renamed over the original, so a crash or a
+//! full disk never leaves a half-written store behind.
+
+use std::io::Write;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+use tauri::{AppHandle, Wry};
+use tauri_plugin_store::{Store, StoreExt};
+use tokio::sync::mpsc;
+
+/// Quiet period after the last change before the writer touches the disk
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Stores changed since they were last written
+static DIRTY: Mutex<Vec<&'static str>> = Mutex::new(Vec::new());
+/// Serializes writes between the writer task and the exit flush
+static WRITE_LOCK: Mutex<()> = Mutex::new(());
+static WRITER: OnceLock<mpsc::UnboundedSender<()>> = OnceLock::new();
+
+/// Open a store with the plugin's own (non-atomic) auto-save disabled.
+pub fn store(app: &AppHandle, name: &str) -> Result<Arc<Store<Wry>>, String> {
+    // An absolute path makes the plugin skip its own app data dir lookup
+    app.store_builder(crate::datadir::dir(app)?.join(name))
+        .disable_auto_save()
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+/// Mark `name` dirty and have the writer task persist it shortly.
+pub fn schedule(app: &AppHandle, name: &'static str) {
+    {
+        let mut dirty = DIRTY.lock().unwrap();
+        if !dirty.contains(&name) {
+            dirty.push(name);
+        }
+    }
+
+    let writer = WRITER.get_or_init(|| {
+        let (tx, mut rx) = mpsc::unbounded_channel::<()>();
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            while rx.recv().await.is_some() {
+                // Coalesce everything that arrives during the quiet period
+                tokio::time::sleep(DEBOUNCE).await;
+                while rx.try_recv().is_ok() {}
+                let app = app.clone();
+                if let Err(e) = tokio::task::spawn_blocking(move || flush(&app)).await {
+                    log::error!("Store writer failed: {}", e);
+                }
+            }
+        });
+        tx
+    });
+    let _ = writer.send(());
+}
+
+/// Write all dirty stores now. Also called on exit so no change is lost.
+pub fn flush(app: &AppHandle) {
+    let _guard = WRITE_LOCK.lock().unwrap();
+    let names = std::mem::take(&mut *DIRTY.lock().unwrap());
+    for name in names {
+        if let Err(e) = write_atomic(app, name) {
+            log::error!("Failed to write {}: {}", name, e);
+        }
+    }
+}
+
+fn write_atomic(app: &AppHandle, name: &str) -> Result<(), String> {
+    let store = store(app, name)?;
+    let contents: serde_json::Map<String, serde_json::Value> = store.entries().into_iter().collect();
+    let bytes = serde_json::to_vec_pretty(&contents).map_err(|e| e.to_string())?;
+
+    let dir = crate::datadir::dir(app)?;
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let path = dir.join(name);
+    let tmp = dir.join(format!("{}.tmp", name));
+
+    let mut file = std::fs::File::create(&tmp).map_err(|e| format!("Cannot create {:?}: {}", tmp, e))?;
+    file.write_all(&bytes)
+        .and_then(|_| file.sync_all())
+        .map_err(|e| format!("Cannot write {:?}: {}", tmp, e))?;
+    drop(file);
+    std::fs::rename(&tmp, &path).map_err(|e| format!("Cannot replace {:?}: {}", path, e))
+}
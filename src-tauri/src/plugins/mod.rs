@@ -0,0 +1,84 @@
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use crate::text::pipeline::ProcessContext;
+
+static PLUGINS_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Set the directory WASM plugins are loaded from. Called once at startup.
+pub fn init(dir: PathBuf) {
+    if let Err(e) = std::fs::create_dir_all(&dir) {
+        log::warn!("Could not create plugins directory {:?}: {}", dir, e);
+    }
+    log::info!("WASM plugins directory: {:?}", dir);
+    let _ = PLUGINS_DIR.set(dir);
+}
+
+/// Input passed to a plugin's `process` export, as JSON.
+#[derive(serde::Serialize)]
+#[cfg_attr(not(feature = "wasm-plugins"), allow(dead_code))]
+struct PluginInput<'a> {
+    text: &'a str,
+    previous: &'a str,
+    language: Option<&'a str>,
+}
+
+/// Run `text` through the plugin file `name` in the plugins directory.
+///
+/// A plugin is any WASM module (extism PDK) exporting
+/// `process(input: json) -> string`, where the input is
+/// `{"text", "previous", "language"}` and the output is the new text.
+/// Failures are logged and the text passes through unchanged, so a broken
+/// plugin never blocks dictation.
+pub fn process(name: &str, text: &str, ctx: &ProcessContext) -> String {
+    match run(name, text, ctx) {
+        Ok(out) => out,
+        Err(e) => {
+            log::error!("WASM plugin '{}' failed: {}", name, e);
+            text.to_string()
+        }
+    }
+}
+
+#[cfg(feature = "wasm-plugins")]
+fn run(name: &str, text: &str, ctx: &ProcessContext) -> Result<String, String> {
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    // Instantiating a module is expensive; keep one instance per plugin
+    static LOADED: OnceLock<Mutex<HashMap<String, extism::Plugin>>> = OnceLock::new();
+
+    let dir = PLUGINS_DIR.get().ok_or("plugins directory not initialized")?;
+    // Plugin names are file names; never let them escape the plugins dir
+    if name.contains('/') || name.contains('\\') || name.contains("..") {
+        return Err(format!("invalid plugin name '{}'", name));
+    }
+
+    let input = serde_json::to_string(&PluginInput {
+        text,
+        previous: ctx.previous,
+        language: ctx.language,
+    })
+    .map_err(|e| e.to_string())?;
+
+    let mut loaded = LOADED.get_or_init(|| Mutex::new(HashMap::new())).lock().unwrap();
+    if !loaded.contains_key(name) {
+        let manifest = extism::Manifest::new([extism::Wasm::file(dir.join(name))]);
+        // No WASI: plugins get no filesystem or network access
+        let plugin = extism::Plugin::new(&manifest, [], false).map_err(|e| e.to_string())?;
+        loaded.insert(name.to_string(), plugin);
+    }
+
+    let plugin = loaded.get_mut(name).ok_or("plugin not loaded")?;
+    plugin
+        .call::<String, String>("process", input)
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(not(feature = "wasm-plugins"))]
+fn run(name: &str, _text: &str, _ctx: &ProcessContext) -> Result<String, String> {
+    Err(format!(
+        "'{}' needs WASM plugin support; rebuild with `--features wasm-plugins`",
+        name
+    ))
+}
@@ -0,0 +1,117 @@
+//! System suspend/resume handling.
+//!
+//! Resume is detected on every platform by watching for wall-clock jumps
+//! that a sleeping poll thread cannot account for. On Linux, logind's
+//! `PrepareForSleep` signal (via `dbus-monitor`) additionally announces the
+//! suspend itself, so an active session can be finished before the network
+//! goes away.
+
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Emitter};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+// A wall-clock gap this much larger than the poll interval means we slept
+const RESUME_THRESHOLD: Duration = Duration::from_secs(10);
+
+/// Wall-clock time of the last handled resume, so the clock watcher and the
+/// logind signal don't both handle the same wake-up.
+static LAST_RESUME: Mutex<Option<SystemTime>> = Mutex::new(None);
+
+/// Start the suspend/resume watchers. Called once at startup.
+pub fn spawn_watcher(app: AppHandle) {
+    #[cfg(target_os = "linux")]
+    spawn_logind_watcher(app.clone());
+
+    std::thread::spawn(move || {
+        let mut last = SystemTime::now();
+        loop {
+            std::thread::sleep(POLL_INTERVAL);
+            let now = SystemTime::now();
+            let gap = now.duration_since(last).unwrap_or_default();
+            last = now;
+            if gap > POLL_INTERVAL + RESUME_THRESHOLD {
+                log::info!("Wall clock jumped {:?}, assuming resume from sleep", gap);
+                on_resume(&app);
+            }
+        }
+    });
+}
+
+/// Follow logind's `PrepareForSleep(bool)` signal through `dbus-monitor`.
+#[cfg(target_os = "linux")]
+fn spawn_logind_watcher(app: AppHandle) {
+    use std::io::{BufRead, BufReader};
+    use std::process::{Command, Stdio};
+
+    std::thread::spawn(move || {
+        let child = Command::new("dbus-monitor")
+            .args([
+                "--system",
+                "type='signal',interface='org.freedesktop.login1.Manager',member='PrepareForSleep'",
+            ])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn();
+        let mut child = match child {
+            Ok(c) => c,
+            Err(e) => {
+                log::info!("dbus-monitor unavailable ({}), suspend is only detected after resume", e);
+                return;
+            }
+        };
+
+        let Some(stdout) = child.stdout.take() else { return };
+        // The signal's argument arrives on the line after the header
+        let mut in_signal = false;
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            if line.contains("member=PrepareForSleep") {
+                in_signal = true;
+            } else if in_signal && line.trim() == "boolean true" {
+                in_signal = false;
+                on_suspend(&app);
+            } else if in_signal && line.trim() == "boolean false" {
+                in_signal = false;
+                on_resume(&app);
+            }
+        }
+        let _ = child.wait();
+    });
+}
+
+fn on_suspend(app: &AppHandle) {
+    log::info!("System is going to sleep");
+    if crate::stop_active_session(app) {
+        crate::console::record(app, "session", "Recording stopped for system sleep");
+    }
+}
+
+fn on_resume(app: &AppHandle) {
+    {
+        let now = SystemTime::now();
+        let mut last = LAST_RESUME.lock().unwrap();
+        let recent = last
+            .and_then(|t| now.duration_since(t).ok())
+            .is_some_and(|d| d < RESUME_THRESHOLD * 3);
+        if recent {
+            return;
+        }
+        *last = Some(now);
+    }
+    log::info!("System resumed, re-validating hotkey and audio device");
+
+    // A session that spanned the sleep has a dead connection; end it cleanly
+    if crate::stop_active_session(app) {
+        crate::console::record(app, "session", "Recording interrupted by system sleep");
+    }
+
+    if let Err(e) = crate::register_hotkey(app) {
+        log::error!("Failed to re-register hotkey after resume: {}", e);
+        app.emit("recording-error", crate::i18n::t_args("hotkey-lost-after-sleep", &[("error", e)])).ok();
+    }
+
+    if !crate::audio::input_device_available() {
+        log::warn!("No input device available after resume");
+        app.emit("recording-error", crate::i18n::t("microphone-missing-after-resume")).ok();
+    }
+}
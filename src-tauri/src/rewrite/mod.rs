@@ -0,0 +1,106 @@
+//! "Fix this text": rewrite the clipboard with a spoken instruction.
+//!
+//! The `rewrite_clipboard` action reads the clipboard and starts a session
+//! whose text is not typed. When it ends, the dictated instruction ("make
+//! this more formal") and the clipboard text go to an OpenAI-compatible
+//! chat completions endpoint (`rewrite_endpoint`, `rewrite_model`, with
+//! `rewrite_api_key` or else the OpenAI API key), and the answer is typed
+//! into the window that was focused when the action ran. The instruction
+//! is not saved to history.
+
+use std::sync::atomic::Ordering;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::error::TranscriptionError;
+
+const SYSTEM_PROMPT: &str = "You rewrite the user's text as instructed. Reply with the rewritten text only, \
+    without quotes, explanations or formatting that was not asked for.";
+
+/// Read the clipboard and start the instruction session.
+pub async fn start(app: &AppHandle) -> Result<(), String> {
+    let state = app.state::<crate::AppState>();
+    if state.is_recording.load(Ordering::SeqCst) {
+        return Err(crate::i18n::t("rewrite-busy"));
+    }
+    if api_key(&state.settings.lock().unwrap()).is_empty() {
+        return Err(crate::i18n::t("openai-key-missing"));
+    }
+    let source = tauri::async_runtime::spawn_blocking(|| arboard::Clipboard::new().and_then(|mut c| c.get_text()))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| crate::i18n::t_args("clipboard-read-failed", &[("error", e.to_string())]))?;
+    if source.trim().is_empty() {
+        return Err(crate::i18n::t("rewrite-clipboard-empty"));
+    }
+
+    log::info!("Rewriting {} characters from the clipboard", source.chars().count());
+    *state.rewrite_source.lock().unwrap() = Some(source);
+    if let Err(e) = crate::toggle_recording(app.clone(), app.state(), None, None).await {
+        state.rewrite_source.lock().unwrap().take();
+        return Err(e);
+    }
+    Ok(())
+}
+
+/// Rewrite `source` as `instruction` says and type the result. Runs in
+/// the background; failures are reported like session errors.
+pub fn finish(app: &AppHandle, source: String, instruction: String) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        crate::console::record(&app, "rewrite", format!("Instruction: {}", instruction));
+        let settings = app.state::<crate::AppState>().settings.lock().unwrap().clone();
+        let target = app.state::<crate::AppState>().target_window_id.lock().unwrap().clone().unwrap_or_default();
+
+        let result = match rewrite(&settings, &source, &instruction).await {
+            Ok(text) => tauri::async_runtime::spawn_blocking(move || crate::typer::type_text(&text, &target))
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(|typed| typed.map_err(|e| format!("Failed to type the rewritten text: {}", e))),
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            log::error!("Clipboard rewrite failed: {}", e);
+            crate::console::record(&app, "error", format!("Clipboard rewrite: {}", e));
+            app.emit("transcription-error", TranscriptionError::Provider(e)).ok();
+        }
+    });
+}
+
+fn api_key(settings: &crate::AppSettings) -> &str {
+    if settings.rewrite_api_key.is_empty() {
+        &settings.openai_api_key
+    } else {
+        &settings.rewrite_api_key
+    }
+}
+
+async fn rewrite(settings: &crate::AppSettings, source: &str, instruction: &str) -> Result<String, String> {
+    let url = format!("{}/chat/completions", settings.rewrite_endpoint.trim_end_matches('/'));
+    let body = serde_json::json!({
+        "model": settings.rewrite_model,
+        "messages": [
+            { "role": "system", "content": SYSTEM_PROMPT },
+            { "role": "user", "content": format!("Instruction: {}\n\nText:\n{}", instruction.trim(), source) },
+        ],
+    });
+    let response = reqwest::Client::new()
+        .post(&url)
+        .bearer_auth(api_key(settings))
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .body(body.to_string())
+        .send()
+        .await
+        .map_err(|e| format!("Request to {} failed: {}", url, e))?;
+    let status = response.status();
+    let text = response.text().await.map_err(|e| e.to_string())?;
+    if !status.is_success() {
+        return Err(format!("{} answered {}: {}", url, status, text));
+    }
+
+    let reply: serde_json::Value = serde_json::from_str(&text).map_err(|e| format!("Unexpected response: {}", e))?;
+    reply["choices"][0]["message"]["content"]
+        .as_str()
+        .map(|content| content.trim().to_string())
+        .filter(|content| !content.is_empty())
+        .ok_or_else(|| "The model returned no text".to_string())
+}
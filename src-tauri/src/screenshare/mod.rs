@@ -0,0 +1,61 @@
+//! Heuristic check for an active screen share or recording, so dictation
+//! can keep its text in the main window for review instead of typing it
+//! live in front of an audience.
+//!
+//! Linux only: portal screencast streams in PipeWire (video sources that
+//! are not cameras), plus running screen recorders. macOS and Windows have
+//! no reliable way to tell that another app is capturing the screen, so
+//! the setting isn't offered there and nothing is detected. Neither check
+//! is certain: a browser sharing on X11 goes unnoticed, and an idle
+//! recorder counts as sharing.
+
+/// Whether this platform can detect screen sharing at all.
+pub const SUPPORTED: bool = cfg!(target_os = "linux");
+
+/// Screen recorders: (process name, display name).
+const KNOWN_CAPTURE_PROGRAMS: &[(&str, &str)] = &[
+    ("obs", "OBS Studio"),
+    ("simplescreenrecorder", "SimpleScreenRecorder"),
+    ("kazam", "Kazam"),
+    ("peek", "Peek"),
+    ("kooha", "Kooha"),
+    ("vokoscreenng", "vokoscreenNG"),
+    ("wf-recorder", "wf-recorder"),
+    ("gpu-screen-recorder", "GPU Screen Recorder"),
+];
+
+/// What appears to be capturing the screen, or None.
+pub fn detect() -> Option<String> {
+    if !SUPPORTED {
+        return None;
+    }
+    if portal_screencast() {
+        return Some(crate::i18n::t("screen-share-portal"));
+    }
+    let running = crate::conflicts::running_programs();
+    KNOWN_CAPTURE_PROGRAMS
+        .iter()
+        .find(|(process, _)| running.iter().any(|p| p == process))
+        .map(|(_, name)| name.to_string())
+}
+
+/// Whether PipeWire has a video source that isn't a camera, which is what
+/// the desktop portal's screencast streams look like. Unknown (no
+/// `pw-dump`) counts as no.
+#[cfg(target_os = "linux")]
+fn portal_screencast() -> bool {
+    let output = match std::process::Command::new("pw-dump").output() {
+        Ok(output) if output.status.success() => output.stdout,
+        _ => return false,
+    };
+    let objects: Vec<serde_json::Value> = serde_json::from_slice(&output).unwrap_or_default();
+    objects.iter().any(|object| {
+        let props = &object["info"]["props"];
+        props["media.class"] == "Video/Source" && props.get("device.api").is_none() && props["media.role"] != "Camera"
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+fn portal_screencast() -> bool {
+    false
+}
@@ -0,0 +1,183 @@
+//! User scripts (Rhai) that react to session events.
+//!
+//! Every `*.rhai` file in the scripts directory may define any of:
+//! - `fn on_text(text, ctx)`: called for each chunk of text that is typed
+//! - `fn on_session_complete(text, ctx)`: called once with the full session
+//!
+//! `ctx` is a map describing the session: `session_id`, `app_id` and
+//! `title` of the app it types into, the active `profile` and the session's
+//! `language` hints (comma separated). Fields that are unknown are empty.
+//!
+//! Scripts run sandboxed: no filesystem or network access besides the
+//! functions registered here, and a hard operation limit per call.
+
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use tauri::{AppHandle, Manager};
+
+/// What hooks learn about the session they run for.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(not(feature = "scripting"), allow(dead_code))]
+pub struct SessionContext {
+    session_id: String,
+    app_id: String,
+    title: String,
+    profile: String,
+    language: String,
+}
+
+impl SessionContext {
+    /// Context of the current session, read when it starts.
+    pub fn current(app: &AppHandle) -> Self {
+        let state = app.state::<crate::AppState>();
+        let focused = state.target_app.lock().unwrap().clone().unwrap_or_default();
+        let session_id = state.session_id.lock().unwrap().clone().unwrap_or_default();
+        let forced_hints = state.session_language_hints.lock().unwrap().clone();
+        let settings = state.settings.lock().unwrap();
+        Self {
+            session_id,
+            app_id: focused.app_id,
+            title: focused.title,
+            profile: settings.active_profile.clone().unwrap_or_default(),
+            language: forced_hints.unwrap_or_else(|| settings.language_hints.clone()).join(","),
+        }
+    }
+}
+
+#[cfg_attr(not(feature = "scripting"), allow(dead_code))]
+struct Dirs {
+    scripts: PathBuf,
+    output: PathBuf,
+}
+
+static DIRS: OnceLock<Dirs> = OnceLock::new();
+
+/// Set the scripts/output directories and load scripts. Called once at startup.
+pub fn init(scripts: PathBuf, output: PathBuf) {
+    for dir in [&scripts, &output] {
+        if let Err(e) = std::fs::create_dir_all(dir) {
+            log::warn!("Could not create scripting directory {:?}: {}", dir, e);
+        }
+    }
+    let _ = DIRS.set(Dirs { scripts, output });
+    reload();
+}
+
+#[cfg(feature = "scripting")]
+mod imp {
+    use super::{SessionContext, DIRS};
+    use rhai::{Engine, Map, Scope, AST};
+    use std::sync::Mutex;
+
+    // Keeps a runaway loop in a user script from stalling the session
+    const MAX_OPERATIONS: u64 = 200_000;
+
+    struct Script {
+        name: String,
+        ast: AST,
+    }
+
+    static SCRIPTS: Mutex<Vec<Script>> = Mutex::new(Vec::new());
+    static ENGINE: std::sync::OnceLock<Engine> = std::sync::OnceLock::new();
+
+    fn engine() -> &'static Engine {
+        ENGINE.get_or_init(|| {
+            let mut engine = Engine::new();
+            engine.set_max_operations(MAX_OPERATIONS);
+            engine.on_print(|msg| log::info!("[script] {}", msg));
+            engine.register_fn("log", |msg: &str| log::info!("[script] {}", msg));
+            engine.register_fn("append_file", append_file);
+            engine
+        })
+    }
+
+    /// Append `text` to `name` inside the script output directory.
+    fn append_file(name: &str, text: &str) -> bool {
+        use std::io::Write;
+
+        let Some(dirs) = DIRS.get() else { return false };
+        if name.is_empty() || name.contains('/') || name.contains('\\') || name.contains("..") {
+            log::warn!("[script] append_file: invalid file name '{}'", name);
+            return false;
+        }
+
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(dirs.output.join(name))
+            .and_then(|mut f| f.write_all(text.as_bytes()))
+            .map_err(|e| log::warn!("[script] append_file '{}' failed: {}", name, e))
+            .is_ok()
+    }
+
+    pub fn reload() {
+        let Some(dirs) = DIRS.get() else { return };
+        let mut scripts = Vec::new();
+
+        if let Ok(entries) = std::fs::read_dir(&dirs.scripts) {
+            for path in entries.flatten().map(|e| e.path()) {
+                if path.extension().and_then(|e| e.to_str()) != Some("rhai") {
+                    continue;
+                }
+                match engine().compile_file(path.clone()) {
+                    Ok(ast) => scripts.push(Script {
+                        name: path.file_name().unwrap_or_default().to_string_lossy().into_owned(),
+                        ast,
+                    }),
+                    Err(e) => log::error!("Failed to compile script {:?}: {}", path, e),
+                }
+            }
+        }
+
+        log::info!("Loaded {} script(s)", scripts.len());
+        *SCRIPTS.lock().unwrap() = scripts;
+    }
+
+    fn to_map(context: &SessionContext) -> Map {
+        let mut map = Map::new();
+        map.insert("session_id".into(), context.session_id.clone().into());
+        map.insert("app_id".into(), context.app_id.clone().into());
+        map.insert("title".into(), context.title.clone().into());
+        map.insert("profile".into(), context.profile.clone().into());
+        map.insert("language".into(), context.language.clone().into());
+        map
+    }
+
+    pub fn call(hook: &str, text: &str, context: &SessionContext) {
+        let scripts = SCRIPTS.lock().unwrap();
+        for script in scripts.iter() {
+            if !script.ast.iter_functions().any(|f| f.name == hook) {
+                continue;
+            }
+            let result = engine().call_fn::<rhai::Dynamic>(
+                &mut Scope::new(),
+                &script.ast,
+                hook,
+                (text.to_string(), to_map(context)),
+            );
+            if let Err(e) = result {
+                log::error!("Script {} {}() failed: {}", script.name, hook, e);
+            }
+        }
+    }
+}
+
+#[cfg(not(feature = "scripting"))]
+mod imp {
+    pub fn reload() {}
+
+    pub fn call(_hook: &str, _text: &str, _context: &super::SessionContext) {}
+}
+
+/// Re-read all scripts from disk.
+pub fn reload() {
+    imp::reload();
+}
+
+pub fn on_text(text: &str, context: &SessionContext) {
+    imp::call("on_text", text, context);
+}
+
+pub fn on_session_complete(text: &str, context: &SessionContext) {
+    imp::call("on_session_complete", text, context);
+}
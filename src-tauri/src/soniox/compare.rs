@@ -0,0 +1,135 @@
+//! Standalone recognition stream that only collects a transcript.
+//!
+//! Comparison mode feeds it a copy of every audio chunk the main session
+//! sends; accuracy evaluation feeds it a WAV file. Nothing it recognizes is
+//! typed.
+
+use super::{
+    append_word_timings, connect_with_retries, endpoint, is_control_token, session_context, SonioxConfig,
+    SonioxResponse, DEFAULT_MODEL,
+};
+use futures_util::{SinkExt, StreamExt};
+use tokio::sync::mpsc;
+use tokio::time::{Duration, Instant};
+use tokio_tungstenite::tungstenite::Message;
+
+/// Stream `audio` to a separate Soniox session, with `comparison` overriding
+/// the model, key and hints from `settings`, and return its final text and
+/// the timings of the recognized words. The stream is finished when `audio`
+/// closes.
+pub async fn run(
+    settings: crate::AppSettings,
+    comparison: crate::ComparisonSettings,
+    mut audio: mpsc::UnboundedReceiver<Vec<u8>>,
+) -> Result<(crate::ComparisonTranscript, Vec<crate::WordTiming>), String> {
+    let model = if comparison.model.is_empty() { DEFAULT_MODEL.to_string() } else { comparison.model.clone() };
+    let api_key = match comparison.credential.as_ref().and_then(|name| settings.credentials.get(name)) {
+        Some(credential) => credential.api_key.clone(),
+        None => settings.effective_api_key().to_string(),
+    };
+    let language_hints = comparison.language_hints.clone().unwrap_or_else(|| settings.language_hints.clone());
+    let default_language = language_hints.first().cloned();
+
+    let network = settings.network_overrides.get(super::PROVIDER_ID).cloned().unwrap_or_default();
+    let endpoint = endpoint(&settings, &network)?;
+
+    let ws_stream = connect_with_retries(None, &endpoint, &network, &settings)
+        .await
+        .map_err(|e| format!("Connection failed: {}", e))?;
+    let (mut ws_write, mut ws_read) = ws_stream.split();
+
+    let config = SonioxConfig {
+        api_key,
+        model: model.clone(),
+        language_hints: if language_hints.is_empty() { None } else { Some(language_hints) },
+        language_restrictions: settings.language_restrictions.clone(),
+        enable_endpoint_detection: settings.endpoint_detection,
+        audio_format: "pcm_s16le".to_string(),
+        sample_rate: 16000,
+        num_channels: 1,
+        context: session_context(&settings),
+        // Comparison and evaluation score recognition, not translation
+        translation: None,
+    };
+    let config_json = serde_json::to_string(&config).map_err(|e| e.to_string())?;
+    ws_write
+        .send(Message::Text(config_json))
+        .await
+        .map_err(|e| format!("Failed to send config: {}", e))?;
+    log::info!("Transcript-only stream connected (model {})", model);
+
+    let mut final_text = String::new();
+    let mut words: Vec<crate::WordTiming> = Vec::new();
+    let mut audio_closed = false;
+    // Same grace period as the main session for final tokens after the end signal
+    let finish_timeout = tokio::time::sleep(Duration::from_secs(3600));
+    tokio::pin!(finish_timeout);
+
+    loop {
+        tokio::select! {
+            chunk = audio.recv(), if !audio_closed => match chunk {
+                Some(data) => {
+                    if let Err(e) = ws_write.send(Message::Binary(data)).await {
+                        return Err(format!("Audio send failed: {}", e));
+                    }
+                }
+                None => {
+                    audio_closed = true;
+                    ws_write.send(Message::Text(String::new())).await.ok();
+                    finish_timeout.as_mut().reset(Instant::now() + Duration::from_millis(settings.finish_timeout_ms));
+                }
+            },
+            _ = &mut finish_timeout, if audio_closed => {
+                log::warn!("Transcript-only stream timed out waiting for final tokens");
+                break;
+            }
+            msg = ws_read.next() => match msg {
+                Some(Ok(Message::Text(text))) => {
+                    let Ok(response) = serde_json::from_str::<SonioxResponse>(&text) else {
+                        continue;
+                    };
+                    if let Some(code) = response.error_code {
+                        return Err(format!(
+                            "Soniox error: {} - {}",
+                            code,
+                            response.error_message.unwrap_or_default()
+                        ));
+                    }
+                    let tokens = response.tokens.unwrap_or_default();
+                    let finals: Vec<_> = tokens
+                        .into_iter()
+                        .filter(|t| t.is_final && !t.text.is_empty() && !is_control_token(&t.text))
+                        .collect();
+                    append_word_timings(&mut words, &finals);
+                    final_text.push_str(&crate::text::join_tokens(
+                        finals.iter().map(|t| (t.text.as_str(), t.language.as_deref())),
+                        default_language.as_deref(),
+                    ));
+                    if response.finished == Some(true) {
+                        break;
+                    }
+                }
+                Some(Ok(Message::Close(_))) | None => break,
+                Some(Err(e)) => return Err(format!("WebSocket error: {}", e)),
+                _ => {}
+            },
+        }
+    }
+
+    // Same corrections as the typed text so only recognition differences remain
+    let corrected = crate::text::pipeline::apply_pronunciations(&settings.pronunciations, &final_text);
+    let text = crate::text::pipeline::apply(
+        settings.effective_text_processors(),
+        &corrected,
+        &crate::text::pipeline::ProcessContext {
+            previous: "",
+            language: default_language.as_deref(),
+            end_of_utterance: true,
+        },
+    );
+    let transcript = crate::ComparisonTranscript {
+        label: model,
+        text: text.trim().to_string(),
+    };
+    Ok((transcript, words))
+}
@@ -0,0 +1,260 @@
+//! Soniox async (file) transcription over the REST API, for recordings
+//! that already exist: upload the WAV, create a transcription, poll until
+//! it completes, fetch the transcript, then delete both from Soniox.
+//!
+//! Unlike `transcribe_samples` the audio is not streamed over a real-time
+//! connection, so a long session comes back in a fraction of its length.
+//! Requests go to the server of `soniox_endpoint` when one is set, with the
+//! provider's network overrides, so recordings never leave for the public
+//! API behind a self-hosted or pinned endpoint's back.
+
+use super::{append_word_timings, is_control_token, LanguageTally, Token};
+use serde::Deserialize;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use tokio::time::{Duration, Instant};
+
+const PUBLIC_API_BASE: &str = "https://api.soniox.com/v1";
+const ASYNC_MODEL: &str = "stt-async-v4";
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+// Async jobs are usually done within a minute; give queued ones time too
+const POLL_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+
+/// Where and as whom the REST requests go.
+struct Api<'a> {
+    client: reqwest::Client,
+    /// "https://host[:port]/v1"
+    base: String,
+    key: &'a str,
+}
+
+impl<'a> Api<'a> {
+    fn new(settings: &'a crate::AppSettings) -> Result<Self, String> {
+        let network = settings.network_overrides.get(super::PROVIDER_ID).cloned().unwrap_or_default();
+        let base = api_base(settings.soniox_endpoint.as_deref())?;
+        Ok(Self { client: client(&base, &network)?, base, key: settings.effective_api_key() })
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}/{}", self.base, path)
+    }
+}
+
+/// The REST API next to the real-time endpoint: the same server as a
+/// custom `soniox_endpoint` (wss becomes https, ws http), otherwise the
+/// public API.
+fn api_base(endpoint: Option<&str>) -> Result<String, String> {
+    let Some(url) = endpoint.map(str::trim).filter(|url| !url.is_empty()) else {
+        return Ok(PUBLIC_API_BASE.to_string());
+    };
+    let uri: tokio_tungstenite::tungstenite::http::Uri =
+        url.parse().map_err(|e| format!("Invalid Soniox endpoint '{}': {}", url, e))?;
+    let scheme = match uri.scheme_str() {
+        Some("wss") => "https",
+        Some("ws") => "http",
+        _ => return Err(format!("Soniox endpoint '{}' must start with wss:// or ws://", url)),
+    };
+    let authority = uri.authority().ok_or_else(|| format!("Soniox endpoint '{}' has no host", url))?;
+    Ok(format!("{}://{}/v1", scheme, authority))
+}
+
+/// An HTTP client honouring the IP pin and IPv4-only overrides. The SNI
+/// override names the real-time host, so it doesn't apply here.
+fn client(base: &str, network: &crate::NetworkOverrides) -> Result<reqwest::Client, String> {
+    let mut builder = reqwest::Client::builder();
+    if network.force_ipv4 {
+        builder = builder.local_address(IpAddr::V4(Ipv4Addr::UNSPECIFIED));
+    }
+    if let Some(ip) = network.resolve_to.as_deref().filter(|ip| !ip.is_empty()) {
+        let ip: IpAddr = ip.parse().map_err(|_| format!("Invalid IP override '{}'", ip))?;
+        let url = reqwest::Url::parse(base).map_err(|e| e.to_string())?;
+        let host = url.host_str().ok_or_else(|| format!("No host in {}", base))?;
+        let port = url.port_or_known_default().unwrap_or(443);
+        builder = builder.resolve(host, SocketAddr::new(ip, port));
+    }
+    builder.build().map_err(|e| format!("Could not create HTTP client: {}", e))
+}
+
+#[derive(Deserialize)]
+struct Created {
+    id: String,
+}
+
+#[derive(Deserialize)]
+struct Transcription {
+    status: String,
+    #[serde(default)]
+    error_message: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Transcript {
+    #[serde(default)]
+    tokens: Vec<TranscriptToken>,
+}
+
+#[derive(Deserialize)]
+struct TranscriptToken {
+    text: String,
+    start_ms: Option<u64>,
+    end_ms: Option<u64>,
+    speaker: Option<i32>,
+    language: Option<String>,
+}
+
+/// Transcribe a WAV file with the async API, using the settings' key,
+/// language hints and text processing. Returns the text, its word timings
+/// and the detected languages, most used first.
+pub async fn transcribe_wav(
+    settings: &crate::AppSettings,
+    file_name: &str,
+    wav: Vec<u8>,
+) -> Result<(String, Vec<crate::WordTiming>, Vec<String>), String> {
+    let api = Api::new(settings)?;
+
+    let file: Created = upload(&api, file_name, wav).await?;
+    let result = transcribe_file(&api, &file.id, &settings.language_hints).await;
+    // Don't leave the recording on Soniox's side, whatever happened
+    if let Err(e) = delete(&api, &format!("files/{}", file.id)).await {
+        log::warn!("Failed to delete uploaded file {}: {}", file.id, e);
+    }
+    let tokens = result?;
+
+    let finals: Vec<Token> = tokens
+        .into_iter()
+        .filter(|t| !t.text.is_empty() && !is_control_token(&t.text))
+        .map(|t| Token {
+            text: t.text,
+            is_final: true,
+            speaker: t.speaker,
+            language: t.language,
+            start_ms: t.start_ms,
+            end_ms: t.end_ms,
+            translation_status: None,
+        })
+        .collect();
+    let mut words = Vec::new();
+    append_word_timings(&mut words, &finals);
+    let mut languages = LanguageTally::default();
+    for token in &finals {
+        languages.add(token);
+    }
+
+    let default_language = settings.language_hints.first().map(String::as_str);
+    let joined = crate::text::join_tokens(
+        finals.iter().map(|t| (t.text.as_str(), t.language.as_deref())),
+        default_language,
+    );
+    let corrected = crate::text::pipeline::apply_pronunciations(&settings.pronunciations, &joined);
+    let text = crate::text::pipeline::apply(
+        settings.effective_text_processors(),
+        &corrected,
+        &crate::text::pipeline::ProcessContext {
+            previous: "",
+            language: default_language,
+            end_of_utterance: true,
+        },
+    );
+    Ok((text.trim().to_string(), words, languages.languages()))
+}
+
+/// Create a transcription for an uploaded file and wait for its tokens.
+async fn transcribe_file(
+    api: &Api<'_>,
+    file_id: &str,
+    language_hints: &[String],
+) -> Result<Vec<TranscriptToken>, String> {
+    let body = serde_json::json!({
+        "model": ASYNC_MODEL,
+        "file_id": file_id,
+        "language_hints": language_hints,
+    });
+    let request = api
+        .client
+        .post(api.url("transcriptions"))
+        .header("Content-Type", "application/json")
+        .body(body.to_string());
+    let transcription: Created = send(request, api.key).await?;
+    log::info!("Soniox transcription {} created for file {}", transcription.id, file_id);
+
+    let result = wait_for_transcript(api, &transcription.id).await;
+    if let Err(e) = delete(api, &format!("transcriptions/{}", transcription.id)).await {
+        log::warn!("Failed to delete transcription {}: {}", transcription.id, e);
+    }
+    result
+}
+
+async fn wait_for_transcript(api: &Api<'_>, id: &str) -> Result<Vec<TranscriptToken>, String> {
+    let deadline = Instant::now() + POLL_TIMEOUT;
+    loop {
+        let status: Transcription = send(api.client.get(api.url(&format!("transcriptions/{}", id))), api.key).await?;
+        match status.status.as_str() {
+            "completed" => break,
+            "error" => {
+                return Err(format!(
+                    "Soniox transcription failed: {}",
+                    status.error_message.unwrap_or_default()
+                ))
+            }
+            _ if Instant::now() >= deadline => return Err("Soniox transcription timed out".to_string()),
+            _ => tokio::time::sleep(POLL_INTERVAL).await,
+        }
+    }
+    let transcript: Transcript =
+        send(api.client.get(api.url(&format!("transcriptions/{}/transcript", id))), api.key).await?;
+    Ok(transcript.tokens)
+}
+
+async fn upload(api: &Api<'_>, file_name: &str, wav: Vec<u8>) -> Result<Created, String> {
+    let part = reqwest::multipart::Part::bytes(wav)
+        .file_name(file_name.to_string())
+        .mime_str("audio/wav")
+        .map_err(|e| e.to_string())?;
+    let form = reqwest::multipart::Form::new().part("file", part);
+    send(api.client.post(api.url("files")).multipart(form), api.key).await
+}
+
+async fn delete(api: &Api<'_>, path: &str) -> Result<(), String> {
+    let response = api
+        .client
+        .delete(api.url(path))
+        .bearer_auth(api.key)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    response.error_for_status().map(|_| ()).map_err(|e| e.to_string())
+}
+
+/// Send an authorized request and parse the JSON response.
+async fn send<T: serde::de::DeserializeOwned>(request: reqwest::RequestBuilder, api_key: &str) -> Result<T, String> {
+    let response = request
+        .bearer_auth(api_key)
+        .send()
+        .await
+        .map_err(|e| format!("Soniox API request failed: {}", e))?;
+    let status = response.status();
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| format!("Soniox API response failed: {}", e))?;
+    if !status.is_success() {
+        return Err(format!("Soniox API error {}: {}", status, String::from_utf8_lossy(&bytes)));
+    }
+    serde_json::from_slice(&bytes).map_err(|e| format!("Unexpected Soniox API response: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rest_api_follows_the_configured_endpoint() {
+        assert_eq!(api_base(None).unwrap(), PUBLIC_API_BASE);
+        assert_eq!(api_base(Some(" ")).unwrap(), PUBLIC_API_BASE);
+        assert_eq!(
+            api_base(Some("wss://stt.example.com/transcribe-websocket")).unwrap(),
+            "https://stt.example.com/v1"
+        );
+        assert_eq!(api_base(Some("ws://10.0.0.5:8080/ws")).unwrap(), "http://10.0.0.5:8080/v1");
+        assert!(api_base(Some("https://stt.example.com")).is_err());
+    }
+}
@@ -1,15 +1,46 @@
+mod compare;
+pub mod files;
+pub mod status;
+pub mod warm;
+
+use futures_util::stream::{SplitSink, SplitStream};
 use futures_util::{SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
-use tauri::Emitter;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use tauri::{Emitter, Manager};
+use tokio::net::TcpStream;
 use tokio::sync::mpsc;
 use tokio::time::{Duration, Instant};
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
 use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+use crate::error::TranscriptionError;
+use crate::text::pipeline::HeldTail;
+use crate::text::spelling::{Fed, SessionVocabulary};
+
+type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
 
 const SONIOX_WSS_HOST: &str = "stt-rt.soniox.com";
+// Key for this provider in `AppSettings::network_overrides`
+const PROVIDER_ID: &str = "soniox";
+const DEFAULT_MODEL: &str = "stt-rt-v4";
+// Asks the server to finalize every non-final token right away
+const FINALIZE_MESSAGE: &str = r#"{"type":"finalize"}"#;
+// Longest tail of the previous session sent as context
+const CARRY_OVER_CHARS: usize = 500;
+// Sessions further apart than this are not treated as one document
+const CARRY_OVER_MAX_AGE: Duration = Duration::from_secs(300);
+// Upper bound for each probe step so an unreachable endpoint can't hang the UI
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+// A stream due for rollover that never reaches a pause is cut this much later
+const ROLLOVER_GRACE: Duration = Duration::from_secs(60);
+// Backoff between connection attempts: doubles from the base up to the cap
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(8);
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 struct SonioxConfig {
     #[serde(rename = "api_key")]
     api_key: String,
@@ -26,12 +57,205 @@ struct SonioxConfig {
     sample_rate: u32,
     #[serde(rename = "num_channels")]
     num_channels: u32,
+    #[serde(rename = "context", skip_serializing_if = "Option::is_none")]
+    context: Option<SonioxContext>,
+    #[serde(rename = "translation", skip_serializing_if = "Option::is_none")]
+    translation: Option<SonioxTranslation>,
+}
+
+/// One-way translation of everything spoken into `target_language`.
+#[derive(Debug, Clone, Serialize)]
+struct SonioxTranslation {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    target_language: String,
+}
+
+fn translation(settings: &crate::AppSettings) -> Option<SonioxTranslation> {
+    let target = settings.effective_translation_target()?;
+    Some(SonioxTranslation { kind: "one_way", target_language: target.to_string() })
+}
+
+/// Context that biases recognition: domain terms, and free text preceding
+/// the audio (the previous session, when carried over).
+#[derive(Debug, Clone, Serialize)]
+struct SonioxContext {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    terms: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    text: Option<String>,
+}
+
+/// Running totals for the `session-stats` event.
+#[derive(Debug, Clone, Serialize)]
+struct SessionStats {
+    /// Confirmed plus provisional words so far
+    words: usize,
+    /// Words per minute of audio between the first and the latest word
+    wpm: f64,
+    /// Audio time between the first and the latest word
+    speaking_ms: u64,
+}
+
+/// Payload of `language-detected`, sent when the main spoken language changes.
+#[derive(Debug, Clone, Serialize)]
+struct LanguageDetected {
+    /// Language of most of the speech so far
+    language: String,
+    /// Every language heard so far, most used first
+    languages: Vec<String>,
+}
+
+/// Languages Soniox tagged the spoken final tokens with, weighted by the
+/// amount of text in each.
+#[derive(Default)]
+struct LanguageTally {
+    counts: Vec<(String, usize)>,
+    reported: Option<String>,
+}
+
+impl LanguageTally {
+    fn add(&mut self, token: &Token) {
+        let Some(language) = token.language.as_deref().filter(|l| !l.is_empty()) else {
+            return;
+        };
+        let weight = token.text.trim().chars().count();
+        match self.counts.iter_mut().find(|(l, _)| l == language) {
+            Some((_, count)) => *count += weight,
+            None => self.counts.push((language.to_string(), weight)),
+        }
+    }
+
+    /// Every language heard, most used first.
+    fn languages(&self) -> Vec<String> {
+        let mut counts = self.counts.clone();
+        counts.sort_by(|a, b| b.1.cmp(&a.1));
+        counts.into_iter().filter(|(_, count)| *count > 0).map(|(l, _)| l).collect()
+    }
+
+    /// The event to send if the main language changed since the last one.
+    fn changed(&mut self) -> Option<LanguageDetected> {
+        let languages = self.languages();
+        let language = languages.first()?.clone();
+        if self.reported.as_ref() == Some(&language) {
+            return None;
+        }
+        self.reported = Some(language.clone());
+        Some(LanguageDetected { language, languages })
+    }
+}
+
+/// Work for the session's typing worker.
+enum Insertion {
+    /// Processed final text, when it was queued and its sequence id in the
+    /// pending-insertion journal (0 when not journaled)
+    Final(String, std::time::Instant, u64),
+    /// The current non-final tail, replacing the previous one on screen
+    /// (provisional typing only)
+    Provisional(String),
+    /// Erase this many characters already typed, then type the text
+    Correction(usize, String),
+}
+
+/// Process final text for output: known misrecognitions and words taught
+/// this session first, then the configured post-processing pipeline.
+fn final_output(
+    settings: &crate::AppSettings,
+    vocabulary: &SessionVocabulary,
+    text: &str,
+    previous: &str,
+    language: Option<&str>,
+    end_of_utterance: bool,
+) -> String {
+    let corrected = crate::text::pipeline::apply_pronunciations(&settings.pronunciations, text);
+    let output = crate::text::pipeline::apply(
+        settings.effective_text_processors(),
+        &vocabulary.apply(&corrected),
+        &crate::text::pipeline::ProcessContext {
+            previous,
+            language,
+            end_of_utterance,
+        },
+    );
+    if settings.plain_ascii_output {
+        crate::text::to_plain_ascii(&output)
+    } else {
+        output
+    }
+}
+
+/// Teach a word spelled right after `output` (what is about to be output,
+/// following `accumulated_text`), fixing the word in `output` when it is
+/// still there. Returns the correction of text that was already typed.
+fn learn_spelled(
+    app: &tauri::AppHandle,
+    vocabulary: &mut SessionVocabulary,
+    word: &str,
+    accumulated_text: &mut String,
+    output: &mut String,
+) -> Option<Insertion> {
+    let Some(correction) = vocabulary.learn(word, &format!("{}{}", accumulated_text, output)) else {
+        log::info!("Nothing to spell \"{}\" over", word);
+        return None;
+    };
+    log::info!("Learned \"{}\" for this session", word);
+    crate::console::record(app, "spelling", format!("Learned '{}'", word));
+    correction
+        .apply(accumulated_text, output)
+        .map(|(erase, text)| Insertion::Correction(erase, text))
+}
+
+impl Insertion {
+    /// Final text, journaled as pending until the worker is through with it.
+    /// Nothing is typed in review mode, so there is nothing to recover.
+    fn journaled_final(text: String, target_window_id: &str, review_mode: bool) -> Self {
+        let seq = if text.is_empty() || review_mode { 0 } else { crate::pending::queue(target_window_id, &text) };
+        Insertion::Final(text, std::time::Instant::now(), seq)
+    }
+}
+
+/// Final text of the last session and when it ended, for context carry-over.
+static PREVIOUS_SESSION: Mutex<Option<(Instant, String)>> = Mutex::new(None);
+
+/// Recognition context for a new session, if there is any to send.
+fn session_context(settings: &crate::AppSettings) -> Option<SonioxContext> {
+    // Soniox has no phonetic hints; passing pronunciation words as context
+    // terms is the closest it offers
+    let terms = settings.context_terms();
+    let text = if settings.context_carry_over {
+        PREVIOUS_SESSION
+            .lock()
+            .unwrap()
+            .as_ref()
+            .filter(|(ended, _)| ended.elapsed() <= CARRY_OVER_MAX_AGE)
+            .map(|(_, text)| tail(text, CARRY_OVER_CHARS))
+    } else {
+        None
+    };
+    if terms.is_empty() && text.is_none() {
+        return None;
+    }
+    Some(SonioxContext { terms, text })
+}
+
+/// The last `max_chars` characters of `text`, starting at a word boundary.
+fn tail(text: &str, max_chars: usize) -> String {
+    let count = text.chars().count();
+    if count <= max_chars {
+        return text.to_string();
+    }
+    let cut: String = text.chars().skip(count - max_chars).collect();
+    match cut.split_once(char::is_whitespace) {
+        Some((_, rest)) => rest.trim_start().to_string(),
+        None => cut,
+    }
 }
 
 #[derive(Debug, Deserialize)]
 struct SonioxResponse {
+    // Soniox sends a numeric HTTP-style code; accept strings as well
     #[serde(rename = "error_code")]
-    error_code: Option<String>,
+    error_code: Option<serde_json::Value>,
     #[serde(rename = "error_message")]
     error_message: Option<String>,
     #[serde(rename = "tokens")]
@@ -40,7 +264,7 @@ struct SonioxResponse {
     finished: Option<bool>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct Token {
     #[serde(rename = "text")]
     text: String,
@@ -50,124 +274,352 @@ struct Token {
     speaker: Option<i32>,
     #[serde(rename = "language")]
     language: Option<String>,
+    #[serde(rename = "start_ms")]
+    start_ms: Option<u64>,
+    #[serde(rename = "end_ms")]
+    end_ms: Option<u64>,
+    /// "original" for spoken tokens that get translated, "translation" for
+    /// their translation, "none" otherwise (translation mode only)
+    #[serde(rename = "translation_status", default)]
+    translation_status: Option<String>,
+}
+
+/// Payload of the `soniox-raw` debug event: one response's tokens as
+/// received, control and original-language tokens included.
+#[derive(Serialize)]
+struct RawTokens<'a> {
+    tokens: &'a [Token],
+    finished: bool,
+    /// Not yet added to the token times (non-zero after a rollover)
+    stream_offset_ms: u64,
 }
 
 pub async fn connect_and_transcribe(
-    api_key: String,
-    language_hints: Vec<String>,
-    language_restrictions: Option<Vec<String>>,
-    stop_signal: Arc<AtomicBool>,
-    audio_rx: &mut mpsc::Receiver<Vec<u8>>,
+    settings: crate::AppSettings,
+    stop_signal: tokio_util::sync::CancellationToken,
+    audio_rx: &mut mpsc::Receiver<crate::audio::AudioChunk>,
     app: tauri::AppHandle,
     target_window_id: String,
-) -> Result<(), String> {
+) -> Result<(), TranscriptionError> {
     eprintln!("DEBUG: connect_and_transcribe called");
 
-    let url = format!("wss://{}/transcribe-websocket", SONIOX_WSS_HOST);
+    let network = settings.network_overrides.get(PROVIDER_ID).cloned().unwrap_or_default();
+    let endpoint = endpoint(&settings, &network).map_err(TranscriptionError::Provider)?;
 
-    log::info!("Connecting to Soniox: {}", url);
-    eprintln!("DEBUG: Attempting WebSocket connection to {}", url);
+    log::info!("Connecting to Soniox: {}", endpoint.url);
+    eprintln!("DEBUG: Attempting WebSocket connection to {}", endpoint.url);
 
-    let (ws_stream, _) = tokio_tungstenite::connect_async(&url).await.map_err(|e| {
-        let err_msg = format!("WebSocket connection failed: {}", e);
-        eprintln!("DEBUG ERROR: {}", err_msg);
-        log::error!("{}", err_msg);
-        err_msg
-    })?;
+    let api_key = settings.effective_api_key().to_string();
+
+    let ws_stream = match warm::take(&endpoint, &network).await {
+        Some(ws) => {
+            log::info!("Using the pre-connected WebSocket");
+            ws
+        }
+        None => connect_with_retries(Some(&app), &endpoint, &network, &settings).await.map_err(|e| {
+            let err_msg = format!("WebSocket connection failed: {}", e);
+            eprintln!("DEBUG ERROR: {}", err_msg);
+            log::error!("{}", err_msg);
+            status::record_error(&app, &api_key, &err_msg, matches!(e, TranscriptionError::Auth(_)));
+            e
+        })?,
+    };
+    status::record_connected(&app, &api_key);
 
     eprintln!("DEBUG: WebSocket connected successfully");
     log::info!("Connected to Soniox");
 
-    let (mut ws_write, mut ws_read) = ws_stream.split();
-    let (ws_tx, mut ws_rx) = mpsc::unbounded_channel::<Message>();
+    let (ws_write, mut ws_read) = ws_stream.split();
+    let mut ws_tx = spawn_writer(ws_write);
 
-    tokio::spawn(async move {
-        while let Some(msg) = ws_rx.recv().await {
-            if let Err(e) = ws_write.send(msg).await {
-                eprintln!("DEBUG ERROR: WebSocket send failed: {}", e);
-                log::error!("WebSocket send failed: {}", e);
-                break;
-            }
-        }
-    });
+    let translation = translation(&settings);
+    // Fallback language for tokens Soniox returns without a language tag
+    let default_language = match &translation {
+        Some(t) => Some(t.target_language.clone()),
+        None => settings.language_hints.first().cloned(),
+    };
+    if let Some(t) = &translation {
+        log::info!("Translating into '{}'", t.target_language);
+    }
 
     let config = SonioxConfig {
         api_key: api_key.clone(),
-        model: "stt-rt-v4".to_string(),
-        language_hints: if language_hints.is_empty() { None } else { Some(language_hints) },
-        language_restrictions,
-        enable_endpoint_detection: true,
-        audio_format: "pcm_s16le".to_string(),
+        model: model(&settings).to_string(),
+        language_hints: if settings.language_hints.is_empty() { None } else { Some(settings.language_hints.clone()) },
+        language_restrictions: settings.language_restrictions.clone(),
+        enable_endpoint_detection: settings.endpoint_detection,
+        audio_format: if settings.compressed_upload { "mulaw" } else { "pcm_s16le" }.to_string(),
         sample_rate: 16000,
         num_channels: 1,
+        context: session_context(&settings),
+        translation,
     };
 
-    let config_json = serde_json::to_string(&config).map_err(|e| e.to_string())?;
+    let config_json = serde_json::to_string(&config).map_err(|e| TranscriptionError::Provider(e.to_string()))?;
     log::info!("Sending config: {}", config_json);
 
     ws_tx
         .send(Message::Text(config_json))
-        .map_err(|e| format!("Failed to queue config: {}", e))?;
+        .map_err(|e| TranscriptionError::Network(format!("Failed to queue config: {}", e)))?;
 
     log::info!("Config sent to Soniox");
 
+    // Comparison mode: a second stream gets a copy of the audio; its
+    // transcript only goes to history
+    let (mut comparison_tx, comparison_task) = if settings.comparison.enabled {
+        let (tx, rx) = mpsc::unbounded_channel::<Vec<u8>>();
+        (Some(tx), Some(tokio::spawn(compare::run(settings.clone(), settings.comparison.clone(), rx))))
+    } else {
+        (None, None)
+    };
+
     // Track the text we've already typed
     let mut typed_text: String = String::new();
     let mut is_transcribing = true;
     let mut audio_channel_closed = false;
     let mut end_signal_sent = false;
     let mut session_finished = false;
+    // Set once Soniox has accepted the key for this session
+    let mut credential_confirmed = false;
     // Track accumulated text for history
     let mut accumulated_text = String::new();
+    // Latest non-final tail, kept in case the session ends before it finalizes
+    let mut pending_non_final = String::new();
+    // Non-final tail last sent to the typing worker (provisional typing)
+    let mut typed_provisional = String::new();
+    // Audio span of the recognized words, for the speaking rate
+    let mut first_word_ms: Option<u64> = None;
+    let mut last_word_ms = 0u64;
+    let mut last_stats_words = 0usize;
+    // Word -> audio offset alignment, when Soniox sends token timestamps
+    let mut word_timings: Vec<crate::WordTiming> = Vec::new();
+    let mut languages = LanguageTally::default();
 
     eprintln!("DEBUG: Starting transcription loop");
     let mut audio_chunks_sent = 0;
     let mut messages_received = 0;
 
+    // Finals re-sent after a reconnect are typed only once
+    let mut duplicates = crate::typer::DuplicateFilter::new();
+    let mut vocabulary = SessionVocabulary::default();
+    let mut held_tail = HeldTail::default();
+    let script_context = crate::scripting::SessionContext::current(&app);
+
+    // Latency samples for this session, shared with the typing worker
+    let latency = Arc::new(Mutex::new(crate::latency::LatencyTracker::new()));
+
     // Dedicated typing worker so insertion never blocks the transcription loop.
-    let (typing_tx, mut typing_rx) = tokio::sync::mpsc::unbounded_channel::<String>();
+    let (typing_tx, mut typing_rx) = tokio::sync::mpsc::unbounded_channel::<Insertion>();
     let typing_target_window = target_window_id.clone();
+    let typing_latency = latency.clone();
+    let typing_app = app.clone();
+    let rich_text_paste = settings.rich_text_paste;
+    let chunking = crate::typer::Chunking::from_settings(&settings);
+    let provisional_typing = settings.provisional_typing;
+    // Screen sharing: the text only goes to the preview and history
+    let review_mode = app.state::<crate::AppState>().review_mode.load(std::sync::atomic::Ordering::SeqCst);
+    let cancel = app.state::<crate::AppState>().session_tokens.lock().unwrap().cancel.clone();
     tokio::spawn(async move {
-        while let Some(text) = typing_rx.recv().await {
-            let twid = typing_target_window.clone();
-            let ttt_for_typing = text.clone();
-            let type_result = tokio::task::spawn_blocking(move || {
-                crate::typer::type_text(&ttt_for_typing, &twid)
-            })
-            .await;
-
-            match type_result {
-                Ok(Ok(())) => {}
-                Ok(Err(e)) => {
-                    eprintln!("DEBUG ERROR: Failed to type text: {}", e);
-                    log::error!("Failed to type text: {}", e);
+        let provisional = Arc::new(Mutex::new(crate::typer::ProvisionalText::new()));
+        loop {
+            let first = tokio::select! {
+                first = typing_rx.recv() => match first {
+                    Some(first) => first,
+                    None => break,
+                },
+                // A cancelled session types nothing more; what is on screen stays
+                _ = cancel.cancelled() => break,
+            };
+            if review_mode {
+                continue;
+            }
+            let mut batch = vec![first];
+            while let Ok(next) = typing_rx.try_recv() {
+                batch.push(next);
+            }
+            // A provisional tail followed by anything newer would be erased
+            // again right away; only the latest one is worth typing
+            let last = batch.len() - 1;
+            for (i, insertion) in batch.into_iter().enumerate() {
+                let twid = typing_target_window.clone();
+                let screen = provisional.clone();
+                if cancel.is_cancelled() {
+                    if let Insertion::Final(_, _, seq) = insertion {
+                        crate::pending::done(seq);
+                    }
+                    continue;
                 }
-                Err(e) => {
-                    eprintln!("DEBUG ERROR: Typing task failed: {}", e);
+                let (text, enqueued_at, seq) = match insertion {
+                    Insertion::Provisional(_) if i < last => continue,
+                    Insertion::Provisional(tail) => {
+                        let shown = tokio::task::spawn_blocking(move || screen.lock().unwrap().show(&tail, &twid)).await;
+                        if let Ok(Err(e)) = shown {
+                            log::warn!("Failed to update provisional text: {}", e);
+                        }
+                        continue;
+                    }
+                    Insertion::Correction(erase, text) => {
+                        let corrected = tokio::task::spawn_blocking(move || {
+                            // The provisional tail sits after the word being corrected
+                            screen.lock().unwrap().clear(&twid)?;
+                            crate::typer::erase_chars(erase, &twid)?;
+                            crate::typer::type_text(&text, &twid)
+                        })
+                        .await;
+                        if let Ok(Err(e)) = corrected {
+                            log::warn!("Failed to correct spelled word: {}", e);
+                        }
+                        continue;
+                    }
+                    Insertion::Final(text, enqueued_at, seq) => (text, enqueued_at, seq),
+                };
+                let ttt_for_typing = text.clone();
+                let ttt_for_console = text.clone();
+                let progress_app = typing_app.clone();
+                let typing_cancel = cancel.clone();
+                let type_result = tokio::task::spawn_blocking(move || {
+                    let rich = rich_text_paste
+                        && !chunking.is_large(&ttt_for_typing)
+                        && crate::text::markdown::has_markup(&ttt_for_typing);
+                    // Rich text is pasted whole, so no provisional prefix can stay
+                    let rest = if provisional_typing {
+                        screen.lock().unwrap().settle(if rich { "" } else { &ttt_for_typing }, &twid)?
+                    } else {
+                        &ttt_for_typing
+                    };
+                    if rich {
+                        let html = crate::text::markdown::to_html(&ttt_for_typing);
+                        crate::typer::paste_rich_text(&ttt_for_typing, &html, &twid)
+                    } else {
+                        crate::typer::type_text_chunked(rest, &twid, chunking, &typing_cancel, |progress| {
+                            progress_app.emit("insert-progress", progress).ok();
+                        })
+                    }
+                })
+                .await;
+
+                match type_result {
+                    Ok(Ok(())) => {
+                        typing_latency.lock().unwrap().record_insertion(enqueued_at);
+                        crate::console::record(&typing_app, "typed", ttt_for_console);
+                    }
+                    Ok(Err(e)) => {
+                        eprintln!("DEBUG ERROR: Failed to type text: {}", e);
+                        log::error!("Failed to type text: {}", e);
+                        crate::metrics::inc_typing_failures();
+                        crate::console::record(&typing_app, "typing-error", format!("'{}': {}", ttt_for_console, e));
+                        typing_app.emit("transcription-error", TranscriptionError::Typing(e)).ok();
+                    }
+                    Err(e) => {
+                        eprintln!("DEBUG ERROR: Typing task failed: {}", e);
+                        crate::metrics::inc_typing_failures();
+                        crate::console::record(&typing_app, "typing-error", format!("Typing task failed: {}", e));
+                    }
                 }
+                crate::pending::done(seq);
             }
         }
+        // After a cancel, drop what is still queued or arrives until the session ends
+        while let Some(insertion) = typing_rx.recv().await {
+            if let Insertion::Final(_, _, seq) = insertion {
+                crate::pending::done(seq);
+            }
+        }
+
+        // Text that never became final does not stay on screen
+        if provisional_typing {
+            let twid = typing_target_window.clone();
+            let cleared = tokio::task::spawn_blocking(move || provisional.lock().unwrap().clear(&twid)).await;
+            if let Ok(Err(e)) = cleared {
+                log::warn!("Failed to erase provisional text: {}", e);
+            }
+        }
+
+        // The channel closes when the session ends; all insertions are done now
+        let report = typing_latency.lock().unwrap().report();
+        log::info!(
+            "Latency (ms, avg/p95): capture->send {:.1}/{:.1}, provider round-trip {:.1}/{:.1}, insertion {:.1}/{:.1}",
+            report.capture_to_send.avg_ms,
+            report.capture_to_send.p95_ms,
+            report.provider_round_trip.avg_ms,
+            report.provider_round_trip.p95_ms,
+            report.insertion.avg_ms,
+            report.insertion.p95_ms,
+        );
+        typing_app.emit("latency-report", report).ok();
     });
 
+    // The profile's session template goes first, ahead of anything dictated
+    if let Some(preamble) = settings.session_preamble() {
+        typing_tx.send(Insertion::journaled_final(preamble, &target_window_id, review_mode)).ok();
+    }
+
+    // Soniox limits how long one stream may run; a long dictation continues
+    // on a new stream, opened once the old one has delivered everything
+    let rollover_after = (settings.session_rollover_mins > 0).then(|| Duration::from_secs(settings.session_rollover_mins * 60));
+    let mut stream_started = Instant::now();
+    // Audio captured while the old stream finishes and the new one
+    // connects, for the new one
+    let mut rollover_held: Option<Vec<crate::audio::AudioChunk>> = None;
+    let mut rollover_ready = false;
+    // The new stream's connect runs beside the loop, so audio keeps flowing in
+    let mut rollover_connect: Option<
+        tokio::task::JoinHandle<Result<(mpsc::UnboundedSender<Message>, SplitStream<WsStream>), TranscriptionError>>,
+    > = None;
+    // Token timestamps restart with each stream; this makes them session time
+    let mut stream_offset_ms = 0u64;
+    let mut audio_ms_sent = 0u64;
+    // The new stream's first words need a space after the old stream's last
+    let mut separate_next = false;
+
     // Use a persistent sleep future to avoid resetting it on every loop iteration.
     // We initialize it with a long duration and reset it when the end signal is sent.
     let finish_timeout = tokio::time::sleep(Duration::from_secs(3600));
     tokio::pin!(finish_timeout);
+    let finish_after = Duration::from_millis(settings.finish_timeout_ms);
+
+    // `partial-text` is throttled to one event per interval; the latest preview
+    // arriving inside the interval is held back and flushed when it elapses.
+    let partial_interval = Duration::from_millis(settings.partial_text_interval_ms);
+    let mut last_partial_emit: Option<Instant> = None;
+    let mut pending_partial: Option<String> = None;
+    let partial_flush = tokio::time::sleep(Duration::from_secs(3600));
+    tokio::pin!(partial_flush);
+
+    // With `non_final_flush_ms`, provisional words waiting that long are
+    // finalized on request instead of at the provider's own pace
+    let non_final_flush_after = (settings.non_final_flush_ms > 0).then(|| Duration::from_millis(settings.non_final_flush_ms));
+    let mut non_final_flush_due = false;
+    let non_final_flush = tokio::time::sleep(Duration::from_secs(3600));
+    tokio::pin!(non_final_flush);
 
     // Loop until session is finished or timeout
     while is_transcribing {
         // Check if we should stop (but keep processing until we get final tokens)
-        let should_stop = stop_signal.load(Ordering::SeqCst);
+        let should_stop = stop_signal.is_cancelled();
+
+        if rollover_ready {
+            rollover_ready = false;
+            let mut next_config = config.clone();
+            let carried = tail(&accumulated_text, CARRY_OVER_CHARS);
+            next_config.context = Some(SonioxContext {
+                terms: settings.context_terms(),
+                text: (!carried.is_empty()).then_some(carried),
+            });
+            let (app, endpoint, network, settings) = (app.clone(), endpoint.clone(), network.clone(), settings.clone());
+            rollover_connect = Some(tokio::spawn(async move {
+                open_stream(&app, &endpoint, &network, &settings, &next_config).await
+            }));
+        }
 
         // If user requested stop and we haven't sent end signal yet
-        if should_stop && !end_signal_sent {
+        // (a rolling-over session sends it to the new stream)
+        if should_stop && !end_signal_sent && rollover_held.is_none() {
             eprintln!("DEBUG: Stop requested, sending end signal to Soniox");
             ws_tx.send(Message::Text("".to_string())).ok();
             end_signal_sent = true;
-            // Start the 5-second countdown to finish the session
-            finish_timeout
-                .as_mut()
-                .reset(Instant::now() + Duration::from_secs(5));
+            // Start the countdown to finish the session
+            finish_timeout.as_mut().reset(Instant::now() + finish_after);
         }
 
         // If session is finished, exit after processing remaining messages
@@ -177,41 +629,100 @@ pub async fn connect_and_transcribe(
         }
 
         tokio::select! {
+            // Wake up to send the end signal as soon as stop is requested
+            _ = stop_signal.cancelled(), if !should_stop => {}
             // Send audio data (or handle closed channel)
             chunk = audio_rx.recv(), if !audio_channel_closed => {
                 match chunk {
-                    Some(audio_data) => {
+                    Some(chunk) => {
                         audio_chunks_sent += 1;
                         if audio_chunks_sent % 100 == 0 {
-                            eprintln!("DEBUG: Sent {} audio chunks, latest size: {} bytes", audio_chunks_sent, audio_data.len());
+                            eprintln!("DEBUG: Sent {} audio chunks, latest size: {} bytes", audio_chunks_sent, chunk.data.len());
+                        }
+                        if let Some(tx) = &comparison_tx {
+                            tx.send(chunk.data.clone()).ok();
                         }
-                        if let Err(e) = ws_tx.send(Message::Binary(audio_data)) {
-                            eprintln!("DEBUG ERROR: Failed to send audio: {}", e);
+                        match rollover_held.as_mut() {
+                            Some(held) => held.push(chunk),
+                            None => audio_ms_sent += send_audio(&ws_tx, &latency, chunk, settings.compressed_upload),
                         }
                     }
                     None => {
                         // Audio channel closed
                         if !audio_channel_closed {
                             eprintln!("DEBUG: Audio channel closed after {} chunks", audio_chunks_sent);
-                            if !end_signal_sent {
+                            // Closing the copy ends the comparison stream too
+                            comparison_tx = None;
+                            if !end_signal_sent && rollover_held.is_none() {
                                 eprintln!("DEBUG: Sending end signal to Soniox");
                                 ws_tx.send(Message::Text("".to_string())).ok();
                                 end_signal_sent = true;
-                                // Start the 5-second countdown to finish the session
-                                finish_timeout.as_mut().reset(Instant::now() + Duration::from_secs(5));
+                                // Start the countdown to finish the session
+                                finish_timeout.as_mut().reset(Instant::now() + finish_after);
                             }
                             audio_channel_closed = true;
                         }
                     }
                 }
             }
+            // The rollover's new stream is connected; the audio held meanwhile goes first
+            opened = async { rollover_connect.as_mut().unwrap().await }, if rollover_connect.is_some() => {
+                rollover_connect = None;
+                match opened.unwrap_or_else(|e| Err(TranscriptionError::Network(e.to_string()))) {
+                    Ok((tx, read)) => {
+                        ws_tx = tx;
+                        ws_read = read;
+                    }
+                    Err(e) => {
+                        let err_msg = format!("Rollover to a new stream failed: {}", e);
+                        log::error!("{}", err_msg);
+                        crate::console::record(&app, "error", err_msg.clone());
+                        status::record_error(&app, &api_key, &err_msg, matches!(e, TranscriptionError::Auth(_)));
+                        app.emit("transcription-error", e).ok();
+                        break;
+                    }
+                }
+                let held = rollover_held.take().unwrap_or_default();
+                log::info!("Continuing on a new stream with {} held audio chunks", held.len());
+                stream_offset_ms = audio_ms_sent;
+                for chunk in held {
+                    audio_ms_sent += send_audio(&ws_tx, &latency, chunk, settings.compressed_upload);
+                }
+                stream_started = Instant::now();
+                typed_text.clear();
+                pending_non_final.clear();
+                separate_next = !accumulated_text.is_empty();
+                finish_timeout.as_mut().reset(Instant::now() + Duration::from_secs(3600));
+                if audio_channel_closed {
+                    ws_tx.send(Message::Text("".to_string())).ok();
+                    end_signal_sent = true;
+                    finish_timeout.as_mut().reset(Instant::now() + finish_after);
+                }
+            }
             // Timeout waiting for final tokens after sending end signal
-            _ = &mut finish_timeout, if end_signal_sent && !session_finished => {
+            _ = &mut finish_timeout, if (end_signal_sent || rollover_held.is_some()) && rollover_connect.is_none() && !session_finished => {
                 eprintln!("DEBUG: Timeout waiting for final tokens from Soniox");
-                is_transcribing = false;
+                if rollover_held.is_some() {
+                    log::warn!("Old stream did not finish in time, rolling over anyway");
+                    rollover_ready = true;
+                } else {
+                    is_transcribing = false;
+                }
+            }
+            // Provisional words waited `non_final_flush_ms`; have them finalized
+            _ = &mut non_final_flush, if non_final_flush_due && !end_signal_sent && rollover_held.is_none() => {
+                non_final_flush_due = false;
+                ws_tx.send(Message::Text(FINALIZE_MESSAGE.to_string())).ok();
             }
-            // Receive transcription results
-            msg = ws_read.next() => {
+            // Emit the preview held back by the partial-text throttle
+            _ = &mut partial_flush, if pending_partial.is_some() => {
+                if let Some(preview_text) = pending_partial.take() {
+                    app.emit("partial-text", preview_text).ok();
+                    last_partial_emit = Some(Instant::now());
+                }
+            }
+            // Receive transcription results (the old stream is done while a rollover connects)
+            msg = ws_read.next(), if rollover_connect.is_none() => {
                 match msg {
                     Some(Ok(Message::Text(text))) => {
                         messages_received += 1;
@@ -223,18 +734,63 @@ pub async fn connect_and_transcribe(
                         if let Ok(response) = serde_json::from_str::<SonioxResponse>(&text) {
                             // Check for errors
                             if let Some(error_code) = response.error_code {
+                                let error_code = match error_code {
+                                    serde_json::Value::String(s) => s,
+                                    other => other.to_string(),
+                                };
                                 let error_msg = response.error_message.unwrap_or_default();
                                 log::error!("Soniox error: {} - {}", error_code, error_msg);
-                                app.emit("transcription-error", format!("{} - {}", error_code, error_msg)).ok();
+                                crate::console::record(&app, "error", format!("Soniox error: {} - {}", error_code, error_msg));
+                                let error = TranscriptionError::from_provider(&error_code, &error_msg);
+                                status::record_error(
+                                    &app,
+                                    &api_key,
+                                    &format!("Soniox error: {} - {}", error_code, error_msg),
+                                    matches!(error, TranscriptionError::Auth(_)),
+                                );
+                                app.emit("transcription-error", error).ok();
                                 break;
                             }
 
+                            if !credential_confirmed && response.tokens.is_some() {
+                                credential_confirmed = true;
+                                status::record_credential_valid(&app, &api_key);
+                            }
+
+                            if settings.debug_raw_tokens {
+                                let raw = RawTokens {
+                                    tokens: response.tokens.as_deref().unwrap_or_default(),
+                                    finished: response.finished.unwrap_or(false),
+                                    stream_offset_ms,
+                                };
+                                app.emit("soniox-raw", raw).ok();
+                            }
+
                             // Build full final text from all final tokens
                             let mut final_tokens: Vec<Token> = Vec::new();
                             let mut non_final_tokens: Vec<Token> = Vec::new();
 
+                            // Endpoint detection marks a finished utterance with a final <end> token
+                            let end_of_utterance = response
+                                .tokens
+                                .as_ref()
+                                .is_some_and(|tokens| tokens.iter().any(|t| t.is_final && t.text == "<end>"));
+
                             if let Some(tokens) = response.tokens {
-                                for token in tokens {
+                                for mut token in tokens {
+                                    token.start_ms = token.start_ms.map(|ms| ms + stream_offset_ms);
+                                    token.end_ms = token.end_ms.map(|ms| ms + stream_offset_ms);
+                                    // What was spoken, not what it was translated into
+                                    if token.is_final
+                                        && token.translation_status.as_deref() != Some("translation")
+                                        && !is_control_token(&token.text)
+                                    {
+                                        languages.add(&token);
+                                    }
+                                    // In translation mode only the translation is typed
+                                    if token.translation_status.as_deref() == Some("original") {
+                                        continue;
+                                    }
                                     if !token.text.is_empty() && !is_control_token(&token.text) {
                                         if token.is_final {
                                             final_tokens.push(token);
@@ -245,10 +801,21 @@ pub async fn connect_and_transcribe(
                                 }
                             }
 
+                            if let Some(detected) = languages.changed() {
+                                crate::console::record(&app, "language", detected.language.clone());
+                                app.emit("language-detected", detected).ok();
+                            }
+
+                            if let Some(end_ms) = final_tokens.iter().filter_map(|t| t.end_ms).max() {
+                                latency.lock().unwrap().record_final(end_ms);
+                            }
+                            append_word_timings(&mut word_timings, &final_tokens);
+
                             // Build the complete final text
-                            let current_final_text: String = final_tokens.iter()
-                                .map(|t| t.text.clone())
-                                .collect();
+                            let current_final_text = crate::text::join_tokens(
+                                final_tokens.iter().map(|t| (t.text.as_str(), t.language.as_deref())),
+                                default_language.as_deref(),
+                            );
 
                             // Check if we have new text to type
                             let text_to_type = if current_final_text.starts_with(&typed_text) {
@@ -266,55 +833,198 @@ pub async fn connect_and_transcribe(
                             if !text_to_type.is_empty() {
                                 eprintln!("DEBUG: New text to type: '{}' (total final: '{}')", text_to_type, current_final_text);
 
+                                let separated;
+                                let text_to_type = if std::mem::take(&mut separate_next) && !text_to_type.starts_with(char::is_whitespace) {
+                                    separated = format!(" {}", text_to_type);
+                                    separated.as_str()
+                                } else {
+                                    text_to_type
+                                };
+                                crate::console::record(&app, "token", text_to_type);
+
+                                // A replacement, pronunciation or number may continue in the next chunk
+                                let continuations = crate::text::pipeline::continuations(
+                                    settings.effective_text_processors(),
+                                    settings.pronunciations.iter().chain(vocabulary.pronunciations()),
+                                    default_language.as_deref(),
+                                );
+                                let text_to_type = held_tail.feed(text_to_type, end_of_utterance, &continuations);
+                                // "Spell that: K-A-V-A-K" may still be spelling
+                                let (text_to_type, spelled) = match vocabulary.feed(&text_to_type, end_of_utterance) {
+                                    Fed::Text(text) => (text, None),
+                                    Fed::Held => (String::new(), None),
+                                    Fed::Spelled(spelled) => (spelled.before, Some((spelled.word, spelled.after))),
+                                };
+                                let mut output = final_output(
+                                    &settings,
+                                    &vocabulary,
+                                    &text_to_type,
+                                    &accumulated_text,
+                                    default_language.as_deref(),
+                                    end_of_utterance,
+                                );
+
+                                if !duplicates.admit(&output) {
+                                    log::warn!("Suppressed repeated final text: '{}'", output);
+                                    crate::console::record(&app, "duplicate", output);
+                                    output = String::new();
+                                }
+
+                                if let Some((word, after)) = spelled {
+                                    if let Some(correction) = learn_spelled(&app, &mut vocabulary, &word, &mut accumulated_text, &mut output) {
+                                        if typing_tx.send(correction).is_err() {
+                                            log::error!("Typing worker channel closed");
+                                        }
+                                    }
+                                    let previous = format!("{}{}", accumulated_text, output);
+                                    output.push_str(&final_output(
+                                        &settings,
+                                        &vocabulary,
+                                        &after,
+                                        &previous,
+                                        default_language.as_deref(),
+                                        end_of_utterance,
+                                    ));
+                                }
+
                                 // Accumulate for history
-                                accumulated_text.push_str(text_to_type);
+                                accumulated_text.push_str(&output);
+                                crate::scripting::on_text(&output, &script_context);
 
                                 // Enqueue typing to the dedicated worker to avoid blocking the loop
-                                let ttt_for_emit = text_to_type.to_string();
-                                if typing_tx.send(text_to_type.to_string()).is_err() {
+                                let ttt_for_emit = output.clone();
+                                // Nothing to type still settles provisional text on screen
+                                if (!output.is_empty() || !typed_provisional.is_empty()) && typing_tx.send(Insertion::journaled_final(output, &target_window_id, review_mode)).is_err() {
                                     eprintln!("DEBUG ERROR: Typing worker channel closed");
                                     log::error!("Typing worker channel closed");
                                 }
 
                                 // Update tracking to full current text
                                 typed_text = current_final_text.clone();
+                                // The worker settled the provisional text with this insertion
+                                typed_provisional.clear();
 
                                 // Emit event with the newly typed text
                                 app.emit("transcribed-text", ttt_for_emit).ok();
                             }
 
                             // Show preview with all final tokens + non-final tokens
-                            let preview_non_final: String = non_final_tokens.iter()
-                                .map(|t| t.text.clone())
-                                .collect();
-                            let preview_text = format!("{}{}", current_final_text, preview_non_final);
+                            let preview_text = crate::text::join_tokens(
+                                final_tokens.iter()
+                                    .chain(non_final_tokens.iter())
+                                    .map(|t| (t.text.as_str(), t.language.as_deref())),
+                                default_language.as_deref(),
+                            );
 
                             if !preview_text.is_empty() {
-                                app.emit("partial-text", preview_text).ok();
+                                let now = Instant::now();
+                                match last_partial_emit.map(|t| t + partial_interval).filter(|due| *due > now) {
+                                    Some(due) => {
+                                        if pending_partial.is_none() {
+                                            partial_flush.as_mut().reset(due);
+                                        }
+                                        pending_partial = Some(preview_text);
+                                    }
+                                    None => {
+                                        app.emit("partial-text", preview_text).ok();
+                                        last_partial_emit = Some(now);
+                                        pending_partial = None;
+                                    }
+                                }
+                            }
+
+                            pending_non_final = crate::text::join_tokens(
+                                non_final_tokens.iter().map(|t| (t.text.as_str(), t.language.as_deref())),
+                                default_language.as_deref(),
+                            );
+                            if let Some(after) = non_final_flush_after {
+                                if pending_non_final.is_empty() {
+                                    non_final_flush_due = false;
+                                } else if !non_final_flush_due {
+                                    non_final_flush_due = true;
+                                    non_final_flush.as_mut().reset(Instant::now() + after);
+                                }
+                            }
+
+                            if settings.provisional_typing {
+                                let unconfirmed = format!("{}{}", held_tail.held(), pending_non_final);
+                                let mut tail = vocabulary.apply(&crate::text::pipeline::apply_pronunciations(&settings.pronunciations, &unconfirmed));
+                                if settings.plain_ascii_output {
+                                    tail = crate::text::to_plain_ascii(&tail);
+                                }
+                                if tail != typed_provisional {
+                                    typing_tx.send(Insertion::Provisional(tail.clone())).ok();
+                                    typed_provisional = tail;
+                                }
+                            }
+
+                            for token in final_tokens.iter().chain(non_final_tokens.iter()) {
+                                if let Some(start_ms) = token.start_ms {
+                                    first_word_ms = Some(first_word_ms.map_or(start_ms, |first| first.min(start_ms)));
+                                }
+                                last_word_ms = last_word_ms.max(token.end_ms.unwrap_or(0));
+                            }
+                            let words = accumulated_text.split_whitespace().count()
+                                + pending_non_final.split_whitespace().count();
+                            if words != last_stats_words {
+                                last_stats_words = words;
+                                let speaking_ms = first_word_ms.map_or(0, |first| last_word_ms.saturating_sub(first));
+                                // Too little audio for a meaningful rate
+                                let wpm = if speaking_ms >= 2000 {
+                                    words as f64 * 60_000.0 / speaking_ms as f64
+                                } else {
+                                    0.0
+                                };
+                                app.emit("session-stats", SessionStats { words, wpm, speaking_ms }).ok();
+                            }
+
+                            // At a pause if possible, mid-sentence if the speaker never pauses
+                            if let Some(after) = rollover_after.filter(|_| rollover_held.is_none() && !end_signal_sent) {
+                                let age = stream_started.elapsed();
+                                if age >= after && (end_of_utterance || pending_non_final.is_empty() || age >= after + ROLLOVER_GRACE) {
+                                    log::info!("Stream open for {:?}, rolling over to a new one", age);
+                                    ws_tx.send(Message::Text("".to_string())).ok();
+                                    rollover_held = Some(Vec::new());
+                                    finish_timeout.as_mut().reset(Instant::now() + finish_after);
+                                }
                             }
 
                             // Check if session is finished
                             if response.finished == Some(true) {
                                 eprintln!("DEBUG: Session finished flag received");
-                                log::info!("Session finished");
-                                session_finished = true;
+                                if rollover_held.is_some() {
+                                    rollover_ready = true;
+                                } else {
+                                    log::info!("Session finished");
+                                    session_finished = true;
+                                }
                             }
                         }
                     }
                     Some(Ok(Message::Close(_))) => {
                         eprintln!("DEBUG: WebSocket closed by server");
                         log::info!("WebSocket closed by server");
-                        is_transcribing = false;
+                        if rollover_held.is_some() {
+                            rollover_ready = true;
+                        } else {
+                            is_transcribing = false;
+                        }
                     }
                     Some(Err(e)) => {
                         eprintln!("DEBUG ERROR: WebSocket error: {}", e);
                         log::error!("WebSocket error: {}", e);
-                        app.emit("transcription-error", e.to_string()).ok();
+                        crate::console::record(&app, "error", format!("WebSocket error: {}", e));
+                        status::record_error(&app, &api_key, &format!("WebSocket error: {}", e), false);
+                        app.emit("transcription-error", TranscriptionError::Network(e.to_string())).ok();
                     }
                     None => {
                         eprintln!("DEBUG: WebSocket stream ended");
                         log::info!("WebSocket stream ended");
-                        is_transcribing = false;
+                        if rollover_held.is_some() {
+                            rollover_ready = true;
+                        } else {
+                            is_transcribing = false;
+                        }
                     }
                     _ => {}
                 }
@@ -322,12 +1032,88 @@ pub async fn connect_and_transcribe(
         }
     }
 
+    // The loop can end inside a throttle interval; show the last preview
+    if let Some(preview_text) = pending_partial.take() {
+        app.emit("partial-text", preview_text).ok();
+    }
+
+    // Text held back when the session ended: a phrase that never got
+    // completed is typed as heard, while a "spell that" still applies, as
+    // it would have at the end of the utterance that never came
+    let held = held_tail.flush();
+    let (held, spelled) = match vocabulary.feed(&held, true) {
+        Fed::Text(text) => (text, None),
+        Fed::Held => (vocabulary.flush(), None),
+        Fed::Spelled(spelled) => (spelled.before, Some((spelled.word, spelled.after))),
+    };
+    if !held.is_empty() || spelled.is_some() {
+        let language = default_language.as_deref();
+        let mut output = final_output(&settings, &vocabulary, &held, &accumulated_text, language, true);
+        if let Some((word, after)) = spelled {
+            if let Some(correction) = learn_spelled(&app, &mut vocabulary, &word, &mut accumulated_text, &mut output) {
+                typing_tx.send(correction).ok();
+            }
+            let previous = format!("{}{}", accumulated_text, output);
+            output.push_str(&final_output(&settings, &vocabulary, &after, &previous, language, true));
+        }
+        accumulated_text.push_str(&output);
+        typing_tx.send(Insertion::journaled_final(output, &target_window_id, review_mode)).ok();
+    }
+
+    // The comparison stream finishes on its own once its audio copy closes
+    drop(comparison_tx);
+    let comparison = match comparison_task {
+        Some(task) => match task.await {
+            Ok(Ok((transcript, _))) => Some(transcript),
+            Ok(Err(e)) => {
+                let e = format!("Comparison stream: {}", e);
+                log::warn!("{}", e);
+                crate::console::record(&app, "error", e);
+                None
+            }
+            Err(e) => {
+                log::warn!("Comparison task failed: {}", e);
+                None
+            }
+        },
+        None => None,
+    };
+
+    // Confirmed text only; the next session's context must not contain markers
+    if !accumulated_text.is_empty() {
+        *PREVIOUS_SESSION.lock().unwrap() = Some((Instant::now(), accumulated_text.clone()));
+    }
+
+    // Optionally keep the unfinalized tail of a cut-off session, clearly marked
+    let pending_tail = pending_non_final.trim();
+    if settings.include_partial_in_history && !pending_tail.is_empty() {
+        log::debug!("Keeping non-final tail in history ({} chars)", pending_tail.chars().count());
+        if !accumulated_text.is_empty() {
+            accumulated_text.push(' ');
+        }
+        accumulated_text.push_str(&format!("[unconfirmed: {}]", pending_tail));
+    }
+
     // Emit the complete accumulated text for history
     if !accumulated_text.is_empty() {
         eprintln!(
             "DEBUG: Emitting session-complete with {} chars",
             accumulated_text.len()
         );
+        crate::scripting::on_session_complete(&accumulated_text, &script_context);
+        // Stash the session for the history entry saved when capture ends
+        let state: tauri::State<'_, crate::AppState> = app.state();
+        *state.last_session_text.lock().unwrap() = Some(accumulated_text.clone());
+        if !word_timings.is_empty() {
+            *state.last_session_words.lock().unwrap() = Some(word_timings);
+        }
+        let detected = languages.languages();
+        if !detected.is_empty() {
+            *state.last_session_languages.lock().unwrap() = Some(detected);
+        }
+        if comparison.is_some() {
+            *state.last_session_comparison.lock().unwrap() = comparison;
+        }
         app.emit("session-complete", accumulated_text).ok();
     }
 
@@ -335,6 +1121,376 @@ pub async fn connect_and_transcribe(
     Ok(())
 }
 
+/// Queue one chunk of audio for the stream, µ-law encoded if `compressed`;
+/// returns its duration in ms.
+fn send_audio(
+    ws_tx: &mpsc::UnboundedSender<Message>,
+    latency: &Mutex<crate::latency::LatencyTracker>,
+    chunk: crate::audio::AudioChunk,
+    compressed: bool,
+) -> u64 {
+    // Stream time is counted in PCM bytes either way
+    let bytes = chunk.data.len();
+    let data = if compressed { crate::audio::mulaw::encode(&chunk.data) } else { chunk.data };
+    if let Err(e) = ws_tx.send(Message::Binary(data)) {
+        eprintln!("DEBUG ERROR: Failed to send audio: {}", e);
+    } else {
+        latency.lock().unwrap().record_send(chunk.captured_at, bytes);
+        crate::metrics::add_audio_bytes(bytes);
+    }
+    // 16 kHz mono s16le: 32 bytes per millisecond
+    bytes as u64 / 32
+}
+
+/// Forward queued messages to the socket until either side goes away.
+fn spawn_writer(mut ws_write: SplitSink<WsStream, Message>) -> mpsc::UnboundedSender<Message> {
+    let (ws_tx, mut ws_rx) = mpsc::unbounded_channel::<Message>();
+    tokio::spawn(async move {
+        while let Some(msg) = ws_rx.recv().await {
+            if let Err(e) = ws_write.send(msg).await {
+                eprintln!("DEBUG ERROR: WebSocket send failed: {}", e);
+                log::error!("WebSocket send failed: {}", e);
+                break;
+            }
+        }
+    });
+    ws_tx
+}
+
+/// Connect and send `config`, for a stream that continues a session.
+async fn open_stream(
+    app: &tauri::AppHandle,
+    endpoint: &Endpoint,
+    network: &crate::NetworkOverrides,
+    settings: &crate::AppSettings,
+    config: &SonioxConfig,
+) -> Result<(mpsc::UnboundedSender<Message>, SplitStream<WsStream>), TranscriptionError> {
+    let ws = connect_with_retries(Some(app), endpoint, network, settings).await?;
+    let (ws_write, ws_read) = ws.split();
+    let ws_tx = spawn_writer(ws_write);
+    let config_json = serde_json::to_string(config).map_err(|e| TranscriptionError::Provider(e.to_string()))?;
+    ws_tx
+        .send(Message::Text(config_json))
+        .map_err(|e| TranscriptionError::Network(format!("Failed to queue config: {}", e)))?;
+    Ok((ws_tx, ws_read))
+}
+
+/// Transcribe 16 kHz mono samples with the configured model, key and
+/// hints. The samples are all queued up front, so they go out as fast as
+/// the WebSocket accepts them rather than at real-time pace. Returns the
+/// text and its word timings.
+pub async fn transcribe_samples(
+    mut settings: crate::AppSettings,
+    samples: Vec<i16>,
+) -> Result<(String, Vec<crate::WordTiming>), String> {
+    // One second of audio per frame
+    const CHUNK_SAMPLES: usize = 16000;
+    // A file is scored on its own, not as a continuation of the last dictation
+    settings.context_carry_over = false;
+    let (tx, rx) = mpsc::unbounded_channel::<Vec<u8>>();
+    for chunk in samples.chunks(CHUNK_SAMPLES) {
+        tx.send(chunk.iter().flat_map(|s| s.to_le_bytes()).collect()).ok();
+    }
+    // Closing the channel ends the stream once everything is sent
+    drop(tx);
+    compare::run(settings, crate::ComparisonSettings::default(), rx)
+        .await
+        .map(|(transcript, words)| (transcript.text, words))
+}
+
+/// Why a connection attempt failed, and whether retrying can help.
+enum ConnectError {
+    /// DNS, TCP, TLS or timeout problems that are worth retrying
+    Transient(String),
+    /// The server answered but refused (bad request, auth, ...)
+    Fatal(TranscriptionError),
+}
+
+/// Where to connect: the URL for the WebSocket handshake, and the host and
+/// port used for DNS and TCP. Shared with the OpenAI provider, which also
+/// needs headers on the handshake.
+#[derive(Clone, PartialEq)]
+pub(crate) struct Endpoint {
+    pub(crate) url: String,
+    pub(crate) host: String,
+    pub(crate) port: u16,
+    /// Extra handshake headers, e.g. authorization
+    pub(crate) headers: Vec<(&'static str, String)>,
+}
+
+/// `soniox_endpoint` when set (any `ws://` or `wss://` URL of a
+/// Soniox-compatible server), otherwise the public API. An SNI override only
+/// applies to the public API, a custom URL already names its host.
+fn endpoint(settings: &crate::AppSettings, network: &crate::NetworkOverrides) -> Result<Endpoint, String> {
+    let custom = settings.soniox_endpoint.as_deref().map(str::trim).filter(|url| !url.is_empty());
+    let Some(url) = custom else {
+        // An SNI override replaces the hostname used for TLS and the Host header
+        let host = network.sni_host.clone().unwrap_or_else(|| SONIOX_WSS_HOST.to_string());
+        return Ok(Endpoint { url: format!("wss://{}/transcribe-websocket", host), host, port: 443, headers: Vec::new() });
+    };
+
+    let uri: tokio_tungstenite::tungstenite::http::Uri =
+        url.parse().map_err(|e| format!("Invalid Soniox endpoint '{}': {}", url, e))?;
+    let default_port = match uri.scheme_str() {
+        Some("wss") => 443,
+        Some("ws") => 80,
+        _ => return Err(format!("Soniox endpoint '{}' must start with wss:// or ws://", url)),
+    };
+    let host = uri
+        .host()
+        .ok_or_else(|| format!("Soniox endpoint '{}' has no host", url))?
+        .trim_matches(|c| c == '[' || c == ']')
+        .to_string();
+    Ok(Endpoint { url: url.to_string(), host, port: uri.port_u16().unwrap_or(default_port), headers: Vec::new() })
+}
+
+/// Progress of `connect_with_retries`, sent as `connection-retry` before
+/// each retry. A connect that succeeds after retrying is followed by
+/// `connection-recovered` with the attempt that made it.
+#[derive(Clone, Serialize)]
+pub struct ConnectionRetry {
+    /// The attempt about to be made, counting from 1
+    pub attempt: u32,
+    pub attempts: u32,
+    pub delay_ms: u64,
+    pub error: String,
+}
+
+/// Connect to `endpoint`, retrying transient failures `connect_retries`
+/// times with exponential backoff. Retries are reported to `app` if given.
+pub(crate) async fn connect_with_retries(
+    app: Option<&tauri::AppHandle>,
+    endpoint: &Endpoint,
+    network: &crate::NetworkOverrides,
+    settings: &crate::AppSettings,
+) -> Result<WsStream, TranscriptionError> {
+    let attempts = settings.connect_retries + 1;
+    let mut last_error = String::new();
+
+    for attempt in 1..=attempts {
+        match connect_once(endpoint, network, settings).await {
+            Ok(ws) => {
+                if let Some(app) = app.filter(|_| attempt > 1) {
+                    app.emit("connection-recovered", attempt).ok();
+                }
+                return Ok(ws);
+            }
+            Err(ConnectError::Fatal(e)) => return Err(e),
+            Err(ConnectError::Transient(e)) => {
+                log::warn!("Connect attempt {}/{} failed: {}", attempt, attempts, e);
+                if attempt < attempts {
+                    crate::metrics::inc_reconnects();
+                    let delay = RETRY_BASE_DELAY.saturating_mul(1u32 << (attempt - 1).min(16)).min(RETRY_MAX_DELAY);
+                    if let Some(app) = app {
+                        app.emit(
+                            "connection-retry",
+                            ConnectionRetry {
+                                attempt: attempt + 1,
+                                attempts,
+                                delay_ms: delay.as_millis() as u64,
+                                error: e.clone(),
+                            },
+                        )
+                        .ok();
+                    }
+                    tokio::time::sleep(delay).await;
+                }
+                last_error = e;
+            }
+        }
+    }
+
+    Err(TranscriptionError::Network(last_error))
+}
+
+/// Resolve the addresses to connect to, honouring the IP pin and IPv4-only
+/// network overrides.
+async fn resolve_addrs(host: &str, port: u16, network: &crate::NetworkOverrides) -> Result<Vec<SocketAddr>, ConnectError> {
+    if let Some(ip) = network.resolve_to.as_deref().filter(|ip| !ip.is_empty()) {
+        let ip: IpAddr = ip
+            .parse()
+            .map_err(|_| ConnectError::Fatal(TranscriptionError::Network(format!("Invalid IP override '{}'", ip))))?;
+        return Ok(vec![SocketAddr::new(ip, port)]);
+    }
+
+    let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, port))
+        .await
+        .map_err(|e| ConnectError::Transient(format!("DNS lookup for {} failed: {}", host, e)))?
+        .filter(|addr| !network.force_ipv4 || addr.is_ipv4())
+        .collect();
+
+    if addrs.is_empty() {
+        return Err(ConnectError::Transient(format!("No usable addresses for {}", host)));
+    }
+    Ok(addrs)
+}
+
+/// One connection attempt: TCP connect (incl. DNS) and TLS + WebSocket
+/// handshake, each bounded by its own configurable timeout.
+async fn connect_once(
+    endpoint: &Endpoint,
+    network: &crate::NetworkOverrides,
+    settings: &crate::AppSettings,
+) -> Result<WsStream, ConnectError> {
+    let connect_timeout = Duration::from_millis(settings.connect_timeout_ms);
+    let handshake_timeout = Duration::from_millis(settings.handshake_timeout_ms);
+
+    let tcp = tokio::time::timeout(connect_timeout, async {
+        let addrs = resolve_addrs(&endpoint.host, endpoint.port, network).await?;
+        TcpStream::connect(addrs.as_slice())
+            .await
+            .map_err(|e| ConnectError::Transient(format!("TCP connect failed: {}", e)))
+    })
+    .await
+    .map_err(|_| ConnectError::Transient(format!("TCP connect timed out after {:?}", connect_timeout)))??;
+    tcp.set_nodelay(true).ok();
+
+    let fatal = |message: String| ConnectError::Fatal(TranscriptionError::Provider(message));
+    let mut request = endpoint.url.as_str().into_client_request().map_err(|e| fatal(e.to_string()))?;
+    for (name, value) in &endpoint.headers {
+        let value = value.parse().map_err(|_| fatal(format!("The {} header contains invalid characters", name)))?;
+        request.headers_mut().insert(*name, value);
+    }
+
+    let (ws, _) = tokio::time::timeout(handshake_timeout, tokio_tungstenite::client_async_tls(request, tcp))
+        .await
+        .map_err(|_| ConnectError::Transient(format!("Handshake timed out after {:?}", handshake_timeout)))?
+        .map_err(|e| match e {
+            tokio_tungstenite::tungstenite::Error::Http(response) => ConnectError::Fatal(TranscriptionError::from_provider(
+                response.status().as_str(),
+                &format!("Server rejected connection: HTTP {}", response.status()),
+            )),
+            other => ConnectError::Transient(other.to_string()),
+        })?;
+
+    Ok(ws)
+}
+
+/// Round-trip measurements for one provider endpoint.
+#[derive(Debug, Clone, Serialize)]
+pub struct EndpointProbe {
+    pub endpoint: String,
+    /// DNS + TCP + TLS + WebSocket upgrade
+    pub connect_ms: Option<f64>,
+    /// WebSocket ping -> pong on the established connection
+    pub ping_ms: Option<f64>,
+    pub error: Option<String>,
+}
+
+/// Measure connection and round-trip latency to the provider endpoint(s).
+pub async fn probe_latency(settings: &crate::AppSettings) -> Vec<EndpointProbe> {
+    let network = settings.network_overrides.get(PROVIDER_ID).cloned().unwrap_or_default();
+    match endpoint(settings, &network) {
+        Ok(endpoint) => vec![probe_endpoint(endpoint.url).await],
+        Err(e) => vec![EndpointProbe {
+            endpoint: settings.soniox_endpoint.clone().unwrap_or_default(),
+            connect_ms: None,
+            ping_ms: None,
+            error: Some(e),
+        }],
+    }
+}
+
+async fn probe_endpoint(url: String) -> EndpointProbe {
+    let mut probe = EndpointProbe {
+        endpoint: url.clone(),
+        connect_ms: None,
+        ping_ms: None,
+        error: None,
+    };
+
+    let started = Instant::now();
+    let mut ws_stream = match tokio::time::timeout(PROBE_TIMEOUT, tokio_tungstenite::connect_async(&url)).await {
+        Ok(Ok((ws, _))) => ws,
+        Ok(Err(e)) => {
+            probe.error = Some(format!("WebSocket connection failed: {}", e));
+            return probe;
+        }
+        Err(_) => {
+            probe.error = Some("Connection timed out".to_string());
+            return probe;
+        }
+    };
+    probe.connect_ms = Some(started.elapsed().as_secs_f64() * 1000.0);
+
+    let ping_started = Instant::now();
+    if let Err(e) = ws_stream.send(Message::Ping(b"probe".to_vec())).await {
+        probe.error = Some(format!("Ping failed: {}", e));
+        return probe;
+    }
+
+    let pong = tokio::time::timeout(PROBE_TIMEOUT, async {
+        while let Some(msg) = ws_stream.next().await {
+            match msg {
+                Ok(Message::Pong(_)) => return Ok(()),
+                Ok(Message::Close(_)) => return Err("Connection closed before pong".to_string()),
+                Ok(_) => {}
+                Err(e) => return Err(e.to_string()),
+            }
+        }
+        Err("Connection ended before pong".to_string())
+    })
+    .await;
+
+    match pong {
+        Ok(Ok(())) => probe.ping_ms = Some(ping_started.elapsed().as_secs_f64() * 1000.0),
+        Ok(Err(e)) => probe.error = Some(e),
+        Err(_) => probe.error = Some("Ping timed out".to_string()),
+    }
+
+    ws_stream.close(None).await.ok();
+    log::info!(
+        "Latency probe {}: connect={:?}ms ping={:?}ms error={:?}",
+        probe.endpoint,
+        probe.connect_ms,
+        probe.ping_ms,
+        probe.error
+    );
+    probe
+}
+
+/// Group timestamped final tokens into words and append them to `words`.
+///
+/// Soniox marks word starts with a leading space; tokens without one
+/// continue the previous word. Tokens missing timestamps are skipped.
+fn append_word_timings(words: &mut Vec<crate::WordTiming>, tokens: &[Token]) {
+    // A new response never continues a word from the previous one
+    let mut continues_word = false;
+    for token in tokens {
+        let (Some(start_ms), Some(end_ms)) = (token.start_ms, token.end_ms) else {
+            continues_word = false;
+            continue;
+        };
+        let word = token.text.trim();
+        if word.is_empty() {
+            continues_word = false;
+            continue;
+        }
+        match words.last_mut() {
+            Some(last) if continues_word && !token.text.starts_with(char::is_whitespace) => {
+                last.word.push_str(word);
+                last.end_ms = end_ms;
+            }
+            // A word keeps the language of its first token
+            _ => words.push(crate::WordTiming {
+                word: word.to_string(),
+                start_ms,
+                end_ms,
+                language: token.language.clone().filter(|l| !l.is_empty()),
+            }),
+        }
+        continues_word = true;
+    }
+}
+
+/// The model the user picked, else the quality preset's, else the default.
+fn model(settings: &crate::AppSettings) -> &str {
+    match settings.soniox_model.trim() {
+        "" => settings.quality_preset.soniox_model().unwrap_or(DEFAULT_MODEL),
+        model => model,
+    }
+}
+
 /// Returns true if the token text is a Soniox control/special token
 /// like <end>, <laugh>, <noise>, etc. that should not be typed.
 fn is_control_token(text: &str) -> bool {
@@ -0,0 +1,70 @@
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter};
+
+/// Provider health shown in the main window status bar.
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct ProviderStatus {
+    /// Unix time (secs) of the last successful connection
+    pub last_connected_at: Option<u64>,
+    pub last_error: Option<String>,
+    /// Unix time (secs) of `last_error`
+    pub last_error_at: Option<u64>,
+    /// Whether Soniox accepted the current API key; None until a session
+    /// has used it
+    pub credential_valid: Option<bool>,
+}
+
+struct Tracked {
+    status: ProviderStatus,
+    /// Key `credential_valid` was determined for; a different key is unknown
+    checked_key: String,
+}
+
+static STATUS: Mutex<Tracked> = Mutex::new(Tracked {
+    status: ProviderStatus {
+        last_connected_at: None,
+        last_error: None,
+        last_error_at: None,
+        credential_valid: None,
+    },
+    checked_key: String::new(),
+});
+
+/// Status as seen with `api_key`, the key currently in effect.
+pub fn current(api_key: &str) -> ProviderStatus {
+    let tracked = STATUS.lock().unwrap();
+    let mut status = tracked.status.clone();
+    if tracked.checked_key != api_key {
+        status.credential_valid = None;
+    }
+    status
+}
+
+fn update(app: &AppHandle, api_key: &str, f: impl FnOnce(&mut Tracked)) {
+    f(&mut STATUS.lock().unwrap());
+    app.emit("provider-status", current(api_key)).ok();
+}
+
+pub fn record_connected(app: &AppHandle, api_key: &str) {
+    update(app, api_key, |t| t.status.last_connected_at = Some(crate::unix_now()));
+}
+
+/// Soniox answered with transcription results, so the key was accepted.
+pub fn record_credential_valid(app: &AppHandle, api_key: &str) {
+    update(app, api_key, |t| {
+        t.status.credential_valid = Some(true);
+        t.checked_key = api_key.to_string();
+    });
+}
+
+/// `auth_failure` marks the current key as rejected.
+pub fn record_error(app: &AppHandle, api_key: &str, error: &str, auth_failure: bool) {
+    update(app, api_key, |t| {
+        t.status.last_error = Some(error.to_string());
+        t.status.last_error_at = Some(crate::unix_now());
+        if auth_failure {
+            t.status.credential_valid = Some(false);
+            t.checked_key = api_key.to_string();
+        }
+    });
+}
@@ -0,0 +1,192 @@
+//! Warm connections: the WebSocket handshake done before audio is ready.
+//!
+//! DNS, TCP, TLS and the upgrade take from a few hundred ms to seconds,
+//! and the first words wait in the capture queue meanwhile. Two things
+//! hide that latency:
+//!
+//! - `prepare` starts connecting the moment the hotkey goes down, while
+//!   the microphone is still being opened; the session picks up the
+//!   result with `take` instead of connecting itself.
+//! - With `keep_connection_warm`, a connection is also opened at startup
+//!   and after every session and kept idle with WebSocket pings until the
+//!   next session. Soniox only starts a session with the config message,
+//!   so an idle connection is free. It is replaced after `MAX_IDLE_AGE`,
+//!   reopened after `REOPEN_DELAY` if the server closes it, and dropped
+//!   when the endpoint or network settings change.
+//!
+//! The API key travels in the config message, not the handshake, so
+//! switching credentials does not invalidate a warm connection.
+
+use super::{Endpoint, WsStream};
+use futures_util::{SinkExt, StreamExt};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use tokio::sync::oneshot;
+use tokio::time::{Duration, Instant};
+use tokio_tungstenite::tungstenite::Message;
+
+const PING_INTERVAL: Duration = Duration::from_secs(15);
+// Long-idle connections are replaced before middleboxes silently drop them
+const MAX_IDLE_AGE: Duration = Duration::from_secs(300);
+// Pause before reopening a connection the server closed
+const REOPEN_DELAY: Duration = Duration::from_secs(30);
+
+/// Where a connection goes; a session only takes a matching one.
+#[derive(Clone, PartialEq)]
+struct Key {
+    endpoint: Endpoint,
+    network: crate::NetworkOverrides,
+}
+
+type Handover = oneshot::Sender<Result<WsStream, crate::error::TranscriptionError>>;
+
+/// The connection being opened or held, and how to ask for it.
+struct Warm {
+    id: u64,
+    key: Key,
+    /// Asks the connection's task to hand its stream over
+    request: oneshot::Sender<Handover>,
+}
+
+static WARM: Mutex<Option<Warm>> = Mutex::new(None);
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Start connecting for a session about to begin, unless a connection to
+/// the same endpoint is already open or on its way.
+pub fn prepare(app: &AppHandle, settings: &crate::AppSettings) {
+    let network = settings.network_overrides.get(super::PROVIDER_ID).cloned().unwrap_or_default();
+    let endpoint = match super::endpoint(settings, &network) {
+        Ok(endpoint) => endpoint,
+        // The session reports the invalid endpoint itself
+        Err(_) => return,
+    };
+    let key = Key { endpoint, network };
+
+    let mut warm = WARM.lock().unwrap();
+    if warm.as_ref().is_some_and(|w| w.key == key && !w.request.is_closed()) {
+        return;
+    }
+    let id = NEXT_ID.fetch_add(1, Ordering::SeqCst);
+    let (request, requests) = oneshot::channel();
+    // Replacing the old entry drops its request sender, which closes that connection
+    *warm = Some(Warm { id, key: key.clone(), request });
+    tauri::async_runtime::spawn(hold(app.clone(), id, key, settings.clone(), requests));
+}
+
+/// Keep a connection open until the next session when
+/// `keep_connection_warm` is on; otherwise close any idle one.
+pub fn keep_warm(app: &AppHandle) {
+    let settings = app.state::<crate::AppState>().settings.lock().unwrap().clone();
+    if settings.keep_connection_warm && settings.provider == crate::TranscriptionProvider::Soniox {
+        prepare(app, &settings);
+    } else {
+        *WARM.lock().unwrap() = None;
+    }
+}
+
+/// The open or pending connection to `endpoint`, if there is one. None
+/// means the session should connect itself.
+pub(super) async fn take(endpoint: &Endpoint, network: &crate::NetworkOverrides) -> Option<WsStream> {
+    let warm = WARM.lock().unwrap().take()?;
+    if warm.key.endpoint != *endpoint || warm.key.network != *network {
+        return None;
+    }
+    let (handover, stream) = oneshot::channel();
+    warm.request.send(handover).ok()?;
+    match stream.await {
+        Ok(Ok(ws)) => Some(ws),
+        Ok(Err(e)) => {
+            log::warn!("Pre-connect failed: {}", e);
+            None
+        }
+        // The connection died while idle
+        Err(_) => None,
+    }
+}
+
+/// Why a held connection stopped idling.
+enum Idle {
+    /// A session asked for it
+    Requested(Handover),
+    /// The entry was replaced or cleared
+    Dropped,
+    /// Older than `MAX_IDLE_AGE`
+    Expired,
+    /// The server closed it or a ping failed
+    Closed,
+}
+
+/// Open a connection and hold it until a session asks for it, the entry
+/// is replaced or the connection ends.
+async fn hold(
+    app: AppHandle,
+    id: u64,
+    key: Key,
+    settings: crate::AppSettings,
+    mut requests: oneshot::Receiver<Handover>,
+) {
+    let connecting = super::connect_with_retries(None, &key.endpoint, &key.network, &settings);
+    tokio::pin!(connecting);
+    let early = tokio::select! {
+        result = &mut connecting => Ok(result),
+        request = &mut requests => Err(request),
+    };
+    let mut ws = match early {
+        Ok(Ok(ws)) => ws,
+        Ok(Err(e)) => {
+            // Dropping `requests` makes a later `take` connect afresh
+            log::warn!("Pre-connect to {} failed: {}", key.endpoint.url, e);
+            return;
+        }
+        // A session wants the connection before it is ready
+        Err(Ok(handover)) => {
+            handover.send(connecting.await).ok();
+            return;
+        }
+        Err(Err(_)) => return,
+    };
+    log::info!("Pre-connected to {}", key.endpoint.url);
+
+    let opened = Instant::now();
+    let mut ping = tokio::time::interval_at(Instant::now() + PING_INTERVAL, PING_INTERVAL);
+    let idle = loop {
+        tokio::select! {
+            request = &mut requests => break request.map_or(Idle::Dropped, Idle::Requested),
+            _ = ping.tick() => {
+                if opened.elapsed() >= MAX_IDLE_AGE {
+                    break Idle::Expired;
+                }
+                if ws.send(Message::Ping(Vec::new())).await.is_err() {
+                    break Idle::Closed;
+                }
+            }
+            message = ws.next() => match message {
+                Some(Ok(Message::Pong(_))) | Some(Ok(Message::Ping(_))) => {}
+                _ => break Idle::Closed,
+            },
+        }
+    };
+    // A `take` from now on must not wait for the reopen below
+    drop(requests);
+
+    match idle {
+        Idle::Requested(handover) => {
+            handover.send(Ok(ws)).ok();
+            return;
+        }
+        Idle::Dropped => return,
+        Idle::Expired => {
+            ws.close(None).await.ok();
+        }
+        Idle::Closed => {
+            log::info!("Warm connection closed by the server, reopening in {:?}", REOPEN_DELAY);
+            tokio::time::sleep(REOPEN_DELAY).await;
+        }
+    }
+    // Unless a session or a settings change moved on in the meantime
+    let current = WARM.lock().unwrap().as_ref().is_some_and(|w| w.id == id);
+    if current {
+        keep_warm(&app);
+    }
+}
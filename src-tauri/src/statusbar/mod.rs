@@ -0,0 +1,141 @@
+//! Recording state for external status bars (Waybar, Polybar, ...).
+//!
+//! The running app keeps a small JSON status file up to date; `--status`
+//! prints it once and `--status --follow` prints a line on every change, so
+//! a custom module can either poll or follow. The JSON uses Waybar's
+//! `text`/`tooltip`/`class` fields and adds the raw state for other bars.
+
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Listener};
+
+// Longest transcript snippet kept in the status
+const SNIPPET_CHARS: usize = 80;
+const FOLLOW_POLL: Duration = Duration::from_millis(250);
+
+#[derive(Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct Status {
+    /// Waybar label
+    pub text: String,
+    pub tooltip: String,
+    /// "recording", "idle" or "stopped" (app not running)
+    pub class: String,
+    pub recording: bool,
+    /// End of the most recent transcript
+    pub last_transcript: String,
+    pub updated_at: u64,
+}
+
+static CURRENT: Mutex<Option<Status>> = Mutex::new(None);
+
+/// `$XDG_RUNTIME_DIR/desktop-dictate-status.json`, or the temp dir where
+/// there is no runtime dir. Instances with their own `--config-dir` keep it
+/// there instead. Needs no app handle so the CLI can find it.
+pub fn status_path() -> PathBuf {
+    if let Some(dir) = crate::datadir::override_dir() {
+        return dir.join("status.json");
+    }
+    std::env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(std::env::temp_dir)
+        .join("desktop-dictate-status.json")
+}
+
+/// Write the idle status and keep it updated from session events.
+pub fn init(app: &AppHandle) {
+    update(|s| s.recording = false);
+
+    app.listen_any("recording-started", |_| update(|s| s.recording = true));
+    app.listen_any("recording-stopped", |_| update(|s| s.recording = false));
+    app.listen_any("session-complete", |event| {
+        if let Ok(text) = serde_json::from_str::<String>(event.payload()) {
+            update(|s| s.last_transcript = snippet(&text));
+        }
+    });
+}
+
+/// Mark the app as stopped on exit, so bars don't show a stale state.
+pub fn shutdown() {
+    let status = Status {
+        text: String::new(),
+        tooltip: crate::i18n::t("status-not-running"),
+        class: "stopped".to_string(),
+        updated_at: crate::unix_now(),
+        ..Default::default()
+    };
+    write(&status);
+}
+
+fn update(change: impl FnOnce(&mut Status)) {
+    let mut current = CURRENT.lock().unwrap();
+    let status = current.get_or_insert_with(Status::default);
+    change(status);
+    status.class = if status.recording { "recording" } else { "idle" }.to_string();
+    status.text = if status.recording { "REC" } else { "" }.to_string();
+    status.tooltip = match (status.recording, status.last_transcript.is_empty()) {
+        (true, _) => crate::i18n::t("status-dictating"),
+        (false, true) => crate::i18n::t("status-ready"),
+        (false, false) => crate::i18n::t_args("status-last", &[("text", status.last_transcript.clone())]),
+    };
+    status.updated_at = crate::unix_now();
+    write(status);
+}
+
+/// The last `SNIPPET_CHARS` characters of `text`, on one line.
+fn snippet(text: &str) -> String {
+    let flat = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let count = flat.chars().count();
+    if count <= SNIPPET_CHARS {
+        return flat;
+    }
+    let tail: String = flat.chars().skip(count - SNIPPET_CHARS).collect();
+    format!("...{}", tail)
+}
+
+/// Replace the status file atomically so readers never see half a write.
+fn write(status: &Status) {
+    let path = status_path();
+    let tmp = path.with_extension("json.tmp");
+    let result = serde_json::to_vec(status)
+        .map_err(|e| e.to_string())
+        .and_then(|json| std::fs::write(&tmp, json).map_err(|e| e.to_string()))
+        .and_then(|()| std::fs::rename(&tmp, &path).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        log::warn!("Failed to write status file {:?}: {}", path, e);
+    }
+}
+
+/// `--status [--follow]`: print the current status as one JSON line, and
+/// with `follow` keep printing a line whenever it changes. Returns the
+/// process exit code.
+pub fn print(follow: bool) -> i32 {
+    let read = || {
+        std::fs::read_to_string(status_path())
+            .ok()
+            .filter(|s| !s.trim().is_empty())
+            .unwrap_or_else(|| {
+                serde_json::json!({
+                    "text": "",
+                    "tooltip": crate::i18n::t("status-not-running"),
+                    "class": "stopped",
+                    "recording": false,
+                })
+                .to_string()
+            })
+    };
+
+    let mut last = read();
+    println!("{}", last.trim());
+    if !follow {
+        return 0;
+    }
+    loop {
+        std::thread::sleep(FOLLOW_POLL);
+        let current = read();
+        if current != last {
+            println!("{}", current.trim());
+            last = current;
+        }
+    }
+}
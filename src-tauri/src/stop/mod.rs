@@ -0,0 +1,40 @@
+//! Cancellation shared by everything a session runs.
+//!
+//! `stop` ends recording: provider loops await `cancelled()` in their
+//! `select!`, audio callbacks check `is_cancelled()` and the capture thread
+//! blocks in `wait`. The provider still delivers its final text. `cancel`,
+//! the parent of `stop`, also ends typing: queued text is dropped and a
+//! chunked insertion stops between pieces. Nothing has to poll a flag on
+//! its own schedule.
+
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+
+/// The tokens of one session; a new session gets new ones.
+#[derive(Clone)]
+pub struct SessionTokens {
+    pub stop: CancellationToken,
+    pub cancel: CancellationToken,
+}
+
+impl Default for SessionTokens {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SessionTokens {
+    pub fn new() -> Self {
+        let cancel = CancellationToken::new();
+        Self { stop: cancel.child_token(), cancel }
+    }
+}
+
+/// Block the calling thread until `token` is cancelled or `timeout`
+/// passes; true if cancelled. For threads outside the async runtime.
+pub fn wait(token: &CancellationToken, timeout: Duration) -> bool {
+    if token.is_cancelled() {
+        return true;
+    }
+    tauri::async_runtime::block_on(tokio::time::timeout(timeout, token.cancelled())).is_ok()
+}
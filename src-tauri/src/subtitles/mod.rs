@@ -0,0 +1,93 @@
+//! SRT and WebVTT subtitles from a session's word timings.
+//!
+//! Words are grouped into cues that end at sentence punctuation, at a pause
+//! longer than `MAX_GAP_MS`, or when a cue would get too long to read.
+//! Cue text is the recognized words, before text processing.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Srt,
+    Vtt,
+}
+
+impl Format {
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name.to_ascii_lowercase().as_str() {
+            "srt" => Ok(Self::Srt),
+            "vtt" => Ok(Self::Vtt),
+            other => Err(format!("Unknown subtitle format '{}' (expected srt or vtt)", other)),
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Srt => "srt",
+            Self::Vtt => "vtt",
+        }
+    }
+}
+
+// Cue limits, roughly the usual two-line subtitle guidelines
+const MAX_CUE_CHARS: usize = 84;
+const MAX_CUE_MS: u64 = 6000;
+const MAX_GAP_MS: u64 = 1000;
+
+struct Cue {
+    start_ms: u64,
+    end_ms: u64,
+    text: String,
+}
+
+fn cues(words: &[crate::WordTiming]) -> Vec<Cue> {
+    let mut cues: Vec<Cue> = Vec::new();
+    let mut open = false;
+    for word in words {
+        match cues.last_mut() {
+            Some(cue)
+                if open
+                    && word.start_ms.saturating_sub(cue.end_ms) <= MAX_GAP_MS
+                    && word.end_ms.saturating_sub(cue.start_ms) <= MAX_CUE_MS
+                    && cue.text.len() + 1 + word.word.len() <= MAX_CUE_CHARS =>
+            {
+                cue.text.push(' ');
+                cue.text.push_str(&word.word);
+                cue.end_ms = word.end_ms;
+            }
+            _ => cues.push(Cue { start_ms: word.start_ms, end_ms: word.end_ms, text: word.word.clone() }),
+        }
+        open = !word.word.ends_with(['.', '!', '?', '。', '？', '！']);
+    }
+    cues
+}
+
+fn timestamp(ms: u64, separator: char) -> String {
+    format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1000 % 60,
+        separator,
+        ms % 1000
+    )
+}
+
+/// Subtitle file contents for `words` in `format`.
+pub fn render(words: &[crate::WordTiming], format: Format) -> String {
+    let mut out = String::new();
+    if format == Format::Vtt {
+        out.push_str("WEBVTT\n\n");
+    }
+    let separator = if format == Format::Srt { ',' } else { '.' };
+    for (i, cue) in cues(words).iter().enumerate() {
+        if format == Format::Srt {
+            out.push_str(&format!("{}\n", i + 1));
+        }
+        out.push_str(&format!(
+            "{} --> {}\n{}\n\n",
+            timestamp(cue.start_ms, separator),
+            timestamp(cue.end_ms, separator),
+            cue.text
+        ));
+    }
+    out
+}
@@ -0,0 +1,117 @@
+//! Minimal Markdown to HTML conversion for rich-text paste.
+//!
+//! Covers what dictated text realistically contains: paragraphs, bullet and
+//! numbered lists, `**bold**`, `*italic*` and `` `code` ``. Anything else is
+//! passed through as escaped text.
+
+/// Whether `text` contains Markdown worth rendering.
+pub fn has_markup(text: &str) -> bool {
+    text.contains("**")
+        || text.contains('`')
+        || text.lines().any(|line| list_item(line).is_some())
+        || text.split_whitespace().any(|w| w.len() > 2 && w.starts_with('*') && w.ends_with('*'))
+}
+
+enum ListKind {
+    Bullet,
+    Numbered,
+}
+
+/// The list kind and item text if `line` is a list item.
+fn list_item(line: &str) -> Option<(ListKind, &str)> {
+    let line = line.trim_start();
+    if let Some(rest) = line.strip_prefix("- ").or_else(|| line.strip_prefix("* ")) {
+        return Some((ListKind::Bullet, rest));
+    }
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    if digits > 0 {
+        if let Some(rest) = line[digits..].strip_prefix(". ") {
+            return Some((ListKind::Numbered, rest));
+        }
+    }
+    None
+}
+
+/// Render `text` as an HTML fragment.
+pub fn to_html(text: &str) -> String {
+    let mut html = String::new();
+    let mut paragraph: Vec<String> = Vec::new();
+    let mut open_list: Option<&str> = None;
+
+    let flush_paragraph = |html: &mut String, paragraph: &mut Vec<String>| {
+        if !paragraph.is_empty() {
+            html.push_str(&format!("<p>{}</p>", paragraph.join("<br>")));
+            paragraph.clear();
+        }
+    };
+
+    for line in text.lines() {
+        match list_item(line) {
+            Some((kind, item)) => {
+                flush_paragraph(&mut html, &mut paragraph);
+                let tag = match kind {
+                    ListKind::Bullet => "ul",
+                    ListKind::Numbered => "ol",
+                };
+                if open_list != Some(tag) {
+                    if let Some(open) = open_list {
+                        html.push_str(&format!("</{}>", open));
+                    }
+                    html.push_str(&format!("<{}>", tag));
+                    open_list = Some(tag);
+                }
+                html.push_str(&format!("<li>{}</li>", inline(item)));
+            }
+            None => {
+                if let Some(open) = open_list.take() {
+                    html.push_str(&format!("</{}>", open));
+                }
+                if line.trim().is_empty() {
+                    flush_paragraph(&mut html, &mut paragraph);
+                } else {
+                    paragraph.push(inline(line.trim()));
+                }
+            }
+        }
+    }
+
+    if let Some(open) = open_list {
+        html.push_str(&format!("</{}>", open));
+    }
+    flush_paragraph(&mut html, &mut paragraph);
+    html
+}
+
+/// Escape and render inline emphasis and code spans.
+fn inline(text: &str) -> String {
+    let mut out = String::new();
+    let mut rest = text;
+    while !rest.is_empty() {
+        let span = [("**", "strong"), ("`", "code"), ("*", "em")]
+            .iter()
+            .find_map(|(marker, tag)| {
+                let inner = rest.strip_prefix(marker)?;
+                let end = inner.find(marker)?;
+                (end > 0).then(|| (*tag, &inner[..end], marker.len() * 2 + end))
+            });
+        match span {
+            Some((tag, inner, len)) => {
+                out.push_str(&format!("<{}>{}</{}>", tag, escape(inner), tag));
+                rest = &rest[len..];
+            }
+            None => {
+                let c = rest.chars().next().unwrap();
+                out.push_str(&escape(&c.to_string()));
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    out
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,131 @@
+pub mod markdown;
+mod numbers;
+pub mod pipeline;
+pub mod spelling;
+pub mod template;
+
+/// Languages that build words by chaining suffixes. Soniox emits sub-word
+/// tokens for these, and suffixes attached with an apostrophe, colon or
+/// hyphen (e.g. Turkish "İstanbul'da", Finnish "EU:n") can arrive split.
+const AGGLUTINATIVE_LANGUAGES: &[&str] = &["tr", "az", "kk", "fi", "et", "hu"];
+
+/// Languages written without spaces between words.
+const UNSPACED_LANGUAGES: &[&str] = &["zh", "ja", "th"];
+
+/// Characters that glue a suffix onto the preceding word.
+const SUFFIX_JOINERS: &[char] = &['\'', '’', ':', '-'];
+
+fn is_agglutinative(language: Option<&str>) -> bool {
+    language.is_some_and(|l| AGGLUTINATIVE_LANGUAGES.contains(&l))
+}
+
+fn is_unspaced(language: Option<&str>) -> bool {
+    language.is_some_and(|l| UNSPACED_LANGUAGES.contains(&l))
+}
+
+/// Join Soniox token texts into a single string.
+///
+/// Soniox marks word starts with a leading space, so most tokens can be
+/// concatenated as-is. On top of that this applies language-aware rules:
+/// - never produce double spaces at a token boundary
+/// - drop spaces between tokens of unspaced scripts (Chinese, Japanese, Thai)
+/// - for agglutinative languages, glue suffixes across apostrophes, colons
+///   and hyphens instead of leaving a space inside the word
+///
+/// `default_language` is used for tokens that carry no language tag.
+pub fn join_tokens<'a>(
+    tokens: impl IntoIterator<Item = (&'a str, Option<&'a str>)>,
+    default_language: Option<&str>,
+) -> String {
+    let mut out = String::new();
+    let mut prev_language: Option<&str> = None;
+
+    for (text, language) in tokens {
+        let language = language.or(default_language);
+        let mut piece = text;
+
+        if out.ends_with(char::is_whitespace) {
+            piece = piece.trim_start();
+        } else if piece.starts_with(char::is_whitespace) {
+            let trimmed = piece.trim_start();
+            let glue_suffix = is_agglutinative(language)
+                && (out.ends_with(SUFFIX_JOINERS) || trimmed.starts_with(SUFFIX_JOINERS))
+                && trimmed.starts_with(|c: char| c.is_alphanumeric() || SUFFIX_JOINERS.contains(&c));
+            let glue_unspaced = is_unspaced(language) && is_unspaced(prev_language);
+            if glue_suffix || glue_unspaced {
+                piece = trimmed;
+            }
+        }
+
+        out.push_str(piece);
+        prev_language = language;
+    }
+
+    out
+}
+
+/// Replace typographic characters with ASCII equivalents, for terminals and
+/// legacy apps that mangle them: curly quotes, dashes, ellipsis and
+/// non-breaking/narrow spaces.
+pub fn to_plain_ascii(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '“' | '”' | '„' | '«' | '»' => out.push('"'),
+            '‘' | '’' | '‚' | '′' => out.push('\''),
+            '–' | '‑' | '−' => out.push('-'),
+            '—' => out.push_str("--"),
+            '…' => out.push_str("..."),
+            '\u{00A0}' | '\u{202F}' | '\u{2009}' | '\u{2007}' => out.push(' '),
+            '\u{200B}' | '\u{FEFF}' => {}
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn joins_spaced_tokens_without_double_spaces() {
+        let tokens = [("Hello ", None), (" world", None), (".", None)];
+        assert_eq!(join_tokens(tokens, Some("en")), "Hello world.");
+    }
+
+    #[test]
+    fn glues_turkish_suffix_after_apostrophe() {
+        let tokens = [(" İstanbul", None), (" 'da", None), (" yaşıyorum", None)];
+        assert_eq!(join_tokens(tokens, Some("tr")), " İstanbul'da yaşıyorum");
+    }
+
+    #[test]
+    fn glues_finnish_suffix_after_colon() {
+        let tokens = [(" EU", Some("fi")), (":", Some("fi")), (" n", Some("fi")), (" jäsen", Some("fi"))];
+        assert_eq!(join_tokens(tokens, None), " EU:n jäsen");
+    }
+
+    #[test]
+    fn keeps_spaces_around_apostrophes_in_other_languages() {
+        let tokens = [(" rock", None), (" 'n'", None), (" roll", None)];
+        assert_eq!(join_tokens(tokens, Some("en")), " rock 'n' roll");
+    }
+
+    #[test]
+    fn token_language_overrides_default() {
+        let tokens = [(" Ankara", Some("tr")), (" 'ya", Some("tr")), (" and", Some("en")), (" 'em", Some("en"))];
+        assert_eq!(join_tokens(tokens, Some("en")), " Ankara'ya and 'em");
+    }
+
+    #[test]
+    fn drops_spaces_between_unspaced_tokens() {
+        let tokens = [("你好", Some("zh")), (" 世界", Some("zh")), (" OK", Some("en"))];
+        assert_eq!(join_tokens(tokens, None), "你好世界 OK");
+    }
+
+    #[test]
+    fn plain_ascii_replaces_typography() {
+        assert_eq!(to_plain_ascii("“Hi” — it’s…\u{00A0}ok"), "\"Hi\" -- it's... ok");
+    }
+}
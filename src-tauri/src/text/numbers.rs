@@ -0,0 +1,539 @@
+//! Spoken numbers to digits and back ("twenty three" ⇄ "23", "five
+//! percent" ⇄ "5%"), for the `numbers` text processor.
+//!
+//! Only languages with a lexicon here are converted (English and Spanish);
+//! text in any other language passes through unchanged. A lone number word
+//! below ten stays a word ("one of them"), following the usual style guide.
+
+// Digit runs longer than this are IDs or phone numbers, not quantities
+const MAX_DIGITS: usize = 12;
+
+struct Lexicon {
+    zero: &'static str,
+    /// Words worth 1-99, including fused compounds ("veintitrés")
+    small: &'static [(&'static str, u64)],
+    /// Words worth a whole number of hundreds ("doscientos")
+    hundreds: &'static [(&'static str, u64)],
+    /// Multiplies the preceding 1-99 by 100 ("two hundred")
+    hundred: Option<&'static str>,
+    /// Thousand, million, billion in their singular and plural spellings
+    scales: &'static [(&'static str, u64)],
+    /// Scales that mean one of themselves with no quantity before them
+    /// ("mil" is 1000); elsewhere "a thousand thanks" stays words
+    lone_scales: &'static [&'static str],
+    /// Word allowed between number words ("and", "y")
+    joiner: &'static str,
+    /// The joiner follows tens ("treinta y tres") rather than hundreds and
+    /// scales ("one hundred and five")
+    joiner_after_tens: bool,
+    /// Spoken forms of the percent sign
+    percent: &'static [&'static [&'static str]],
+    spell: fn(u64) -> String,
+}
+
+const ENGLISH: Lexicon = Lexicon {
+    zero: "zero",
+    small: &[
+        ("one", 1), ("two", 2), ("three", 3), ("four", 4), ("five", 5),
+        ("six", 6), ("seven", 7), ("eight", 8), ("nine", 9), ("ten", 10),
+        ("eleven", 11), ("twelve", 12), ("thirteen", 13), ("fourteen", 14),
+        ("fifteen", 15), ("sixteen", 16), ("seventeen", 17), ("eighteen", 18),
+        ("nineteen", 19), ("twenty", 20), ("thirty", 30), ("forty", 40),
+        ("fifty", 50), ("sixty", 60), ("seventy", 70), ("eighty", 80), ("ninety", 90),
+    ],
+    hundreds: &[],
+    hundred: Some("hundred"),
+    scales: &[("thousand", 1_000), ("million", 1_000_000), ("billion", 1_000_000_000)],
+    lone_scales: &[],
+    joiner: "and",
+    joiner_after_tens: false,
+    percent: &[&["percent"], &["per", "cent"]],
+    spell: english_words,
+};
+
+const SPANISH: Lexicon = Lexicon {
+    zero: "cero",
+    small: &[
+        ("un", 1), ("uno", 1), ("una", 1), ("dos", 2), ("tres", 3), ("cuatro", 4),
+        ("cinco", 5), ("seis", 6), ("siete", 7), ("ocho", 8), ("nueve", 9),
+        ("diez", 10), ("once", 11), ("doce", 12), ("trece", 13), ("catorce", 14),
+        ("quince", 15), ("dieciséis", 16), ("diecisiete", 17), ("dieciocho", 18),
+        ("diecinueve", 19), ("veinte", 20), ("veintiún", 21), ("veintiuno", 21),
+        ("veintiuna", 21), ("veintidós", 22), ("veintitrés", 23), ("veinticuatro", 24),
+        ("veinticinco", 25), ("veintiséis", 26), ("veintisiete", 27), ("veintiocho", 28),
+        ("veintinueve", 29), ("treinta", 30), ("cuarenta", 40), ("cincuenta", 50),
+        ("sesenta", 60), ("setenta", 70), ("ochenta", 80), ("noventa", 90),
+    ],
+    hundreds: &[
+        ("cien", 100), ("ciento", 100), ("doscientos", 200), ("doscientas", 200),
+        ("trescientos", 300), ("trescientas", 300), ("cuatrocientos", 400),
+        ("cuatrocientas", 400), ("quinientos", 500), ("quinientas", 500),
+        ("seiscientos", 600), ("seiscientas", 600), ("setecientos", 700),
+        ("setecientas", 700), ("ochocientos", 800), ("ochocientas", 800),
+        ("novecientos", 900), ("novecientas", 900),
+    ],
+    hundred: None,
+    scales: &[
+        ("mil", 1_000),
+        ("millón", 1_000_000),
+        ("millones", 1_000_000),
+        ("mil millones", 1_000_000_000),
+    ],
+    lone_scales: &["mil", "mil millones"],
+    joiner: "y",
+    joiner_after_tens: true,
+    percent: &[&["por", "ciento"]],
+    spell: spanish_words,
+};
+
+impl Lexicon {
+    /// Whether `word` can be part of a number, joiners aside.
+    fn is_number_word(&self, word: &str) -> bool {
+        word == self.zero
+            || self.small.iter().any(|(w, _)| *w == word)
+            || self.hundreds.iter().any(|(w, _)| *w == word)
+            || self.hundred == Some(word)
+            || self.scales.iter().any(|(w, _)| w.split(' ').any(|part| part == word))
+    }
+}
+
+fn lexicon(language: Option<&str>) -> Option<&'static Lexicon> {
+    match language {
+        None | Some("en") => Some(&ENGLISH),
+        Some("es") => Some(&SPANISH),
+        _ => None,
+    }
+}
+
+/// Where a spoken number ending `text` starts, if one does. A stream may
+/// finalize "twenty" and "three" in separate chunks, so the caller holds
+/// this part back until it sees what follows.
+pub fn trailing_number_start(text: &str, language: Option<&str>) -> Option<usize> {
+    let lexicon = lexicon(language)?;
+    let words = words(text);
+    let &(_, last_end) = words.last()?;
+    if !text[last_end..].trim().is_empty() {
+        return None;
+    }
+
+    let mut start = None;
+    for (i, &(from, to)) in words.iter().enumerate().rev() {
+        if i + 1 < words.len() && !adjacent(text, words[i], words[i + 1]) {
+            break;
+        }
+        let word = text[from..to].to_lowercase();
+        if lexicon.is_number_word(&word) {
+            start = Some(from);
+        } else if word != lexicon.joiner && !lexicon.percent.iter().any(|p| p.contains(&word.as_str())) {
+            break;
+        }
+    }
+    start
+}
+
+/// Replace spoken numbers with digits.
+pub fn to_digits(text: &str, language: Option<&str>) -> String {
+    let Some(lexicon) = lexicon(language) else {
+        return text.to_string();
+    };
+
+    let words = words(text);
+    let mut out = String::with_capacity(text.len());
+    let mut copied = 0;
+    let mut i = 0;
+    while i < words.len() {
+        let Some((value, used)) = parse(text, &words[i..], lexicon) else {
+            i += 1;
+            continue;
+        };
+        let mut end = words[i + used - 1].1;
+        let mut digits = value.to_string();
+        let rest = &words[i + used..];
+        out.push_str(&text[copied..words[i].0]);
+        i += used;
+        if let Some(phrase) = lexicon.percent.iter().find(|p| phrase_at(text, end, rest, p)) {
+            digits.push('%');
+            end = rest[phrase.len() - 1].1;
+            i += phrase.len();
+        }
+        out.push_str(&digits);
+        copied = end;
+    }
+    out.push_str(&text[copied..]);
+    out
+}
+
+/// Replace standalone whole numbers with words. Decimals, grouped numbers
+/// ("1,000"), versions and numbers attached to letters are left alone.
+pub fn to_words(text: &str, language: Option<&str>) -> String {
+    let Some(lexicon) = lexicon(language) else {
+        return text.to_string();
+    };
+
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let mut out = String::with_capacity(text.len());
+    let mut copied = 0;
+    let mut i = 0;
+    while i < chars.len() {
+        if !chars[i].1.is_ascii_digit() || (i > 0 && attached(&chars, i - 1, i.checked_sub(2))) {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < chars.len() && chars[i].1.is_ascii_digit() {
+            i += 1;
+        }
+        let len = i - start;
+        if i < chars.len() && attached(&chars, i, Some(i + 1)) {
+            continue;
+        }
+        if len > MAX_DIGITS || (len > 1 && chars[start].1 == '0') {
+            continue;
+        }
+
+        let byte_start = chars[start].0;
+        let mut byte_end = chars.get(i).map_or(text.len(), |c| c.0);
+        let Ok(value) = text[byte_start..byte_end].parse::<u64>() else {
+            continue;
+        };
+        let mut words = (lexicon.spell)(value);
+        if chars.get(i).is_some_and(|c| c.1 == '%') {
+            words.push(' ');
+            words.push_str(&lexicon.percent[0].join(" "));
+            byte_end += '%'.len_utf8();
+            i += 1;
+        }
+        out.push_str(&text[copied..byte_start]);
+        out.push_str(&words);
+        copied = byte_end;
+    }
+    out.push_str(&text[copied..]);
+    out
+}
+
+/// Whether the character at `at` ties a digit run to its surroundings:
+/// a letter or digit, or a separator with a digit on its other side
+/// ("3.5", "1,000", "10:30"). `beyond` is the index past the separator.
+fn attached(chars: &[(usize, char)], at: usize, beyond: Option<usize>) -> bool {
+    let c = chars[at].1;
+    if c.is_alphanumeric() {
+        return true;
+    }
+    matches!(c, '.' | ',' | ':' | '/')
+        && beyond
+            .and_then(|b| chars.get(b))
+            .is_some_and(|(_, d)| d.is_ascii_digit())
+}
+
+/// Byte ranges of the alphabetic words in `text`, skipping words glued to
+/// digits ("mp3", "4k").
+fn words(text: &str) -> Vec<(usize, usize)> {
+    let mut out = Vec::new();
+    let mut start: Option<usize> = None;
+    let mut prev: Option<char> = None;
+    for (i, c) in text.char_indices().chain(std::iter::once((text.len(), ' '))) {
+        match (c.is_alphabetic(), start) {
+            (true, None) => {
+                start = Some(i);
+                if prev.is_some_and(|p| p.is_ascii_digit()) {
+                    start = Some(usize::MAX);
+                }
+            }
+            (false, Some(s)) => {
+                if s != usize::MAX && !c.is_ascii_digit() {
+                    out.push((s, i));
+                }
+                start = None;
+            }
+            _ => {}
+        }
+        prev = Some(c);
+    }
+    out
+}
+
+/// Words of one number may be separated by spaces or a single hyphen
+/// ("twenty-three").
+fn adjacent(text: &str, before: (usize, usize), after: (usize, usize)) -> bool {
+    let gap = text[before.1..after.0].trim_matches(' ');
+    (gap.is_empty() && before.1 < after.0) || gap == "-"
+}
+
+fn phrase_at(text: &str, end: usize, rest: &[(usize, usize)], phrase: &[&str]) -> bool {
+    let mut prev = (end, end);
+    phrase.len() <= rest.len()
+        && phrase.iter().zip(rest).all(|(expected, &word)| {
+            let matches = text[prev.1..word.0].trim_matches(' ').is_empty()
+                && prev.1 < word.0
+                && text[word.0..word.1].to_lowercase() == *expected;
+            prev = word;
+            matches
+        })
+}
+
+#[derive(Clone, Copy)]
+struct State {
+    total: u64,
+    current: u64,
+    last_scale: u64,
+    words: usize,
+    zero: bool,
+}
+
+impl State {
+    /// Take `word` into the number if it can continue it.
+    fn step(&mut self, word: &str, lexicon: &Lexicon) -> bool {
+        if self.zero {
+            return false;
+        }
+        if word == lexicon.zero {
+            if self.words > 0 {
+                return false;
+            }
+            self.zero = true;
+        } else if let Some(&(_, v)) = lexicon.small.iter().find(|(w, _)| *w == word) {
+            let after_tens = self.current % 100 >= 20 && self.current % 10 == 0 && v < 10;
+            if self.current % 100 != 0 && !after_tens {
+                return false;
+            }
+            self.current += v;
+        } else if let Some(&(_, v)) = lexicon.hundreds.iter().find(|(w, _)| *w == word) {
+            if self.current != 0 {
+                return false;
+            }
+            self.current = v;
+        } else if lexicon.hundred == Some(word) {
+            if !(1..100).contains(&self.current) {
+                return false;
+            }
+            self.current *= 100;
+        } else if let Some(&(_, scale)) = lexicon.scales.iter().find(|(w, _)| *w == word) {
+            let lone = self.words == 0 && lexicon.lone_scales.contains(&word);
+            if scale >= self.last_scale || (self.current == 0 && !lone) {
+                return false;
+            }
+            self.total += self.current.max(1) * scale;
+            self.current = 0;
+            self.last_scale = scale;
+        } else {
+            return false;
+        }
+        self.words += 1;
+        true
+    }
+
+    fn joiner_allowed(&self, lexicon: &Lexicon) -> bool {
+        if lexicon.joiner_after_tens {
+            self.current % 100 >= 30 && self.current % 10 == 0
+        } else {
+            self.words > 0 && self.current % 100 == 0
+        }
+    }
+}
+
+/// The longest number spelled out at the start of `words`, with how many
+/// words it used.
+fn parse(text: &str, words: &[(usize, usize)], lexicon: &Lexicon) -> Option<(u64, usize)> {
+    let mut state = State { total: 0, current: 0, last_scale: u64::MAX, words: 0, zero: false };
+    let mut used = 0;
+    while used < words.len() {
+        if used > 0 && !adjacent(text, words[used - 1], words[used]) {
+            break;
+        }
+        let word = text[words[used].0..words[used].1].to_lowercase();
+
+        // "mil millones" is one scale made of two words
+        if used + 1 < words.len() && adjacent(text, words[used], words[used + 1]) {
+            let pair = format!("{} {}", word, text[words[used + 1].0..words[used + 1].1].to_lowercase());
+            if lexicon.scales.iter().any(|(w, _)| *w == pair) && state.step(&pair, lexicon) {
+                used += 2;
+                continue;
+            }
+        }
+
+        if word == lexicon.joiner && state.joiner_allowed(lexicon) {
+            let mut ahead = state;
+            let next = words.get(used + 1).filter(|&&next| adjacent(text, words[used], next));
+            match next {
+                Some(next) if ahead.step(&text[next.0..next.1].to_lowercase(), lexicon) => {
+                    state = ahead;
+                    used += 2;
+                    continue;
+                }
+                _ => break,
+            }
+        }
+
+        if !state.step(&word, lexicon) {
+            break;
+        }
+        used += 1;
+    }
+
+    let value = state.total + state.current;
+    if state.words == 0 || (state.words == 1 && value < 10) {
+        return None;
+    }
+    Some((value, used))
+}
+
+fn english_words(n: u64) -> String {
+    const ONES: [&str; 20] = [
+        "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten",
+        "eleven", "twelve", "thirteen", "fourteen", "fifteen", "sixteen", "seventeen",
+        "eighteen", "nineteen",
+    ];
+    const TENS: [&str; 10] = [
+        "", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+    ];
+
+    fn below_thousand(n: u64) -> String {
+        let mut parts = Vec::new();
+        if n >= 100 {
+            parts.push(format!("{} hundred", ONES[(n / 100) as usize]));
+        }
+        let rest = n % 100;
+        if rest >= 20 {
+            let tens = TENS[(rest / 10) as usize];
+            parts.push(match rest % 10 {
+                0 => tens.to_string(),
+                unit => format!("{}-{}", tens, ONES[unit as usize]),
+            });
+        } else if rest > 0 || parts.is_empty() {
+            parts.push(ONES[rest as usize].to_string());
+        }
+        parts.join(" ")
+    }
+
+    let mut parts = Vec::new();
+    for (scale, name) in [(1_000_000_000, "billion"), (1_000_000, "million"), (1_000, "thousand")] {
+        if n >= scale && (n / scale) % 1000 > 0 {
+            parts.push(format!("{} {}", below_thousand((n / scale) % 1000), name));
+        }
+    }
+    if n % 1000 > 0 || parts.is_empty() {
+        parts.push(below_thousand(n % 1000));
+    }
+    parts.join(" ")
+}
+
+fn spanish_words(n: u64) -> String {
+    const ONES: [&str; 30] = [
+        "cero", "uno", "dos", "tres", "cuatro", "cinco", "seis", "siete", "ocho", "nueve",
+        "diez", "once", "doce", "trece", "catorce", "quince", "dieciséis", "diecisiete",
+        "dieciocho", "diecinueve", "veinte", "veintiuno", "veintidós", "veintitrés",
+        "veinticuatro", "veinticinco", "veintiséis", "veintisiete", "veintiocho", "veintinueve",
+    ];
+    const TENS: [&str; 10] = [
+        "", "", "", "treinta", "cuarenta", "cincuenta", "sesenta", "setenta", "ochenta", "noventa",
+    ];
+    const HUNDREDS: [&str; 10] = [
+        "", "ciento", "doscientos", "trescientos", "cuatrocientos", "quinientos", "seiscientos",
+        "setecientos", "ochocientos", "novecientos",
+    ];
+
+    fn below_thousand(n: u64) -> String {
+        if n == 100 {
+            return "cien".to_string();
+        }
+        let mut parts = Vec::new();
+        if n >= 100 {
+            parts.push(HUNDREDS[(n / 100) as usize].to_string());
+        }
+        let rest = n % 100;
+        if rest >= 30 {
+            let tens = TENS[(rest / 10) as usize];
+            parts.push(match rest % 10 {
+                0 => tens.to_string(),
+                unit => format!("{} y {}", tens, ONES[unit as usize]),
+            });
+        } else if rest > 0 || parts.is_empty() {
+            parts.push(ONES[rest as usize].to_string());
+        }
+        parts.join(" ")
+    }
+
+    // "uno" shortens before a noun: "veintiún mil", "un millón"
+    fn before_scale(n: u64) -> String {
+        let words = below_thousand(n);
+        match words.strip_suffix("veintiuno") {
+            Some(head) => format!("{}veintiún", head),
+            None => match words.strip_suffix("uno") {
+                Some(head) => format!("{}un", head),
+                None => words,
+            },
+        }
+    }
+
+    let millions = n / 1_000_000;
+    let thousands = (n / 1_000) % 1_000;
+    let mut parts = Vec::new();
+    match millions {
+        0 => {}
+        1 => parts.push("un millón".to_string()),
+        // At most MAX_DIGITS digits, so below a million millions
+        m => {
+            let high = m / 1_000;
+            let low = m % 1_000;
+            let mut words = Vec::new();
+            match high {
+                0 => {}
+                1 => words.push("mil".to_string()),
+                h => words.push(format!("{} mil", before_scale(h))),
+            }
+            if low > 0 {
+                words.push(before_scale(low));
+            }
+            parts.push(format!("{} millones", words.join(" ")));
+        }
+    }
+    match thousands {
+        0 => {}
+        1 => parts.push("mil".to_string()),
+        t => parts.push(format!("{} mil", before_scale(t))),
+    }
+    if n % 1000 > 0 || parts.is_empty() {
+        parts.push(below_thousand(n % 1000));
+    }
+    parts.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spoken_numbers_become_digits() {
+        assert_eq!(to_digits("I owe twenty three dollars", None), "I owe 23 dollars");
+        assert_eq!(to_digits("two hundred and five people", Some("en")), "205 people");
+        assert_eq!(to_digits("up fifteen percent", Some("en")), "up 15%");
+        assert_eq!(to_digits("one of them", Some("en")), "one of them");
+        assert_eq!(to_digits("treinta y tres años", Some("es")), "33 años");
+        assert_eq!(to_digits("mil quinientos euros", Some("es")), "1500 euros");
+        assert_eq!(to_digits("vingt-trois", Some("fr")), "vingt-trois");
+    }
+
+    #[test]
+    fn a_scale_needs_a_quantity_before_it() {
+        assert_eq!(to_digits("a thousand thanks", Some("en")), "a thousand thanks");
+        assert_eq!(to_digits("two thousand thanks", Some("en")), "2000 thanks");
+        assert_eq!(to_digits("one million", Some("en")), "1000000");
+    }
+
+    #[test]
+    fn digits_become_words() {
+        assert_eq!(to_words("I owe 23 dollars", Some("en")), "I owe twenty-three dollars");
+        assert_eq!(to_words("up 5%", Some("en")), "up five percent");
+        assert_eq!(to_words("version 3.5 and 1,000 and mp3", Some("en")), "version 3.5 and 1,000 and mp3");
+        assert_eq!(to_words("21000", Some("es")), "veintiún mil");
+    }
+
+    #[test]
+    fn finds_a_number_that_may_continue() {
+        assert_eq!(trailing_number_start("I owe twenty", None), Some(6));
+        assert_eq!(trailing_number_start("I owe twenty and ", None), Some(6));
+        assert_eq!(trailing_number_start("I owe twenty.", None), None);
+        assert_eq!(trailing_number_start("rock and", None), None);
+        assert_eq!(trailing_number_start("twenty dollars", None), None);
+    }
+}
@@ -0,0 +1,522 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// One step of the text post-processing pipeline, as stored in settings.
+/// Processors run in list order on every chunk of finalized text.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessorConfig {
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(flatten)]
+    pub processor: Processor,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Processor {
+    /// Spoken punctuation and layout ("comma", "new line", ...)
+    VoiceCommands,
+    /// Whole-word, case-insensitive find/replace rules
+    Replacements { rules: Vec<Replacement> },
+    /// Force the case of the output
+    Casing { mode: CasingMode },
+    /// Mask sensitive words
+    Redaction {
+        words: Vec<String>,
+        #[serde(default = "default_mask")]
+        mask: String,
+    },
+    /// User WASM module from the plugins directory (file name)
+    Wasm { plugin: String },
+    /// Punctuation house style on top of what the provider produces
+    Punctuation {
+        /// Capitalize the first letter of each sentence
+        #[serde(default)]
+        sentence_case: bool,
+        /// End each utterance with a period if it has no final punctuation
+        #[serde(default)]
+        trailing_period: bool,
+        #[serde(default)]
+        quotes: QuoteStyle,
+        #[serde(default)]
+        ellipsis: EllipsisStyle,
+    },
+    /// Spoken numbers as digits or digits as words
+    Numbers {
+        #[serde(default)]
+        style: NumberStyle,
+        /// Per-language override of `style`, keyed by language code
+        #[serde(default)]
+        languages: BTreeMap<String, NumberStyle>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NumberStyle {
+    /// "twenty three percent" becomes "23%"
+    #[default]
+    Digits,
+    /// "23%" becomes "twenty-three percent"
+    Words,
+    Keep,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuoteStyle {
+    /// Leave quotes as the provider sent them
+    #[default]
+    Keep,
+    /// " and '
+    Straight,
+    /// “ ” and ‘ ’
+    Smart,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EllipsisStyle {
+    #[default]
+    Keep,
+    /// Three periods: ...
+    Dots,
+    /// Single character: …
+    Character,
+}
+
+fn default_mask() -> String {
+    "***".to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Replacement {
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CasingMode {
+    Lower,
+    Upper,
+    /// Capitalize the first letter of each sentence
+    Sentence,
+}
+
+/// A word the provider keeps misrecognizing, with the spellings it tends
+/// to produce instead ("Kavak" sounds like "cavac", "kah vahk").
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Pronunciation {
+    pub word: String,
+    #[serde(default)]
+    pub sounds_like: Vec<String>,
+}
+
+/// What a processor can know about the session besides the current chunk.
+pub struct ProcessContext<'a> {
+    /// Text already output earlier in this session
+    pub previous: &'a str,
+    pub language: Option<&'a str>,
+    /// The provider marked the end of an utterance after this chunk
+    pub end_of_utterance: bool,
+}
+
+// (spoken phrase, output); English only for now
+const VOICE_COMMANDS: &[(&str, &str)] = &[
+    ("new paragraph", "\n\n"),
+    ("new line", "\n"),
+    ("full stop", "."),
+    ("period", "."),
+    ("comma", ","),
+    ("question mark", "?"),
+    ("exclamation mark", "!"),
+    ("colon", ":"),
+    ("semicolon", ";"),
+];
+
+/// Run `text` through every enabled processor in order.
+pub fn apply(processors: &[ProcessorConfig], text: &str, ctx: &ProcessContext) -> String {
+    let mut out = text.to_string();
+    for config in processors.iter().filter(|p| p.enabled) {
+        out = match &config.processor {
+            Processor::VoiceCommands => voice_commands(&out, ctx),
+            Processor::Replacements { rules } => rules
+                .iter()
+                .fold(out, |acc, r| replace_phrase(&acc, &r.from, &r.to)),
+            Processor::Casing { mode } => casing(&out, *mode, ctx),
+            Processor::Redaction { words, mask } => words
+                .iter()
+                .fold(out, |acc, w| replace_phrase(&acc, w, mask)),
+            Processor::Wasm { plugin } => crate::plugins::process(plugin, &out, ctx),
+            Processor::Punctuation { sentence_case, trailing_period, quotes, ellipsis } => {
+                punctuation(&out, *sentence_case, *trailing_period, *quotes, *ellipsis, ctx)
+            }
+            Processor::Numbers { style, languages } => {
+                let style = ctx.language.and_then(|l| languages.get(l)).unwrap_or(style);
+                match style {
+                    NumberStyle::Digits => super::numbers::to_digits(&out, ctx.language),
+                    NumberStyle::Words => super::numbers::to_words(&out, ctx.language),
+                    NumberStyle::Keep => out,
+                }
+            }
+        };
+    }
+    out
+}
+
+/// Final text a streaming provider may finalize across several chunks:
+/// phrases the processors and pronunciations match on, and spoken numbers
+/// when they are written as digits.
+#[derive(Default)]
+pub struct Continuations {
+    pub phrases: Vec<String>,
+    /// Language of the spoken numbers to hold, if numbers become digits
+    pub numbers: Option<Option<String>>,
+}
+
+pub fn continuations<'a>(
+    processors: &[ProcessorConfig],
+    pronunciations: impl IntoIterator<Item = &'a Pronunciation>,
+    language: Option<&str>,
+) -> Continuations {
+    let mut phrases: Vec<String> = pronunciations
+        .into_iter()
+        .flat_map(|p| p.sounds_like.iter().cloned())
+        .collect();
+    let mut numbers = None;
+    for config in processors.iter().filter(|p| p.enabled) {
+        match &config.processor {
+            Processor::VoiceCommands => phrases.extend(VOICE_COMMANDS.iter().map(|(phrase, _)| phrase.to_string())),
+            Processor::Replacements { rules } => phrases.extend(rules.iter().map(|r| r.from.clone())),
+            Processor::Redaction { words, .. } => phrases.extend(words.iter().cloned()),
+            Processor::Numbers { style, languages } => {
+                let style = language.and_then(|l| languages.get(l)).unwrap_or(style);
+                if *style == NumberStyle::Digits {
+                    numbers = Some(language.map(str::to_string));
+                }
+            }
+            _ => {}
+        }
+    }
+    Continuations { phrases, numbers }
+}
+
+/// Final text held back from the end of a chunk because the next chunk may
+/// complete it: "kah" when a rule matches "kah vahk", "cav" of "cavac"
+/// split into sub-word tokens, or "twenty" of "twenty three". Released at
+/// the end of an utterance.
+#[derive(Default)]
+pub struct HeldTail {
+    held: String,
+}
+
+impl HeldTail {
+    /// Prepend the held text to `text` and hold back a new tail that could
+    /// continue into the next chunk. Returns the text to process now.
+    pub fn feed(&mut self, text: &str, end_of_utterance: bool, continuations: &Continuations) -> String {
+        let mut text = std::mem::take(&mut self.held) + text;
+        if end_of_utterance {
+            return text;
+        }
+        let phrase = word_starts(&text)
+            .find(|&i| continuations.phrases.iter().any(|p| is_partial(&text[i..], p)));
+        let number = continuations
+            .numbers
+            .as_ref()
+            .and_then(|language| super::numbers::trailing_number_start(&text, language.as_deref()));
+        if let Some(cut) = phrase.into_iter().chain(number).min() {
+            self.held = text.split_off(cut);
+        }
+        text
+    }
+
+    /// The text held back so far, e.g. to keep it on screen provisionally.
+    pub fn held(&self) -> &str {
+        &self.held
+    }
+
+    /// Text held back when the session ends.
+    pub fn flush(&mut self) -> String {
+        std::mem::take(&mut self.held)
+    }
+}
+
+/// Byte offsets where words start in `text`, first to last.
+fn word_starts(text: &str) -> impl Iterator<Item = usize> + '_ {
+    let mut prev: Option<char> = None;
+    text.char_indices().filter_map(move |(i, c)| {
+        let start = c.is_alphanumeric() && prev.is_none_or(|p| !p.is_alphanumeric());
+        prev = Some(c);
+        start.then_some(i)
+    })
+}
+
+/// Whether `text` is the beginning of `phrase` but not all of it
+/// (case-insensitive).
+fn is_partial(text: &str, phrase: &str) -> bool {
+    let text: Vec<char> = text.chars().flat_map(char::to_lowercase).collect();
+    let phrase: Vec<char> = phrase.chars().flat_map(char::to_lowercase).collect();
+    text.len() < phrase.len() && phrase.starts_with(&text)
+}
+
+/// Replace sounds-like spellings with their word. Runs before the
+/// configured processors so later rules see the corrected name.
+pub fn apply_pronunciations(pronunciations: &[Pronunciation], text: &str) -> String {
+    pronunciations.iter().fold(text.to_string(), |acc, p| {
+        p.sounds_like
+            .iter()
+            .fold(acc, |acc, spoken| replace_phrase(&acc, spoken, &p.word))
+    })
+}
+
+fn voice_commands(text: &str, ctx: &ProcessContext) -> String {
+    if !matches!(ctx.language, None | Some("en")) {
+        return text.to_string();
+    }
+
+    let mut out = text.to_string();
+    for (phrase, replacement) in VOICE_COMMANDS {
+        out = replace_phrase(&out, phrase, replacement);
+    }
+
+    // Soniox may already have punctuated around the command ("comma," / "new line.")
+    // and words keep their leading space; tidy both so commands read naturally.
+    let mut cleaned = String::with_capacity(out.len());
+    for c in out.chars() {
+        let is_punct = matches!(c, '.' | ',' | '?' | '!' | ':' | ';');
+        if is_punct || c == '\n' {
+            while cleaned.ends_with(' ') {
+                cleaned.pop();
+            }
+        }
+        if c == ' ' && cleaned.ends_with('\n') {
+            continue;
+        }
+        if is_punct && cleaned.ends_with(|p: char| matches!(p, '.' | ',' | '?' | '!' | ':' | ';')) {
+            cleaned.pop();
+        }
+        cleaned.push(c);
+    }
+    cleaned
+}
+
+fn punctuation(
+    text: &str,
+    sentence_case: bool,
+    trailing_period: bool,
+    quotes: QuoteStyle,
+    ellipsis: EllipsisStyle,
+    ctx: &ProcessContext,
+) -> String {
+    let mut out = match ellipsis {
+        EllipsisStyle::Keep => text.to_string(),
+        EllipsisStyle::Dots => text.replace('…', "...").replace(". . .", "..."),
+        EllipsisStyle::Character => text.replace(". . .", "…").replace("...", "…"),
+    };
+
+    out = match quotes {
+        QuoteStyle::Keep => out,
+        QuoteStyle::Straight => out
+            .chars()
+            .map(|c| match c {
+                '“' | '”' | '„' => '"',
+                '‘' | '’' | '‚' => '\'',
+                other => other,
+            })
+            .collect(),
+        QuoteStyle::Smart => smart_quotes(&out, ctx.previous.chars().last()),
+    };
+
+    if sentence_case {
+        out = casing(&out, CasingMode::Sentence, ctx);
+    }
+
+    if trailing_period && ctx.end_of_utterance {
+        let trimmed = out.trim_end();
+        let ends_sentence = trimmed
+            .trim_end_matches(|c: char| matches!(c, '"' | '\'' | '”' | '’' | ')'))
+            .ends_with(|c: char| matches!(c, '.' | '?' | '!' | '…' | ':' | ';'));
+        if !trimmed.is_empty() && !ends_sentence {
+            let trailing = out[trimmed.len()..].to_string();
+            out.truncate(trimmed.len());
+            out.push('.');
+            out.push_str(&trailing);
+        }
+    }
+
+    out
+}
+
+/// Curl straight quotes: opening after whitespace/brackets or at the start,
+/// closing otherwise (which also turns apostrophes into ’).
+fn smart_quotes(text: &str, before: Option<char>) -> String {
+    let mut prev = before;
+    text.chars()
+        .map(|c| {
+            let opening = prev.is_none_or(|p| p.is_whitespace() || matches!(p, '(' | '[' | '{' | '“' | '‘'));
+            let out = match c {
+                '"' if opening => '“',
+                '"' => '”',
+                '\'' if opening => '‘',
+                '\'' => '’',
+                other => other,
+            };
+            prev = Some(out);
+            out
+        })
+        .collect()
+}
+
+fn casing(text: &str, mode: CasingMode, ctx: &ProcessContext) -> String {
+    match mode {
+        CasingMode::Lower => text.to_lowercase(),
+        CasingMode::Upper => text.to_uppercase(),
+        CasingMode::Sentence => {
+            // Start of session counts as the start of a sentence
+            let mut capitalize = ctx
+                .previous
+                .trim_end()
+                .chars()
+                .last()
+                .is_none_or(|c| matches!(c, '.' | '?' | '!' | '\n'));
+            let mut out = String::with_capacity(text.len());
+            for c in text.chars() {
+                if capitalize && c.is_alphabetic() {
+                    out.extend(c.to_uppercase());
+                    capitalize = false;
+                } else {
+                    out.push(c);
+                    if matches!(c, '.' | '?' | '!' | '\n') {
+                        capitalize = true;
+                    }
+                }
+            }
+            out
+        }
+    }
+}
+
+/// Replace whole-word, case-insensitive occurrences of `phrase`.
+fn replace_phrase(text: &str, phrase: &str, replacement: &str) -> String {
+    let needle: Vec<char> = phrase.chars().flat_map(char::to_lowercase).collect();
+    if needle.is_empty() {
+        return text.to_string();
+    }
+
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    let mut prev: Option<char> = None;
+
+    while let Some(c) = rest.chars().next() {
+        if prev.is_none_or(|p| !p.is_alphanumeric()) {
+            if let Some(len) = match_len(rest, &needle) {
+                let next = rest[len..].chars().next();
+                if next.is_none_or(|n| !n.is_alphanumeric()) {
+                    out.push_str(replacement);
+                    prev = rest[..len].chars().last();
+                    rest = &rest[len..];
+                    continue;
+                }
+            }
+        }
+        out.push(c);
+        prev = Some(c);
+        rest = &rest[c.len_utf8()..];
+    }
+
+    out
+}
+
+/// Byte length of the prefix of `haystack` matching `needle` (lowercased chars).
+fn match_len(haystack: &str, needle: &[char]) -> Option<usize> {
+    let mut expected = needle.iter();
+    for (i, c) in haystack.char_indices() {
+        for lc in c.to_lowercase() {
+            match expected.next() {
+                Some(n) if *n == lc => {}
+                _ => return None,
+            }
+        }
+        if expected.len() == 0 {
+            return Some(i + c.len_utf8());
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn phrases(list: &[&str]) -> Continuations {
+        Continuations { phrases: list.iter().map(|p| p.to_string()).collect(), numbers: None }
+    }
+
+    #[test]
+    fn holds_the_start_of_a_phrase_until_the_next_chunk() {
+        let phrases = phrases(&["kah vahk"]);
+        let mut tail = HeldTail::default();
+        assert_eq!(tail.feed("I met kah", false, &phrases), "I met ");
+        assert_eq!(tail.held(), "kah");
+        assert_eq!(tail.feed(" vahk today", false, &phrases), "kah vahk today");
+        assert_eq!(tail.held(), "");
+    }
+
+    #[test]
+    fn holds_sub_word_tokens() {
+        let phrases = phrases(&["cavac"]);
+        let mut tail = HeldTail::default();
+        assert_eq!(tail.feed("Mr Cav", false, &phrases), "Mr ");
+        assert_eq!(tail.feed("ac", false, &phrases), "Cavac");
+    }
+
+    #[test]
+    fn releases_unrelated_text_and_everything_at_the_end_of_an_utterance() {
+        let phrases = phrases(&["new line"]);
+        let mut tail = HeldTail::default();
+        assert_eq!(tail.feed("a newt", false, &phrases), "a newt");
+        assert_eq!(tail.feed(" and new", false, &phrases), " and ");
+        assert_eq!(tail.feed(" year", false, &phrases), "new year");
+        assert_eq!(tail.feed(" new", true, &phrases), " new");
+        assert_eq!(tail.flush(), "");
+    }
+
+    #[test]
+    fn replacements_apply_across_chunks() {
+        let processors = vec![ProcessorConfig {
+            enabled: true,
+            processor: Processor::Replacements {
+                rules: vec![Replacement { from: "desk top".to_string(), to: "desktop".to_string() }],
+            },
+        }];
+        let phrases = continuations(&processors, &[], Some("en"));
+        let ctx = ProcessContext { previous: "", language: Some("en"), end_of_utterance: false };
+        let mut tail = HeldTail::default();
+        let out: String = ["the desk", " top app"]
+            .iter()
+            .map(|chunk| apply(&processors, &tail.feed(chunk, false, &phrases), &ctx))
+            .collect();
+        assert_eq!(out, "the desktop app");
+    }
+
+    #[test]
+    fn numbers_apply_across_chunks() {
+        let processors = vec![ProcessorConfig {
+            enabled: true,
+            processor: Processor::Numbers { style: NumberStyle::Digits, languages: BTreeMap::new() },
+        }];
+        let continuations = continuations(&processors, &[], Some("en"));
+        let ctx = ProcessContext { previous: "", language: Some("en"), end_of_utterance: false };
+        let mut tail = HeldTail::default();
+        let out: String = ["I owe twenty", " three dollars"]
+            .iter()
+            .map(|chunk| apply(&processors, &tail.feed(chunk, false, &continuations), &ctx))
+            .collect();
+        assert_eq!(out, "I owe 23 dollars");
+    }
+}
@@ -0,0 +1,280 @@
+//! "Spell that: K-A-V-A-K" — teaching a name in the middle of dictation.
+//!
+//! The spelled word replaces the word just before the command, and what the
+//! provider wrote there becomes a pronunciation of it for the rest of the
+//! session, so later occurrences come out right too. Nothing is saved;
+//! add the word to Pronunciations in the settings to keep it.
+
+use super::pipeline::{apply_pronunciations, Pronunciation};
+
+const TRIGGER: &str = "spell that";
+/// Fewer spelled letters than this is more likely ordinary speech
+const MIN_LETTERS: usize = 2;
+
+/// A complete command found in final text.
+pub struct Spelled {
+    /// Text before the command
+    pub before: String,
+    pub word: String,
+    /// Text after the letters
+    pub after: String,
+}
+
+pub enum Fed {
+    /// No command; output the text as usual
+    Text(String),
+    /// A command whose letters may continue in the next chunk
+    Held,
+    Spelled(Spelled),
+}
+
+/// Replace the last `erase` characters of the output with `text`.
+pub struct Correction {
+    pub erase: usize,
+    pub text: String,
+}
+
+impl Correction {
+    /// Apply the correction to `typed` (already output) and `output` (about
+    /// to be). When the word is still in `output` it is fixed there;
+    /// otherwise `output` is folded into the correction and the characters
+    /// to erase on screen and the text to type instead are returned.
+    pub fn apply(self, typed: &mut String, output: &mut String) -> Option<(usize, String)> {
+        let output_chars = output.chars().count();
+        if self.erase <= output_chars {
+            *output = output.chars().take(output_chars - self.erase).collect();
+            output.push_str(&self.text);
+            return None;
+        }
+        let erase = self.erase - output_chars;
+        let kept = typed.chars().count() - erase;
+        *typed = typed.chars().take(kept).collect();
+        typed.push_str(&self.text);
+        output.clear();
+        Some((erase, self.text))
+    }
+}
+
+/// Words taught in the current session.
+#[derive(Default)]
+pub struct SessionVocabulary {
+    pronunciations: Vec<Pronunciation>,
+    held: String,
+}
+
+impl SessionVocabulary {
+    /// Look for a command in the next chunk of final text. Until the
+    /// utterance ends, a command running to the end of the chunk is held
+    /// back, since the provider finalizes spelled letters one by one.
+    pub fn feed(&mut self, text: &str, end_of_utterance: bool) -> Fed {
+        let text = std::mem::take(&mut self.held) + text;
+        let Some((start, end)) = find_trigger(&text) else {
+            if !end_of_utterance && ends_with_word(&text, "spell") {
+                self.held = text;
+                return Fed::Held;
+            }
+            return Fed::Text(text);
+        };
+
+        let (letters, consumed) = parse_letters(&text[end..]);
+        let complete = !text[end + consumed..].trim().is_empty();
+        if !complete && !end_of_utterance {
+            self.held = text;
+            return Fed::Held;
+        }
+        if letters.chars().count() < MIN_LETTERS {
+            return Fed::Text(text);
+        }
+        Fed::Spelled(Spelled {
+            before: text[..start].trim_end().to_string(),
+            word: title_case(&letters),
+            after: text[end + consumed..].to_string(),
+        })
+    }
+
+    /// Text held back when the session ends, output as it was heard.
+    pub fn flush(&mut self) -> String {
+        std::mem::take(&mut self.held)
+    }
+
+    /// Teach `word` in place of the last word of `output` (everything
+    /// output so far). Returns how to correct the output, or `None` when
+    /// there is no word to replace.
+    pub fn learn(&mut self, word: &str, output: &str) -> Option<Correction> {
+        let head = output.trim_end_matches(|c: char| !c.is_alphanumeric());
+        let trailing = &output[head.len()..];
+        let start = head
+            .char_indices()
+            .rev()
+            .find(|&(_, c)| !(c.is_alphanumeric() || c == '\'' || c == '’'))
+            .map_or(0, |(i, c)| i + c.len_utf8());
+        let heard = &head[start..];
+        if heard.is_empty() {
+            return None;
+        }
+
+        if !heard.eq_ignore_ascii_case(word) {
+            let heard = heard.to_lowercase();
+            match self.pronunciations.iter_mut().find(|p| p.word == word) {
+                Some(p) if !p.sounds_like.contains(&heard) => p.sounds_like.push(heard),
+                Some(_) => {}
+                None => self.pronunciations.push(Pronunciation {
+                    word: word.to_string(),
+                    sounds_like: vec![heard],
+                }),
+            }
+        }
+        Some(Correction {
+            erase: heard.chars().count() + trailing.chars().count(),
+            text: format!("{}{}", word, trailing),
+        })
+    }
+
+    /// Words taught so far.
+    pub fn pronunciations(&self) -> &[Pronunciation] {
+        &self.pronunciations
+    }
+
+    /// Apply the words taught so far.
+    pub fn apply(&self, text: &str) -> String {
+        apply_pronunciations(&self.pronunciations, text)
+    }
+}
+
+/// Byte range of the last trigger phrase in `text`, on word boundaries.
+fn find_trigger(text: &str) -> Option<(usize, usize)> {
+    text.char_indices().rev().find_map(|(i, _)| {
+        let candidate = text[i..].get(..TRIGGER.len())?;
+        let before = text[..i].chars().next_back();
+        let after = text[i + TRIGGER.len()..].chars().next();
+        (candidate.eq_ignore_ascii_case(TRIGGER)
+            && before.is_none_or(|c| !c.is_alphanumeric())
+            && after.is_none_or(|c| !c.is_alphanumeric()))
+        .then_some((i, i + TRIGGER.len()))
+    })
+}
+
+/// The spelled letters at the start of `rest` and how many bytes they take.
+/// Accepts separate letters ("K-A-V-A-K", "K. A. V.") or one word the
+/// provider already joined ("KAVAK").
+fn parse_letters(rest: &str) -> (String, usize) {
+    let mut letters = String::new();
+    let mut consumed = 0;
+    let mut offset = 0;
+    for token in rest.split_inclusive(char::is_whitespace) {
+        let start = offset;
+        offset += token.len();
+        let pieces: Vec<&str> = token
+            .split(|c: char| c.is_whitespace() || matches!(c, '-' | '.' | ',' | ':'))
+            .filter(|p| !p.is_empty())
+            .collect();
+        if pieces.is_empty() {
+            // Separators only, like the colon in "spell that: K"
+            continue;
+        }
+        let single_letters = pieces.iter().all(|p| p.chars().count() == 1 && p.chars().all(char::is_alphabetic));
+        if single_letters {
+            letters.extend(pieces.iter().copied());
+            consumed = start + token.trim_end().len();
+        } else if letters.is_empty() && pieces.len() == 1 && pieces[0].chars().all(char::is_alphabetic) {
+            letters = pieces[0].to_string();
+            consumed = start + token.trim_end().len();
+            break;
+        } else {
+            break;
+        }
+    }
+    (letters, consumed)
+}
+
+fn ends_with_word(text: &str, word: &str) -> bool {
+    let trimmed = text.trim_end_matches(|c: char| !c.is_alphanumeric());
+    trimmed.len() >= word.len()
+        && trimmed.is_char_boundary(trimmed.len() - word.len())
+        && trimmed[trimmed.len() - word.len()..].eq_ignore_ascii_case(word)
+        && trimmed[..trimmed.len() - word.len()]
+            .chars()
+            .next_back()
+            .is_none_or(|c| !c.is_alphanumeric())
+}
+
+fn title_case(letters: &str) -> String {
+    let mut chars = letters.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars.flat_map(char::to_lowercase)).collect())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spelled(fed: Fed) -> Spelled {
+        match fed {
+            Fed::Spelled(spelled) => spelled,
+            Fed::Text(text) => panic!("no command in {:?}", text),
+            Fed::Held => panic!("command held"),
+        }
+    }
+
+    #[test]
+    fn finds_the_last_trigger_on_word_boundaries() {
+        assert_eq!(find_trigger("my name is cavac, Spell that: K"), Some((18, 28)));
+        assert_eq!(find_trigger("spell that, then spell that"), Some((17, 27)));
+        assert_eq!(find_trigger("misspell thatch"), None);
+    }
+
+    #[test]
+    fn parses_separate_and_joined_letters() {
+        assert_eq!(parse_letters(": K-A-V-A-K and more"), ("KAVAK".to_string(), 11));
+        assert_eq!(parse_letters(" K. A. V. A. K."), ("KAVAK".to_string(), 15));
+        assert_eq!(parse_letters(" KAVAK today"), ("KAVAK".to_string(), 6));
+        assert_eq!(parse_letters(" K A then"), ("KA".to_string(), 4));
+        assert_eq!(parse_letters(" 42"), (String::new(), 0));
+    }
+
+    #[test]
+    fn holds_a_command_until_the_letters_end() {
+        let mut vocabulary = SessionVocabulary::default();
+        assert!(matches!(vocabulary.feed("my name is cavac, spell", false), Fed::Held));
+        assert!(matches!(vocabulary.feed(" that: K-A", false), Fed::Held));
+        let spelled = spelled(vocabulary.feed("-V-A-K, thanks", false));
+        assert_eq!(spelled.before, "my name is cavac,");
+        assert_eq!(spelled.word, "Kavak");
+        assert_eq!(spelled.after, " thanks");
+    }
+
+    #[test]
+    fn a_single_letter_is_not_a_command() {
+        let mut vocabulary = SessionVocabulary::default();
+        assert!(matches!(vocabulary.feed("spell that A.", true), Fed::Text(_)));
+        assert!(matches!(vocabulary.feed("ordinary text", false), Fed::Text(_)));
+    }
+
+    #[test]
+    fn learns_the_word_before_the_command() {
+        let mut vocabulary = SessionVocabulary::default();
+        let correction = vocabulary.learn("Kavak", "my name is cavac,").unwrap();
+        assert_eq!(correction.erase, "cavac,".len());
+        assert_eq!(correction.text, "Kavak,");
+        assert_eq!(vocabulary.apply("ask cavac"), "ask Kavak");
+        assert!(vocabulary.learn("Kavak", " ...").is_none());
+    }
+
+    #[test]
+    fn corrects_output_in_place_or_on_screen() {
+        let mut typed = "my name is ".to_string();
+        let mut output = "cavac,".to_string();
+        let correction = Correction { erase: 6, text: "Kavak,".to_string() };
+        assert_eq!(correction.apply(&mut typed, &mut output), None);
+        assert_eq!(output, "Kavak,");
+
+        let mut typed = "my name is cav".to_string();
+        let mut output = "ac,".to_string();
+        let correction = Correction { erase: 6, text: "Kavak,".to_string() };
+        assert_eq!(correction.apply(&mut typed, &mut output), Some((3, "Kavak,".to_string())));
+        assert_eq!(typed, "my name is Kavak,");
+        assert_eq!(output, "");
+    }
+}
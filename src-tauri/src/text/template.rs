@@ -0,0 +1,69 @@
+//! Session templates: text a profile types when a session starts.
+//!
+//! Placeholders are expanded in local time: `{date}` (2024-05-31),
+//! `{time}` (14:05), `{year}`, `{month}`, `{day}`, `{weekday}` (Friday).
+//! Anything else in braces is typed as written.
+
+use chrono::{Local, NaiveDateTime};
+
+/// `template` with its placeholders filled in for the current local time.
+pub fn expand(template: &str) -> String {
+    expand_at(template, Local::now().naive_local())
+}
+
+/// Today's local date, YYYY-MM-DD.
+pub fn today() -> String {
+    Local::now().format("%Y-%m-%d").to_string()
+}
+
+/// `template` expanded for the local wall-clock time `now`.
+fn expand_at(template: &str, now: NaiveDateTime) -> String {
+    template
+        .replace("{date}", &now.format("%Y-%m-%d").to_string())
+        .replace("{time}", &now.format("%H:%M").to_string())
+        .replace("{year}", &now.format("%Y").to_string())
+        .replace("{month}", &now.format("%m").to_string())
+        .replace("{day}", &now.format("%d").to_string())
+        .replace("{weekday}", &now.format("%A").to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    /// Friday 2024-05-31 14:05:09
+    fn friday() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2024, 5, 31).unwrap().and_hms_opt(14, 5, 9).unwrap()
+    }
+
+    #[test]
+    fn expands_each_placeholder() {
+        assert_eq!(expand_at("{date}", friday()), "2024-05-31");
+        assert_eq!(expand_at("{time}", friday()), "14:05");
+        assert_eq!(expand_at("{year}", friday()), "2024");
+        assert_eq!(expand_at("{month}", friday()), "05");
+        assert_eq!(expand_at("{day}", friday()), "31");
+        assert_eq!(expand_at("{weekday}", friday()), "Friday");
+    }
+
+    #[test]
+    fn expands_placeholders_in_text() {
+        assert_eq!(
+            expand_at("Standup {weekday} {date}, {time}:", friday()),
+            "Standup Friday 2024-05-31, 14:05:"
+        );
+    }
+
+    #[test]
+    fn keeps_unknown_placeholders_and_percent_signs() {
+        assert_eq!(expand_at("{foo} 100% {date}", friday()), "{foo} 100% 2024-05-31");
+    }
+
+    #[test]
+    fn today_is_a_date() {
+        let today = today();
+        assert_eq!(today.len(), 10);
+        assert!(NaiveDate::parse_from_str(&today, "%Y-%m-%d").is_ok());
+    }
+}
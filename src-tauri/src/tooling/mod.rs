@@ -0,0 +1,214 @@
+//! Setup helper for the external tools the Linux typer relies on.
+//!
+//! Reports which of `typer::LINUX_TOOLS` are missing for the current
+//! session and what each would enable. Installation is offered only through
+//! the distribution's package manager behind `pkexec` (the user confirms in
+//! a polkit prompt) and only for package names from that table. For
+//! ydotool it can also install a systemd user unit for `ydotoold`; access
+//! to /dev/uinput is reported but never changed, since that needs root and
+//! a policy decision (udev rule or `input` group).
+
+use serde::Serialize;
+
+#[derive(Clone, Serialize)]
+pub struct ToolStatus {
+    pub name: String,
+    pub enables: String,
+    pub package: String,
+    pub installed: bool,
+    /// The typer tries this tool in the current session type
+    pub used_in_session: bool,
+    pub needs_daemon: bool,
+}
+
+#[derive(Clone, Serialize)]
+pub struct DaemonStatus {
+    pub running: bool,
+    /// A `ydotoold` unit exists (user or system)
+    pub unit_installed: bool,
+    /// /dev/uinput is writable by this user, which ydotoold needs
+    pub uinput_access: bool,
+}
+
+#[derive(Clone, Default, Serialize)]
+pub struct DependencyReport {
+    /// False on platforms that type without external tools
+    pub supported: bool,
+    /// "wayland" or "x11"
+    pub session: String,
+    pub tools: Vec<ToolStatus>,
+    /// Detected package manager ("apt-get", "dnf", "pacman", "zypper")
+    pub package_manager: Option<String>,
+    /// Shell command installing every missing tool used in this session
+    pub install_command: Option<String>,
+    pub ydotoold: Option<DaemonStatus>,
+}
+
+#[cfg(target_os = "linux")]
+const UNIT_NAME: &str = "ydotoold.service";
+
+/// Package managers in detection order, with their non-interactive install
+/// arguments.
+#[cfg(target_os = "linux")]
+const PACKAGE_MANAGERS: &[(&str, &[&str])] = &[
+    ("apt-get", &["install", "-y"]),
+    ("dnf", &["install", "-y"]),
+    ("pacman", &["-S", "--noconfirm", "--needed"]),
+    ("zypper", &["--non-interactive", "install"]),
+];
+
+#[cfg(target_os = "linux")]
+pub fn check() -> DependencyReport {
+    let session = crate::typer::session_type();
+    let tools: Vec<ToolStatus> = crate::typer::LINUX_TOOLS
+        .iter()
+        .map(|tool| ToolStatus {
+            name: tool.name.to_string(),
+            enables: tool.enables.to_string(),
+            package: tool.package.to_string(),
+            installed: crate::typer::tool_installed(tool.name),
+            used_in_session: tool.sessions.contains(&session),
+            needs_daemon: tool.needs_daemon,
+        })
+        .collect();
+
+    let package_manager = package_manager();
+    let missing: Vec<&str> = tools
+        .iter()
+        .filter(|t| t.used_in_session && !t.installed)
+        .map(|t| t.package.as_str())
+        .collect();
+    let install_command = package_manager.filter(|_| !missing.is_empty()).map(|(pm, args)| {
+        format!("sudo {} {} {}", pm, args.join(" "), missing.join(" "))
+    });
+
+    let ydotoold = tools
+        .iter()
+        .any(|t| t.needs_daemon && t.installed)
+        .then(|| DaemonStatus {
+            running: command_succeeds("pgrep", &["-x", "ydotoold"]),
+            unit_installed: command_succeeds("systemctl", &["--user", "cat", UNIT_NAME])
+                || command_succeeds("systemctl", &["cat", UNIT_NAME])
+                || command_succeeds("systemctl", &["cat", "ydotool.service"]),
+            uinput_access: std::fs::OpenOptions::new().write(true).open("/dev/uinput").is_ok(),
+        });
+
+    DependencyReport {
+        supported: true,
+        session: session.to_string(),
+        tools,
+        package_manager: package_manager.map(|(pm, _)| pm.to_string()),
+        install_command,
+        ydotoold,
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn check() -> DependencyReport {
+    DependencyReport::default()
+}
+
+/// Install the package for one of the typer's tools, asking for
+/// authorization through polkit. Blocks until the package manager exits.
+#[cfg(target_os = "linux")]
+pub fn install(name: &str) -> Result<(), String> {
+    let tool = crate::typer::LINUX_TOOLS
+        .iter()
+        .find(|t| t.name == name)
+        .ok_or_else(|| format!("Unknown typing tool '{}'", name))?;
+    let (pm, args) = package_manager().ok_or("No supported package manager found (apt, dnf, pacman, zypper)")?;
+    if !command_succeeds("which", &["pkexec"]) {
+        return Err(format!(
+            "pkexec is not available; install manually with: sudo {} {} {}",
+            pm,
+            args.join(" "),
+            tool.package
+        ));
+    }
+
+    log::info!("Installing {} with {}", tool.package, pm);
+    let status = std::process::Command::new("pkexec")
+        .arg(pm)
+        .args(*args)
+        .arg(tool.package)
+        .status()
+        .map_err(|e| format!("pkexec failed: {}", e))?;
+    match status.code() {
+        Some(0) => Ok(()),
+        // pkexec's codes for a dismissed or denied authorization prompt
+        Some(126) | Some(127) => Err(crate::i18n::t("tooling-not-authorized")),
+        _ => Err(format!("{} exited with status: {}", pm, status)),
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn install(_name: &str) -> Result<(), String> {
+    Err(crate::i18n::t("tooling-linux-only"))
+}
+
+/// Write a systemd user unit for `ydotoold`, then enable and start it.
+#[cfg(target_os = "linux")]
+pub fn setup_ydotoold() -> Result<(), String> {
+    let daemon = std::process::Command::new("which")
+        .arg("ydotoold")
+        .output()
+        .ok()
+        .filter(|out| out.status.success())
+        .map(|out| String::from_utf8_lossy(&out.stdout).trim().to_string())
+        .ok_or("ydotoold is not installed")?;
+
+    let config = std::env::var_os("XDG_CONFIG_HOME")
+        .map(std::path::PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| std::path::PathBuf::from(home).join(".config")))
+        .ok_or("Cannot find the user config directory")?;
+    let unit_dir = config.join("systemd").join("user");
+    std::fs::create_dir_all(&unit_dir).map_err(|e| e.to_string())?;
+    let unit_path = unit_dir.join(UNIT_NAME);
+    let unit = format!(
+        "[Unit]\nDescription=ydotool daemon (installed by desktop-dictate)\n\n\
+         [Service]\nExecStart={}\nRestart=on-failure\n\n\
+         [Install]\nWantedBy=default.target\n",
+        daemon
+    );
+    std::fs::write(&unit_path, unit).map_err(|e| format!("Failed to write {:?}: {}", unit_path, e))?;
+    log::info!("Wrote {:?}", unit_path);
+
+    for args in [&["--user", "daemon-reload"][..], &["--user", "enable", "--now", UNIT_NAME][..]] {
+        let output = std::process::Command::new("systemctl")
+            .args(args)
+            .output()
+            .map_err(|e| format!("systemctl failed: {}", e))?;
+        if !output.status.success() {
+            return Err(format!(
+                "systemctl {} failed: {}",
+                args.join(" "),
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn setup_ydotoold() -> Result<(), String> {
+    Err("ydotoold is only used on Linux".to_string())
+}
+
+#[cfg(target_os = "linux")]
+fn package_manager() -> Option<(&'static str, &'static [&'static str])> {
+    PACKAGE_MANAGERS
+        .iter()
+        .copied()
+        .find(|(pm, _)| command_succeeds("which", &[pm]))
+}
+
+#[cfg(target_os = "linux")]
+fn command_succeeds(program: &str, args: &[&str]) -> bool {
+    std::process::Command::new(program)
+        .args(args)
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .map(|s| s.success())
+        .unwrap_or(false)
+}
@@ -0,0 +1,77 @@
+//! Suppression of final text a provider sends twice.
+//!
+//! After a reconnect a provider can re-send finals it already delivered,
+//! which would type the same words again. Hashes of recent insertions are
+//! kept for `WINDOW`; an exact repeat inside it is dropped. Short texts
+//! ("the", ".") legitimately repeat and are always let through.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::VecDeque;
+use std::hash::{Hash, Hasher};
+use std::time::{Duration, Instant};
+
+const WINDOW: Duration = Duration::from_secs(3);
+// Texts shorter than this (trimmed, in chars) are never suppressed
+const MIN_CHARS: usize = 10;
+
+#[derive(Default)]
+pub struct DuplicateFilter {
+    recent: VecDeque<(u64, Instant)>,
+}
+
+impl DuplicateFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether `text` should be inserted; false for an exact repeat of a
+    /// recent insertion.
+    pub fn admit(&mut self, text: &str) -> bool {
+        let text = text.trim();
+        if text.chars().count() < MIN_CHARS {
+            return true;
+        }
+        let now = Instant::now();
+        while self.recent.front().is_some_and(|(_, at)| now.duration_since(*at) > WINDOW) {
+            self.recent.pop_front();
+        }
+        let mut hasher = DefaultHasher::new();
+        text.hash(&mut hasher);
+        let hash = hasher.finish();
+        if self.recent.iter().any(|(h, _)| *h == hash) {
+            return false;
+        }
+        self.recent.push_back((hash, now));
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drops_a_recent_exact_repeat() {
+        let mut filter = DuplicateFilter::new();
+        assert!(filter.admit("Meet me at the station."));
+        assert!(!filter.admit("Meet me at the station."));
+        // Surrounding whitespace doesn't make it new
+        assert!(!filter.admit(" Meet me at the station. "));
+        assert!(filter.admit("Meet me at the airport."));
+    }
+
+    #[test]
+    fn short_texts_always_pass() {
+        let mut filter = DuplicateFilter::new();
+        assert!(filter.admit("Yes."));
+        assert!(filter.admit("Yes."));
+    }
+
+    #[test]
+    fn repeats_after_the_window_pass() {
+        let mut filter = DuplicateFilter::new();
+        assert!(filter.admit("Meet me at the station."));
+        filter.recent[0].1 -= WINDOW + Duration::from_millis(1);
+        assert!(filter.admit("Meet me at the station."));
+    }
+}
@@ -0,0 +1,239 @@
+//! The application a session dictates into.
+//!
+//! - macOS: the frontmost app from `lsappinfo` (bundle id, name, pid); the
+//!   window title comes from System Events, which needs the Accessibility
+//!   permission the typer already asks for
+//! - Windows: `GetForegroundWindow` plus the owning process's executable
+//! - Linux X11: `xdotool` for the active window plus `/proc/<pid>/comm`;
+//!   Wayland does not expose other clients' windows
+//!
+//! Target ids (`window=<hwnd>` on Windows, `window=<xid>` on X11,
+//! `app=<bundle id>` on macOS) let the typer bring the target back to the
+//! front before each insertion, in case the user or a notification moved
+//! focus meanwhile. With refocusing off the target id is plain "active"
+//! and text follows focus.
+
+use serde::{Deserialize, Serialize};
+#[cfg(not(target_os = "windows"))]
+use std::process::Command;
+
+/// The focused application and window.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FocusedApp {
+    /// Bundle id on macOS, lowercase executable name elsewhere
+    pub app_id: String,
+    /// Window title, or the app name when the title is unavailable
+    pub title: String,
+    pub pid: Option<u32>,
+    /// Native window handle (HWND, X window id)
+    #[serde(skip)]
+    pub window: Option<u64>,
+}
+
+#[cfg_attr(not(any(target_os = "windows", target_os = "linux")), allow(dead_code))]
+const WINDOW_TARGET_PREFIX: &str = "window=";
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+const APP_TARGET_PREFIX: &str = "app=";
+
+/// Target id that refocuses `app` before typing, or "active" when the
+/// platform has no way to.
+pub fn target_id(app: Option<&FocusedApp>) -> String {
+    match app {
+        #[cfg(any(target_os = "windows", target_os = "linux"))]
+        Some(FocusedApp { window: Some(window), .. }) => format!("{}{}", WINDOW_TARGET_PREFIX, window),
+        #[cfg(target_os = "macos")]
+        Some(app) if is_bundle_id(&app.app_id) => format!("{}{}", APP_TARGET_PREFIX, app.app_id),
+        _ => "active".to_string(),
+    }
+}
+
+/// Bundle ids end up in an AppleScript string; only accept the plain form.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn is_bundle_id(id: &str) -> bool {
+    !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-' || c == '_')
+}
+
+/// Bundle id, name and pid of the frontmost app, from `lsappinfo`.
+#[cfg(target_os = "macos")]
+fn front_app_info() -> Option<(String, String, Option<u32>)> {
+    let run = |args: &[&str]| -> Option<String> {
+        let output = Command::new("lsappinfo").args(args).output().ok()?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    };
+    let asn = run(&["front"])?;
+    let info = run(&["info", "-only", "bundleid", "-only", "name", "-only", "pid", &asn])?;
+
+    // Lines look like `"CFBundleIdentifier"="com.apple.Safari"` or `"pid"=42`
+    let field = |key: &str| {
+        info.lines().find_map(|line| {
+            let (k, v) = line.split_once('=')?;
+            (k.trim().trim_matches('"') == key).then(|| v.trim().trim_matches('"').to_string())
+        })
+    };
+    Some((
+        field("CFBundleIdentifier").unwrap_or_default(),
+        field("LSDisplayName").unwrap_or_default(),
+        field("pid").and_then(|p| p.parse().ok()),
+    ))
+}
+
+#[cfg(target_os = "macos")]
+pub fn focused_app() -> Option<FocusedApp> {
+    let (app_id, name, pid) = front_app_info()?;
+    let title = Command::new("osascript")
+        .args([
+            "-e",
+            "tell application \"System Events\" to get name of front window of (first process whose frontmost is true)",
+        ])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+        .filter(|t| !t.is_empty())
+        .unwrap_or(name);
+
+    Some(FocusedApp { app_id, title, pid, window: None })
+}
+
+#[cfg(target_os = "windows")]
+pub fn focused_app() -> Option<FocusedApp> {
+    use windows_sys::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowTextW, GetWindowThreadProcessId};
+
+    unsafe {
+        let hwnd = GetForegroundWindow();
+        if hwnd.is_null() {
+            return None;
+        }
+        let mut buffer = [0u16; 512];
+        let len = GetWindowTextW(hwnd, buffer.as_mut_ptr(), buffer.len() as i32).max(0) as usize;
+        let title = String::from_utf16_lossy(&buffer[..len]);
+        let mut pid = 0u32;
+        GetWindowThreadProcessId(hwnd, &mut pid);
+        Some(FocusedApp {
+            app_id: process_name(pid).unwrap_or_default(),
+            title,
+            pid: (pid != 0).then_some(pid),
+            window: Some(hwnd as usize as u64),
+        })
+    }
+}
+
+/// Executable file name of process `pid`, lowercased ("winword.exe").
+#[cfg(target_os = "windows")]
+unsafe fn process_name(pid: u32) -> Option<String> {
+    use windows_sys::Win32::Foundation::CloseHandle;
+    use windows_sys::Win32::System::Threading::{
+        OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+
+    let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+    if process.is_null() {
+        return None;
+    }
+    let mut buffer = [0u16; 1024];
+    let mut size = buffer.len() as u32;
+    let ok = QueryFullProcessImageNameW(process, PROCESS_NAME_WIN32, buffer.as_mut_ptr(), &mut size);
+    CloseHandle(process);
+    if ok == 0 {
+        return None;
+    }
+    let path = String::from_utf16_lossy(&buffer[..size as usize]);
+    std::path::Path::new(&path)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_lowercase())
+}
+
+#[cfg(target_os = "linux")]
+pub fn focused_app() -> Option<FocusedApp> {
+    if super::is_wayland() || !super::command_exists("xdotool") {
+        return None;
+    }
+    let xdotool = |args: &[&str]| -> Option<String> {
+        let output = Command::new("xdotool").args(args).output().ok()?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    };
+    let window: u64 = xdotool(&["getactivewindow"])?.parse().ok()?;
+    let id = window.to_string();
+    let title = xdotool(&["getwindowname", &id]).unwrap_or_default();
+    let pid: Option<u32> = xdotool(&["getwindowpid", &id]).and_then(|p| p.parse().ok());
+    let app_id = pid
+        .and_then(|pid| std::fs::read_to_string(format!("/proc/{}/comm", pid)).ok())
+        .map(|comm| comm.trim().to_lowercase())
+        .unwrap_or_default();
+    Some(FocusedApp { app_id, title, pid, window: Some(window) })
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+pub fn focused_app() -> Option<FocusedApp> {
+    None
+}
+
+/// Bring the target back to the front if something else took focus.
+#[cfg_attr(not(any(target_os = "macos", target_os = "windows", target_os = "linux")), allow(unused_variables))]
+pub fn refocus(target_window_id: &str) {
+    #[cfg(target_os = "linux")]
+    if let Some(window) = target_window_id.strip_prefix(WINDOW_TARGET_PREFIX) {
+        let is_active = || {
+            Command::new("xdotool")
+                .arg("getactivewindow")
+                .output()
+                .is_ok_and(|o| String::from_utf8_lossy(&o.stdout).trim() == window)
+        };
+        if is_active() {
+            return;
+        }
+        // Not --sync: it waits forever when the window manager refuses to
+        // activate the window. Poll for a bounded time instead.
+        match Command::new("xdotool").args(["windowactivate", window]).status() {
+            Ok(status) if status.success() => {
+                for _ in 0..10 {
+                    std::thread::sleep(std::time::Duration::from_millis(50));
+                    if is_active() {
+                        return;
+                    }
+                }
+                log::warn!("Window {} did not come to the front", window);
+            }
+            Ok(_) | Err(_) => log::warn!("Could not bring window {} back to the front", window),
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    if let Some(hwnd) = target_window_id
+        .strip_prefix(WINDOW_TARGET_PREFIX)
+        .and_then(|id| id.parse::<u64>().ok())
+    {
+        use windows_sys::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, IsWindow, SetForegroundWindow};
+
+        let hwnd = hwnd as usize as windows_sys::Win32::Foundation::HWND;
+        unsafe {
+            if IsWindow(hwnd) == 0 || GetForegroundWindow() == hwnd {
+                return;
+            }
+            if SetForegroundWindow(hwnd) == 0 {
+                log::warn!("Could not bring the target window back to the front");
+                return;
+            }
+        }
+        // Give the window a moment to take keyboard focus
+        std::thread::sleep(std::time::Duration::from_millis(50));
+    }
+
+    #[cfg(target_os = "macos")]
+    if let Some(bundle) = target_window_id.strip_prefix(APP_TARGET_PREFIX) {
+        if !is_bundle_id(bundle) || front_app_info().is_some_and(|(front, _, _)| front == bundle) {
+            return;
+        }
+        let script = format!("tell application id \"{}\" to activate", bundle);
+        match Command::new("osascript").args(["-e", &script]).status() {
+            Ok(status) if status.success() => std::thread::sleep(std::time::Duration::from_millis(100)),
+            Ok(_) | Err(_) => log::warn!("Could not bring {} back to the front", bundle),
+        }
+    }
+}
@@ -2,9 +2,63 @@ use arboard::Clipboard;
 use enigo::{Direction, Enigo, Key, Keyboard, Settings};
 use std::panic::{catch_unwind, AssertUnwindSafe};
 use std::process::Command;
+use std::sync::Mutex;
 use std::thread;
 use std::time::Duration;
 
+mod dedup;
+mod focus;
+mod provisional;
+pub use dedup::DuplicateFilter;
+pub use focus::{focused_app, FocusedApp};
+pub use provisional::ProvisionalText;
+
+/// Pauses around a clipboard paste: before the paste shortcut, so the new
+/// contents are registered, and after it, so the target app has read them
+/// before the previous contents are restored.
+#[derive(Clone, Copy)]
+pub struct ClipboardDelays {
+    pub settle: Duration,
+    pub read: Duration,
+}
+
+impl Default for ClipboardDelays {
+    fn default() -> Self {
+        Self { settle: Duration::from_millis(30), read: Duration::from_millis(150) }
+    }
+}
+
+static CLIPBOARD_DELAYS: Mutex<Option<ClipboardDelays>> = Mutex::new(None);
+
+/// Use `delays` for clipboard pastes from now on. Set at session start,
+/// once the profile for the target app is known.
+pub fn set_clipboard_delays(delays: ClipboardDelays) {
+    *CLIPBOARD_DELAYS.lock().unwrap() = Some(delays);
+}
+
+fn clipboard_delays() -> ClipboardDelays {
+    CLIPBOARD_DELAYS.lock().unwrap().unwrap_or_default()
+}
+
+// How long to wait for the clipboard owner to serve the text just set
+const HOLD_ATTEMPTS: u32 = 10;
+const HOLD_RETRY: Duration = Duration::from_millis(20);
+
+/// Re-check `holds` until it succeeds or `HOLD_ATTEMPTS` run out. Setting
+/// the clipboard hands it to an owner process that may not answer
+/// requests yet, so a single immediate readback can fail spuriously.
+fn wait_until_held(mut holds: impl FnMut() -> bool) -> bool {
+    for attempt in 0..HOLD_ATTEMPTS {
+        if holds() {
+            return true;
+        }
+        if attempt + 1 < HOLD_ATTEMPTS {
+            thread::sleep(HOLD_RETRY);
+        }
+    }
+    false
+}
+
 /// Detect if we're running on Wayland.
 #[cfg(target_os = "linux")]
 fn is_wayland() -> bool {
@@ -35,12 +89,12 @@ fn command_exists(name: &str) -> bool {
 ///
 /// Flow: save clipboard -> set text -> Ctrl+V -> wait -> restore clipboard.
 #[cfg(target_os = "linux")]
-fn type_via_xclip_paste(text: &str) -> Result<(), String> {
+fn type_via_xclip_paste(text: &str, display: Option<&str>) -> Result<(), String> {
     use std::io::Write;
     use std::process::Stdio;
 
     // 1. Save current clipboard contents (ok to fail if clipboard is empty/non-text)
-    let previous = Command::new("xclip")
+    let previous = x11_command("xclip", display)
         .args(["-selection", "clipboard", "-o"])
         .output()
         .ok()
@@ -53,7 +107,7 @@ fn type_via_xclip_paste(text: &str) -> Result<(), String> {
         });
 
     // 2. Set clipboard to our text via stdin pipe
-    let mut child = Command::new("xclip")
+    let mut child = x11_command("xclip", display)
         .args(["-selection", "clipboard"])
         .stdin(Stdio::piped())
         .stdout(Stdio::null())
@@ -76,11 +130,23 @@ fn type_via_xclip_paste(text: &str) -> Result<(), String> {
         return Err(format!("xclip exited with status: {}", status));
     }
 
+    // Make sure the selection really holds our text before pasting it
+    let held = wait_until_held(|| {
+        x11_command("xclip", display)
+            .args(["-selection", "clipboard", "-o"])
+            .output()
+            .is_ok_and(|o| o.stdout == text.as_bytes())
+    });
+    if !held {
+        return Err(crate::i18n::t("clipboard-not-held"));
+    }
+
     // 3. Small delay to let the clipboard settle
-    thread::sleep(Duration::from_millis(30));
+    let delays = clipboard_delays();
+    thread::sleep(delays.settle);
 
     // 4. Simulate Ctrl+V via xdotool (single key combo, instant)
-    let paste_status = Command::new("xdotool")
+    let paste_status = x11_command("xdotool", display)
         .args(["key", "--clearmodifiers", "ctrl+v"])
         .status()
         .map_err(|e| format!("xdotool key exec failed: {}", e))?;
@@ -90,11 +156,11 @@ fn type_via_xclip_paste(text: &str) -> Result<(), String> {
     }
 
     // 5. Wait for the target application to read from clipboard
-    thread::sleep(Duration::from_millis(150));
+    thread::sleep(delays.read);
 
     // 6. Restore previous clipboard contents (best-effort)
     if let Some(prev) = previous {
-        if let Ok(mut restore) = Command::new("xclip")
+        if let Ok(mut restore) = x11_command("xclip", display)
             .args(["-selection", "clipboard"])
             .stdin(Stdio::piped())
             .stdout(Stdio::null())
@@ -149,12 +215,158 @@ fn type_via_wtype(text: &str) -> Result<(), String> {
     }
 }
 
-/// Capture the currently focused window ID and name.
+/// An external program the Linux typer can use, and what it enables.
+#[derive(Debug, Clone, Copy, serde::Serialize)]
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub struct ToolCapability {
+    pub name: &'static str,
+    /// Human-readable description of the typing path the tool enables
+    pub enables: &'static str,
+    /// Session types ("wayland", "x11") in which the typer tries the tool
+    pub sessions: &'static [&'static str],
+    /// Distribution package providing it (same name in apt, dnf, pacman, zypper)
+    pub package: &'static str,
+    /// Only works while the `ydotoold` daemon is running
+    pub needs_daemon: bool,
+}
+
+/// Tools `type_text_linux` tries, in order of preference per session.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub const LINUX_TOOLS: &[ToolCapability] = &[
+    ToolCapability {
+        name: "ydotool",
+        enables: "Typing through /dev/uinput on any Wayland compositor",
+        sessions: &["wayland"],
+        package: "ydotool",
+        needs_daemon: true,
+    },
+    ToolCapability {
+        name: "wtype",
+        enables: "Typing through the virtual-keyboard protocol (wlroots compositors: Sway, Hyprland, ...)",
+        sessions: &["wayland"],
+        package: "wtype",
+        needs_daemon: false,
+    },
+    ToolCapability {
+        name: "xdotool",
+        enables: "Instant clipboard paste (with xclip), window geometry for overlay placement, typing into other X displays",
+        sessions: &["x11"],
+        package: "xdotool",
+        needs_daemon: false,
+    },
+    ToolCapability {
+        name: "xclip",
+        enables: "Clipboard paste without touching the clipboard history (with xdotool)",
+        sessions: &["x11"],
+        package: "xclip",
+        needs_daemon: false,
+    },
+];
+
+/// "wayland" or "x11", as the typer sees the current session.
+#[cfg(target_os = "linux")]
+pub fn session_type() -> &'static str {
+    if is_wayland() {
+        "wayland"
+    } else {
+        "x11"
+    }
+}
+
+/// Whether an external typing tool is on $PATH.
+#[cfg(target_os = "linux")]
+pub fn tool_installed(name: &str) -> bool {
+    command_exists(name)
+}
+
+/// Target id for the focused window `app`: a handle the typer refocuses
+/// before inserting text where the platform allows it ("window=<id>" on
+/// Windows and X11, "app=<bundle id>" on macOS), "active" otherwise.
+pub fn capture_focused_window(app: Option<&FocusedApp>) -> Result<String, String> {
+    Ok(focus::target_id(app))
+}
+
+// Target ids of the form "display=<DISPLAY>" type into that X display
+const DISPLAY_TARGET_PREFIX: &str = "display=";
+
+/// Like `capture_focused_window`, but for the active window of another X
+/// display when `display` is set (multi-head/multi-seat setups).
+pub fn capture_focused_window_on(display: Option<&str>, app: Option<&FocusedApp>) -> Result<String, String> {
+    match display {
+        Some(display) => {
+            #[cfg(not(target_os = "linux"))]
+            log::warn!("Typing into display {} is only supported on Linux X11", display);
+            Ok(format!("{}{}", DISPLAY_TARGET_PREFIX, display))
+        }
+        None => capture_focused_window(app),
+    }
+}
+
+/// X display a target id points at, if it isn't our own.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn target_display(target_window_id: &str) -> Option<&str> {
+    target_window_id.strip_prefix(DISPLAY_TARGET_PREFIX)
+}
+
+/// Command for an X11 tool, pointed at `display` when given.
+#[cfg(target_os = "linux")]
+fn x11_command(program: &str, display: Option<&str>) -> Command {
+    let mut command = Command::new(program);
+    if let Some(display) = display {
+        command.env("DISPLAY", display);
+    }
+    command
+}
+
+/// Screen geometry of a window in physical pixels.
+#[derive(Debug, Clone, Copy)]
+pub struct WindowGeometry {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Geometry of the currently focused window, if the platform exposes it.
 ///
-/// With cross-platform clipboard paste we do not rely on a window ID,
-/// but keep the API for compatibility with the rest of the app.
-pub fn capture_focused_window() -> Result<String, String> {
-    Ok("active".to_string())
+/// Only implemented on Linux X11 (via xdotool); Wayland compositors do not
+/// let clients query other windows' positions.
+pub fn focused_window_geometry() -> Option<WindowGeometry> {
+    #[cfg(target_os = "linux")]
+    {
+        if is_wayland() || !command_exists("xdotool") {
+            return None;
+        }
+
+        let output = Command::new("xdotool")
+            .args(["getactivewindow", "getwindowgeometry", "--shell"])
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+
+        // Output is KEY=VALUE lines: WINDOW, X, Y, WIDTH, HEIGHT, SCREEN
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let field = |key: &str| -> Option<i64> {
+            stdout
+                .lines()
+                .find_map(|line| line.strip_prefix(key)?.strip_prefix('='))
+                .and_then(|v| v.trim().parse().ok())
+        };
+
+        Some(WindowGeometry {
+            x: field("X")? as i32,
+            y: field("Y")? as i32,
+            width: field("WIDTH")? as u32,
+            height: field("HEIGHT")? as u32,
+        })
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        None
+    }
 }
 
 /// Insert text into the focused window.
@@ -173,25 +385,145 @@ pub fn capture_focused_window() -> Result<String, String> {
 /// **macOS / Windows**:
 ///   1. enigo.text()  (native input methods, wrapped in catch_unwind)
 ///   2. clipboard paste fallback
-pub fn type_text(text: &str, _target_window_id: &str) -> Result<(), String> {
+///
+/// Targets on another X display (seats) always use xclip + xdotool with
+/// `DISPLAY` pointed at that display. A captured target window is brought
+/// back to the front first (see `focus`).
+pub fn type_text(text: &str, target_window_id: &str) -> Result<(), String> {
     if text.is_empty() {
         return Ok(());
     }
+    if crate::faults::fire(crate::faults::Fault::TypingFailure) {
+        return Err("Injected typing failure".to_string());
+    }
 
     #[cfg(target_os = "linux")]
-    {
-        return type_text_linux(text);
-    }
+    let method = match target_display(target_window_id) {
+        // Another display's window can only be reached through its X server
+        Some(display) => type_via_xclip_paste(text, Some(display)).map(|()| "xclip-paste")?,
+        None => {
+            focus::refocus(target_window_id);
+            type_text_linux(text)?
+        }
+    };
 
     #[cfg(not(target_os = "linux"))]
-    {
-        return type_text_nonlinux(text);
+    let method = {
+        focus::refocus(target_window_id);
+        type_text_nonlinux(text)?
+    };
+
+    crate::journal::record("insert", method, target_window_id, text);
+    Ok(())
+}
+
+// Piece size for large insertions when no chunk size is configured
+const LARGE_INSERT_CHUNK_CHARS: usize = 1000;
+
+/// How insertions are split up (see `type_text_chunked`).
+#[derive(Clone, Copy)]
+pub struct Chunking {
+    /// Split insertions longer than this; 0 inserts everything at once
+    pub chunk_chars: usize,
+    pub delay: Duration,
+    /// Insertions longer than this are split even when `chunk_chars` is 0;
+    /// 0 turns the guard off
+    pub large_insert_chars: usize,
+}
+
+impl Chunking {
+    pub fn from_settings(settings: &crate::AppSettings) -> Self {
+        Self {
+            chunk_chars: settings.insert_chunk_chars,
+            delay: Duration::from_millis(settings.insert_chunk_delay_ms),
+            large_insert_chars: settings.large_insert_chars,
+        }
+    }
+
+    /// Whether `text` is too large to paste in one go. Rich text is pasted
+    /// whole, so large rich text is typed as plain text instead.
+    pub fn is_large(&self, text: &str) -> bool {
+        self.large_insert_chars > 0 && text.chars().count() > self.large_insert_chars
     }
+
+    fn chunk_size(&self, text: &str) -> usize {
+        if self.chunk_chars > 0 {
+            self.chunk_chars
+        } else if self.is_large(text) {
+            LARGE_INSERT_CHUNK_CHARS
+        } else {
+            0
+        }
+    }
+}
+
+/// Progress of a split insertion, sent as `insert-progress`.
+#[derive(Clone, serde::Serialize)]
+pub struct InsertProgress {
+    pub inserted: usize,
+    pub total: usize,
+}
+
+/// `type_text` in pieces, cut after whitespace where possible, with a
+/// pause between them. Some apps truncate or drop large pastes, and a
+/// single huge paste makes some (Java) apps hang. `on_progress` gets the
+/// characters inserted so far and the total after each piece. Cancelling
+/// `cancel` stops before the next piece; what is typed stays.
+pub fn type_text_chunked(
+    text: &str,
+    target_window_id: &str,
+    chunking: Chunking,
+    cancel: &tokio_util::sync::CancellationToken,
+    on_progress: impl Fn(InsertProgress),
+) -> Result<(), String> {
+    let chunk_chars = chunking.chunk_size(text);
+    let total = text.chars().count();
+    if chunk_chars == 0 || total <= chunk_chars {
+        return type_text(text, target_window_id);
+    }
+    let chunks = split_chunks(text, chunk_chars);
+    log::info!("Inserting {} characters in {} chunks", total, chunks.len());
+    let mut inserted = 0;
+    for (i, chunk) in chunks.iter().enumerate() {
+        if i > 0 && crate::stop::wait(cancel, chunking.delay) {
+            log::info!("Insertion cancelled after {} of {} characters", inserted, total);
+            return Ok(());
+        }
+        type_text(chunk, target_window_id).map_err(|e| {
+            format!("Chunk {} of {} failed after {} characters: {}", i + 1, chunks.len(), inserted, e)
+        })?;
+        inserted += chunk.chars().count();
+        on_progress(InsertProgress { inserted, total });
+    }
+    Ok(())
+}
+
+fn split_chunks(text: &str, max_chars: usize) -> Vec<&str> {
+    let mut chunks = Vec::new();
+    let mut rest = text;
+    while !rest.is_empty() {
+        let end = rest.char_indices().nth(max_chars).map_or(rest.len(), |(i, _)| i);
+        if end == rest.len() {
+            chunks.push(rest);
+            break;
+        }
+        // Keep the whitespace at the end of the chunk so words stay separated
+        let cut = rest[..end]
+            .char_indices()
+            .rev()
+            .find(|(_, c)| c.is_whitespace())
+            .map(|(i, c)| i + c.len_utf8())
+            .unwrap_or(end);
+        chunks.push(&rest[..cut]);
+        rest = &rest[cut..];
+    }
+    chunks
 }
 
 /// Linux text insertion: process-based tools first, clipboard fallback last.
+/// Returns the method that worked.
 #[cfg(target_os = "linux")]
-fn type_text_linux(text: &str) -> Result<(), String> {
+fn type_text_linux(text: &str) -> Result<&'static str, String> {
     let wayland = is_wayland();
     log::info!(
         "type_text_linux: session={}, text='{}'",
@@ -205,7 +537,7 @@ fn type_text_linux(text: &str) -> Result<(), String> {
             match type_via_ydotool(text) {
                 Ok(()) => {
                     log::debug!("ydotool succeeded");
-                    return Ok(());
+                    return Ok("ydotool");
                 }
                 Err(e) => log::warn!("ydotool failed: {}", e),
             }
@@ -215,7 +547,7 @@ fn type_text_linux(text: &str) -> Result<(), String> {
             match type_via_wtype(text) {
                 Ok(()) => {
                     log::debug!("wtype succeeded");
-                    return Ok(());
+                    return Ok("wtype");
                 }
                 Err(e) => log::warn!("wtype failed: {}", e),
             }
@@ -225,10 +557,10 @@ fn type_text_linux(text: &str) -> Result<(), String> {
     } else {
         // X11 tier: xclip+xdotool paste (fast) -> arboard clipboard paste
         if command_exists("xclip") && command_exists("xdotool") {
-            match type_via_xclip_paste(text) {
+            match type_via_xclip_paste(text, None) {
                 Ok(()) => {
                     log::debug!("xclip+xdotool paste succeeded");
-                    return Ok(());
+                    return Ok("xclip-paste");
                 }
                 Err(e) => log::warn!("xclip+xdotool paste failed: {}", e),
             }
@@ -237,23 +569,24 @@ fn type_text_linux(text: &str) -> Result<(), String> {
 
     // Final fallback for both X11 and Wayland
     log::info!("Falling back to clipboard paste");
-    type_text_clipboard(text)
+    type_text_clipboard(text).map(|()| "clipboard-paste")
 }
 
 /// Non-Linux (macOS, Windows): enigo.text() first, clipboard fallback.
+/// Returns the method that worked.
 #[cfg(not(target_os = "linux"))]
-fn type_text_nonlinux(text: &str) -> Result<(), String> {
+fn type_text_nonlinux(text: &str) -> Result<&'static str, String> {
     match type_text_enigo(text) {
         Ok(()) => {
             log::debug!("enigo.text() succeeded");
-            return Ok(());
+            return Ok("enigo");
         }
         Err(e) => {
             log::warn!("enigo.text() failed ({}), falling back to clipboard paste", e);
         }
     }
 
-    type_text_clipboard(text)
+    type_text_clipboard(text).map(|()| "clipboard-paste")
 }
 
 /// Use enigo.text() for direct keystroke input, wrapped in catch_unwind
@@ -302,10 +635,14 @@ fn type_text_clipboard(text: &str) -> Result<(), String> {
     clipboard
         .set_text(text.to_string())
         .map_err(|e| format!("Failed to set clipboard: {}", e))?;
+    if !wait_until_held(|| clipboard.get_text().ok().as_deref() == Some(text)) {
+        return Err(crate::i18n::t("clipboard-not-held"));
+    }
 
     // Small delay to let the clipboard settle before simulating paste.
     // Some compositors/apps need time to register the new clipboard content.
-    thread::sleep(Duration::from_millis(30));
+    let delays = clipboard_delays();
+    thread::sleep(delays.settle);
 
     // Simulate paste shortcut
     simulate_paste()?;
@@ -313,7 +650,7 @@ fn type_text_clipboard(text: &str) -> Result<(), String> {
     // Delay to allow the target application to read from the clipboard.
     // This is the CRITICAL fix: without this delay, restoring the old clipboard
     // content races with the target app's paste read.
-    thread::sleep(Duration::from_millis(150));
+    thread::sleep(delays.read);
 
     // Restore previous clipboard contents
     if let Some(prev) = previous {
@@ -326,6 +663,126 @@ fn type_text_clipboard(text: &str) -> Result<(), String> {
     Ok(())
 }
 
+/// Paste `text` as rich text: the clipboard carries both the rendered
+/// `html` and the plain text, so apps that understand HTML (mail clients,
+/// document editors) keep the formatting and everything else gets plain text.
+///
+/// Targets on another X display fall back to plain `type_text`, since the
+/// clipboard library only talks to this process's display.
+pub fn paste_rich_text(text: &str, html: &str, target_window_id: &str) -> Result<(), String> {
+    if text.is_empty() {
+        return Ok(());
+    }
+    if crate::faults::fire(crate::faults::Fault::TypingFailure) {
+        return Err("Injected typing failure".to_string());
+    }
+
+    #[cfg(target_os = "linux")]
+    if target_display(target_window_id).is_some() {
+        return type_text(text, target_window_id);
+    }
+    focus::refocus(target_window_id);
+
+    let mut clipboard = Clipboard::new().map_err(|e| format!("Clipboard init failed: {}", e))?;
+    let previous = clipboard.get_text().ok();
+
+    clipboard
+        .set_html(html.to_string(), Some(text.to_string()))
+        .map_err(|e| format!("Failed to set HTML clipboard: {}", e))?;
+    let delays = clipboard_delays();
+    thread::sleep(delays.settle);
+
+    // Same delays as type_text_clipboard: the target must read the clipboard
+    // before the previous contents are restored
+    simulate_paste()?;
+    thread::sleep(delays.read);
+
+    if let Some(prev) = previous {
+        if let Err(e) = clipboard.set_text(prev) {
+            log::warn!("Failed to restore previous clipboard: {}", e);
+        }
+    }
+
+    crate::journal::record("insert", "rich-paste", target_window_id, text);
+    Ok(())
+}
+
+/// Delete `count` characters before the cursor with Backspace presses.
+///
+/// Uses the same tools as typing: xdotool on X11 (and other displays),
+/// ydotool or wtype on Wayland, enigo elsewhere or when none is available.
+pub fn erase_chars(count: usize, target_window_id: &str) -> Result<(), String> {
+    if count == 0 {
+        return Ok(());
+    }
+    let method = erase_with_backspace(count, target_window_id)?;
+    crate::journal::record("erase", method, target_window_id, &count.to_string());
+    Ok(())
+}
+
+/// `erase_chars`; returns the method that worked.
+fn erase_with_backspace(count: usize, target_window_id: &str) -> Result<&'static str, String> {
+    #[cfg(target_os = "linux")]
+    {
+        let display = target_display(target_window_id);
+        if display.is_none() {
+            focus::refocus(target_window_id);
+        }
+        if display.is_some() || (!is_wayland() && command_exists("xdotool")) {
+            let status = x11_command("xdotool", display)
+                .args(["key", "--clearmodifiers", "--delay", "0", "--repeat", &count.to_string(), "BackSpace"])
+                .status()
+                .map_err(|e| format!("xdotool exec failed: {}", e))?;
+            return if status.success() {
+                Ok("xdotool")
+            } else {
+                Err(format!("xdotool exited with status: {}", status))
+            };
+        }
+        if is_wayland() && command_exists("ydotool") {
+            // 14 is KEY_BACKSPACE; each press is a key down and a key up
+            let mut command = Command::new("ydotool");
+            command.arg("key");
+            for _ in 0..count {
+                command.args(["14:1", "14:0"]);
+            }
+            match command.status() {
+                Ok(status) if status.success() => return Ok("ydotool"),
+                Ok(status) => log::warn!("ydotool exited with status: {}", status),
+                Err(e) => log::warn!("ydotool exec failed: {}", e),
+            }
+        }
+        if is_wayland() && command_exists("wtype") {
+            let mut command = Command::new("wtype");
+            for _ in 0..count {
+                command.args(["-k", "BackSpace"]);
+            }
+            match command.status() {
+                Ok(status) if status.success() => return Ok("wtype"),
+                Ok(status) => log::warn!("wtype exited with status: {}", status),
+                Err(e) => log::warn!("wtype exec failed: {}", e),
+            }
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    focus::refocus(target_window_id);
+
+    let result = catch_unwind(AssertUnwindSafe(|| -> Result<(), String> {
+        let mut enigo = Enigo::new(&Settings::default())
+            .map_err(|e| format!("Enigo init failed: {:?}", e))?;
+        for _ in 0..count {
+            enigo
+                .key(Key::Backspace, Direction::Click)
+                .map_err(|e| format!("Failed to click Backspace: {:?}", e))?;
+        }
+        Ok(())
+    }));
+    result
+        .unwrap_or_else(|_| Err("enigo panicked while erasing".to_string()))
+        .map(|()| "enigo")
+}
+
 /// Simulate the platform-specific paste keyboard shortcut.
 /// Wrapped in catch_unwind to handle enigo internal panics.
 fn simulate_paste() -> Result<(), String> {
@@ -377,3 +834,31 @@ fn simulate_paste() -> Result<(), String> {
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn short_text_is_one_chunk() {
+        assert_eq!(split_chunks("hello world", 100), vec!["hello world"]);
+    }
+
+    #[test]
+    fn splits_after_whitespace() {
+        let chunks = split_chunks("one two three four", 9);
+        assert_eq!(chunks, vec!["one two ", "three ", "four"]);
+        assert_eq!(chunks.concat(), "one two three four");
+    }
+
+    #[test]
+    fn splits_long_words_at_the_limit() {
+        assert_eq!(split_chunks("abcdefghij", 4), vec!["abcd", "efgh", "ij"]);
+    }
+
+    #[test]
+    fn counts_characters_not_bytes() {
+        let chunks = split_chunks("şöyle güzel", 7);
+        assert_eq!(chunks, vec!["şöyle ", "güzel"]);
+    }
+}
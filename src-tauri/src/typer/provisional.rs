@@ -0,0 +1,61 @@
+//! Provisional typing: non-final text is typed as soon as it is recognized
+//! and corrected in place when it changes or becomes final.
+//!
+//! Only the provisional tail after the last final insertion is tracked.
+//! A correction keeps the part both versions share and replaces the rest
+//! with Backspace presses and retyping, so a change in the last word costs
+//! a few keystrokes rather than the whole line. It assumes the cursor
+//! stays at the end of the inserted text; moving it meanwhile corrupts
+//! the correction.
+
+/// The provisional text currently on screen.
+#[derive(Default)]
+pub struct ProvisionalText {
+    on_screen: String,
+}
+
+impl ProvisionalText {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replace the provisional text on screen with `text`.
+    pub fn show(&mut self, text: &str, target_window_id: &str) -> Result<(), String> {
+        let keep = self.erase_after_common_prefix(text, target_window_id)?;
+        super::type_text(&text[keep..], target_window_id)?;
+        self.on_screen = text.to_string();
+        Ok(())
+    }
+
+    /// Prepare to insert `final_text` over the provisional text: the part
+    /// of the provisional text that differs is erased, and the rest of
+    /// `final_text` still to be inserted is returned. Afterwards nothing
+    /// provisional is on screen.
+    pub fn settle<'a>(&mut self, final_text: &'a str, target_window_id: &str) -> Result<&'a str, String> {
+        let keep = self.erase_after_common_prefix(final_text, target_window_id)?;
+        Ok(&final_text[keep..])
+    }
+
+    /// Erase the provisional text, e.g. when the session ends without it
+    /// becoming final.
+    pub fn clear(&mut self, target_window_id: &str) -> Result<(), String> {
+        self.settle("", target_window_id).map(|_| ())
+    }
+
+    /// Erase what follows the longest common prefix of the screen and
+    /// `text`, returning the prefix length in bytes of `text`.
+    fn erase_after_common_prefix(&mut self, text: &str, target_window_id: &str) -> Result<usize, String> {
+        let (keep, kept_chars) = self
+            .on_screen
+            .chars()
+            .zip(text.chars())
+            .take_while(|(a, b)| a == b)
+            .fold((0, 0), |(bytes, chars), (c, _)| (bytes + c.len_utf8(), chars + 1));
+        let erase = self.on_screen.chars().count() - kept_chars;
+        // Forget the text before erasing: if that fails, the screen state is
+        // unknown and tracking it further would only do more damage
+        self.on_screen.clear();
+        super::erase_chars(erase, target_window_id)?;
+        Ok(keep)
+    }
+}
@@ -0,0 +1,140 @@
+//! Audio usage per provider and day, for estimating the provider bill.
+//!
+//! Seconds of audio handed to the provider are added up when a session
+//! (or file transcription) ends and kept in `usage.json` as
+//! `{ "2024-05-31": { "soniox": 812.4 } }`, by local date. Comparison
+//! streams are billed too and count toward Soniox.
+//!
+//! Dictation sessions are also counted per provider and day under
+//! `sessions`, with how many failed and their own audio length, for the
+//! local analytics.
+
+use std::collections::BTreeMap;
+use tauri::AppHandle;
+
+const USAGE_STORE_PATH: &str = "usage.json";
+
+type Days = BTreeMap<String, BTreeMap<String, f64>>;
+type SessionDays = BTreeMap<String, BTreeMap<String, SessionCounts>>;
+
+/// Dictation sessions with one provider, on one day or in total.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct SessionCounts {
+    pub sessions: u64,
+    pub failed: u64,
+    /// Audio of the sessions themselves, without comparison streams
+    pub seconds: f64,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct UsageDay {
+    /// Local date, YYYY-MM-DD
+    pub date: String,
+    pub provider: String,
+    pub seconds: f64,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct UsageStats {
+    /// Oldest first
+    pub days: Vec<UsageDay>,
+    /// Seconds per provider in the current calendar month
+    pub month_seconds: BTreeMap<String, f64>,
+    /// This month's Soniox audio at `soniox_price_per_hour`
+    pub month_soniox_cost: f64,
+}
+
+pub fn provider_id(provider: crate::TranscriptionProvider) -> String {
+    serde_json::to_value(provider)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+fn load(app: &AppHandle) -> Result<Days, String> {
+    let store = crate::persist::store(app, USAGE_STORE_PATH)?;
+    Ok(store.get("days").and_then(|v| serde_json::from_value(v).ok()).unwrap_or_default())
+}
+
+fn load_sessions(app: &AppHandle) -> Result<SessionDays, String> {
+    let store = crate::persist::store(app, USAGE_STORE_PATH)?;
+    Ok(store.get("sessions").and_then(|v| serde_json::from_value(v).ok()).unwrap_or_default())
+}
+
+/// Count a dictation session of `seconds` of audio with `provider` today.
+pub fn record_session(app: &AppHandle, provider: crate::TranscriptionProvider, seconds: f64, failed: bool) {
+    let result = load_sessions(app).and_then(|mut days| {
+        let counts = days
+            .entry(crate::text::template::today())
+            .or_default()
+            .entry(provider_id(provider))
+            .or_default();
+        counts.sessions += 1;
+        counts.failed += failed as u64;
+        counts.seconds += seconds;
+        let store = crate::persist::store(app, USAGE_STORE_PATH)?;
+        store.set("sessions", serde_json::to_value(&days).map_err(|e| e.to_string())?);
+        crate::persist::schedule(app, USAGE_STORE_PATH);
+        Ok(())
+    });
+    if let Err(e) = result {
+        log::warn!("Failed to record session: {}", e);
+    }
+}
+
+/// Dictation sessions per provider over all recorded days.
+pub fn session_totals(app: &AppHandle) -> Result<BTreeMap<String, SessionCounts>, String> {
+    let mut totals: BTreeMap<String, SessionCounts> = BTreeMap::new();
+    for (provider, counts) in load_sessions(app)?.into_values().flatten() {
+        let total = totals.entry(provider).or_default();
+        total.sessions += counts.sessions;
+        total.failed += counts.failed;
+        total.seconds += counts.seconds;
+    }
+    Ok(totals)
+}
+
+/// Add `seconds` of audio sent to `provider` today.
+pub fn record(app: &AppHandle, provider: crate::TranscriptionProvider, seconds: f64) {
+    if seconds <= 0.0 {
+        return;
+    }
+    let result = load(app).and_then(|mut days| {
+        *days
+            .entry(crate::text::template::today())
+            .or_default()
+            .entry(provider_id(provider))
+            .or_default() += seconds;
+        let store = crate::persist::store(app, USAGE_STORE_PATH)?;
+        store.set("days", serde_json::to_value(&days).map_err(|e| e.to_string())?);
+        crate::persist::schedule(app, USAGE_STORE_PATH);
+        Ok(())
+    });
+    if let Err(e) = result {
+        log::warn!("Failed to record usage: {}", e);
+    }
+}
+
+pub fn stats(app: &AppHandle, soniox_price_per_hour: f64) -> Result<UsageStats, String> {
+    let days = load(app)?;
+    let month = crate::text::template::today()[..7].to_string();
+    let mut month_seconds: BTreeMap<String, f64> = BTreeMap::new();
+    for providers in days.iter().filter(|(date, _)| date.starts_with(&month)).map(|(_, p)| p) {
+        for (provider, seconds) in providers {
+            *month_seconds.entry(provider.clone()).or_default() += seconds;
+        }
+    }
+    let soniox = month_seconds.get(&provider_id(crate::TranscriptionProvider::Soniox)).copied().unwrap_or(0.0);
+    Ok(UsageStats {
+        days: days
+            .into_iter()
+            .flat_map(|(date, providers)| {
+                providers
+                    .into_iter()
+                    .map(move |(provider, seconds)| UsageDay { date: date.clone(), provider, seconds })
+            })
+            .collect(),
+        month_seconds,
+        month_soniox_cost: soniox / 3600.0 * soniox_price_per_hour,
+    })
+}
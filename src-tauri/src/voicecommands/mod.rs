@@ -0,0 +1,310 @@
+//! Voice commands while idle, recognized locally with Vosk.
+//!
+//! With `voice_commands` on, the microphone stays open whenever no session
+//! is running, and a Vosk recognizer restricted to a small grammar listens
+//! for the phrases in `voice_command_phrases` (phrase -> palette action,
+//! e.g. "start dictation" -> `start`), plus "switch to <profile>" for every
+//! profile. Audio never leaves the machine and is dropped once the
+//! recognizer has seen it; anything that is not a command is recognized
+//! as unknown and ignored.
+//!
+//! While a session records, the idle listener pauses and only the phrases
+//! mapped to `stop` (e.g. "stop dictation") are listened for, on the
+//! session's own audio rather than a second microphone stream. The
+//! provider hears that phrase too, so it can end up in the transcript.
+//! Needs the `vosk` feature and a downloaded model (`vosk_model`).
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::sync::mpsc;
+
+// How often the listener checks for a started session or a stop request
+const POLL: Duration = Duration::from_millis(200);
+
+/// Model and phrases of the running listener, and the flag that stops it.
+static LISTENER: Mutex<Option<(String, BTreeMap<String, String>, Arc<AtomicBool>)>> = Mutex::new(None);
+
+/// Start, restart or stop the listener to match the settings. Called at
+/// startup and whenever settings are saved.
+pub fn sync(app: &AppHandle) {
+    let wanted = {
+        let state = app.state::<crate::AppState>();
+        let settings = state.settings.lock().unwrap();
+        settings
+            .voice_commands
+            .then(|| (settings.vosk_model.trim().to_string(), phrases(&settings)))
+    };
+
+    let mut current = LISTENER.lock().unwrap();
+    if current.as_ref().map(|(model, phrases, _)| (model, phrases)) == wanted.as_ref().map(|(m, p)| (m, p)) {
+        return;
+    }
+    if let Some((_, _, stop)) = current.take() {
+        stop.store(true, Ordering::SeqCst);
+    }
+    let Some((model, phrases)) = wanted else { return };
+    if model.is_empty() {
+        report(app, "Voice commands need a Vosk model; download one in the Vosk settings".to_string());
+        return;
+    }
+    log::info!("Listening for {} voice commands", phrases.len());
+    let stop = Arc::new(AtomicBool::new(false));
+    tauri::async_runtime::spawn(run(app.clone(), model.clone(), phrases.clone(), stop.clone()));
+    *current = Some((model, phrases, stop));
+}
+
+/// Phrase -> action, normalized, including one profile switch per profile.
+fn phrases(settings: &crate::AppSettings) -> BTreeMap<String, String> {
+    let mut phrases: BTreeMap<String, String> = settings
+        .profiles
+        .keys()
+        .map(|name| (normalize(&format!("switch to {}", name)), format!("switch_profile:{}", name)))
+        .collect();
+    phrases.insert("switch to default profile".to_string(), "switch_profile".to_string());
+    for (phrase, action) in &settings.voice_command_phrases {
+        let phrase = normalize(phrase);
+        if !phrase.is_empty() {
+            phrases.insert(phrase, action.clone());
+        }
+    }
+    phrases
+}
+
+/// Vosk reports lowercase words separated by single spaces.
+fn normalize(phrase: &str) -> String {
+    phrase.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+fn report(app: &AppHandle, message: String) {
+    log::error!("Voice commands: {}", message);
+    crate::console::record(app, "error", format!("Voice commands: {}", message));
+}
+
+fn is_recording(app: &AppHandle) -> bool {
+    app.state::<crate::AppState>().is_recording.load(Ordering::SeqCst)
+}
+
+/// Forget the listener behind `stop` once it has exited, so the next
+/// `sync` starts a new one instead of taking the dead one for running.
+fn clear(stop: &Arc<AtomicBool>) {
+    let mut current = LISTENER.lock().unwrap();
+    if current.as_ref().is_some_and(|(_, _, running)| Arc::ptr_eq(running, stop)) {
+        *current = None;
+    }
+}
+
+async fn run(app: AppHandle, model: String, phrases: BTreeMap<String, String>, stop: Arc<AtomicBool>) {
+    if let Err(e) = listen_until_stopped(&app, &model, &phrases, &stop).await {
+        report(&app, e);
+    }
+    clear(&stop);
+    log::info!("Voice commands stopped");
+}
+
+async fn listen_until_stopped(
+    app: &AppHandle,
+    model: &str,
+    phrases: &BTreeMap<String, String>,
+    stop: &AtomicBool,
+) -> Result<(), String> {
+    let model_dir = crate::vosk::models_dir(app)?.join(model);
+    if !model_dir.is_dir() {
+        return Err(format!("Vosk model '{}' is not downloaded", model));
+    }
+
+    while !stop.load(Ordering::SeqCst) {
+        if is_recording(app) {
+            tokio::time::sleep(POLL).await;
+            continue;
+        }
+        if let Some((phrase, action)) = listen(app, &model_dir, phrases, stop).await? {
+            log::info!("Voice command '{}' -> {}", phrase, action);
+            crate::console::record(app, "session", format!("Voice command: {}", phrase));
+            if let Err(e) = crate::actions::invoke(app, &action).await {
+                report(app, e);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Pass session audio through, listening on it for the phrases mapped to
+/// `stop` and stopping the session when one is heard. Returns `rx` as is
+/// unless the listener runs and some phrase stops.
+pub fn watch_for_stop(
+    mut rx: mpsc::Receiver<crate::audio::AudioChunk>,
+    app: AppHandle,
+) -> mpsc::Receiver<crate::audio::AudioChunk> {
+    let (model, grammar) = {
+        let current = LISTENER.lock().unwrap();
+        let Some((model, phrases, _)) = current.as_ref() else { return rx };
+        let grammar: Vec<String> = phrases
+            .iter()
+            .filter(|(_, action)| action.as_str() == "stop")
+            .map(|(phrase, _)| phrase.clone())
+            .collect();
+        (model.clone(), grammar)
+    };
+    if grammar.is_empty() {
+        return rx;
+    }
+    let model_dir = match crate::vosk::models_dir(&app) {
+        Ok(dir) => dir.join(model),
+        Err(e) => {
+            report(&app, e);
+            return rx;
+        }
+    };
+
+    let (chunk_tx, chunk_rx) = std::sync::mpsc::channel::<Vec<i16>>();
+    let (heard_tx, mut heard_rx) = mpsc::unbounded_channel::<Result<String, String>>();
+    tokio::task::spawn_blocking(move || recognize(&model_dir, &grammar, chunk_rx, heard_tx));
+
+    let (tx, watched_rx) = mpsc::channel::<crate::audio::AudioChunk>(100);
+    tokio::spawn(async move {
+        let mut listening = true;
+        loop {
+            tokio::select! {
+                chunk = rx.recv() => {
+                    let Some(chunk) = chunk else { break };
+                    if listening {
+                        chunk_tx.send(samples(&chunk.data)).ok();
+                    }
+                    if tx.send(chunk).await.is_err() {
+                        break;
+                    }
+                }
+                heard = heard_rx.recv(), if listening => {
+                    listening = false;
+                    match heard {
+                        Some(Ok(phrase)) => {
+                            log::info!("Voice command '{}' -> stop", phrase);
+                            if crate::stop_active_session(&app) {
+                                crate::console::record(&app, "session", format!("Voice command: {}", phrase));
+                            }
+                        }
+                        Some(Err(e)) => report(&app, e),
+                        None => {}
+                    }
+                }
+            }
+        }
+        // Closing the channel ends the recognizer thread
+        drop(chunk_tx);
+    });
+    watched_rx
+}
+
+/// pcm16 little-endian bytes as samples.
+fn samples(data: &[u8]) -> Vec<i16> {
+    data.chunks_exact(2).map(|b| i16::from_le_bytes([b[0], b[1]])).collect()
+}
+
+/// Listen until a phrase is heard, a session starts or the listener is
+/// stopped. The microphone is closed again before returning.
+async fn listen(
+    app: &AppHandle,
+    model_dir: &Path,
+    phrases: &BTreeMap<String, String>,
+    stop: &AtomicBool,
+) -> Result<Option<(String, String)>, String> {
+    let mic_stop = tokio_util::sync::CancellationToken::new();
+    let (audio_tx, mut audio_rx) = mpsc::channel::<crate::audio::AudioChunk>(100);
+    let (mic, _) = crate::audio::spawn_mic_capture(app, audio_tx, mic_stop.clone())?;
+
+    let (chunk_tx, chunk_rx) = std::sync::mpsc::channel::<Vec<i16>>();
+    let (heard_tx, mut heard_rx) = mpsc::unbounded_channel::<Result<String, String>>();
+    let grammar: Vec<String> = phrases.keys().cloned().collect();
+    let dir = model_dir.to_path_buf();
+    let worker = tokio::task::spawn_blocking(move || recognize(&dir, &grammar, chunk_rx, heard_tx));
+
+    let mut poll = tokio::time::interval(POLL);
+    let result = loop {
+        tokio::select! {
+            chunk = audio_rx.recv() => match chunk {
+                Some(chunk) => {
+                    chunk_tx.send(samples(&chunk.data)).ok();
+                }
+                None => break Err("Microphone stream ended".to_string()),
+            },
+            _ = poll.tick() => {
+                if stop.load(Ordering::SeqCst) || is_recording(app) {
+                    break Ok(None);
+                }
+            }
+            heard = heard_rx.recv() => match heard {
+                Some(Ok(phrase)) => {
+                    if let Some(action) = phrases.get(&phrase) {
+                        break Ok(Some((phrase, action.clone())));
+                    }
+                }
+                Some(Err(e)) => break Err(e),
+                None => break Err("Voice command recognizer stopped".to_string()),
+            },
+        }
+    };
+
+    mic_stop.cancel();
+    // Dropping the receiver unblocks a capture callback waiting on a full channel
+    drop(audio_rx);
+    drop(chunk_tx);
+    worker.await.ok();
+    // The capture thread wakes as soon as the stop signal fires
+    tokio::task::spawn_blocking(move || mic.join()).await.ok();
+    result
+}
+
+#[cfg(feature = "vosk")]
+const SAMPLE_RATE: f32 = 16000.0;
+
+/// Feed chunks to a grammar-restricted recognizer until the channel closes,
+/// reporting each recognized phrase.
+#[cfg(feature = "vosk")]
+fn recognize(
+    model_dir: &Path,
+    grammar: &[String],
+    chunks: std::sync::mpsc::Receiver<Vec<i16>>,
+    heard: mpsc::UnboundedSender<Result<String, String>>,
+) {
+    let model = match crate::vosk::load_model(model_dir) {
+        Ok(model) => model,
+        Err(e) => {
+            heard.send(Err(e)).ok();
+            return;
+        }
+    };
+    // "[unk]" absorbs other speech instead of forcing it onto the closest phrase
+    let mut grammar: Vec<&str> = grammar.iter().map(String::as_str).collect();
+    grammar.push("[unk]");
+    let Some(mut recognizer) = ::vosk::Recognizer::new_with_grammar(&model, SAMPLE_RATE, &grammar) else {
+        heard.send(Err("Failed to create Vosk recognizer".to_string())).ok();
+        return;
+    };
+
+    for samples in chunks {
+        if let Ok(::vosk::DecodingState::Finalized) = recognizer.accept_waveform(&samples) {
+            if let Some(result) = recognizer.result().single() {
+                let text = result.text.trim();
+                if !text.is_empty() && !text.contains("[unk]") {
+                    heard.send(Ok(text.to_string())).ok();
+                }
+            }
+        }
+    }
+}
+
+#[cfg(not(feature = "vosk"))]
+fn recognize(
+    _model_dir: &Path,
+    _grammar: &[String],
+    _chunks: std::sync::mpsc::Receiver<Vec<i16>>,
+    heard: mpsc::UnboundedSender<Result<String, String>>,
+) {
+    heard
+        .send(Err(crate::i18n::t("vosk-not-built")))
+        .ok();
+}
@@ -0,0 +1,286 @@
+//! Offline transcription with Vosk (Kaldi), plus model management.
+//!
+//! Unlike Whisper, Vosk recognizes a stream: audio is fed as it arrives,
+//! partial results drive the live preview and each endpoint (a pause the
+//! recognizer detects itself) yields a final utterance for the shared
+//! `output` path. Recognition runs on a blocking thread.
+//!
+//! Models are directories under `vosk-models/` in the app data directory.
+//! `download_model` fetches one of the published zips from alphacephei.com,
+//! emitting `model-download-progress` events while it runs.
+
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tauri::Emitter;
+use tokio::sync::mpsc;
+
+#[cfg_attr(not(feature = "vosk"), allow(dead_code))]
+const MODELS_URL: &str = "https://alphacephei.com/vosk/models";
+#[cfg_attr(not(feature = "vosk"), allow(dead_code))]
+const SAMPLE_RATE: f32 = 16000.0;
+
+#[cfg_attr(not(feature = "vosk"), allow(dead_code))]
+enum Recognized {
+    Partial(String),
+    Final(String),
+}
+
+#[derive(Clone, Serialize)]
+pub struct DownloadProgress {
+    pub name: String,
+    pub downloaded: u64,
+    /// Zip size, when the server reports it
+    pub total: Option<u64>,
+    /// "downloading", "extracting", "done" or "failed"
+    pub stage: String,
+}
+
+/// `vosk-models/` in the app data directory.
+pub fn models_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    crate::datadir::dir(app).map(|dir| dir.join("vosk-models"))
+}
+
+/// Names of the models already downloaded.
+pub fn installed_models(app: &tauri::AppHandle) -> Vec<String> {
+    let Ok(dir) = models_dir(app) else { return Vec::new() };
+    let mut names: Vec<String> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|e| e.path().is_dir())
+                .filter_map(|e| e.file_name().into_string().ok())
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names
+}
+
+pub async fn transcribe(
+    settings: crate::AppSettings,
+    stop_signal: tokio_util::sync::CancellationToken,
+    audio_rx: &mut mpsc::Receiver<crate::audio::AudioChunk>,
+    app: tauri::AppHandle,
+    target_window_id: String,
+) -> Result<(), String> {
+    let name = settings.vosk_model.trim();
+    if name.is_empty() {
+        return Err(crate::i18n::t("vosk-model-missing"));
+    }
+    let model_dir = models_dir(&app)?.join(name);
+    if !model_dir.is_dir() {
+        return Err(crate::i18n::t_args("vosk-model-not-downloaded", &[("name", name.to_string())]));
+    }
+
+    let (chunk_tx, chunk_rx) = std::sync::mpsc::channel::<Vec<i16>>();
+    let (result_tx, mut result_rx) = mpsc::unbounded_channel::<Result<Recognized, String>>();
+    let worker = tokio::task::spawn_blocking(move || recognize_stream(&model_dir, chunk_rx, result_tx));
+
+    let mut output = crate::output::Utterances::new(settings.clone(), app.clone(), target_window_id);
+    output.begin().await;
+    // Dropped when the audio ends, which makes the worker flush its final result
+    let mut chunk_tx = Some(chunk_tx);
+
+    loop {
+        tokio::select! {
+            chunk = audio_rx.recv(), if chunk_tx.is_some() => match chunk {
+                Some(chunk) if !stop_signal.is_cancelled() => {
+                    let samples = chunk
+                        .data
+                        .chunks_exact(2)
+                        .map(|b| i16::from_le_bytes([b[0], b[1]]))
+                        .collect();
+                    if let Some(tx) = &chunk_tx {
+                        tx.send(samples).ok();
+                    }
+                }
+                _ => chunk_tx = None,
+            },
+            _ = stop_signal.cancelled(), if chunk_tx.is_some() => chunk_tx = None,
+            result = result_rx.recv() => match result {
+                Some(Ok(Recognized::Partial(text))) => {
+                    app.emit("partial-text", text).ok();
+                }
+                Some(Ok(Recognized::Final(text))) => output.deliver(&text).await,
+                Some(Err(e)) => {
+                    log::error!("Vosk: {}", e);
+                    crate::console::record(&app, "error", format!("Vosk: {}", e));
+                    app.emit("transcription-error", e.clone()).ok();
+                    output.finish();
+                    return Err(e);
+                }
+                // The worker exits after flushing the last utterance
+                None => break,
+            },
+        }
+    }
+
+    worker.await.map_err(|e| format!("Vosk worker failed: {}", e))?;
+    output.finish();
+    Ok(())
+}
+
+/// Feed chunks to a recognizer until the channel closes, reporting partial
+/// and final results.
+#[cfg(feature = "vosk")]
+fn recognize_stream(
+    model_dir: &Path,
+    chunks: std::sync::mpsc::Receiver<Vec<i16>>,
+    results: mpsc::UnboundedSender<Result<Recognized, String>>,
+) {
+    let model = match load_model(model_dir) {
+        Ok(model) => model,
+        Err(e) => {
+            results.send(Err(e)).ok();
+            return;
+        }
+    };
+    // `::vosk` is the library crate, which this module shares its name with
+    let Some(mut recognizer) = ::vosk::Recognizer::new(&model, SAMPLE_RATE) else {
+        results.send(Err("Failed to create Vosk recognizer".to_string())).ok();
+        return;
+    };
+    log::info!("Vosk model loaded, transcribing locally");
+
+    let mut last_partial = String::new();
+    for samples in chunks {
+        match recognizer.accept_waveform(&samples) {
+            Ok(::vosk::DecodingState::Finalized) => {
+                if let Some(result) = recognizer.result().single() {
+                    results.send(Ok(Recognized::Final(result.text.to_string()))).ok();
+                }
+                last_partial.clear();
+            }
+            Ok(::vosk::DecodingState::Running) => {
+                let partial = recognizer.partial_result().partial.to_string();
+                if !partial.is_empty() && partial != last_partial {
+                    last_partial = partial.clone();
+                    results.send(Ok(Recognized::Partial(partial))).ok();
+                }
+            }
+            Ok(::vosk::DecodingState::Failed) => log::warn!("Vosk failed to decode a chunk"),
+            Err(e) => log::warn!("Vosk rejected a chunk: {:?}", e),
+        }
+    }
+    if let Some(result) = recognizer.final_result().single() {
+        results.send(Ok(Recognized::Final(result.text.to_string()))).ok();
+    }
+}
+
+/// Load a model, reusing the last loaded one if it is the same directory.
+#[cfg(feature = "vosk")]
+pub fn load_model(dir: &Path) -> Result<Arc<::vosk::Model>, String> {
+    use std::sync::Mutex;
+
+    // Loading a model takes seconds; keep the last one between sessions
+    static LOADED: Mutex<Option<(PathBuf, Arc<::vosk::Model>)>> = Mutex::new(None);
+
+    let mut loaded = LOADED.lock().unwrap();
+    if let Some((loaded_dir, model)) = loaded.as_ref() {
+        if loaded_dir == dir {
+            return Ok(model.clone());
+        }
+    }
+    let model = ::vosk::Model::new(dir.to_string_lossy())
+        .map(Arc::new)
+        .ok_or_else(|| format!("Failed to load Vosk model from {:?}", dir))?;
+    *loaded = Some((dir.to_path_buf(), model.clone()));
+    Ok(model)
+}
+
+#[cfg(not(feature = "vosk"))]
+fn recognize_stream(
+    _model_dir: &Path,
+    _chunks: std::sync::mpsc::Receiver<Vec<i16>>,
+    results: mpsc::UnboundedSender<Result<Recognized, String>>,
+) {
+    results.send(Err(crate::i18n::t("vosk-not-built"))).ok();
+}
+
+/// Download and unpack model `name` into the models directory.
+#[cfg(feature = "vosk")]
+pub async fn download_model(app: &tauri::AppHandle, name: &str) -> Result<(), String> {
+    use futures_util::StreamExt;
+    use tokio::io::AsyncWriteExt;
+
+    // The name ends up in a URL and a path; only allow published model names
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.' || c == '_') {
+        return Err(format!("Invalid model name '{}'", name));
+    }
+    let dir = models_dir(app)?;
+    tokio::fs::create_dir_all(&dir).await.map_err(|e| e.to_string())?;
+
+    let progress = |downloaded: u64, total: Option<u64>, stage: &str| {
+        app.emit(
+            "model-download-progress",
+            DownloadProgress { name: name.to_string(), downloaded, total, stage: stage.to_string() },
+        )
+        .ok();
+    };
+
+    let result = async {
+        let url = format!("{}/{}.zip", MODELS_URL, name);
+        log::info!("Downloading Vosk model from {}", url);
+        let response = reqwest::get(&url)
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| format!("Download of {} failed: {}", url, e))?;
+        let total = response.content_length();
+
+        let zip_path = dir.join(format!("{}.zip.part", name));
+        let mut file = tokio::fs::File::create(&zip_path).await.map_err(|e| e.to_string())?;
+        let mut downloaded = 0u64;
+        // Report about every 1% instead of every network chunk
+        let step = total.map_or(1 << 20, |t| (t / 100).max(1));
+        let mut next_report = 0u64;
+        let mut stream = response.bytes_stream();
+        while let Some(bytes) = stream.next().await {
+            let bytes = bytes.map_err(|e| format!("Download interrupted: {}", e))?;
+            file.write_all(&bytes).await.map_err(|e| e.to_string())?;
+            downloaded += bytes.len() as u64;
+            if downloaded >= next_report {
+                progress(downloaded, total, "downloading");
+                next_report = downloaded + step;
+            }
+        }
+        file.flush().await.map_err(|e| e.to_string())?;
+        drop(file);
+
+        progress(downloaded, total, "extracting");
+        let target = dir.clone();
+        let archive = zip_path.clone();
+        tokio::task::spawn_blocking(move || -> Result<(), String> {
+            let file = std::fs::File::open(&archive).map_err(|e| e.to_string())?;
+            let mut zip = zip::ZipArchive::new(file).map_err(|e| format!("Invalid model archive: {}", e))?;
+            zip.extract(&target).map_err(|e| format!("Extracting model failed: {}", e))
+        })
+        .await
+        .map_err(|e| e.to_string())??;
+        tokio::fs::remove_file(&zip_path).await.ok();
+
+        if !dir.join(name).is_dir() {
+            return Err(format!("Archive did not contain a '{}' directory", name));
+        }
+        Ok(downloaded)
+    }
+    .await;
+
+    match result {
+        Ok(size) => {
+            log::info!("Vosk model '{}' installed", name);
+            progress(size, Some(size), "done");
+            Ok(())
+        }
+        Err(e) => {
+            log::error!("{}", e);
+            progress(0, None, "failed");
+            Err(e)
+        }
+    }
+}
+
+#[cfg(not(feature = "vosk"))]
+pub async fn download_model(_app: &tauri::AppHandle, _name: &str) -> Result<(), String> {
+    Err(crate::i18n::t("vosk-not-built"))
+}
@@ -0,0 +1,83 @@
+//! Keeps the global hotkey alive.
+//!
+//! Some desktops silently drop global shortcut grabs when the display
+//! configuration changes (monitor hotplug, resolution or scale change). The
+//! watchdog polls the monitor layout and re-registers the hotkeys when it
+//! changed or the main hotkey is no longer registered; a working grab is
+//! left alone. A failed re-registration is surfaced to the UI once.
+
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_global_shortcut::GlobalShortcutExt;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Start the watchdog thread. Called once at startup, after the first
+/// registration.
+pub fn spawn(app: AppHandle) {
+    std::thread::spawn(move || {
+        let mut layout = monitor_layout(&app);
+        // Set after a failed re-registration so the UI is only told once
+        let mut reported_failure = false;
+        loop {
+            std::thread::sleep(CHECK_INTERVAL);
+
+            // Never pull the hotkey out from under a running session
+            if app.state::<crate::AppState>().is_recording.load(Ordering::SeqCst) {
+                continue;
+            }
+
+            let current = monitor_layout(&app);
+            let reason = if current != layout {
+                layout = current;
+                "hotkey-reason-display-changed"
+            } else if !main_hotkey_registered(&app) {
+                "hotkey-reason-unregistered"
+            } else {
+                continue;
+            };
+            let reason = crate::i18n::t(reason);
+
+            log::warn!("Re-registering global hotkey: {}", reason);
+            match crate::register_hotkey(&app) {
+                Ok(()) => {
+                    reported_failure = false;
+                    crate::console::record(&app, "session", format!("Hotkey re-registered ({})", reason));
+                }
+                Err(e) => {
+                    log::error!("Failed to re-register hotkey: {}", e);
+                    if !reported_failure {
+                        reported_failure = true;
+                        app.emit(
+                            "recording-error",
+                            crate::i18n::t_args("hotkey-lost", &[("reason", reason), ("error", e)]),
+                        )
+                        .ok();
+                    }
+                }
+            }
+        }
+    });
+}
+
+/// Position, size and scale of every monitor, for change detection.
+fn monitor_layout(app: &AppHandle) -> Vec<(i32, i32, u32, u32, u64)> {
+    app.available_monitors()
+        .unwrap_or_default()
+        .iter()
+        .map(|m| {
+            let (pos, size) = (m.position(), m.size());
+            (pos.x, pos.y, size.width, size.height, m.scale_factor().to_bits())
+        })
+        .collect()
+}
+
+fn main_hotkey_registered(app: &AppHandle) -> bool {
+    let hotkey = app.state::<crate::AppState>().settings.lock().unwrap().hotkey.clone();
+    match hotkey.parse::<tauri_plugin_global_shortcut::Shortcut>() {
+        Ok(shortcut) => app.global_shortcut().is_registered(shortcut),
+        // An invalid hotkey can't be fixed by re-registering
+        Err(_) => true,
+    }
+}
@@ -0,0 +1,223 @@
+//! Offline transcription with whisper.cpp (through whisper-rs).
+//!
+//! Whisper is not a streaming recognizer, so the session is cut into
+//! utterances with a simple energy gate: speech followed by a pause (or a
+//! segment reaching Whisper's 30 s window) is transcribed, processed and
+//! typed like a batch of Soniox final tokens. While an utterance is still
+//! open it is re-transcribed every couple of seconds for the live preview.
+//! Previews are `partial-text` events; finished utterances go through the
+//! shared `output` path like any other provider.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tauri::Emitter;
+use tokio::sync::mpsc;
+
+const SAMPLE_RATE: usize = 16000;
+// RMS level (of i16 full scale) above which a chunk counts as speech
+const SPEECH_RMS: f64 = 500.0;
+// Pause that ends an utterance
+const END_OF_UTTERANCE_MS: usize = 700;
+// Whisper processes at most 30 s per call; cut a little earlier
+const MAX_SEGMENT_MS: usize = 25_000;
+// How much new audio triggers another preview transcription
+const PREVIEW_INTERVAL_MS: usize = 2_000;
+
+enum Job {
+    /// Open utterance, for the live preview only
+    Preview(Vec<f32>),
+    /// Finished utterance: processed, typed and kept for history
+    Final(Vec<f32>),
+}
+
+pub async fn transcribe(
+    settings: crate::AppSettings,
+    stop_signal: tokio_util::sync::CancellationToken,
+    audio_rx: &mut mpsc::Receiver<crate::audio::AudioChunk>,
+    app: tauri::AppHandle,
+    target_window_id: String,
+) -> Result<(), String> {
+    let model_path = settings
+        .whisper_model_path
+        .clone()
+        .filter(|p| !p.is_empty())
+        .ok_or("No Whisper model file configured")?;
+    let recognizer = tokio::task::spawn_blocking(move || Recognizer::load(&model_path))
+        .await
+        .map_err(|e| format!("Loading Whisper model failed: {}", e))??;
+    log::info!("Whisper model loaded, transcribing locally");
+
+    let (job_tx, job_rx) = mpsc::unbounded_channel::<Job>();
+    // Set while a preview is being transcribed, so previews never queue up
+    let preview_busy = Arc::new(AtomicBool::new(false));
+    let worker = tokio::spawn(run_jobs(
+        recognizer,
+        job_rx,
+        preview_busy.clone(),
+        crate::output::Utterances::new(settings.clone(), app.clone(), target_window_id),
+        settings.language_hints.first().cloned(),
+        app.clone(),
+    ));
+
+    let mut segment: Vec<f32> = Vec::new();
+    let mut silence_samples = 0usize;
+    let mut since_preview = 0usize;
+
+    loop {
+        let chunk = tokio::select! {
+            chunk = audio_rx.recv() => match chunk {
+                Some(chunk) => chunk,
+                None => break,
+            },
+            _ = stop_signal.cancelled() => break,
+        };
+
+        let samples: Vec<f32> = chunk
+            .data
+            .chunks_exact(2)
+            .map(|b| i16::from_le_bytes([b[0], b[1]]) as f32 / 32768.0)
+            .collect();
+        let rms = (samples.iter().map(|s| (*s as f64 * 32768.0).powi(2)).sum::<f64>()
+            / samples.len().max(1) as f64)
+            .sqrt();
+        let speech = rms > SPEECH_RMS;
+
+        // Leading silence is dropped; once speech started, pauses are kept
+        if segment.is_empty() && !speech {
+            continue;
+        }
+        silence_samples = if speech { 0 } else { silence_samples + samples.len() };
+        since_preview += samples.len();
+        segment.extend_from_slice(&samples);
+
+        let paused = silence_samples >= END_OF_UTTERANCE_MS * SAMPLE_RATE / 1000;
+        let full = segment.len() >= MAX_SEGMENT_MS * SAMPLE_RATE / 1000;
+        if paused || full {
+            job_tx.send(Job::Final(std::mem::take(&mut segment))).ok();
+            silence_samples = 0;
+            since_preview = 0;
+        } else if since_preview >= PREVIEW_INTERVAL_MS * SAMPLE_RATE / 1000
+            && !preview_busy.swap(true, Ordering::SeqCst)
+        {
+            job_tx.send(Job::Preview(segment.clone())).ok();
+            since_preview = 0;
+        }
+    }
+
+    if !segment.is_empty() {
+        job_tx.send(Job::Final(segment)).ok();
+    }
+    drop(job_tx);
+
+    let output = worker.await.map_err(|e| format!("Whisper worker failed: {}", e))?;
+    output.finish();
+    Ok(())
+}
+
+/// Transcribe jobs in order, delivering finished utterances to `output`.
+async fn run_jobs(
+    recognizer: Recognizer,
+    mut jobs: mpsc::UnboundedReceiver<Job>,
+    preview_busy: Arc<AtomicBool>,
+    mut output: crate::output::Utterances,
+    language: Option<String>,
+    app: tauri::AppHandle,
+) -> crate::output::Utterances {
+    let recognizer = Arc::new(recognizer);
+    output.begin().await;
+
+    while let Some(job) = jobs.recv().await {
+        let (samples, is_final) = match job {
+            Job::Preview(samples) => (samples, false),
+            Job::Final(samples) => (samples, true),
+        };
+        let r = recognizer.clone();
+        let lang = language.clone();
+        let result = tokio::task::spawn_blocking(move || r.recognize(&samples, lang.as_deref())).await;
+        if !is_final {
+            preview_busy.store(false, Ordering::SeqCst);
+        }
+
+        let text = match result {
+            Ok(Ok(text)) => text,
+            Ok(Err(e)) => {
+                log::error!("Whisper transcription failed: {}", e);
+                crate::console::record(&app, "error", format!("Whisper: {}", e));
+                app.emit("transcription-error", e).ok();
+                continue;
+            }
+            Err(e) => {
+                log::error!("Whisper task failed: {}", e);
+                continue;
+            }
+        };
+        if is_final {
+            output.deliver(&text).await;
+        } else if !text.trim().is_empty() {
+            app.emit("partial-text", text.trim().to_string()).ok();
+        }
+    }
+
+    output
+}
+
+#[cfg(feature = "whisper")]
+struct Recognizer {
+    context: Arc<whisper_rs::WhisperContext>,
+}
+
+#[cfg(feature = "whisper")]
+impl Recognizer {
+    /// Load `path`, reusing the last loaded model if it is the same file.
+    fn load(path: &str) -> Result<Self, String> {
+        use std::sync::Mutex;
+
+        // Loading a model takes seconds; keep the last one between sessions
+        static LOADED: Mutex<Option<(String, Arc<whisper_rs::WhisperContext>)>> = Mutex::new(None);
+
+        let mut loaded = LOADED.lock().unwrap();
+        if let Some((loaded_path, context)) = loaded.as_ref() {
+            if loaded_path == path {
+                return Ok(Self { context: context.clone() });
+            }
+        }
+        let context = whisper_rs::WhisperContext::new_with_params(path, whisper_rs::WhisperContextParameters::default())
+            .map_err(|e| format!("Failed to load Whisper model '{}': {}", path, e))?;
+        let context = Arc::new(context);
+        *loaded = Some((path.to_string(), context.clone()));
+        Ok(Self { context })
+    }
+
+    fn recognize(&self, samples: &[f32], language: Option<&str>) -> Result<String, String> {
+        let mut state = self.context.create_state().map_err(|e| e.to_string())?;
+        let mut params = whisper_rs::FullParams::new(whisper_rs::SamplingStrategy::Greedy { best_of: 1 });
+        params.set_language(Some(language.unwrap_or("auto")));
+        params.set_print_progress(false);
+        params.set_print_realtime(false);
+        params.set_print_special(false);
+        params.set_print_timestamps(false);
+        params.set_suppress_blank(true);
+
+        state.full(params, samples).map_err(|e| e.to_string())?;
+        let segments = state.full_n_segments().map_err(|e| e.to_string())?;
+        let mut text = String::new();
+        for i in 0..segments {
+            text.push_str(&state.full_get_segment_text(i).map_err(|e| e.to_string())?);
+        }
+        Ok(text)
+    }
+}
+
+#[cfg(not(feature = "whisper"))]
+struct Recognizer;
+
+#[cfg(not(feature = "whisper"))]
+impl Recognizer {
+    fn load(_path: &str) -> Result<Self, String> {
+        Err(crate::i18n::t("whisper-not-built"))
+    }
+
+    fn recognize(&self, _samples: &[f32], _language: Option<&str>) -> Result<String, String> {
+        unreachable!("a Recognizer is never constructed without the whisper feature")
+    }
+}
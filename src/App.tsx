@@ -2,47 +2,351 @@ import { useState, useEffect, useRef } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
 import Settings from "./components/Settings";
+import CommandPalette from "./components/CommandPalette";
 
 interface AppSettings {
+  provider: "soniox" | "openai" | "azure" | "whisper" | "vosk";
+  quality_preset: QualityPreset;
+  whisper_model_path: string | null;
+  vosk_model: string;
+  openai_api_key: string;
+  openai_model: string;
+  rewrite_endpoint: string;
+  rewrite_model: string;
+  rewrite_api_key: string;
+  azure_speech_key: string;
+  azure_region: string;
+  provider_punctuation: "automatic" | "spoken" | "off";
   api_key: string;
   hotkey: string;
+  ui_language: string;
+  tray_left_click: string;
   language_hints: string[];
   language_restrictions: string[] | null;
+  translation_target_language: string | null;
+  pronunciations: { word: string; sounds_like: string[] }[];
+  vocabulary: string[];
+  text_processors: ({ type: string; enabled?: boolean } & Record<string, unknown>)[];
+  audio_fixture_path?: string | null;
+  include_partial_in_history: boolean;
+  context_carry_over: boolean;
+  plain_ascii_output: boolean;
+  rich_text_paste: boolean;
+  provisional_typing: boolean;
+  audit_journal: boolean;
+  local_analytics: boolean;
+  comparison: { enabled: boolean; model: string; credential: string | null; language_hints: string[] | null };
+  save_session_audio: boolean;
+  calendar_ics: string | null;
+  watch_folder: string | null;
+  watch_folder_write_text: boolean;
+  voice_commands: boolean;
+  voice_command_phrases: Record<string, string>;
+  selected_device: string | null;
+  battery_saver: boolean;
+  review_while_sharing: boolean;
+  refocus_target: boolean;
+  duck_media: boolean;
+  duck_volume_percent: number;
+  history_merge_window_secs: number;
+  auto_stop_silence_secs: number;
+  hotkey_cooldown_ms: number;
+  partial_text_interval_ms: number;
+  endpoint_detection: boolean;
+  soniox_model: string;
+  audio_chunk_ms: number;
+  speech_threshold: number;
+  non_final_flush_ms: number;
+  finish_timeout_ms: number;
+  session_rollover_mins: number;
+  insert_chunk_chars: number;
+  insert_chunk_delay_ms: number;
+  large_insert_chars: number;
+  clipboard_settle_ms: number;
+  clipboard_read_ms: number;
+  debug_raw_tokens: boolean;
+  overlay_placement: "screen_top" | "near_target";
+  unload_hidden_windows: boolean;
+  overlay_backend: "webview" | "native";
+  connect_timeout_ms: number;
+  handshake_timeout_ms: number;
+  connect_retries: number;
+  keep_connection_warm: boolean;
+  soniox_price_per_hour: number;
+  soniox_endpoint: string | null;
+  network_overrides: Record<string, { force_ipv4: boolean; resolve_to: string | null; sni_host: string | null }>;
+  credentials: Record<string, { api_key: string }>;
+  active_credential: string | null;
 }
 
 interface TranscriptionEntry {
+  id: string;
   text: string;
   timestamp: number;
   language: string;
+  audio_path: string | null;
+  words: WordTiming[] | null;
+  comparison: { label: string; text: string } | null;
+  app?: { app_id: string; title: string; pid: number | null } | null;
+  title?: string | null;
+  source?: EntrySource;
+  merged?: boolean;
+}
+
+type EntrySource = "live" | "file" | "import";
+
+interface WordTiming {
+  word: string;
+  start_ms: number;
+  end_ms: number;
+  language?: string | null;
+}
+
+interface ProviderStatus {
+  last_connected_at: number | null;
+  last_error: string | null;
+  last_error_at: number | null;
+  credential_valid: boolean | null;
+}
+
+interface StoreIssue {
+  store: string;
+  error: string;
+  restored_from: string | null;
+  corrupt_copy: string | null;
+}
+
+interface UnsentInsertion {
+  seq: number;
+  target: string;
+  text: string;
+  queued_at: number;
+}
+
+interface LanguageDetected {
+  language: string;
+  languages: string[];
+}
+
+interface Conflict {
+  kind: "dictation" | "hotkey" | "typing";
+  name: string;
+  message: string;
+}
+
+interface ProviderError {
+  code: string;
+  message: string;
+}
+
+// Soniox and audio capture errors; other sources still send plain strings
+interface TranscriptionError {
+  kind: "auth" | "quota" | "network" | "audio-device" | "typing" | "unsupported-language" | "provider";
+  message: string;
+}
+
+type QualityPreset = "custom" | "fast" | "balanced" | "accurate";
+
+interface RawToken {
+  text: string;
+  is_final: boolean;
+  speaker: number | null;
+  language: string | null;
+  start_ms: number | null;
+  end_ms: number | null;
+  translation_status: string | null;
+}
+
+interface RawTokens {
+  tokens: RawToken[];
+  finished: boolean;
+  stream_offset_ms: number;
+}
+
+// Responses kept in the raw token panel
+const RAW_TOKENS_LIMIT = 50;
+
+interface ConnectionRetry {
+  attempt: number;
+  attempts: number;
+  delay_ms: number;
+  error: string;
+}
+
+interface TranscriptionPage {
+  entries: TranscriptionEntry[];
+  total: number;
+}
+
+interface SessionStats {
+  words: number;
+  wpm: number;
+  speaking_ms: number;
+}
+
+interface ImportReport {
+  found: number;
+  imported: number;
+  duplicates: number;
 }
 
 type View = "home" | "settings" | "history";
 
+const HISTORY_PAGE_SIZE = 20;
+
+// The error message plus what the user can do about it
+function describeError(error: string | TranscriptionError): string {
+  if (typeof error === "string") return error;
+  const { kind, message } = error;
+  switch (kind) {
+    case "auth":
+      return `The transcription provider rejected the API key: ${message}. Please check your settings.`;
+    case "quota":
+      return `Soniox quota exceeded: ${message}. Top up your balance at https://console.soniox.com`;
+    case "network":
+      return `Could not reach the transcription provider: ${message}. Check your connection, VPN or proxy.`;
+    case "audio-device":
+      return `Microphone problem: ${message}. Check that an input device is connected and not used exclusively by another app.`;
+    case "typing":
+      return `Could not type into the target window: ${message}. Check the typing tools (xdotool, ydotool) and permissions.`;
+    case "unsupported-language":
+      return `Unsupported language: ${message}. Adjust language hints/restrictions in settings.`;
+    default:
+      return message;
+  }
+}
+
 function App() {
   const [settings, setSettings] = useState<AppSettings>({
+    provider: "soniox",
+    quality_preset: "custom",
+    whisper_model_path: null,
+    vosk_model: "",
+    openai_api_key: "",
+    openai_model: "gpt-4o-transcribe",
+    rewrite_endpoint: "https://api.openai.com/v1",
+    rewrite_model: "gpt-4o-mini",
+    rewrite_api_key: "",
+    azure_speech_key: "",
+    azure_region: "",
+    provider_punctuation: "automatic",
     api_key: "",
     hotkey: "",
+    ui_language: "en",
+    tray_left_click: "show_window",
     language_hints: ["en"],
     language_restrictions: null,
+    translation_target_language: null,
+    pronunciations: [],
+    vocabulary: [],
+    text_processors: [],
+    include_partial_in_history: false,
+    context_carry_over: false,
+    plain_ascii_output: false,
+    rich_text_paste: false,
+    provisional_typing: false,
+    audit_journal: false,
+    local_analytics: false,
+    comparison: { enabled: false, model: "", credential: null, language_hints: null },
+    save_session_audio: false,
+    calendar_ics: null,
+    watch_folder: null,
+    watch_folder_write_text: false,
+    voice_commands: false,
+    voice_command_phrases: { "start dictation": "start", "stop dictation": "stop", "open settings": "show_window" },
+    selected_device: null,
+    battery_saver: false,
+    review_while_sharing: false,
+    refocus_target: false,
+    duck_media: false,
+    duck_volume_percent: 20,
+    history_merge_window_secs: 0,
+    auto_stop_silence_secs: 0,
+    hotkey_cooldown_ms: 300,
+    partial_text_interval_ms: 100,
+    endpoint_detection: true,
+    soniox_model: "",
+    audio_chunk_ms: 0,
+    speech_threshold: 500,
+    non_final_flush_ms: 0,
+    finish_timeout_ms: 5000,
+    session_rollover_mins: 55,
+    insert_chunk_chars: 2000,
+    insert_chunk_delay_ms: 50,
+    large_insert_chars: 8000,
+    clipboard_settle_ms: 30,
+    clipboard_read_ms: 150,
+    debug_raw_tokens: false,
+    overlay_placement: "screen_top",
+    unload_hidden_windows: false,
+    overlay_backend: "webview",
+    connect_timeout_ms: 5000,
+    handshake_timeout_ms: 10000,
+    connect_retries: 2,
+    keep_connection_warm: false,
+    soniox_price_per_hour: 0.12,
+    soniox_endpoint: null,
+    network_overrides: {},
+    credentials: {},
+    active_credential: null,
   });
   const [isRecording, setIsRecording] = useState(false);
   const isRecordingRef = useRef(false);
   const [currentView, setCurrentView] = useState<View>("home");
   const [partialText, setPartialText] = useState("");
   const [sessionText, setSessionText] = useState("");
+  const [sessionStats, setSessionStats] = useState<SessionStats | null>(null);
+  const [rawTokens, setRawTokens] = useState<RawTokens[]>([]);
+  const [detectedLanguages, setDetectedLanguages] = useState<string[]>([]);
   const [error, setError] = useState<string | null>(null);
+  // Set when screen sharing keeps the session's text from being typed
+  const [reviewNotice, setReviewNotice] = useState<string | null>(null);
+  // Incognito mode (command palette): sessions are not saved
+  const [incognito, setIncognito] = useState(false);
   const [history, setHistory] = useState<TranscriptionEntry[]>([]);
+  const [historyTotal, setHistoryTotal] = useState(0);
+  const [historyFilter, setHistoryFilter] = useState("");
+  const historyFilterRef = useRef("");
+  const [historySource, setHistorySource] = useState<EntrySource | "">("");
+  const historySourceRef = useRef<EntrySource | "">("");
   const [copiedIndex, setCopiedIndex] = useState<number | null>(null);
+  const [exported, setExported] = useState<{ id: string; path: string } | null>(null);
+  // History entry being re-transcribed: its hints input and whether the job runs
+  const [retranscribing, setRetranscribing] = useState<{ id: string; hints: string; running: boolean } | null>(null);
+  const [providerStatus, setProviderStatus] = useState<ProviderStatus | null>(null);
+  const [storeIssues, setStoreIssues] = useState<StoreIssue[]>([]);
+  const [conflicts, setConflicts] = useState<Conflict[]>([]);
+  const [unsent, setUnsent] = useState<UnsentInsertion[]>([]);
+  const [paletteOpen, setPaletteOpen] = useState(false);
+  const [importOpen, setImportOpen] = useState(false);
+  const [importPath, setImportPath] = useState("");
+  const [importFormat, setImportFormat] = useState("text-folder");
+  const [importResult, setImportResult] = useState("");
 
   useEffect(() => {
     loadSettings();
     loadHistory();
+    loadProviderStatus();
+    loadStoreIssues();
+    loadConflicts();
+    loadUnsent();
     setupEventListeners();
     return () => {
       cleanupEventListeners();
     };
   }, []);
 
+  useEffect(() => {
+    function handleKeyDown(e: KeyboardEvent) {
+      if ((e.ctrlKey || e.metaKey) && e.key.toLowerCase() === "k") {
+        e.preventDefault();
+        setPaletteOpen((open) => !open);
+      }
+    }
+    window.addEventListener("keydown", handleKeyDown);
+    return () => window.removeEventListener("keydown", handleKeyDown);
+  }, []);
+
   async function loadSettings() {
     try {
       const s = await invoke<AppSettings>("get_settings");
@@ -54,25 +358,146 @@ function App() {
 
   async function loadHistory() {
     try {
-      const entries = await invoke<TranscriptionEntry[]>("get_transcriptions");
-      setHistory(entries);
+      const page = await invoke<TranscriptionPage>("get_transcriptions_page", {
+        offset: 0,
+        limit: HISTORY_PAGE_SIZE,
+        filter: historyFilterRef.current,
+        source: historySourceRef.current || null,
+      });
+      setHistory(page.entries);
+      setHistoryTotal(page.total);
+    } catch (e) {
+      console.error("Failed to load transcription history:", e);
+    }
+  }
+
+  async function loadMoreHistory() {
+    try {
+      const page = await invoke<TranscriptionPage>("get_transcriptions_page", {
+        offset: history.length,
+        limit: HISTORY_PAGE_SIZE,
+        filter: historyFilterRef.current,
+        source: historySourceRef.current || null,
+      });
+      setHistory([...history, ...page.entries]);
+      setHistoryTotal(page.total);
     } catch (e) {
       console.error("Failed to load transcription history:", e);
     }
   }
 
+  function filterHistory(filter: string) {
+    setHistoryFilter(filter);
+    historyFilterRef.current = filter;
+    loadHistory();
+  }
+
+  function filterHistorySource(source: EntrySource | "") {
+    setHistorySource(source);
+    historySourceRef.current = source;
+    loadHistory();
+  }
+
+  async function loadStoreIssues() {
+    try {
+      setStoreIssues(await invoke<StoreIssue[]>("get_store_issues"));
+    } catch (e) {
+      console.error("Failed to load store issues:", e);
+    }
+  }
+
+  async function dismissStoreIssues() {
+    try {
+      await invoke("dismiss_store_issues");
+    } catch (e) {
+      console.error("Failed to dismiss store issues:", e);
+    }
+    setStoreIssues([]);
+  }
+
+  async function loadUnsent() {
+    try {
+      setUnsent(await invoke<UnsentInsertion[]>("get_unsent_insertions"));
+    } catch (e) {
+      console.error("Failed to load unsent insertions:", e);
+    }
+  }
+
+  async function replayUnsent() {
+    try {
+      await invoke("replay_unsent_insertions");
+    } catch (e) {
+      setError(String(e));
+    }
+    setUnsent([]);
+  }
+
+  async function discardUnsent() {
+    try {
+      await invoke("discard_unsent_insertions");
+    } catch (e) {
+      console.error("Failed to discard unsent insertions:", e);
+    }
+    setUnsent([]);
+  }
+
+  async function loadConflicts() {
+    try {
+      setConflicts(await invoke<Conflict[]>("get_conflicts"));
+    } catch (e) {
+      console.error("Failed to load conflicts:", e);
+    }
+  }
+
+  async function dismissConflicts() {
+    try {
+      await invoke("dismiss_conflicts");
+    } catch (e) {
+      console.error("Failed to dismiss conflicts:", e);
+    }
+    setConflicts([]);
+  }
+
+  async function loadProviderStatus() {
+    try {
+      setProviderStatus(await invoke<ProviderStatus>("get_provider_status"));
+    } catch (e) {
+      console.error("Failed to load provider status:", e);
+    }
+  }
+
   async function setupEventListeners() {
     await listen("recording-started", () => {
       setIsRecording(true);
       isRecordingRef.current = true;
       setError(null);
       setSessionText("");
+      setSessionStats(null);
+      setRawTokens([]);
+      setDetectedLanguages([]);
+      setReviewNotice(null);
+    });
+
+    await listen("incognito-changed", (event) => {
+      setIncognito(event.payload as boolean);
+    });
+
+    await listen("review-mode", (event) => {
+      setReviewNotice(event.payload as string);
     });
 
     await listen("recording-stopped", () => {
       setIsRecording(false);
       isRecordingRef.current = false;
-      // The session-complete event will handle saving
+      // The backend saves the session to history and sends history-changed
+    });
+
+    await listen("session-stats", (event) => {
+      setSessionStats(event.payload as SessionStats);
+    });
+
+    await listen("language-detected", (event) => {
+      setDetectedLanguages((event.payload as LanguageDetected).languages);
     });
 
     await listen("partial-text", (event) => {
@@ -87,26 +512,85 @@ function App() {
 
     await listen("session-complete", (event) => {
       const text = event.payload as string;
-      // Clean up whitespace and save to history
+      // Clean up whitespace for the preview
       const cleanedText = text
         .replace(/\s+/g, ' ')  // Normalize multiple spaces
         .replace(/ ([.,!?;:])/g, '$1')  // Remove space before punctuation
         .trim();
       if (cleanedText) {
         setSessionText(cleanedText);
-        saveTranscription(cleanedText);
       }
       setPartialText("");
     });
 
+    await listen("soniox-raw", (event) => {
+      const raw = event.payload as RawTokens;
+      setRawTokens((prev) => [...prev.slice(-(RAW_TOKENS_LIMIT - 1)), raw]);
+    });
+
+    await listen("conflicts-detected", (event) => {
+      setConflicts(event.payload as Conflict[]);
+    });
+
+    await listen("connection-retry", (event) => {
+      const { attempt, attempts } = event.payload as ConnectionRetry;
+      // Only a session shows it
+      if (isRecordingRef.current) {
+        setPartialText(`Reconnecting, attempt ${attempt}/${attempts}…`);
+      }
+    });
+
+    await listen("connection-recovered", () => {
+      setPartialText((prev) => (prev.startsWith("Reconnecting") ? "" : prev));
+    });
+
+    await listen("provider-status", (event) => {
+      setProviderStatus(event.payload as ProviderStatus);
+    });
+
+    await listen("settings-changed", (event) => {
+      setSettings(event.payload as AppSettings);
+      loadProviderStatus();
+    });
+
+    await listen("history-changed", () => {
+      loadHistory();
+    });
+
     await listen("transcription-error", (event) => {
-      setError(event.payload as string);
-      setIsRecording(false);
+      const error = event.payload as string | TranscriptionError;
+      setError(describeError(error));
+      // A failed insertion doesn't end the session
+      if (typeof error === "string" || error.kind !== "typing") {
+        setIsRecording(false);
+      }
+      if (typeof error !== "string" && error.kind === "auth") {
+        setCurrentView("settings");
+      }
     });
 
     await listen("recording-error", (event) => {
+      const error = event.payload as string | TranscriptionError;
+      setError(describeError(error));
+      setIsRecording(false);
+      if (typeof error !== "string" && error.kind === "auth") {
+        setCurrentView("settings");
+      }
+    });
+
+    await listen("microphone-muted", (event) => {
       setError(event.payload as string);
+    });
+
+    await listen("bluetooth-input-warning", (event) => {
+      setError(event.payload as string);
+    });
+
+    await listen("auth-error", (event) => {
+      const { message } = event.payload as ProviderError;
+      setError(`The transcription provider rejected the API key: ${message}. Please check your settings.`);
       setIsRecording(false);
+      setCurrentView("settings");
     });
   }
 
@@ -114,12 +598,64 @@ function App() {
     // Cleanup handled by Tauri
   }
 
-  async function saveTranscription(text: string) {
+  async function playSessionAudio(id: string, startMs?: number) {
+    try {
+      await invoke("play_session_audio", { id, startMs });
+    } catch (e) {
+      setError(String(e));
+    }
+  }
+
+  async function exportSubtitles(id: string, format: "srt" | "vtt") {
+    try {
+      const path = await invoke<string>("export_subtitles", { id, format });
+      setExported({ id, path });
+    } catch (e) {
+      setError(String(e));
+    }
+  }
+
+  async function retranscribeSession(id: string, hints: string) {
+    setRetranscribing({ id, hints, running: true });
+    try {
+      const languageHints = hints.split(",").map((h) => h.trim()).filter((h) => h);
+      await invoke<TranscriptionEntry>("retranscribe_session", { id, languageHints });
+      setRetranscribing(null);
+    } catch (e) {
+      setError(String(e));
+      setRetranscribing({ id, hints, running: false });
+    }
+  }
+
+  async function switchCredential(name: string | null) {
+    try {
+      const s = await invoke<AppSettings>("switch_credential", { name });
+      setSettings(s);
+      loadProviderStatus();
+    } catch (e) {
+      setError(String(e));
+    }
+  }
+
+  async function importHistory() {
+    if (importFormat === "audio") {
+      setImportResult("Transcribing (takes as long as the recording)...");
+      try {
+        await invoke<string>("transcribe_file", { path: importPath });
+        setImportResult("Transcribed and added to history");
+      } catch (e) {
+        setImportResult(String(e));
+      }
+      return;
+    }
     try {
-      await invoke("save_transcription", { text, languageHints: settings.language_hints });
-      await loadHistory();
+      const report = await invoke<ImportReport>("import_from", { path: importPath, format: importFormat });
+      setImportResult(
+        `Imported ${report.imported} of ${report.found}` +
+          (report.duplicates ? `, ${report.duplicates} already in history` : ""),
+      );
     } catch (e) {
-      console.error("Failed to save transcription:", e);
+      setImportResult(String(e));
     }
   }
 
@@ -127,6 +663,7 @@ function App() {
     try {
       await invoke("clear_transcriptions");
       setHistory([]);
+      setHistoryTotal(0);
     } catch (e) {
       console.error("Failed to clear history:", e);
     }
@@ -157,6 +694,7 @@ function App() {
       console.error("Failed to save settings:", e);
     }
     setCurrentView("home");
+    loadProviderStatus();
   }
 
   function copyToClipboard(text: string, index: number) {
@@ -209,6 +747,53 @@ function App() {
         </div>
       </header>
 
+      {paletteOpen && <CommandPalette onClose={() => setPaletteOpen(false)} onError={setError} />}
+
+      {storeIssues.length > 0 && (
+        <div className="error-message store-issues">
+          {storeIssues.map((issue) => (
+            <p key={issue.store}>
+              {issue.store} was corrupted ({issue.error}).{" "}
+              {issue.restored_from ? `Restored from ${issue.restored_from}.` : "No valid backup was found, so it was reset."}
+              {issue.corrupt_copy && ` The damaged file was kept at ${issue.corrupt_copy}.`}
+            </p>
+          ))}
+          <button className="clear-history-btn" onClick={dismissStoreIssues}>
+            Dismiss
+          </button>
+        </div>
+      )}
+
+      {unsent.length > 0 && (
+        <div className="error-message store-issues">
+          <p>
+            The app stopped before typing this text, so it was never inserted. It is not typed again automatically;
+            choose Type to insert it into the window behind this one.
+          </p>
+          <p>{unsent.map((insertion) => insertion.text).join("")}</p>
+          <button className="clear-history-btn" onClick={replayUnsent}>
+            Type
+          </button>
+          <button className="clear-history-btn" onClick={() => navigator.clipboard.writeText(unsent.map((i) => i.text).join(""))}>
+            Copy
+          </button>
+          <button className="clear-history-btn" onClick={discardUnsent}>
+            Discard
+          </button>
+        </div>
+      )}
+
+      {conflicts.length > 0 && (
+        <div className="error-message store-issues conflicts">
+          {conflicts.map((conflict) => (
+            <p key={`${conflict.kind}-${conflict.name}`}>{conflict.message}</p>
+          ))}
+          <button className="clear-history-btn" onClick={dismissConflicts}>
+            Dismiss
+          </button>
+        </div>
+      )}
+
       {currentView === "settings" ? (
         <Settings
           settings={settings}
@@ -219,13 +804,57 @@ function App() {
         <div className="history">
           <div className="history-header">
             <h2>Transcription History</h2>
-            {history.length > 0 && (
+            <button className="clear-history-btn" onClick={() => setImportOpen(!importOpen)}>
+              Import
+            </button>
+            {historyTotal > 0 && (
               <button className="clear-history-btn" onClick={clearHistory}>
                 Clear All
               </button>
             )}
           </div>
-          {history.length === 0 ? (
+          {importOpen && (
+            <div className="history-import">
+              <select value={importFormat} onChange={(e) => setImportFormat(e.target.value)}>
+                <option value="text-folder">Folder of .txt files</option>
+                <option value="nerd-dictation">nerd-dictation output</option>
+                <option value="talon">Talon log</option>
+                <option value="audio">Audio file (WAV/MP3/OGG), transcribe</option>
+              </select>
+              <input
+                type="text"
+                value={importPath}
+                onChange={(e) => setImportPath(e.target.value)}
+                placeholder={importFormat === "text-folder" ? "/path/to/folder" : "/path/to/file"}
+              />
+              <button className="clear-history-btn" disabled={!importPath.trim()} onClick={importHistory}>
+                Import
+              </button>
+              {importResult && <p className="field-help">{importResult}</p>}
+            </div>
+          )}
+          <input
+            className="history-search"
+            type="text"
+            value={historyFilter}
+            onChange={(e) => filterHistory(e.target.value)}
+            placeholder="Search history..."
+          />
+          <select
+            className="history-source"
+            value={historySource}
+            onChange={(e) => filterHistorySource(e.target.value as EntrySource | "")}
+          >
+            <option value="">All sources</option>
+            <option value="live">Dictation</option>
+            <option value="file">Files</option>
+            <option value="import">Imported</option>
+          </select>
+          {history.length === 0 && (historyFilter || historySource) ? (
+            <div className="history-empty">
+              <p>No matching transcriptions</p>
+            </div>
+          ) : history.length === 0 ? (
             <div className="history-empty">
               <svg width="48" height="48" viewBox="0 0 24 24" fill="none" stroke="#ccc" strokeWidth="1.5" strokeLinecap="round" strokeLinejoin="round">
                 <path d="M14 2H6a2 2 0 0 0-2 2v16a2 2 0 0 0 2 2h12a2 2 0 0 0 2-2V8z"/>
@@ -242,17 +871,94 @@ function App() {
                 <div key={`${entry.timestamp}-${i}`} className="history-item">
                   <div className="history-item-header">
                     <span className="history-time">{formatTimestamp(entry.timestamp)}</span>
+                    {entry.title && <span className="history-title">{entry.title}</span>}
+                    {entry.source && entry.source !== "live" && (
+                      <span className="history-lang">{entry.source}</span>
+                    )}
+                    {entry.app && (
+                      <span className="history-app" title={entry.app.title}>
+                        {entry.app.app_id || entry.app.title}
+                      </span>
+                    )}
                     <span className="history-lang">{entry.language || "N/A"}</span>
                   </div>
-                  <div className="history-text">{entry.text}</div>
+                  <div className="history-text">
+                    {entry.audio_path && entry.words && entry.words.length > 0
+                      ? entry.words.map((w, j) => (
+                          <span
+                            key={j}
+                            className="history-word"
+                            title={`Play from ${(w.start_ms / 1000).toFixed(1)}s${w.language ? ` (${w.language})` : ""}`}
+                            onClick={() => playSessionAudio(entry.id, w.start_ms)}
+                          >
+                            {w.word}{" "}
+                          </span>
+                        ))
+                      : entry.text}
+                  </div>
+                  {entry.comparison && (
+                    <div className="history-comparison">
+                      <span className="history-lang">{entry.comparison.label}</span> {entry.comparison.text}
+                    </div>
+                  )}
                   <button
                     className={`history-copy-btn ${copiedIndex === i ? "copied" : ""}`}
                     onClick={() => copyToClipboard(entry.text, i)}
                   >
                     {copiedIndex === i ? "Copied" : "Copy"}
                   </button>
+                  {entry.audio_path && (
+                    <button className="history-copy-btn" onClick={() => playSessionAudio(entry.id)}>
+                      Play
+                    </button>
+                  )}
+                  {entry.audio_path && !entry.merged && (
+                    <button
+                      className="history-copy-btn"
+                      onClick={() => setRetranscribing({ id: entry.id, hints: entry.language, running: false })}
+                    >
+                      Re-transcribe
+                    </button>
+                  )}
+                  {entry.words && entry.words.length > 0 && (
+                    <>
+                      <button className="history-copy-btn" onClick={() => exportSubtitles(entry.id, "srt")}>
+                        SRT
+                      </button>
+                      <button className="history-copy-btn" onClick={() => exportSubtitles(entry.id, "vtt")}>
+                        VTT
+                      </button>
+                    </>
+                  )}
+                  {exported?.id === entry.id && <p className="history-export-path">Saved to {exported.path}</p>}
+                  {retranscribing?.id === entry.id && (
+                    <div className="history-import">
+                      <input
+                        type="text"
+                        value={retranscribing.hints}
+                        onChange={(e) => setRetranscribing({ ...retranscribing, hints: e.target.value })}
+                        placeholder="Language hints, e.g. en,de"
+                        disabled={retranscribing.running}
+                      />
+                      <button
+                        className="clear-history-btn"
+                        disabled={retranscribing.running}
+                        onClick={() => retranscribeSession(entry.id, retranscribing.hints)}
+                      >
+                        {retranscribing.running ? "Transcribing…" : "Run"}
+                      </button>
+                      <button className="clear-history-btn" disabled={retranscribing.running} onClick={() => setRetranscribing(null)}>
+                        Cancel
+                      </button>
+                    </div>
+                  )}
                 </div>
               ))}
+              {history.length < historyTotal && (
+                <button className="clear-history-btn" onClick={loadMoreHistory}>
+                  Load more ({historyTotal - history.length} remaining)
+                </button>
+              )}
             </div>
           )}
         </div>
@@ -261,8 +967,36 @@ function App() {
           <div className="status">
             <div className={`status-indicator ${isRecording ? "recording" : ""}`} />
             <span>{isRecording ? "Recording..." : "Ready"}</span>
+            {sessionStats && (
+              <span className="session-stats">
+                {sessionStats.words} words{sessionStats.wpm > 0 && ` · ${Math.round(sessionStats.wpm)} wpm`}
+              </span>
+            )}
+            {detectedLanguages.length > 0 && (
+              <span className="session-stats" title="Detected languages, most spoken first">
+                {detectedLanguages.join(", ")}
+              </span>
+            )}
           </div>
 
+          {providerStatus && (
+            <div className="provider-status">
+              <span>
+                Soniox:{" "}
+                {providerStatus.credential_valid === false
+                  ? "API key rejected"
+                  : providerStatus.last_connected_at
+                    ? `connected ${formatTimestamp(providerStatus.last_connected_at)}`
+                    : "not connected yet"}
+              </span>
+              {providerStatus.last_error && (
+                <span className="provider-status-error" title={providerStatus.last_error}>
+                  Last error{providerStatus.last_error_at ? ` (${formatTimestamp(providerStatus.last_error_at)})` : ""}: {providerStatus.last_error}
+                </span>
+              )}
+            </div>
+          )}
+
           <div className="hotkey-display">
             <span className="label">Hotkey:</span>
             <span className="value">{settings.hotkey}</span>
@@ -273,8 +1007,44 @@ function App() {
             <span className="value">{settings.language_hints.length > 0 ? settings.language_hints.join(", ") : "Auto-detect"}</span>
           </div>
 
+          {Object.keys(settings.credentials).length > 0 && (
+            <div className="language-display">
+              <span className="label">Workspace:</span>
+              <select
+                value={settings.active_credential ?? ""}
+                onChange={(e) => switchCredential(e.target.value || null)}
+                disabled={isRecording}
+              >
+                <option value="">Default</option>
+                {Object.keys(settings.credentials).map((name) => (
+                  <option key={name} value={name}>{name}</option>
+                ))}
+              </select>
+            </div>
+          )}
+
           {error && <div className="error-message">{error}</div>}
 
+          {incognito && (
+            <div className="review-notice">
+              <span>Incognito mode is on: sessions are not saved to history or recorded.</span>
+            </div>
+          )}
+
+          {reviewNotice && (
+            <div className="review-notice">
+              <span>{reviewNotice}</span>
+              {!isRecording && sessionText && (
+                <button
+                  className={`history-copy-btn ${copiedIndex === -1 ? "copied" : ""}`}
+                  onClick={() => copyToClipboard(sessionText, -1)}
+                >
+                  {copiedIndex === -1 ? "Copied" : "Copy"}
+                </button>
+              )}
+            </div>
+          )}
+
           <div className="preview-area">
             <div className="preview-label">{isRecording ? "Recording..." : sessionText ? "Last Session:" : "Preview:"}</div>
             <div className="preview-text">
@@ -286,6 +1056,21 @@ function App() {
             </div>
           </div>
 
+          {settings.debug_raw_tokens && rawTokens.length > 0 && (
+            <details className="raw-tokens">
+              <summary>Raw tokens ({rawTokens.length} responses)</summary>
+              <pre>
+                {rawTokens
+                  .map((raw) =>
+                    raw.tokens
+                      .map((t) => `${t.is_final ? "F" : "·"} ${t.speaker ?? "-"} ${t.language ?? "--"} ${JSON.stringify(t.text)}`)
+                      .join("\n") + (raw.finished ? "\n[finished]" : ""),
+                  )
+                  .join("\n---\n")}
+              </pre>
+            </details>
+          )}
+
           <button
             className={`record-btn ${isRecording ? "recording" : ""}`}
             onClick={isRecording ? stopRecording : startRecording}
@@ -0,0 +1,85 @@
+import { useState, useEffect, useRef } from "react";
+import { invoke } from "@tauri-apps/api/core";
+
+interface ActionInfo {
+  name: string;
+  title: string;
+  enabled: boolean;
+}
+
+interface CommandPaletteProps {
+  onClose: () => void;
+  onError: (message: string) => void;
+}
+
+function CommandPalette({ onClose, onError }: CommandPaletteProps) {
+  const [actions, setActions] = useState<ActionInfo[]>([]);
+  const [query, setQuery] = useState("");
+  const [selected, setSelected] = useState(0);
+  const inputRef = useRef<HTMLInputElement>(null);
+
+  useEffect(() => {
+    invoke<ActionInfo[]>("list_actions")
+      .then(setActions)
+      .catch((e) => console.error("Failed to load actions:", e));
+    inputRef.current?.focus();
+  }, []);
+
+  const filtered = actions.filter((a) => a.enabled && a.title.toLowerCase().includes(query.toLowerCase()));
+
+  async function run(action: ActionInfo) {
+    onClose();
+    try {
+      await invoke("invoke_action", { name: action.name });
+    } catch (e) {
+      onError(String(e));
+    }
+  }
+
+  function handleKeyDown(e: React.KeyboardEvent) {
+    if (e.key === "Escape") {
+      onClose();
+    } else if (e.key === "ArrowDown") {
+      e.preventDefault();
+      setSelected((i) => Math.min(i + 1, filtered.length - 1));
+    } else if (e.key === "ArrowUp") {
+      e.preventDefault();
+      setSelected((i) => Math.max(i - 1, 0));
+    } else if (e.key === "Enter" && filtered[selected]) {
+      run(filtered[selected]);
+    }
+  }
+
+  return (
+    <div className="palette-backdrop" onClick={onClose}>
+      <div className="palette" onClick={(e) => e.stopPropagation()}>
+        <input
+          ref={inputRef}
+          type="text"
+          value={query}
+          onChange={(e) => {
+            setQuery(e.target.value);
+            setSelected(0);
+          }}
+          onKeyDown={handleKeyDown}
+          placeholder="Type a command..."
+        />
+        <div className="palette-list">
+          {filtered.map((action, i) => (
+            <div
+              key={action.name}
+              className={`palette-item ${i === selected ? "selected" : ""}`}
+              onMouseEnter={() => setSelected(i)}
+              onClick={() => run(action)}
+            >
+              {action.title}
+            </div>
+          ))}
+          {filtered.length === 0 && <div className="palette-empty">No matching commands</div>}
+        </div>
+      </div>
+    </div>
+  );
+}
+
+export default CommandPalette;
@@ -1,10 +1,120 @@
 import { useState, useEffect, useRef, useCallback } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import TypingTools from "./TypingTools";
+import VoskModels from "./VoskModels";
+
+type QualityPreset = "custom" | "fast" | "balanced" | "accurate";
+
+type TextProcessor = { type: string; enabled?: boolean } & Record<string, unknown>;
+
+type PunctuationProcessor = {
+  type: "punctuation";
+  enabled?: boolean;
+  sentence_case?: boolean;
+  trailing_period?: boolean;
+  quotes?: "keep" | "straight" | "smart";
+  ellipsis?: "keep" | "dots" | "character";
+};
 
 interface AppSettings {
+  provider: "soniox" | "openai" | "azure" | "whisper" | "vosk";
+  quality_preset: QualityPreset;
+  whisper_model_path: string | null;
+  vosk_model: string;
+  openai_api_key: string;
+  openai_model: string;
+  rewrite_endpoint: string;
+  rewrite_model: string;
+  rewrite_api_key: string;
+  azure_speech_key: string;
+  azure_region: string;
+  provider_punctuation: "automatic" | "spoken" | "off";
   api_key: string;
   hotkey: string;
+  ui_language: string;
+  tray_left_click: string;
   language_hints: string[];
   language_restrictions: string[] | null;
+  translation_target_language: string | null;
+  pronunciations: { word: string; sounds_like: string[] }[];
+  vocabulary: string[];
+  text_processors: TextProcessor[];
+  audio_fixture_path?: string | null;
+  include_partial_in_history: boolean;
+  context_carry_over: boolean;
+  plain_ascii_output: boolean;
+  rich_text_paste: boolean;
+  provisional_typing: boolean;
+  audit_journal: boolean;
+  local_analytics: boolean;
+  soniox_price_per_hour: number;
+  comparison: { enabled: boolean; model: string; credential: string | null; language_hints: string[] | null };
+  save_session_audio: boolean;
+  calendar_ics: string | null;
+  watch_folder: string | null;
+  watch_folder_write_text: boolean;
+  voice_commands: boolean;
+  voice_command_phrases: Record<string, string>;
+  selected_device: string | null;
+  battery_saver: boolean;
+  review_while_sharing: boolean;
+  refocus_target: boolean;
+  duck_media: boolean;
+  duck_volume_percent: number;
+  history_merge_window_secs: number;
+  auto_stop_silence_secs: number;
+  hotkey_cooldown_ms: number;
+  partial_text_interval_ms: number;
+  endpoint_detection: boolean;
+  soniox_model: string;
+  audio_chunk_ms: number;
+  speech_threshold: number;
+  non_final_flush_ms: number;
+  finish_timeout_ms: number;
+  session_rollover_mins: number;
+  insert_chunk_chars: number;
+  insert_chunk_delay_ms: number;
+  large_insert_chars: number;
+  clipboard_settle_ms: number;
+  clipboard_read_ms: number;
+  debug_raw_tokens: boolean;
+  overlay_placement: "screen_top" | "near_target";
+  unload_hidden_windows: boolean;
+  overlay_backend: "webview" | "native";
+  connect_timeout_ms: number;
+  handshake_timeout_ms: number;
+  connect_retries: number;
+  keep_connection_warm: boolean;
+  soniox_endpoint: string | null;
+  network_overrides: Record<string, NetworkOverrides>;
+  credentials: Record<string, { api_key: string }>;
+  active_credential: string | null;
+}
+
+interface NetworkOverrides {
+  force_ipv4: boolean;
+  resolve_to: string | null;
+  sni_host: string | null;
+}
+
+interface AudioDevice {
+  name: string;
+  is_default: boolean;
+}
+
+interface UsageStats {
+  days: { date: string; provider: string; seconds: number }[];
+  month_seconds: Record<string, number>;
+  month_soniox_cost: number;
+}
+
+interface Analytics {
+  top_apps: { app: string; sessions: number }[];
+  hours: number[];
+  average_session_secs: number;
+  providers: { provider: string; sessions: number; failed: number; error_rate: number; average_session_secs: number }[];
+  typing_failures: number;
+  reconnects: number;
 }
 
 interface SettingsProps {
@@ -76,6 +186,20 @@ const LANGUAGES = [
   { code: "cy", name: "Welsh" },
 ];
 
+// Mirrors QualityPreset::apply in the backend, which has the final say on save
+const QUALITY_PRESETS: Record<Exclude<QualityPreset, "custom">, {
+  endpoint_detection: boolean;
+  partial_text_interval_ms: number;
+  finish_timeout_ms: number;
+  audio_chunk_ms: number;
+  speech_threshold: number;
+  non_final_flush_ms: number;
+}> = {
+  fast: { endpoint_detection: true, partial_text_interval_ms: 0, finish_timeout_ms: 2000, audio_chunk_ms: 0, speech_threshold: 800, non_final_flush_ms: 1000 },
+  balanced: { endpoint_detection: true, partial_text_interval_ms: 100, finish_timeout_ms: 5000, audio_chunk_ms: 100, speech_threshold: 500, non_final_flush_ms: 0 },
+  accurate: { endpoint_detection: false, partial_text_interval_ms: 250, finish_timeout_ms: 8000, audio_chunk_ms: 200, speech_threshold: 300, non_final_flush_ms: 0 },
+};
+
 const HOTKEY_PRESETS = [
   { value: "Insert", label: "Insert" },
   { value: "F1", label: "F1" },
@@ -241,25 +365,272 @@ function MultiSelect({ options, selected, onChange, placeholder }: MultiSelectPr
   );
 }
 
+// Update the punctuation processor where it is, so the pipeline order stays
+// as configured; a new one runs last
+function withPunctuation(processors: TextProcessor[], punctuation: PunctuationProcessor): TextProcessor[] {
+  if (processors.some((p) => p.type === "punctuation")) {
+    return processors.map((p) => (p.type === "punctuation" ? { ...p, ...punctuation } : p));
+  }
+  return punctuation.enabled ? [...processors, punctuation] : processors;
+}
+
 function Settings({ settings, onSave, onCancel }: SettingsProps) {
+  const [provider, setProvider] = useState(settings.provider);
+  const [selectedDevice, setSelectedDevice] = useState(settings.selected_device || "");
+  const [audioDevices, setAudioDevices] = useState<AudioDevice[]>([]);
+
+  useEffect(() => {
+    invoke<AudioDevice[]>("list_audio_devices")
+      .then(setAudioDevices)
+      .catch((e) => console.error("Failed to list audio devices:", e));
+  }, []);
+  const [qualityPreset, setQualityPreset] = useState(settings.quality_preset);
+  const [whisperModelPath, setWhisperModelPath] = useState(settings.whisper_model_path || "");
+  const [voskModel, setVoskModel] = useState(settings.vosk_model);
   const [apiKey, setApiKey] = useState(settings.api_key);
+  const [openaiApiKey, setOpenaiApiKey] = useState(settings.openai_api_key);
+  const [openaiModel, setOpenaiModel] = useState(settings.openai_model);
+  const [rewriteEndpoint, setRewriteEndpoint] = useState(settings.rewrite_endpoint);
+  const [rewriteModel, setRewriteModel] = useState(settings.rewrite_model);
+  const [rewriteApiKey, setRewriteApiKey] = useState(settings.rewrite_api_key);
+  const [azureSpeechKey, setAzureSpeechKey] = useState(settings.azure_speech_key);
+  const [azureRegion, setAzureRegion] = useState(settings.azure_region);
   const [hotkey, setHotkey] = useState(settings.hotkey);
   const [languageHints, setLanguageHints] = useState(settings.language_hints);
   const [languageRestrictions, setLanguageRestrictions] = useState<string[]>(settings.language_restrictions || []);
+  const [translationTarget, setTranslationTarget] = useState(settings.translation_target_language || "");
   const [useRestrictions, setUseRestrictions] = useState(!!settings.language_restrictions);
+  const [vocabulary, setVocabulary] = useState((settings.vocabulary ?? []).join("\n"));
+  const [pronunciations, setPronunciations] = useState(
+    (settings.pronunciations ?? []).map((p) => `${p.word}: ${p.sounds_like.join(", ")}`).join("\n")
+  );
+  const [includePartial, setIncludePartial] = useState(settings.include_partial_in_history);
+  const [contextCarryOver, setContextCarryOver] = useState(settings.context_carry_over);
+  const [plainAscii, setPlainAscii] = useState(settings.plain_ascii_output);
+  const [providerPunctuation, setProviderPunctuation] = useState(settings.provider_punctuation);
+  const punctuationProcessor = (settings.text_processors ?? []).find((p) => p.type === "punctuation") as
+    | PunctuationProcessor
+    | undefined;
+  const [localPunctuation, setLocalPunctuation] = useState(!!punctuationProcessor && punctuationProcessor.enabled !== false);
+  const [sentenceCase, setSentenceCase] = useState(!!punctuationProcessor?.sentence_case);
+  const [trailingPeriod, setTrailingPeriod] = useState(!!punctuationProcessor?.trailing_period);
+  const [quoteStyle, setQuoteStyle] = useState(punctuationProcessor?.quotes ?? "keep");
+  const [ellipsisStyle, setEllipsisStyle] = useState(punctuationProcessor?.ellipsis ?? "keep");
+  const [richTextPaste, setRichTextPaste] = useState(settings.rich_text_paste);
+  const [provisionalTyping, setProvisionalTyping] = useState(settings.provisional_typing);
+  const [auditJournal, setAuditJournal] = useState(settings.audit_journal);
+  const [journalMessage, setJournalMessage] = useState<string | null>(null);
+  const [localAnalytics, setLocalAnalytics] = useState(settings.local_analytics);
+  const [analytics, setAnalytics] = useState<Analytics | null>(null);
+
+  useEffect(() => {
+    if (!settings.local_analytics) return;
+    invoke<Analytics>("get_analytics")
+      .then(setAnalytics)
+      .catch((e) => console.error("Failed to load analytics:", e));
+  }, [settings.local_analytics]);
+  const [sonioxPrice, setSonioxPrice] = useState(settings.soniox_price_per_hour);
+  const [usage, setUsage] = useState<UsageStats | null>(null);
+
+  useEffect(() => {
+    invoke<UsageStats>("get_usage_stats")
+      .then(setUsage)
+      .catch((e) => console.error("Failed to load usage:", e));
+  }, []);
+  const [saveAudio, setSaveAudio] = useState(settings.save_session_audio);
+  const [calendarIcs, setCalendarIcs] = useState(settings.calendar_ics || "");
+  const [watchFolder, setWatchFolder] = useState(settings.watch_folder || "");
+  const [watchWriteText, setWatchWriteText] = useState(settings.watch_folder_write_text);
+  const [voiceCommands, setVoiceCommands] = useState(settings.voice_commands);
+  const [voicePhrases, setVoicePhrases] = useState(
+    Object.entries(settings.voice_command_phrases ?? {})
+      .map(([phrase, action]) => `${phrase} = ${action}`)
+      .join("\n")
+  );
+  const [batterySaver, setBatterySaver] = useState(settings.battery_saver);
+  const [reviewWhileSharing, setReviewWhileSharing] = useState(settings.review_while_sharing);
+  const [sharingDetectable, setSharingDetectable] = useState(false);
+
+  useEffect(() => {
+    invoke<boolean>("screen_share_detection_supported")
+      .then(setSharingDetectable)
+      .catch((e) => console.error("Failed to check screen share detection:", e));
+  }, []);
+  const [refocusTarget, setRefocusTarget] = useState(settings.refocus_target);
+  const [duckMedia, setDuckMedia] = useState(settings.duck_media);
+  const [duckVolume, setDuckVolume] = useState(settings.duck_volume_percent);
+  const [compareEnabled, setCompareEnabled] = useState(settings.comparison.enabled);
+  const [compareModel, setCompareModel] = useState(settings.comparison.model);
+  const [mergeWindow, setMergeWindow] = useState(settings.history_merge_window_secs);
+  const [autoStop, setAutoStop] = useState(settings.auto_stop_silence_secs);
+  const [hotkeyCooldown, setHotkeyCooldown] = useState(settings.hotkey_cooldown_ms);
+  const [partialInterval, setPartialInterval] = useState(settings.partial_text_interval_ms);
+  const [endpointDetection, setEndpointDetection] = useState(settings.endpoint_detection);
+  const [finishTimeout, setFinishTimeout] = useState(settings.finish_timeout_ms);
+  const [sonioxModel, setSonioxModel] = useState(settings.soniox_model);
+  const [audioChunkMs, setAudioChunkMs] = useState(settings.audio_chunk_ms);
+  const [speechThreshold, setSpeechThreshold] = useState(settings.speech_threshold);
+  const [nonFinalFlushMs, setNonFinalFlushMs] = useState(settings.non_final_flush_ms);
+  const [rolloverMins, setRolloverMins] = useState(settings.session_rollover_mins);
+
+  function chooseQualityPreset(preset: QualityPreset) {
+    setQualityPreset(preset);
+    if (preset === "custom") return;
+    const values = QUALITY_PRESETS[preset];
+    setEndpointDetection(values.endpoint_detection);
+    setPartialInterval(values.partial_text_interval_ms);
+    setFinishTimeout(values.finish_timeout_ms);
+    setAudioChunkMs(values.audio_chunk_ms);
+    setSpeechThreshold(values.speech_threshold);
+    setNonFinalFlushMs(values.non_final_flush_ms);
+  }
+  const presetControlled = qualityPreset !== "custom";
+  const [chunkChars, setChunkChars] = useState(settings.insert_chunk_chars);
+  const [chunkDelay, setChunkDelay] = useState(settings.insert_chunk_delay_ms);
+  const [largeInsertChars, setLargeInsertChars] = useState(settings.large_insert_chars);
+  const [clipboardSettleMs, setClipboardSettleMs] = useState(settings.clipboard_settle_ms);
+  const [clipboardReadMs, setClipboardReadMs] = useState(settings.clipboard_read_ms);
+  const [debugRawTokens, setDebugRawTokens] = useState(settings.debug_raw_tokens);
+  const [overlayPlacement, setOverlayPlacement] = useState(settings.overlay_placement);
+  const [unloadHidden, setUnloadHidden] = useState(settings.unload_hidden_windows);
+  const [overlayBackend, setOverlayBackend] = useState(settings.overlay_backend);
+  const [uiLanguage, setUiLanguage] = useState(settings.ui_language);
+  const [trayLeftClick, setTrayLeftClick] = useState(settings.tray_left_click);
+  const [connectTimeout, setConnectTimeout] = useState(settings.connect_timeout_ms);
+  const [handshakeTimeout, setHandshakeTimeout] = useState(settings.handshake_timeout_ms);
+  const [connectRetries, setConnectRetries] = useState(settings.connect_retries);
+  const [keepWarm, setKeepWarm] = useState(settings.keep_connection_warm);
+  const [sonioxEndpoint, setSonioxEndpoint] = useState(settings.soniox_endpoint ?? "");
+  const sonioxNetwork = settings.network_overrides?.soniox;
+  const [forceIpv4, setForceIpv4] = useState(sonioxNetwork?.force_ipv4 ?? false);
+  const [resolveTo, setResolveTo] = useState(sonioxNetwork?.resolve_to ?? "");
+  const [sniHost, setSniHost] = useState(sonioxNetwork?.sni_host ?? "");
   const [showApiKey, setShowApiKey] = useState(false);
+  const [credentials, setCredentials] = useState<{ name: string; api_key: string }[]>(
+    Object.entries(settings.credentials ?? {}).map(([name, c]) => ({ name, api_key: c.api_key }))
+  );
 
   const [hotkeyMode, setHotkeyMode] = useState<"preset" | "custom">("preset");
   const [isRecording, setIsRecording] = useState(false);
   const [recordedKeys, setRecordedKeys] = useState<string[]>([]);
 
+  function updateCredential(index: number, field: "name" | "api_key", value: string) {
+    setCredentials(credentials.map((c, i) => (i === index ? { ...c, [field]: value } : c)));
+  }
+
   function handleSubmit(e: React.FormEvent) {
     e.preventDefault();
+    const namedCredentials = Object.fromEntries(
+      credentials
+        .filter((c) => c.name.trim() && c.api_key.trim())
+        .map((c) => [c.name.trim(), { api_key: c.api_key.trim() }])
+    );
     onSave({
+      ...settings,
+      provider,
+      selected_device: selectedDevice || null,
+      whisper_model_path: whisperModelPath.trim() || null,
+      vosk_model: voskModel.trim(),
+      openai_api_key: openaiApiKey.trim(),
+      quality_preset: qualityPreset,
+      openai_model: openaiModel.trim() || "gpt-4o-transcribe",
+      rewrite_endpoint: rewriteEndpoint.trim() || "https://api.openai.com/v1",
+      rewrite_model: rewriteModel.trim() || "gpt-4o-mini",
+      rewrite_api_key: rewriteApiKey,
+      azure_speech_key: azureSpeechKey.trim(),
+      azure_region: azureRegion.trim(),
       api_key: apiKey,
       hotkey,
       language_hints: languageHints,
       language_restrictions: useRestrictions && languageRestrictions.length > 0 ? languageRestrictions : null,
+      translation_target_language: translationTarget || null,
+      pronunciations: pronunciations
+        .split("\n")
+        .map((line) => {
+          const [word, soundsLike = ""] = line.split(":");
+          return {
+            word: word.trim(),
+            sounds_like: soundsLike.split(",").map((s) => s.trim()).filter(Boolean),
+          };
+        })
+        .filter((p) => p.word),
+      vocabulary: vocabulary
+        .split("\n")
+        .map((term) => term.trim())
+        .filter(Boolean),
+      include_partial_in_history: includePartial,
+      context_carry_over: contextCarryOver,
+      plain_ascii_output: plainAscii,
+      provider_punctuation: providerPunctuation,
+      text_processors: withPunctuation(settings.text_processors ?? [], {
+        type: "punctuation",
+        enabled: localPunctuation,
+        sentence_case: sentenceCase,
+        trailing_period: trailingPeriod,
+        quotes: quoteStyle,
+        ellipsis: ellipsisStyle,
+      }),
+      rich_text_paste: richTextPaste,
+      provisional_typing: provisionalTyping,
+      audit_journal: auditJournal,
+      local_analytics: localAnalytics,
+      soniox_price_per_hour: sonioxPrice,
+      save_session_audio: saveAudio,
+      calendar_ics: calendarIcs.trim() || null,
+      watch_folder: watchFolder.trim() || null,
+      watch_folder_write_text: watchWriteText,
+      voice_commands: voiceCommands,
+      voice_command_phrases: Object.fromEntries(
+        voicePhrases
+          .split("\n")
+          .map((line) => line.split("="))
+          .filter((parts) => parts.length === 2 && parts[0].trim() && parts[1].trim())
+          .map(([phrase, action]) => [phrase.trim(), action.trim()])
+      ),
+      battery_saver: batterySaver,
+      review_while_sharing: reviewWhileSharing,
+      refocus_target: refocusTarget,
+      duck_media: duckMedia,
+      duck_volume_percent: duckVolume,
+      comparison: { ...settings.comparison, enabled: compareEnabled, model: compareModel.trim() },
+      history_merge_window_secs: mergeWindow,
+      auto_stop_silence_secs: autoStop,
+      hotkey_cooldown_ms: hotkeyCooldown,
+      partial_text_interval_ms: partialInterval,
+      endpoint_detection: endpointDetection,
+      soniox_model: sonioxModel.trim(),
+      audio_chunk_ms: audioChunkMs,
+      speech_threshold: speechThreshold,
+      non_final_flush_ms: nonFinalFlushMs,
+      finish_timeout_ms: finishTimeout,
+      session_rollover_mins: rolloverMins,
+      insert_chunk_chars: chunkChars,
+      insert_chunk_delay_ms: chunkDelay,
+      large_insert_chars: largeInsertChars,
+      clipboard_settle_ms: clipboardSettleMs,
+      clipboard_read_ms: clipboardReadMs,
+      debug_raw_tokens: debugRawTokens,
+      overlay_placement: overlayPlacement,
+      unload_hidden_windows: unloadHidden,
+      overlay_backend: overlayBackend,
+      ui_language: uiLanguage,
+      tray_left_click: trayLeftClick,
+      connect_timeout_ms: connectTimeout,
+      handshake_timeout_ms: handshakeTimeout,
+      connect_retries: connectRetries,
+      keep_connection_warm: keepWarm,
+      credentials: namedCredentials,
+      active_credential:
+        settings.active_credential && settings.active_credential in namedCredentials ? settings.active_credential : null,
+      soniox_endpoint: sonioxEndpoint.trim() || null,
+      network_overrides: {
+        ...settings.network_overrides,
+        soniox: {
+          force_ipv4: forceIpv4,
+          resolve_to: resolveTo.trim() || null,
+          sni_host: sniHost.trim() || null,
+        },
+      },
     });
   }
 
@@ -304,6 +675,109 @@ function Settings({ settings, onSave, onCancel }: SettingsProps) {
     <div className="settings">
       <h2>Settings</h2>
       <form onSubmit={handleSubmit}>
+        <div className="form-group">
+          <label>Transcription Engine</label>
+          <p className="field-help">Soniox, OpenAI and Azure stream audio to the cloud. Whisper and Vosk run local models offline (builds with the "whisper" or "vosk" feature only).</p>
+          <select value={provider} onChange={(e) => setProvider(e.target.value as AppSettings["provider"])}>
+            <option value="soniox">Soniox (cloud)</option>
+            <option value="openai">OpenAI Realtime (cloud)</option>
+            <option value="azure">Azure Speech (cloud)</option>
+            <option value="whisper">Whisper (offline)</option>
+            <option value="vosk">Vosk (offline)</option>
+          </select>
+          {provider === "whisper" && (
+            <input
+              type="text"
+              value={whisperModelPath}
+              onChange={(e) => setWhisperModelPath(e.target.value)}
+              placeholder="/path/to/ggml-base.en.bin"
+            />
+          )}
+          {provider === "vosk" && <VoskModels model={voskModel} onChange={setVoskModel} />}
+          {provider === "openai" && (
+            <>
+              <input
+                type="password"
+                value={openaiApiKey}
+                onChange={(e) => setOpenaiApiKey(e.target.value)}
+                placeholder="OpenAI API key"
+              />
+              <input
+                type="text"
+                value={openaiModel}
+                onChange={(e) => setOpenaiModel(e.target.value)}
+                placeholder="gpt-4o-transcribe"
+              />
+            </>
+          )}
+          {provider === "azure" && (
+            <>
+              <input
+                type="password"
+                value={azureSpeechKey}
+                onChange={(e) => setAzureSpeechKey(e.target.value)}
+                placeholder="Azure Speech key"
+              />
+              <input
+                type="text"
+                value={azureRegion}
+                onChange={(e) => setAzureRegion(e.target.value)}
+                placeholder="Region, e.g. westeurope"
+              />
+            </>
+          )}
+        </div>
+
+        <div className="form-group">
+          <label>Clipboard Rewrite</label>
+          <p className="field-help">The "Rewrite clipboard" palette action records a spoken instruction ("make this more formal"), sends it with the clipboard text to an OpenAI-compatible chat model and types the answer. Leave the key empty to use the OpenAI API key.</p>
+          <input
+            type="text"
+            value={rewriteEndpoint}
+            onChange={(e) => setRewriteEndpoint(e.target.value)}
+            placeholder="https://api.openai.com/v1"
+          />
+          <input
+            type="text"
+            value={rewriteModel}
+            onChange={(e) => setRewriteModel(e.target.value)}
+            placeholder="gpt-4o-mini"
+          />
+          <input
+            type="password"
+            value={rewriteApiKey}
+            onChange={(e) => setRewriteApiKey(e.target.value)}
+            placeholder="API key (optional)"
+          />
+        </div>
+
+        <div className="form-group">
+          <label>Microphone</label>
+          <p className="field-help">Input device to record from. If the selected device is not connected when a session starts, the system default is used.</p>
+          <select value={selectedDevice} onChange={(e) => setSelectedDevice(e.target.value)}>
+            <option value="">System default</option>
+            {audioDevices.map((device) => (
+              <option key={device.name} value={device.name}>
+                {device.name}{device.is_default ? " (default)" : ""}
+              </option>
+            ))}
+            {selectedDevice && !audioDevices.some((d) => d.name === selectedDevice) && (
+              <option value={selectedDevice}>{selectedDevice} (not connected)</option>
+            )}
+          </select>
+        </div>
+
+        <div className="form-group">
+          <label>Quality</label>
+          <p className="field-help">Trade speed for accuracy without tuning each setting. Fast sends audio sooner and finalizes words quickly; Accurate waits until recording stops so the engine sees whole sentences. The settings a preset controls are locked until you choose Custom; provisional typing and the models you entered stay as they are.</p>
+          <select value={qualityPreset} onChange={(e) => chooseQualityPreset(e.target.value as QualityPreset)}>
+            <option value="custom">Custom</option>
+            <option value="fast">Fast</option>
+            <option value="balanced">Balanced</option>
+            <option value="accurate">Accurate</option>
+          </select>
+        </div>
+
         <div className="form-group">
           <label>Soniox API Key</label>
           <div className="api-key-input">
@@ -317,6 +791,47 @@ function Settings({ settings, onSave, onCancel }: SettingsProps) {
           </a>
         </div>
 
+        <div className="form-group">
+          <label>Workspaces (optional)</label>
+          <p className="field-help">Extra named API keys (e.g. "personal", "work") to bill dictation to different accounts. Switch between them from the main window.</p>
+          {credentials.map((c, i) => (
+            <div key={i} className="api-key-input">
+              <input type="text" value={c.name} onChange={(e) => updateCredential(i, "name", e.target.value)} placeholder="Name" />
+              <input type="password" value={c.api_key} onChange={(e) => updateCredential(i, "api_key", e.target.value)} placeholder="API key" />
+              <button type="button" className="toggle-visibility" onClick={() => setCredentials(credentials.filter((_, j) => j !== i))}>
+                Remove
+              </button>
+            </div>
+          ))}
+          <button type="button" className="toggle-visibility" onClick={() => setCredentials([...credentials, { name: "", api_key: "" }])}>
+            Add Workspace
+          </button>
+        </div>
+
+        <div className="form-group">
+          <label>Usage This Month</label>
+          <p className="field-help">Audio sent to each engine, counted on this computer. The cost is a rough estimate at the price per hour below; your Soniox plan may differ.</p>
+          {usage && Object.keys(usage.month_seconds).length > 0 ? (
+            <ul className="field-help">
+              {Object.entries(usage.month_seconds).map(([name, seconds]) => (
+                <li key={name}>
+                  {name}: {(seconds / 3600).toFixed(2)} h
+                  {name === "soniox" && ` (about $${usage.month_soniox_cost.toFixed(2)})`}
+                </li>
+              ))}
+            </ul>
+          ) : (
+            <p className="field-help">Nothing yet.</p>
+          )}
+          <input
+            type="number"
+            min={0}
+            step={0.01}
+            value={sonioxPrice}
+            onChange={(e) => setSonioxPrice(Math.max(0, parseFloat(e.target.value) || 0))}
+          />
+        </div>
+
         <div className="form-group">
           <label>Global Hotkey</label>
 
@@ -421,6 +936,17 @@ function Settings({ settings, onSave, onCancel }: SettingsProps) {
           </div>
         </div>
 
+        <div className="form-group">
+          <label>Hotkey Cooldown (ms)</label>
+          <p className="field-help">Presses within this window after the previous one are ignored, so key repeat or double presses don't toggle recording twice.</p>
+          <input
+            type="number"
+            min={0}
+            value={hotkeyCooldown}
+            onChange={(e) => setHotkeyCooldown(Math.max(0, parseInt(e.target.value, 10) || 0))}
+          />
+        </div>
+
         <div className="form-group">
           <label>Language Hints (optional)</label>
           <p className="field-help">Select languages that might be used in your dictation. Leave empty for automatic detection.</p>
@@ -448,6 +974,580 @@ function Settings({ settings, onSave, onCancel }: SettingsProps) {
           )}
         </div>
 
+        <div className="form-group">
+          <label>Translate Into</label>
+          <p className="field-help">Type a translation of what you say instead of the transcript (Soniox only).</p>
+          <select value={translationTarget} onChange={(e) => setTranslationTarget(e.target.value)}>
+            <option value="">Off</option>
+            {LANGUAGES.map((lang) => (
+              <option key={lang.code} value={lang.code}>
+                {lang.name}
+              </option>
+            ))}
+          </select>
+        </div>
+
+        <div className="form-group">
+          <label>Vocabulary (optional)</label>
+          <p className="field-help">Names, product terms and jargon, one per line. They are sent to the provider as context so uncommon words are recognized.</p>
+          <textarea
+            rows={3}
+            value={vocabulary}
+            onChange={(e) => setVocabulary(e.target.value)}
+            placeholder={"Kubernetes\nDr. Okonkwo"}
+          />
+        </div>
+
+        <div className="form-group">
+          <label>Pronunciations (optional)</label>
+          <p className="field-help">One word per line as "Word: sounds like, other spelling". The words are sent to Soniox as context, and the listed spellings are replaced with the word.</p>
+          <textarea
+            rows={3}
+            value={pronunciations}
+            onChange={(e) => setPronunciations(e.target.value)}
+            placeholder="Kavak: cavac, kah vahk"
+          />
+        </div>
+
+        <div className="form-group">
+          <label>Punctuation</label>
+          <p className="field-help">How the provider punctuates. Only Azure offers a choice: "Spoken" writes "comma" or "question mark" as symbols, "Off" returns plain words. Soniox, OpenAI and Whisper always punctuate; Vosk never does.</p>
+          <select
+            value={providerPunctuation}
+            onChange={(e) => setProviderPunctuation(e.target.value as AppSettings["provider_punctuation"])}
+          >
+            <option value="automatic">Automatic</option>
+            <option value="spoken">Spoken (Azure)</option>
+            <option value="off">Off (Azure)</option>
+          </select>
+          <label className="checkbox-label">
+            <input type="checkbox" checked={localPunctuation} onChange={(e) => setLocalPunctuation(e.target.checked)} />
+            Adjust punctuation locally
+          </label>
+          <p className="field-help">Apply a house style to the provider's punctuation before typing (the "punctuation" text processor).</p>
+          {localPunctuation && (
+            <>
+              <label className="checkbox-label">
+                <input type="checkbox" checked={sentenceCase} onChange={(e) => setSentenceCase(e.target.checked)} />
+                Capitalize sentences
+              </label>
+              <label className="checkbox-label">
+                <input type="checkbox" checked={trailingPeriod} onChange={(e) => setTrailingPeriod(e.target.checked)} />
+                End utterances with a period
+              </label>
+              <select value={quoteStyle} onChange={(e) => setQuoteStyle(e.target.value as typeof quoteStyle)}>
+                <option value="keep">Quotes as sent</option>
+                <option value="straight">Straight quotes (" ')</option>
+                <option value="smart">Smart quotes (“ ” ‘ ’)</option>
+              </select>
+              <select value={ellipsisStyle} onChange={(e) => setEllipsisStyle(e.target.value as typeof ellipsisStyle)}>
+                <option value="keep">Ellipses as sent</option>
+                <option value="dots">Three periods (...)</option>
+                <option value="character">Ellipsis character (…)</option>
+              </select>
+            </>
+          )}
+        </div>
+
+        <div className="form-group">
+          <label>Message Language</label>
+          <p className="field-help">Language of errors and warnings reported by the app itself and of the command palette (the tray menu changes after a restart).</p>
+          <select value={uiLanguage} onChange={(e) => setUiLanguage(e.target.value)}>
+            <option value="en">English</option>
+            <option value="de">Deutsch</option>
+            <option value="tr">Türkçe</option>
+          </select>
+        </div>
+
+        <div className="form-group">
+          <label>Tray Icon Click</label>
+          <p className="field-help">What a left click on the tray icon does. Some Linux trays always open the menu.</p>
+          <select value={trayLeftClick} onChange={(e) => setTrayLeftClick(e.target.value)}>
+            <option value="show_window">Show window</option>
+            <option value="toggle">Start/stop dictation</option>
+            <option value="menu">Open menu</option>
+          </select>
+        </div>
+
+        <div className="form-group">
+          <label>Overlay Position</label>
+          <p className="field-help">Where the recording overlay appears. "Near target window" falls back to the screen top where window geometry is unavailable (e.g. Wayland).</p>
+          <select
+            value={overlayPlacement}
+            onChange={(e) => setOverlayPlacement(e.target.value as AppSettings["overlay_placement"])}
+          >
+            <option value="screen_top">Top of screen</option>
+            <option value="near_target">Near target window</option>
+          </select>
+        </div>
+
+        <div className="form-group">
+          <label>Overlay Style</label>
+          <p className="field-help">The minimal style draws a native indicator without a webview (Linux and macOS), using fewer resources and avoiding transparency glitches.</p>
+          <select
+            value={overlayBackend}
+            onChange={(e) => setOverlayBackend(e.target.value as AppSettings["overlay_backend"])}
+          >
+            <option value="webview">Animated (webview)</option>
+            <option value="native">Minimal (native)</option>
+          </select>
+        </div>
+
+        <div className="form-group">
+          <label className="checkbox-label">
+            <input type="checkbox" checked={unloadHidden} onChange={(e) => setUnloadHidden(e.target.checked)} />
+            Low Memory Mode
+          </label>
+          <p className="field-help">Unload windows while hidden to reduce idle memory. Opening the app or showing the overlay takes slightly longer.</p>
+        </div>
+
+        <div className="form-group">
+          <label className="checkbox-label">
+            <input type="checkbox" checked={plainAscii} onChange={(e) => setPlainAscii(e.target.checked)} />
+            Plain ASCII Output
+          </label>
+          <p className="field-help">Type straight quotes, "--" and regular spaces instead of smart quotes, em dashes and non-breaking spaces, for terminals and legacy apps.</p>
+        </div>
+
+        <div className="form-group">
+          <label className="checkbox-label">
+            <input type="checkbox" checked={richTextPaste} onChange={(e) => setRichTextPaste(e.target.checked)} />
+            Rich Text Paste
+          </label>
+          <p className="field-help">When dictated text contains Markdown (**bold**, lists), paste it as formatted HTML with a plain-text fallback so email clients and editors keep the formatting.</p>
+        </div>
+
+        <div className="form-group">
+          <label className="checkbox-label">
+            <input type="checkbox" checked={provisionalTyping} onChange={(e) => setProvisionalTyping(e.target.checked)} />
+            Provisional Typing
+          </label>
+          <p className="field-help">Type words as soon as they are heard and fix them with backspace and retyping when the final result differs (Soniox only). Much faster feedback, but don't move the cursor while dictating.</p>
+        </div>
+
+        <div className="form-group">
+          <label className="checkbox-label">
+            <input type="checkbox" checked={auditJournal} onChange={(e) => setAuditJournal(e.target.checked)} />
+            Audit Journal
+          </label>
+          <p className="field-help">Record exactly what was typed, when, how and into which window for every session, separately from history. Export writes all sessions to a CSV file in your downloads folder.</p>
+          <button
+            type="button"
+            className="clear-history-btn"
+            onClick={() =>
+              invoke<string>("export_audit_journal", {})
+                .then((path) => setJournalMessage(`Exported to ${path}`))
+                .catch((e) => setJournalMessage(String(e)))
+            }
+          >
+            Export Journal
+          </button>
+          {journalMessage && <p className="field-help">{journalMessage}</p>}
+        </div>
+
+        <div className="form-group">
+          <label className="checkbox-label">
+            <input type="checkbox" checked={localAnalytics} onChange={(e) => setLocalAnalytics(e.target.checked)} />
+            Local Analytics
+          </label>
+          <p className="field-help">Summarize sessions, their length and failures per engine from the usage statistics, and the apps and hours you dictate in from history. Computed on this computer; nothing is sent anywhere.</p>
+          {analytics && (
+            <ul className="field-help">
+              <li>Average session: {Math.round(analytics.average_session_secs)} s</li>
+              {analytics.top_apps.length > 0 && (
+                <li>Top apps: {analytics.top_apps.map((a) => `${a.app} (${a.sessions})`).join(", ")}</li>
+              )}
+              {analytics.hours.some((n) => n > 0) && (
+                <li>
+                  Busiest hours:{" "}
+                  {analytics.hours
+                    .map((n, hour) => ({ n, hour }))
+                    .filter(({ n }) => n > 0)
+                    .sort((a, b) => b.n - a.n)
+                    .slice(0, 3)
+                    .map(({ hour, n }) => `${String(hour).padStart(2, "0")}:00 (${n})`)
+                    .join(", ")}
+                </li>
+              )}
+              {analytics.providers.map((p) => (
+                <li key={p.provider}>
+                  {p.provider}: {p.sessions} sessions, {(p.error_rate * 100).toFixed(1)}% failed, {Math.round(p.average_session_secs)} s average
+                </li>
+              ))}
+              <li>
+                Since start: {analytics.typing_failures} failed insertions, {analytics.reconnects} reconnects
+              </li>
+            </ul>
+          )}
+        </div>
+
+        <div className="form-group">
+          <label className="checkbox-label">
+            <input type="checkbox" checked={includePartial} onChange={(e) => setIncludePartial(e.target.checked)} />
+            Keep Unconfirmed Text in History
+          </label>
+          <p className="field-help">If a session ends before the last words are finalized, save them to history marked as [unconfirmed: ...].</p>
+        </div>
+
+        <div className="form-group">
+          <label className="checkbox-label">
+            <input type="checkbox" checked={refocusTarget} onChange={(e) => setRefocusTarget(e.target.checked)} />
+            Refocus Target Window
+          </label>
+          <p className="field-help">Switch back to the window you started dictating in before inserting text. Turn off to have text follow whichever window has focus.</p>
+        </div>
+
+        <div className="form-group">
+          <label className="checkbox-label">
+            <input type="checkbox" checked={contextCarryOver} onChange={(e) => setContextCarryOver(e.target.checked)} />
+            Continue From Previous Session
+          </label>
+          <p className="field-help">Send the end of the previous session to Soniox as context when the next one starts within 5 minutes, so short push-to-talk bursts are recognized as one document.</p>
+        </div>
+
+        <div className="form-group">
+          <label className="checkbox-label">
+            <input type="checkbox" checked={saveAudio} onChange={(e) => setSaveAudio(e.target.checked)} />
+            Save Session Audio
+          </label>
+          <p className="field-help">Keep a recording of each session so you can play it back from history.</p>
+        </div>
+
+        <div className="form-group">
+          <label>Calendar (optional)</label>
+          <p className="field-help">ICS file or URL (e.g. a calendar's secret iCal address). Sessions that overlap an event are titled with its name in history.</p>
+          <input
+            type="text"
+            value={calendarIcs}
+            onChange={(e) => setCalendarIcs(e.target.value)}
+            placeholder="https://calendar.example.com/basic.ics"
+          />
+        </div>
+
+        <div className="form-group">
+          <label>Watch Folder (optional)</label>
+          <p className="field-help">New WAV, MP3 and OGG files in this folder are transcribed with Soniox and added to history, e.g. voice memos synced from your phone.</p>
+          <input
+            type="text"
+            value={watchFolder}
+            onChange={(e) => setWatchFolder(e.target.value)}
+            placeholder="/home/me/Sync/Voice Memos"
+          />
+          <label className="checkbox-label">
+            <input type="checkbox" checked={watchWriteText} onChange={(e) => setWatchWriteText(e.target.checked)} />
+            Save a .txt next to each recording
+          </label>
+        </div>
+
+        <div className="form-group">
+          <label className="checkbox-label">
+            <input type="checkbox" checked={voiceCommands} onChange={(e) => setVoiceCommands(e.target.checked)} />
+            Voice Commands While Idle
+          </label>
+          <p className="field-help">Keeps the microphone open while not dictating and listens locally with Vosk for the phrases below, one per line as "phrase = action" (actions as in the command palette). "Switch to &lt;profile&gt;" works for every profile. Phrases mapped to "stop" are also heard during a session and end it. Needs a downloaded Vosk model and a build with the "vosk" feature.</p>
+          {voiceCommands && (
+            <>
+              {provider !== "vosk" && <VoskModels model={voskModel} onChange={setVoskModel} />}
+              <textarea
+                rows={3}
+                value={voicePhrases}
+                onChange={(e) => setVoicePhrases(e.target.value)}
+                placeholder={"start dictation = start\nstop dictation = stop\nopen settings = show_window"}
+              />
+            </>
+          )}
+        </div>
+
+        <div className="form-group">
+          <label className="checkbox-label">
+            <input type="checkbox" checked={batterySaver} onChange={(e) => setBatterySaver(e.target.checked)} />
+            Battery Saver
+          </label>
+          <p className="field-help">When a session starts on battery power, update the live preview less often, keep the overlay static and upload compressed audio to Soniox (Linux, macOS and Windows).</p>
+        </div>
+
+        {sharingDetectable && (
+          <div className="form-group">
+            <label className="checkbox-label">
+              <input type="checkbox" checked={reviewWhileSharing} onChange={(e) => setReviewWhileSharing(e.target.checked)} />
+              Don't Type While Screen Sharing
+            </label>
+            <p className="field-help">When a session starts while the screen seems to be shared or recorded (a desktop screencast, OBS, ...), keep the text in this window to review and copy instead of typing it live.</p>
+          </div>
+        )}
+
+        <div className="form-group">
+          <label className="checkbox-label">
+            <input type="checkbox" checked={duckMedia} onChange={(e) => setDuckMedia(e.target.checked)} />
+            Lower Media While Dictating
+          </label>
+          <p className="field-help">Turn down other applications' audio while recording so it doesn't bleed into the microphone (Linux with PulseAudio/PipeWire, Windows).</p>
+          {duckMedia && (
+            <input
+              type="number"
+              min={0}
+              max={100}
+              value={duckVolume}
+              onChange={(e) => setDuckVolume(Math.min(100, Math.max(0, parseInt(e.target.value, 10) || 0)))}
+            />
+          )}
+        </div>
+
+        <div className="form-group">
+          <label className="checkbox-label">
+            <input type="checkbox" checked={compareEnabled} onChange={(e) => setCompareEnabled(e.target.checked)} />
+            Comparison Mode
+          </label>
+          <p className="field-help">Also stream each session to a second Soniox model and show both transcripts in history. Only the main transcript is typed; the second stream is billed too. Soniox only: both streams must be Soniox, and the setting has no effect with another transcription engine.</p>
+          {compareEnabled && (
+            <input
+              type="text"
+              value={compareModel}
+              onChange={(e) => setCompareModel(e.target.value)}
+              placeholder="Model, e.g. stt-rt-preview (empty: same as main)"
+            />
+          )}
+        </div>
+
+        <div className="form-group">
+          <label>Merge Consecutive Sessions (seconds)</label>
+          <p className="field-help">Sessions started within this many seconds of the previous one ending are merged into a single history entry. 0 disables merging.</p>
+          <input
+            type="number"
+            min={0}
+            value={mergeWindow}
+            onChange={(e) => setMergeWindow(Math.max(0, parseInt(e.target.value, 10) || 0))}
+          />
+        </div>
+
+        <div className="form-group">
+          <label>Stop After Silence (seconds)</label>
+          <p className="field-help">Stop recording automatically when nothing has been said for this long. The overlay counts down the last few seconds. 0 keeps recording until you stop it.</p>
+          <input
+            type="number"
+            min={0}
+            value={autoStop}
+            onChange={(e) => setAutoStop(Math.max(0, parseInt(e.target.value, 10) || 0))}
+          />
+        </div>
+
+        <TypingTools />
+
+        <details className="form-group advanced-settings">
+          <summary>Advanced</summary>
+
+          <div className="form-group">
+            <label>Connection Timeout (ms)</label>
+            <p className="field-help">Time allowed for DNS lookup and TCP connect to Soniox.</p>
+            <input
+              type="number"
+              min={500}
+              value={connectTimeout}
+              onChange={(e) => setConnectTimeout(Math.max(500, parseInt(e.target.value, 10) || 0))}
+            />
+          </div>
+
+          <div className="form-group">
+            <label>Handshake Timeout (ms)</label>
+            <p className="field-help">Time allowed for the TLS and WebSocket handshake.</p>
+            <input
+              type="number"
+              min={500}
+              value={handshakeTimeout}
+              onChange={(e) => setHandshakeTimeout(Math.max(500, parseInt(e.target.value, 10) || 0))}
+            />
+          </div>
+
+          <div className="form-group">
+            <label>Connection Retries</label>
+            <p className="field-help">Extra attempts on transient network failures before reporting an error, waiting twice as long before each.</p>
+            <input
+              type="number"
+              min={0}
+              max={10}
+              value={connectRetries}
+              onChange={(e) => setConnectRetries(Math.min(10, Math.max(0, parseInt(e.target.value, 10) || 0)))}
+            />
+          </div>
+
+          <div className="form-group">
+            <label className="checkbox-label">
+              <input type="checkbox" checked={keepWarm} onChange={(e) => setKeepWarm(e.target.checked)} />
+              Keep Connection Warm
+            </label>
+            <p className="field-help">Hold an idle Soniox connection between sessions so dictation starts without a connection delay. The connection always starts as soon as the hotkey is pressed.</p>
+          </div>
+
+          <div className="form-group">
+            <label>Live Preview Interval (ms)</label>
+            <p className="field-help">Minimum time between live preview updates. Higher values reduce overlay redraws during fast speech; 0 updates on every result.</p>
+            <input
+              type="number"
+              min={0}
+              value={partialInterval}
+              disabled={presetControlled}
+              onChange={(e) => setPartialInterval(Math.max(0, parseInt(e.target.value, 10) || 0))}
+            />
+          </div>
+
+          <div className="form-group">
+            <label className="checkbox-label">
+              <input type="checkbox" checked={endpointDetection} disabled={presetControlled} onChange={(e) => setEndpointDetection(e.target.checked)} />
+              Endpoint Detection
+            </label>
+            <p className="field-help">Finalize text at pauses in speech. Turn off to keep everything provisional until recording stops.</p>
+          </div>
+
+          <div className="form-group">
+            <label>Soniox Model</label>
+            <p className="field-help">Real-time model to transcribe with. Leave empty for the default, or the Fast preset's faster model.</p>
+            <input type="text" value={sonioxModel} onChange={(e) => setSonioxModel(e.target.value)} placeholder="stt-rt-v4" />
+          </div>
+
+          <div className="form-group">
+            <label>Audio Block Size (ms)</label>
+            <p className="field-help">Send microphone audio in blocks of at least this length. Smaller blocks reach the engine sooner; 0 sends audio as the device delivers it.</p>
+            <input
+              type="number"
+              min={0}
+              value={audioChunkMs}
+              disabled={presetControlled}
+              onChange={(e) => setAudioChunkMs(Math.max(0, parseInt(e.target.value, 10) || 0))}
+            />
+          </div>
+
+          <div className="form-group">
+            <label>Speech Detection Threshold</label>
+            <p className="field-help">Input level above which you count as speaking, for the speaking indicator and auto-stop. Raise it in a noisy room; lower it if quiet speech is missed.</p>
+            <input
+              type="number"
+              min={0}
+              value={speechThreshold}
+              disabled={presetControlled}
+              onChange={(e) => setSpeechThreshold(Math.max(0, parseInt(e.target.value, 10) || 0))}
+            />
+          </div>
+
+          <div className="form-group">
+            <label>Finalize Provisional Words After (ms)</label>
+            <p className="field-help">Ask Soniox to finalize words that stayed provisional this long, so they are typed sooner. 0 leaves it to Soniox.</p>
+            <input
+              type="number"
+              min={0}
+              value={nonFinalFlushMs}
+              disabled={presetControlled}
+              onChange={(e) => setNonFinalFlushMs(Math.max(0, parseInt(e.target.value, 10) || 0))}
+            />
+          </div>
+
+          <div className="form-group">
+            <label>Finish Timeout (ms)</label>
+            <p className="field-help">How long to wait for the last words after recording stops before the session ends.</p>
+            <input
+              type="number"
+              min={500}
+              value={finishTimeout}
+              disabled={presetControlled}
+              onChange={(e) => setFinishTimeout(Math.max(500, parseInt(e.target.value, 10) || 0))}
+            />
+          </div>
+
+          <div className="form-group">
+            <label>Session Rollover (minutes)</label>
+            <p className="field-help">Soniox limits how long one stream may run. Longer dictations continue on a new stream at the next pause after this many minutes, without losing audio. 0 turns rollover off.</p>
+            <input
+              type="number"
+              min={0}
+              value={rolloverMins}
+              onChange={(e) => setRolloverMins(Math.max(0, parseInt(e.target.value, 10) || 0))}
+            />
+          </div>
+
+          <div className="form-group">
+            <label>Split Long Insertions</label>
+            <p className="field-help">Insert text longer than this many characters in pieces, with a pause (ms) between them, for apps that cut off large pastes. 0 inserts everything at once.</p>
+            <input
+              type="number"
+              min={0}
+              value={chunkChars}
+              onChange={(e) => setChunkChars(Math.max(0, parseInt(e.target.value, 10) || 0))}
+            />
+            <input
+              type="number"
+              min={0}
+              value={chunkDelay}
+              onChange={(e) => setChunkDelay(Math.max(0, parseInt(e.target.value, 10) || 0))}
+            />
+          </div>
+
+          <div className="form-group">
+            <label>Large Insertion Guard</label>
+            <p className="field-help">Text longer than this many characters is always typed in paced pieces, even with splitting off or rich text paste on, because one huge paste can freeze slow apps. Progress shows in the overlay. 0 turns the guard off.</p>
+            <input
+              type="number"
+              min={0}
+              value={largeInsertChars}
+              onChange={(e) => setLargeInsertChars(Math.max(0, parseInt(e.target.value, 10) || 0))}
+            />
+          </div>
+
+          <div className="form-group">
+            <label>Clipboard Paste Delays</label>
+            <p className="field-help">Pause (ms) before the paste shortcut and after it, before your previous clipboard is restored. Raise them if an app pastes old clipboard contents (Electron over RDP); profiles can set their own for specific apps.</p>
+            <input
+              type="number"
+              min={0}
+              value={clipboardSettleMs}
+              onChange={(e) => setClipboardSettleMs(Math.max(0, parseInt(e.target.value, 10) || 0))}
+            />
+            <input
+              type="number"
+              min={0}
+              value={clipboardReadMs}
+              onChange={(e) => setClipboardReadMs(Math.max(0, parseInt(e.target.value, 10) || 0))}
+            />
+          </div>
+
+          <div className="form-group">
+            <label className="checkbox-label">
+              <input type="checkbox" checked={debugRawTokens} onChange={(e) => setDebugRawTokens(e.target.checked)} />
+              Show Raw Tokens
+            </label>
+            <p className="field-help">Lists every Soniox token as received (final flag, speaker, language, text) below the preview, to diagnose revised or control tokens.</p>
+          </div>
+
+          <div className="form-group">
+            <label className="checkbox-label">
+              <input type="checkbox" checked={forceIpv4} onChange={(e) => setForceIpv4(e.target.checked)} />
+              Force IPv4
+            </label>
+            <p className="field-help">Some VPNs break IPv6 WebSocket connections.</p>
+          </div>
+
+          <div className="form-group">
+            <label>Soniox Endpoint</label>
+            <p className="field-help">WebSocket URL of a self-hosted or regional Soniox-compatible server. Empty uses the public API; the host override below only applies to the public API.</p>
+            <input
+              type="text"
+              value={sonioxEndpoint}
+              onChange={(e) => setSonioxEndpoint(e.target.value)}
+              placeholder="wss://stt-rt.soniox.com/transcribe-websocket"
+            />
+          </div>
+
+          <div className="form-group">
+            <label>Soniox IP Override</label>
+            <p className="field-help">Connect to this IP address instead of resolving the Soniox host via DNS.</p>
+            <input type="text" value={resolveTo} onChange={(e) => setResolveTo(e.target.value)} placeholder="e.g. 203.0.113.10" />
+          </div>
+
+          <div className="form-group">
+            <label>Soniox Host (SNI) Override</label>
+            <p className="field-help">Hostname used for TLS and the Host header.</p>
+            <input type="text" value={sniHost} onChange={(e) => setSniHost(e.target.value)} placeholder="stt-rt.soniox.com" />
+          </div>
+        </details>
+
         <div className="form-actions">
           <button type="button" className="cancel-btn" onClick={onCancel}>
             Cancel
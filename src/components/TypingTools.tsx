@@ -0,0 +1,115 @@
+import { useState, useEffect } from "react";
+import { invoke } from "@tauri-apps/api/core";
+
+interface ToolStatus {
+  name: string;
+  enables: string;
+  package: string;
+  installed: boolean;
+  used_in_session: boolean;
+  needs_daemon: boolean;
+}
+
+interface DaemonStatus {
+  running: boolean;
+  unit_installed: boolean;
+  uinput_access: boolean;
+}
+
+interface DependencyReport {
+  supported: boolean;
+  session: string;
+  tools: ToolStatus[];
+  package_manager: string | null;
+  install_command: string | null;
+  ydotoold: DaemonStatus | null;
+}
+
+function TypingTools() {
+  const [report, setReport] = useState<DependencyReport | null>(null);
+  const [busy, setBusy] = useState<string | null>(null);
+  const [message, setMessage] = useState("");
+
+  function refresh() {
+    invoke<DependencyReport>("get_typing_dependencies")
+      .then(setReport)
+      .catch((e) => console.error("Failed to check typing tools:", e));
+  }
+
+  useEffect(refresh, []);
+
+  async function run(label: string, command: string, args: Record<string, unknown> = {}) {
+    setBusy(label);
+    setMessage("");
+    try {
+      await invoke(command, args);
+    } catch (e) {
+      setMessage(String(e));
+    }
+    setBusy(null);
+    refresh();
+  }
+
+  if (!report || !report.supported) {
+    return null;
+  }
+
+  return (
+    <div className="form-group typing-tools">
+      <label>Typing Tools ({report.session})</label>
+      <p className="field-help">External programs used to type text on Linux. Only the ones marked for this session are tried.</p>
+      {report.tools.map((tool) => (
+        <div key={tool.name} className={`typing-tool ${tool.used_in_session ? "" : "unused"}`}>
+          <span className={tool.installed ? "tool-installed" : "tool-missing"}>
+            {tool.name}: {tool.installed ? "installed" : "missing"}
+          </span>
+          <p className="field-help">{tool.enables}</p>
+          {!tool.installed && tool.used_in_session && report.package_manager && (
+            <button
+              type="button"
+              className="clear-history-btn"
+              disabled={busy !== null}
+              onClick={() => run(tool.name, "install_typing_tool", { name: tool.name })}
+            >
+              {busy === tool.name ? "Installing..." : `Install ${tool.package}`}
+            </button>
+          )}
+        </div>
+      ))}
+      {report.install_command && (
+        <p className="field-help">
+          Or in a terminal: <code>{report.install_command}</code>
+        </p>
+      )}
+      {report.ydotoold && (
+        <div className="typing-tool">
+          <span className={report.ydotoold.running ? "tool-installed" : "tool-missing"}>
+            ydotoold: {report.ydotoold.running ? "running" : "not running"}
+          </span>
+          {!report.ydotoold.uinput_access && (
+            <p className="field-help">
+              ydotoold needs write access to /dev/uinput, e.g. by adding yourself to the <code>input</code> group or a udev rule.
+            </p>
+          )}
+          {!report.ydotoold.running && (
+            <button
+              type="button"
+              className="clear-history-btn"
+              disabled={busy !== null}
+              onClick={() => run("ydotoold", "setup_ydotoold_service")}
+            >
+              {busy === "ydotoold"
+                ? "Starting..."
+                : report.ydotoold.unit_installed
+                  ? "Start ydotoold (user service)"
+                  : "Install ydotoold user service"}
+            </button>
+          )}
+        </div>
+      )}
+      {message && <p className="provider-status-error">{message}</p>}
+    </div>
+  );
+}
+
+export default TypingTools;
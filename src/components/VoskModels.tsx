@@ -0,0 +1,80 @@
+import { useState, useEffect } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+
+interface DownloadProgress {
+  name: string;
+  downloaded: number;
+  total: number | null;
+  stage: "downloading" | "extracting" | "done" | "failed";
+}
+
+interface VoskModelsProps {
+  model: string;
+  onChange: (model: string) => void;
+}
+
+function formatMb(bytes: number): string {
+  return `${(bytes / 1024 / 1024).toFixed(1)} MB`;
+}
+
+function VoskModels({ model, onChange }: VoskModelsProps) {
+  const [installed, setInstalled] = useState<string[]>([]);
+  const [progress, setProgress] = useState<DownloadProgress | null>(null);
+  const [error, setError] = useState("");
+
+  function refresh() {
+    invoke<string[]>("list_models")
+      .then(setInstalled)
+      .catch((e) => console.error("Failed to list Vosk models:", e));
+  }
+
+  useEffect(() => {
+    refresh();
+    const unlisten = listen<DownloadProgress>("model-download-progress", (event) => setProgress(event.payload));
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  async function download() {
+    setError("");
+    try {
+      await invoke("download_model", { name: model });
+      refresh();
+    } catch (e) {
+      setError(String(e));
+    }
+  }
+
+  const downloading = progress !== null && (progress.stage === "downloading" || progress.stage === "extracting");
+
+  return (
+    <>
+      <input
+        type="text"
+        list="vosk-models"
+        value={model}
+        onChange={(e) => onChange(e.target.value)}
+        placeholder="vosk-model-small-en-us-0.15"
+      />
+      <datalist id="vosk-models">
+        {installed.map((name) => (
+          <option key={name} value={name} />
+        ))}
+      </datalist>
+      {model && !installed.includes(model) && (
+        <button type="button" className="clear-history-btn" disabled={downloading} onClick={download}>
+          {downloading && progress
+            ? progress.stage === "extracting"
+              ? "Extracting..."
+              : `Downloading ${formatMb(progress.downloaded)}${progress.total ? ` of ${formatMb(progress.total)}` : ""}`
+            : "Download model"}
+        </button>
+      )}
+      {error && <p className="provider-status-error">{error}</p>}
+    </>
+  );
+}
+
+export default VoskModels;